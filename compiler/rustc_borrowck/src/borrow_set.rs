@@ -3,6 +3,7 @@
 
 use rustc_data_structures::fx::{FxIndexMap, FxIndexSet};
 use rustc_index::bit_set::DenseBitSet;
+pub use rustc_middle::mir::borrowck_facts::TwoPhaseActivation;
 use rustc_middle::mir::visit::{MutatingUseContext, NonUseContext, PlaceContext, Visitor};
 use rustc_middle::mir::{self, Body, Local, Location, traversal};
 use rustc_middle::span_bug;
@@ -60,15 +61,6 @@ fn index(&self, index: BorrowIndex) -> &BorrowData<'tcx> {
     }
 }
 
-/// Location where a two-phase borrow is activated, if a borrow
-/// is in fact a two-phase borrow.
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
-pub enum TwoPhaseActivation {
-    NotTwoPhase,
-    NotActivated,
-    ActivatedAt(Location),
-}
-
 #[derive(Debug, Clone)]
 pub struct BorrowData<'tcx> {
     /// Location where the borrow reservation starts.
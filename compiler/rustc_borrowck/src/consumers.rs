@@ -1,11 +1,16 @@
 //! This file provides API for compiler consumers.
 
-use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
 use rustc_hir::def_id::LocalDefId;
 use rustc_index::IndexVec;
 use rustc_middle::bug;
+use rustc_middle::mir::borrowck_facts::{
+    BorrowFact, BorrowckFacts, MoveFact, MovePathFact, OutlivesFact, RegionSccFact,
+};
 use rustc_middle::mir::{Body, Promoted};
-use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_mir_dataflow::move_paths::MoveData;
+use rustc_span::ErrorGuaranteed;
 
 pub use super::borrow_set::{BorrowData, BorrowSet, TwoPhaseActivation};
 pub use super::constraints::OutlivesConstraint;
@@ -17,7 +22,8 @@
     RichLocation, RustcFacts,
 };
 pub use super::region_infer::RegionInferenceContext;
-use crate::BorrowCheckRootCtxt;
+pub use super::type_check::Locations;
+use crate::{BorrowCheckRootCtxt, mir_borrowck_with_consumer};
 
 /// Struct used during mir borrowck to collect bodies with facts for a typeck root and all
 /// its nested bodies.
@@ -105,6 +111,89 @@ pub struct BodyWithBorrowckFacts<'tcx> {
     pub output_facts: Option<Box<PoloniusOutput>>,
 }
 
+impl<'tcx> BodyWithBorrowckFacts<'tcx> {
+    /// Converts these facts to the [`BorrowckFacts`] that external tools can keep for the rest
+    /// of the session, see [`rustc_middle::mir::borrowck_facts`].
+    pub fn into_borrowck_facts(self, tcx: TyCtxt<'tcx>) -> BorrowckFacts<'tcx> {
+        let regioncx = &self.region_inference_context;
+        let borrows_out_of_scope =
+            calculate_borrows_out_of_scope_at_location(&self.body, regioncx, &self.borrow_set)
+                .into_iter()
+                .map(|(location, borrows)| {
+                    (location, borrows.into_iter().map(|borrow| borrow.as_usize()).collect())
+                })
+                .collect();
+        let borrows = self
+            .borrow_set
+            .location_map()
+            .values()
+            .map(|borrow| BorrowFact {
+                reserve_location: borrow.reserve_location(),
+                activation: borrow.activation_location(),
+                kind: borrow.kind(),
+                region: borrow.region(),
+                borrowed_place: borrow.borrowed_place(),
+                assigned_place: borrow.assigned_place(),
+            })
+            .collect();
+        let outlives_constraints = regioncx
+            .outlives_constraints()
+            .map(|constraint| OutlivesFact {
+                sup: constraint.sup,
+                sub: constraint.sub,
+                location: constraint.locations.from_location(),
+            })
+            .collect();
+        let constraint_sccs = regioncx.constraint_sccs();
+        let region_sccs =
+            regioncx.regions().map(|region| constraint_sccs.scc(region).as_usize()).collect();
+        let sccs = constraint_sccs
+            .all_sccs()
+            .map(|scc| RegionSccFact {
+                successors: constraint_sccs
+                    .successors(scc)
+                    .iter()
+                    .map(|successor| successor.as_usize())
+                    .collect(),
+                locations: regioncx.scc_values().locations_outlived_by(scc).collect(),
+                universal_regions: regioncx
+                    .scc_values()
+                    .universal_regions_outlived_by(scc)
+                    .collect(),
+            })
+            .collect();
+        let move_data = MoveData::gather_moves(&self.body, tcx, |_| true);
+        let move_paths = move_data
+            .move_paths
+            .iter()
+            .map(|path| MovePathFact {
+                place: path.place,
+                parent: path.parent.map(|parent| parent.as_usize()),
+                children: path
+                    .children(&move_data.move_paths)
+                    .map(|(child, _)| child.as_usize())
+                    .collect(),
+            })
+            .collect();
+        let moves = move_data
+            .moves
+            .iter()
+            .map(|move_out| MoveFact { path: move_out.path.as_usize(), source: move_out.source })
+            .collect();
+        BorrowckFacts {
+            universal_regions: regioncx.universal_regions_iter().collect(),
+            region_sccs,
+            sccs,
+            borrows,
+            borrows_out_of_scope,
+            outlives_constraints,
+            move_paths,
+            moves,
+            body: self.body,
+        }
+    }
+}
+
 /// This function computes borrowck facts for the given def id and all its nested bodies.
 /// It must be called with a typeck root which will then borrowck all nested bodies as well.
 /// The [`ConsumerOptions`] determine which facts are returned. This function makes a copy
@@ -130,3 +219,23 @@ pub fn get_bodies_with_borrowck_facts(
     root_cx.do_mir_borrowck();
     root_cx.consumer.unwrap().bodies
 }
+
+/// Borrow checks `root_def_id` and all its nested bodies like the `mir_borrowck` query, and
+/// additionally returns the bodies with borrowck facts like [`get_bodies_with_borrowck_facts`].
+///
+/// This is meant for overrides of the `mir_borrowck` query that collect the facts of every body,
+/// which would otherwise have to borrow check each body twice. Unlike
+/// [`get_bodies_with_borrowck_facts`], no bodies are returned for bodies that are tainted by
+/// errors or don't need to be borrow checked.
+pub fn mir_borrowck_with_facts<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    root_def_id: LocalDefId,
+    options: ConsumerOptions,
+) -> (
+    Result<&'tcx FxIndexMap<LocalDefId, ty::DefinitionSiteHiddenType<'tcx>>, ErrorGuaranteed>,
+    FxHashMap<LocalDefId, BodyWithBorrowckFacts<'tcx>>,
+) {
+    let (result, consumer) =
+        mir_borrowck_with_consumer(tcx, root_def_id, Some(BorrowckConsumer::new(options)));
+    (result, consumer.unwrap().bodies)
+}
@@ -35,6 +35,7 @@
 use rustc_trait_selection::error_reporting::InferCtxtErrorExt;
 use rustc_trait_selection::error_reporting::traits::FindExprBySpan;
 use rustc_trait_selection::error_reporting::traits::call_kind::CallKind;
+use rustc_trait_selection::error_reporting::traits::on_unimplemented::OnUnimplementedNote;
 use rustc_trait_selection::infer::InferCtxtExt;
 use rustc_trait_selection::traits::query::evaluate_obligation::InferCtxtExt as _;
 use rustc_trait_selection::traits::{
@@ -151,6 +152,21 @@ pub(crate) fn report_use_of_moved_or_uninitialized(
                 ),
             );
 
+            let moved_ty = self.move_data.move_paths
+                [self.move_data.moves[move_out_indices[0]].path]
+                .place
+                .ty(self.body, self.infcx.tcx)
+                .ty;
+            let OnUnimplementedNote {
+                message: on_move_message,
+                label: on_move_label,
+                notes: on_move_notes,
+                ..
+            } = self.infcx.err_ctxt().on_type_note(sym::on_move, moved_ty, err.long_ty_path());
+            if let Some(message) = on_move_message {
+                err.primary_message(message);
+            }
+
             let reinit_spans = maybe_reinitialized_locations
                 .iter()
                 .take(3)
@@ -232,7 +248,9 @@ pub(crate) fn report_use_of_moved_or_uninitialized(
 
             use_spans.var_path_only_subdiag(&mut err, desired_action);
 
-            if !is_loop_move {
+            if let Some(label) = on_move_label {
+                err.span_label(span, label);
+            } else if !is_loop_move {
                 err.span_label(
                     span,
                     format!(
@@ -241,6 +259,9 @@ pub(crate) fn report_use_of_moved_or_uninitialized(
                     ),
                 );
             }
+            for note in on_move_notes {
+                err.note(note);
+            }
 
             let ty = used_place.ty(self.body, self.infcx.tcx).ty;
             let needs_note = match ty.kind() {
@@ -54,7 +54,7 @@
 use tracing::{debug, instrument};
 
 use crate::borrow_set::{BorrowData, BorrowSet};
-use crate::consumers::{BodyWithBorrowckFacts, RustcFacts};
+use crate::consumers::{BodyWithBorrowckFacts, BorrowckConsumer, RustcFacts};
 use crate::dataflow::{BorrowIndex, Borrowck, BorrowckDomain, Borrows};
 use crate::diagnostics::{
     AccessKind, BorrowckDiagnosticsBuffer, IllegalMoveOriginKind, MoveError, RegionName,
@@ -118,6 +118,19 @@ fn mir_borrowck(
     tcx: TyCtxt<'_>,
     def: LocalDefId,
 ) -> Result<&FxIndexMap<LocalDefId, ty::DefinitionSiteHiddenType<'_>>, ErrorGuaranteed> {
+    mir_borrowck_with_consumer(tcx, def, None).0
+}
+
+/// Borrow checks the typeck root `def` and all its nested bodies, and returns `consumer` with
+/// the bodies that were borrow checked.
+fn mir_borrowck_with_consumer<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def: LocalDefId,
+    consumer: Option<BorrowckConsumer<'tcx>>,
+) -> (
+    Result<&'tcx FxIndexMap<LocalDefId, ty::DefinitionSiteHiddenType<'tcx>>, ErrorGuaranteed>,
+    Option<BorrowckConsumer<'tcx>>,
+) {
     assert!(!tcx.is_typeck_child(def.to_def_id()));
     let (input_body, _) = tcx.mir_promoted(def);
     debug!("run query mir_borrowck: {}", tcx.def_path_str(def));
@@ -125,15 +138,16 @@ fn mir_borrowck(
     let input_body: &Body<'_> = &input_body.borrow();
     if let Some(guar) = input_body.tainted_by_errors {
         debug!("Skipping borrowck because of tainted body");
-        Err(guar)
+        (Err(guar), consumer)
     } else if input_body.should_skip() {
         debug!("Skipping borrowck because of injected body");
         let opaque_types = Default::default();
-        Ok(tcx.arena.alloc(opaque_types))
+        (Ok(tcx.arena.alloc(opaque_types)), consumer)
     } else {
-        let mut root_cx = BorrowCheckRootCtxt::new(tcx, def, None);
+        let mut root_cx = BorrowCheckRootCtxt::new(tcx, def, consumer);
         root_cx.do_mir_borrowck();
-        root_cx.finalize()
+        let consumer = root_cx.consumer.take();
+        (root_cx.finalize(), consumer)
     }
 }
 
@@ -441,10 +441,15 @@ pub(crate) fn to_region_vid(&self, r: ty::Region<'tcx>) -> RegionVid {
     }
 
     /// Returns an iterator over all the outlives constraints.
-    pub(crate) fn outlives_constraints(&self) -> impl Iterator<Item = OutlivesConstraint<'tcx>> {
+    pub fn outlives_constraints(&self) -> impl Iterator<Item = OutlivesConstraint<'tcx>> {
         self.constraints.outlives().iter().copied()
     }
 
+    /// Returns an iterator over the universal regions, i.e. the free regions of the body.
+    pub fn universal_regions_iter(&self) -> impl Iterator<Item = RegionVid> + 'static {
+        self.universal_regions().universal_regions_iter()
+    }
+
     /// Adds annotations for `#[rustc_regions]`; see `UniversalRegions::annotate`.
     pub(crate) fn annotate(&self, tcx: TyCtxt<'tcx>, err: &mut Diag<'_, ()>) {
         self.universal_regions().annotate(tcx, err)
@@ -1885,6 +1890,11 @@ pub fn constraint_sccs(&self) -> &ConstraintSccs {
         &self.constraint_sccs
     }
 
+    /// The inferred values of the SCCs of the constraint graph.
+    pub(crate) fn scc_values(&self) -> &RegionValues<'tcx, ConstraintSccIndex> {
+        &self.scc_values
+    }
+
     /// Returns the representative `RegionVid` for a given SCC.
     /// See `RegionTracker` for how a region variable ID is chosen.
     ///
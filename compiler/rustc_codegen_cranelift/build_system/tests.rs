@@ -6,7 +6,9 @@
 use crate::prepare::{GitRepo, apply_patches};
 use crate::rustc_info::get_default_sysroot;
 use crate::shared_utils::rustflags_from_env;
-use crate::utils::{CargoProject, Compiler, LogGroup, ensure_empty_dir, spawn_and_wait};
+use crate::utils::{
+    CargoProject, Compiler, LogGroup, ensure_empty_dir, spawn_and_wait, spawn_and_wait_with_output,
+};
 use crate::{CodegenBackend, SysrootKind, build_sysroot, config};
 
 static BUILD_EXAMPLE_OUT_DIR: RelPath = RelPath::build("example");
@@ -89,6 +91,40 @@ const fn jit_bin(config: &'static str, source: &'static str, args: &'static str)
     TestCase::build_bin_and_run("aot.issue-72793", "example/issue-72793.rs", &[]),
     TestCase::build_bin("aot.issue-59326", "example/issue-59326.rs"),
     TestCase::build_bin_and_run("aot.neon", "example/neon.rs", &[]),
+    TestCase::custom("aot.simd_crosscheck", &|runner| {
+        runner.run_rustc(["example/simd_crosscheck.rs"]);
+        if !runner.is_native {
+            eprintln!("Cross-Compiling: Not comparing with the LLVM backend");
+            return;
+        }
+
+        // Compare the results of the `core::arch` intrinsics with those of the LLVM backend.
+        let out_dir = BUILD_EXAMPLE_OUT_DIR.to_path(&runner.dirs);
+        let clif_output = spawn_and_wait_with_output(Command::new(out_dir.join("simd_crosscheck")));
+
+        let mut llvm_cmd = Command::new(&runner.host_compiler.rustc);
+        llvm_cmd.args(&runner.host_compiler.rustflags);
+        llvm_cmd.arg("example/simd_crosscheck.rs");
+        llvm_cmd.args(["--edition=2024", "--crate-name", "simd_crosscheck_llvm", "--out-dir"]);
+        llvm_cmd.arg(&out_dir);
+        spawn_and_wait(llvm_cmd);
+        let llvm_output =
+            spawn_and_wait_with_output(Command::new(out_dir.join("simd_crosscheck_llvm")));
+
+        let mut mismatches = 0;
+        for (clif_line, llvm_line) in clif_output.lines().zip(llvm_output.lines()) {
+            if clif_line != llvm_line {
+                eprintln!("cg_clif: {clif_line}");
+                eprintln!("LLVM:    {llvm_line}");
+                mismatches += 1;
+            }
+        }
+        assert_eq!(clif_output.lines().count(), llvm_output.lines().count());
+        if mismatches != 0 {
+            eprintln!("{mismatches} intrinsic results differ from the LLVM backend");
+            std::process::exit(1);
+        }
+    }),
     TestCase::build_bin_and_run("aot.gen_block_iterate", "example/gen_block_iterate.rs", &[]),
     TestCase::build_bin_and_run("aot.raw-dylib", "example/raw-dylib.rs", &[]),
     TestCase::custom("test.sysroot", &|runner| {
@@ -252,6 +288,7 @@ pub(crate) fn run_tests(
         let runner = TestRunner::new(
             dirs.clone(),
             target_compiler,
+            bootstrap_host_compiler.clone(),
             use_unstable_features,
             panic_unwind_support,
             skip_tests,
@@ -286,6 +323,7 @@ pub(crate) fn run_tests(
         let mut runner = TestRunner::new(
             dirs.clone(),
             target_compiler,
+            bootstrap_host_compiler.clone(),
             use_unstable_features,
             panic_unwind_support,
             skip_tests,
@@ -317,6 +355,8 @@ struct TestRunner<'a> {
     skip_tests: &'a [&'a str],
     dirs: Dirs,
     target_compiler: Compiler,
+    /// The compiler using the LLVM backend that cg_clif was built with.
+    host_compiler: Compiler,
     stdlib_source: PathBuf,
 }
 
@@ -324,6 +364,7 @@ impl<'a> TestRunner<'a> {
     fn new(
         dirs: Dirs,
         mut target_compiler: Compiler,
+        host_compiler: Compiler,
         use_unstable_features: bool,
         panic_unwind_support: bool,
         skip_tests: &'a [&'a str],
@@ -343,6 +384,7 @@ fn new(
             skip_tests,
             dirs,
             target_compiler,
+            host_compiler,
             stdlib_source,
         }
     }
@@ -1,6 +1,6 @@
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
-use std::process::{self, Command};
+use std::process::{self, Command, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::{env, fs, io};
 
@@ -176,6 +176,16 @@ pub(crate) fn spawn_and_wait(mut cmd: Command) {
     }
 }
 
+#[track_caller]
+pub(crate) fn spawn_and_wait_with_output(mut cmd: Command) -> String {
+    let output = cmd.stderr(Stdio::inherit()).output().unwrap();
+    if !output.status.success() {
+        eprintln!("{cmd:?} exited with status {:?}", output.status);
+        process::exit(1);
+    }
+    String::from_utf8(output.stdout).unwrap()
+}
+
 /// Create the specified directory if it doesn't exist yet and delete all contents.
 pub(crate) fn ensure_empty_dir(path: &Path) {
     fs::create_dir_all(path).unwrap();
@@ -0,0 +1,301 @@
+// Prints the results of `core::arch` intrinsics for a fixed set of inputs. The test runner
+// compares the output of cg_clif with that of the LLVM backend line by line, so only intrinsics
+// with fully deterministic results may be added here. (`_mm_rcp_ps` and friends are approximations
+// whose exact result differs between cpus and thus can't be tested this way.)
+
+#![allow(unused_unsafe)]
+
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+#[allow(unused_imports)]
+use std::mem::transmute;
+
+#[allow(dead_code)]
+fn hex<T: Copy>(value: T) -> String {
+    let bytes = unsafe {
+        std::slice::from_raw_parts(&value as *const T as *const u8, std::mem::size_of::<T>())
+    };
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+macro_rules! show {
+    ($e:expr) => {
+        println!("{}: {}", stringify!($e), hex(unsafe { $e }));
+    };
+}
+
+#[allow(dead_code)]
+const I8S: [i8; 32] = [
+    0, 1, -1, 2, -2, 127, -128, 126, -127, 64, -64, 100, -100, 33, -33, 7, //
+    -7, 55, -55, 15, -16, 17, -18, 19, -20, 21, -22, 23, -24, 25, 126, -126,
+];
+
+#[allow(dead_code)]
+const I16S: [i16; 16] = [
+    0, 1, -1, 0x7fff, -0x8000, 0x4000, -0x4000, 1234, -1234, 0x7ffe, -0x7fff, 300, -300, 99, 16,
+    -16,
+];
+
+#[allow(dead_code)]
+const I32S: [i32; 8] = [0, -1, i32::MAX, i32::MIN, 0x1234_5678, -0x1234_5678, 65536, -65537];
+
+#[allow(dead_code)]
+const SHIFTS: [u64; 8] = [0, 3, 15, 16, 31, 32, 64, 1000];
+
+#[allow(dead_code)]
+const F32S: [f32; 8] = [0.5, -1.5, 2.5, -0.0, 1e10, -3.75, 0.49999997, 7.0];
+
+#[allow(dead_code)]
+const F64S: [f64; 4] = [1.5, -2.5, 1e300, -0.25];
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2,ssse3,sse4.1,avx,avx2")]
+unsafe fn x86_intrinsics() {
+    let a8: __m128i = transmute(<[i8; 16]>::try_from(&I8S[..16]).unwrap());
+    let b8: __m128i = transmute(<[i8; 16]>::try_from(&I8S[16..]).unwrap());
+    let a16: __m128i = transmute(<[i16; 8]>::try_from(&I16S[..8]).unwrap());
+    let b16: __m128i = transmute(<[i16; 8]>::try_from(&I16S[8..]).unwrap());
+    let a32: __m128i = transmute(<[i32; 4]>::try_from(&I32S[..4]).unwrap());
+    let b32: __m128i = transmute(<[i32; 4]>::try_from(&I32S[4..]).unwrap());
+    let w8: __m256i = transmute(I8S);
+    let w16: __m256i = transmute(I16S);
+    let w32: __m256i = transmute(I32S);
+    let mut rotated16 = I16S;
+    rotated16.rotate_left(8);
+    let wb16: __m256i = transmute(rotated16);
+    let aps: __m128 = transmute(<[f32; 4]>::try_from(&F32S[..4]).unwrap());
+    let bps: __m128 = transmute(<[f32; 4]>::try_from(&F32S[4..]).unwrap());
+    let wps: __m256 = transmute(F32S);
+    let apd: __m128d = transmute(<[f64; 2]>::try_from(&F64S[..2]).unwrap());
+    let bpd: __m128d = transmute(<[f64; 2]>::try_from(&F64S[2..]).unwrap());
+    let wpd: __m256d = transmute(F64S);
+
+    for shift in SHIFTS {
+        let count = _mm_set_epi64x(-1, shift as i64);
+        println!("shift by {shift}");
+        show!(_mm_sll_epi16(a16, count));
+        show!(_mm_srl_epi16(a16, count));
+        show!(_mm_sra_epi16(a16, count));
+        show!(_mm_sll_epi32(a32, count));
+        show!(_mm_srl_epi32(a32, count));
+        show!(_mm_sra_epi32(a32, count));
+        show!(_mm_sll_epi64(a32, count));
+        show!(_mm_srl_epi64(a32, count));
+        show!(_mm256_sll_epi16(w16, count));
+        show!(_mm256_srl_epi32(w32, count));
+        show!(_mm256_sra_epi32(w32, count));
+        show!(_mm256_sll_epi64(w32, count));
+    }
+
+    let counts32 = _mm_setr_epi32(0, 5, 31, 32);
+    let counts64 = _mm_set_epi64x(64, 17);
+    let wcounts32 = _mm256_setr_epi32(0, 1, 16, 31, 32, 33, -1, 1000);
+    let wcounts64 = _mm256_setr_epi64x(0, 63, 64, -1);
+    show!(_mm_sllv_epi32(a32, counts32));
+    show!(_mm_srlv_epi32(a32, counts32));
+    show!(_mm_srav_epi32(a32, counts32));
+    show!(_mm_sllv_epi64(b32, counts64));
+    show!(_mm_srlv_epi64(b32, counts64));
+    show!(_mm256_sllv_epi32(w32, wcounts32));
+    show!(_mm256_srlv_epi32(w32, wcounts32));
+    show!(_mm256_srav_epi32(w32, wcounts32));
+    show!(_mm256_sllv_epi64(w32, wcounts64));
+    show!(_mm256_srlv_epi64(w32, wcounts64));
+
+    let zero = _mm_setzero_si128();
+    let ones = _mm_set1_epi8(-1);
+    for (x, y) in [(a8, b8), (a8, zero), (zero, a8), (ones, a8), (a8, ones), (ones, ones)] {
+        show!(_mm_testz_si128(x, y));
+        show!(_mm_testc_si128(x, y));
+        show!(_mm_testnzc_si128(x, y));
+        let wx = _mm256_set_m128i(x, y);
+        let wy = _mm256_set_m128i(y, y);
+        show!(_mm256_testc_si256(wx, wy));
+        show!(_mm256_testnzc_si256(wx, wy));
+    }
+
+    show!(_mm_hadd_epi16(a16, b16));
+    show!(_mm_hadds_epi16(a16, b16));
+    show!(_mm_hsub_epi16(a16, b16));
+    show!(_mm_hsubs_epi16(a16, b16));
+    show!(_mm_hadd_epi32(a32, b32));
+    show!(_mm_hsub_epi32(a32, b32));
+    show!(_mm256_hadd_epi16(w16, wb16));
+    show!(_mm256_hadds_epi16(w16, wb16));
+    show!(_mm256_hsubs_epi16(w16, wb16));
+    show!(_mm256_hadd_epi32(w32, w32));
+    show!(_mm_hadd_ps(aps, bps));
+    show!(_mm_hsub_ps(aps, bps));
+    show!(_mm_hadd_pd(apd, bpd));
+    show!(_mm_hsub_pd(apd, bpd));
+    show!(_mm256_hadd_ps(wps, wps));
+    show!(_mm256_hsub_pd(wpd, wpd));
+
+    show!(_mm_sign_epi8(a8, b8));
+    show!(_mm_sign_epi16(a16, b16));
+    show!(_mm_sign_epi32(a32, b32));
+    show!(_mm256_sign_epi8(w8, w8));
+    show!(_mm256_sign_epi16(w16, wb16));
+
+    show!(_mm_mulhi_epi16(a16, b16));
+    show!(_mm_mulhi_epu16(a16, b16));
+    show!(_mm_mulhrs_epi16(a16, b16));
+    show!(_mm256_mulhi_epi16(w16, wb16));
+    show!(_mm256_mulhi_epu16(w16, wb16));
+    show!(_mm256_mulhrs_epi16(w16, wb16));
+
+    show!(_mm_movemask_epi8(a8));
+    show!(_mm_movemask_ps(aps));
+    show!(_mm_movemask_pd(apd));
+    show!(_mm256_movemask_epi8(w8));
+    show!(_mm256_movemask_ps(wps));
+    show!(_mm256_movemask_pd(wpd));
+
+    show!(_mm_round_ps::<{ _MM_FROUND_TO_NEAREST_INT | _MM_FROUND_NO_EXC }>(aps));
+    show!(_mm_round_ps::<{ _MM_FROUND_TO_NEG_INF }>(aps));
+    show!(_mm_round_ps::<{ _MM_FROUND_TO_POS_INF }>(bps));
+    show!(_mm_round_ps::<{ _MM_FROUND_TO_ZERO }>(bps));
+    show!(_mm_round_pd::<{ _MM_FROUND_TO_NEAREST_INT }>(apd));
+    show!(_mm256_round_ps::<{ _MM_FROUND_TO_NEAREST_INT }>(wps));
+    show!(_mm256_round_pd::<{ _MM_FROUND_TO_POS_INF }>(wpd));
+
+    show!(_mm_permutevar_ps(aps, _mm_setr_epi32(3, 2, 5, -1)));
+    show!(_mm_permutevar_pd(apd, _mm_set_epi64x(0, 2)));
+    show!(_mm256_permutevar_ps(wps, _mm256_setr_epi32(3, 2, 1, 0, 0, 1, 6, 7)));
+    show!(_mm256_permutevar_pd(wpd, _mm256_setr_epi64x(2, 0, 0, 2)));
+}
+
+#[cfg(target_arch = "aarch64")]
+unsafe fn neon_intrinsics() {
+    let a8: [i8; 16] = I8S[..16].try_into().unwrap();
+    let b8: [i8; 16] = I8S[16..].try_into().unwrap();
+    let a16: [i16; 8] = I16S[..8].try_into().unwrap();
+    let b16: [i16; 8] = I16S[8..].try_into().unwrap();
+    let a32: [i32; 4] = I32S[..4].try_into().unwrap();
+    let b32: [i32; 4] = I32S[4..].try_into().unwrap();
+    let aps: [f32; 4] = F32S[..4].try_into().unwrap();
+    let bps: [f32; 4] = F32S[4..].try_into().unwrap();
+
+    let su8: uint8x16_t = transmute(a8);
+    let tu8: uint8x16_t = transmute(b8);
+    let hu8: uint8x8_t = transmute(<[i8; 8]>::try_from(&I8S[..8]).unwrap());
+    let ku8: uint8x8_t = transmute(<[i8; 8]>::try_from(&I8S[8..16]).unwrap());
+    let s16: int16x8_t = transmute(a16);
+    let t16: int16x8_t = transmute(b16);
+    let u16s: uint16x8_t = transmute(a16);
+    let h16: int16x4_t = transmute(<[i16; 4]>::try_from(&I16S[..4]).unwrap());
+    let k16: int16x4_t = transmute(<[i16; 4]>::try_from(&I16S[4..8]).unwrap());
+    let s32: int32x4_t = transmute(a32);
+    let t32: int32x4_t = transmute(b32);
+    let u32s: uint32x4_t = transmute(a32);
+    let fa: float32x4_t = transmute(aps);
+    let fb: float32x4_t = transmute(bps);
+
+    show!(vaddlvq_u8(su8));
+    show!(vaddlvq_s8(transmute(a8)));
+    show!(vaddlvq_u16(u16s));
+    show!(vaddlvq_s16(s16));
+    show!(vaddlvq_s32(s32));
+    show!(vaddlv_u8(hu8));
+    show!(vaddlv_s16(h16));
+
+    show!(vpaddlq_u8(su8));
+    show!(vpaddlq_s8(transmute(a8)));
+    show!(vpaddlq_s16(s16));
+    show!(vpaddlq_u32(u32s));
+    show!(vpaddl_u8(hu8));
+
+    show!(vmull_u8(hu8, ku8));
+    show!(vmull_s8(transmute(hu8), transmute(ku8)));
+    show!(vmull_s16(h16, k16));
+    show!(vmull_u16(transmute(h16), transmute(k16)));
+
+    show!(vqdmulhq_s16(s16, t16));
+    show!(vqdmulhq_s32(s32, t32));
+    show!(vqrdmulhq_s16(s16, t16));
+    show!(vqrdmulhq_s32(s32, t32));
+    show!(vqdmulhq_s16(vdupq_n_s16(i16::MIN), vdupq_n_s16(i16::MIN)));
+
+    show!(vabdq_u8(su8, tu8));
+    show!(vabdq_s8(transmute(a8), transmute(b8)));
+    show!(vabdq_s16(s16, t16));
+    show!(vabdq_s32(s32, t32));
+    show!(vabdq_f32(fa, fb));
+
+    show!(vhaddq_u8(su8, tu8));
+    show!(vhaddq_s16(s16, t16));
+    show!(vhaddq_s32(s32, t32));
+    show!(vrhaddq_u8(su8, tu8));
+    show!(vrhaddq_s16(s16, t16));
+    show!(vrhaddq_u32(u32s, transmute(t32)));
+
+    show!(vqmovn_s16(s16));
+    show!(vqmovn_u16(u16s));
+    show!(vqmovn_s32(s32));
+    show!(vqmovn_u32(u32s));
+    show!(vqmovun_s16(s16));
+    show!(vqmovun_s32(s32));
+
+    show!(vrshrn_n_u16::<1>(u16s));
+    show!(vrshrn_n_u16::<8>(u16s));
+    show!(vrshrn_n_s32::<3>(s32));
+    show!(vrshrn_n_s32::<16>(s32));
+
+    let shifts16: int16x8_t = transmute([0i16, 1, -1, 15, -15, 16, -16, 100]);
+    let shifts32: int32x4_t = transmute([3i32, -3, 31, -33]);
+    show!(vshlq_s16(s16, shifts16));
+    show!(vshlq_u16(u16s, shifts16));
+    show!(vshlq_s32(s32, shifts32));
+    show!(vshlq_u32(u32s, shifts32));
+
+    show!(vrndnq_f32(fa));
+    show!(vrndnq_f32(fb));
+
+    let idx: uint8x16_t =
+        transmute([0u8, 15, 16, 31, 32, 47, 48, 63, 64, 255, 1, 17, 33, 49, 2, 3]);
+    show!(vqtbl1q_u8(su8, idx));
+    show!(vqtbl1_u8(su8, vget_low_u8(idx)));
+    show!(vqtbl2q_u8(uint8x16x2_t(su8, tu8), idx));
+    show!(vqtbl3q_u8(uint8x16x3_t(su8, tu8, su8), idx));
+    show!(vqtbl4q_u8(uint8x16x4_t(su8, tu8, tu8, su8), idx));
+    show!(vqtbx1q_u8(tu8, su8, idx));
+    show!(vqtbx2q_u8(tu8, uint8x16x2_t(su8, tu8), idx));
+}
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "crc")]
+unsafe fn crc_intrinsics() {
+    for (crc, data) in [(0u32, 0u64), (!0, 0x0123_4567_89ab_cdef), (0xdead_beef, 0xffff_0000)] {
+        show!(__crc32b(crc, data as u8));
+        show!(__crc32h(crc, data as u16));
+        show!(__crc32w(crc, data as u32));
+        show!(__crc32d(crc, data));
+        show!(__crc32cb(crc, data as u8));
+        show!(__crc32ch(crc, data as u16));
+        show!(__crc32cw(crc, data as u32));
+        show!(__crc32cd(crc, data));
+    }
+}
+
+fn main() {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx2") && is_x86_feature_detected!("sse4.1") {
+        unsafe {
+            x86_intrinsics();
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    unsafe {
+        neon_intrinsics();
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    if std::arch::is_aarch64_feature_detected!("crc") {
+        unsafe {
+            crc_intrinsics();
+        }
+    }
+}
@@ -44,7 +44,7 @@ pub(crate) fn codegen_fn<'tcx>(
     let symbol_name = tcx.symbol_name(instance).name.to_string();
     let _timer = tcx.prof.generic_activity_with_arg("codegen fn", &*symbol_name);
 
-    let mir = tcx.instance_mir(instance.def);
+    let mir = tcx.codegen_instance_mir(instance);
     let _mir_guard = crate::PrintOnPanic(|| {
         let mut buf = Vec::new();
         with_no_trimmed_paths!({
@@ -118,6 +118,7 @@ pub(crate) fn codegen_fn<'tcx>(
     };
 
     tcx.prof.generic_activity("codegen clif ir").run(|| codegen_fn_body(&mut fx, start_block));
+    FunctionDebugContext::define_scopes_and_variables(&mut fx, type_dbg);
     fx.bcx.seal_all_blocks();
     fx.bcx.finalize();
 
@@ -253,6 +254,7 @@ pub(crate) fn compile_fn(
             codegened_func.func_debug_cx.unwrap().finalize(
                 debug_context,
                 codegened_func.func_id,
+                module.isa(),
                 context,
             );
         }
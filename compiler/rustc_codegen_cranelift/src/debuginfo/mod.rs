@@ -6,6 +6,7 @@
 mod object;
 mod types;
 mod unwind;
+mod variables;
 
 use cranelift_codegen::ir::Endianness;
 use cranelift_codegen::isa::TargetIsa;
@@ -19,6 +20,7 @@
 use rustc_codegen_ssa::debuginfo::type_names;
 use rustc_hir::def::DefKind;
 use rustc_hir::def_id::DefIdMap;
+use rustc_index::IndexVec;
 use rustc_session::Session;
 use rustc_session::config::DebugInfo;
 use rustc_span::{RemapPathScopeComponents, SourceFileHash, StableSourceFileId};
@@ -27,6 +29,7 @@
 pub(crate) use self::emit::{DebugReloc, DebugRelocName};
 pub(crate) use self::types::TypeDebugContext;
 pub(crate) use self::unwind::{EXCEPTION_HANDLER_CATCH, EXCEPTION_HANDLER_CLEANUP, UnwindContext};
+pub(crate) use self::variables::{VarLoc, VarPiece};
 use crate::debuginfo::emit::{address_for_data, address_for_func};
 use crate::prelude::*;
 
@@ -34,6 +37,28 @@ pub(crate) fn producer(sess: &Session) -> String {
     format!("rustc version {} with cranelift {}", sess.cfg_version, cranelift_codegen::VERSION)
 }
 
+fn function_name<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> String {
+    let mut name = String::new();
+    type_names::push_item_name(tcx, instance.def_id(), false, &mut name);
+
+    // Find the enclosing function, in case this is a closure.
+    let enclosing_fn_def_id = tcx.typeck_root_def_id(instance.def_id());
+
+    // We look up the generics of the enclosing function and truncate the args
+    // to their length in order to cut off extra stuff that might be in there for
+    // closures or coroutines.
+    let generics = tcx.generics_of(enclosing_fn_def_id);
+    let args = instance.args.truncate_to(tcx, generics);
+
+    type_names::push_generic_params(
+        tcx,
+        tcx.normalize_erasing_regions(ty::TypingEnv::fully_monomorphized(), args),
+        &mut name,
+    );
+
+    name
+}
+
 pub(crate) struct DebugContext {
     endian: RunTimeEndian,
 
@@ -43,6 +68,8 @@ pub(crate) struct DebugContext {
     stack_pointer_register: Register,
     namespace_map: DefIdMap<UnitEntryId>,
     array_size_type: Option<UnitEntryId>,
+    /// Abstract `DW_TAG_subprogram` entries for inlined functions, keyed by linkage name.
+    abstract_functions: FxHashMap<String, UnitEntryId>,
 
     embed_source: bool,
 }
@@ -50,7 +77,10 @@ pub(crate) struct DebugContext {
 pub(crate) struct FunctionDebugContext {
     entry_id: Option<UnitEntryId>,
     function_source_loc: (FileId, u64, u64),
-    source_loc_set: IndexSet<(FileId, u64, u64)>,
+    source_loc_set: IndexSet<(FileId, u64, u64, SourceScope)>,
+    scope_parents: IndexVec<SourceScope, Option<SourceScope>>,
+    scope_entries: FxHashMap<SourceScope, UnitEntryId>,
+    variables: Vec<(UnitEntryId, Vec<VarPiece>)>,
 }
 
 impl DebugContext {
@@ -179,6 +209,7 @@ pub(crate) fn new(
             stack_pointer_register,
             namespace_map: DefIdMap::default(),
             array_size_type,
+            abstract_functions: FxHashMap::default(),
             embed_source,
         })
     }
@@ -225,28 +256,15 @@ pub(crate) fn define_function<'tcx>(
                 entry_id: None,
                 function_source_loc: (file_id, line, column),
                 source_loc_set: IndexSet::new(),
+                scope_parents: IndexVec::new(),
+                scope_entries: FxHashMap::default(),
+                variables: vec![],
             };
         }
 
         let scope = self.item_namespace(tcx, tcx.parent(instance.def_id()));
 
-        let mut name = String::new();
-        type_names::push_item_name(tcx, instance.def_id(), false, &mut name);
-
-        // Find the enclosing function, in case this is a closure.
-        let enclosing_fn_def_id = tcx.typeck_root_def_id(instance.def_id());
-
-        // We look up the generics of the enclosing function and truncate the args
-        // to their length in order to cut off extra stuff that might be in there for
-        // closures or coroutines.
-        let generics = tcx.generics_of(enclosing_fn_def_id);
-        let args = instance.args.truncate_to(tcx, generics);
-
-        type_names::push_generic_params(
-            tcx,
-            tcx.normalize_erasing_regions(ty::TypingEnv::fully_monomorphized(), args),
-            &mut name,
-        );
+        let name = function_name(tcx, instance);
 
         let entry_id = self.dwarf.unit.add(scope, gimli::DW_TAG_subprogram);
         let entry = self.dwarf.unit.get_mut(entry_id);
@@ -288,6 +306,9 @@ pub(crate) fn define_function<'tcx>(
             entry_id: Some(entry_id),
             function_source_loc: (file_id, line, column),
             source_loc_set: IndexSet::new(),
+            scope_parents: IndexVec::new(),
+            scope_entries: FxHashMap::default(),
+            variables: vec![],
         }
     }
 
@@ -359,6 +380,7 @@ pub(crate) fn finalize(
         mut self,
         debug_context: &mut DebugContext,
         func_id: FuncId,
+        isa: &dyn TargetIsa,
         context: &Context,
     ) {
         let end = self.create_debug_lines(debug_context, func_id, context);
@@ -374,6 +396,9 @@ pub(crate) fn finalize(
             entry.set(gimli::DW_AT_low_pc, AttributeValue::Address(address_for_func(func_id)));
             // Using Udata for DW_AT_high_pc requires at least DWARF4
             entry.set(gimli::DW_AT_high_pc, AttributeValue::Udata(u64::from(end)));
+
+            self.define_scope_ranges(debug_context, func_id, context);
+            self.define_variable_locations(debug_context, func_id, isa, context, end);
         }
     }
 }
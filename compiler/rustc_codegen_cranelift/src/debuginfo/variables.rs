@@ -0,0 +1,529 @@
+//! Debuginfo for variables and the lexical and inlined scopes they are defined in.
+
+use cranelift_codegen::ir::ValueLabel;
+use cranelift_codegen::isa::TargetIsa;
+use cranelift_codegen::{LabelValueLoc, MachSrcLoc};
+use cranelift_module::FuncId;
+use gimli::Register;
+use gimli::write::{
+    Address, AttributeValue, Expression, Location, LocationList, Range, RangeList, UnitEntryId,
+};
+use rustc_middle::mir::{OUTERMOST_SOURCE_SCOPE, PlaceElem, SourceScope, VarDebugInfoContents};
+use rustc_span::Symbol;
+
+use super::emit::address_for_func;
+use super::{DebugContext, FunctionDebugContext, TypeDebugContext};
+use crate::prelude::*;
+
+/// A contiguous part of a variable together with the location of its bytes.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct VarPiece {
+    size: Size,
+    loc: Option<VarLoc>,
+}
+
+impl VarPiece {
+    pub(crate) fn new(size: Size, loc: Option<VarLoc>) -> Self {
+        VarPiece { size, loc }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum VarLoc {
+    /// Stored in the value with the given label.
+    Value(ValueLabel),
+    /// Stored in the given stack slot at the given offset.
+    Stack(StackSlot, i64),
+    /// Stored in memory at the given offset from the address held by the value with the given
+    /// label.
+    Indirect(ValueLabel, i64),
+}
+
+/// Slice the bytes `offset..offset + size` out of the variable described by `pieces`.
+fn slice_pieces(pieces: &[VarPiece], offset: Size, size: Size) -> Vec<VarPiece> {
+    let mut sliced = vec![];
+    let mut piece_start = Size::ZERO;
+    for piece in pieces {
+        let piece_end = piece_start + piece.size;
+        let start = piece_start.max(offset);
+        let end = piece_end.min(offset + size);
+        if start < end {
+            let extra_offset = i64::try_from((start - piece_start).bytes()).unwrap();
+            let loc = match piece.loc {
+                Some(VarLoc::Stack(stack_slot, offset)) => {
+                    Some(VarLoc::Stack(stack_slot, offset + extra_offset))
+                }
+                Some(VarLoc::Indirect(label, offset)) => {
+                    Some(VarLoc::Indirect(label, offset + extra_offset))
+                }
+                // Part of a register can't be described without DW_OP_bit_piece, which isn't
+                // supported by all debuggers.
+                Some(VarLoc::Value(label)) if start == piece_start && end == piece_end => {
+                    Some(VarLoc::Value(label))
+                }
+                Some(VarLoc::Value(_)) | None => None,
+            };
+            sliced.push(VarPiece::new(end - start, loc));
+        }
+        piece_start = piece_end;
+    }
+    sliced
+}
+
+/// Compute the pieces of `place`. Returns `None` if the location of the place can't be described.
+fn place_pieces<'tcx>(
+    fx: &mut FunctionCx<'_, '_, 'tcx>,
+    place: mir::Place<'tcx>,
+) -> Option<(TyAndLayout<'tcx>, Vec<VarPiece>)> {
+    let local_place = fx.get_local_place(place.local);
+    let mut layout = local_place.layout();
+    let mut pieces = local_place.debug_pieces(fx)?;
+    pieces.retain(|piece| piece.size != Size::ZERO);
+
+    for elem in place.projection {
+        match elem {
+            PlaceElem::Field(field, _ty) => {
+                let field_layout = layout.field(&*fx, field.index());
+                if field_layout.is_unsized() {
+                    return None;
+                }
+                pieces =
+                    slice_pieces(&pieces, layout.fields.offset(field.index()), field_layout.size);
+                layout = field_layout;
+            }
+            PlaceElem::Deref => {
+                // Only pointers which are kept in a single register can be dereferenced.
+                let &[VarPiece { loc: Some(VarLoc::Value(label)), .. }] = &pieces[..] else {
+                    return None;
+                };
+                let pointee_layout = fx.layout_of(layout.ty.builtin_deref(true)?);
+                if pointee_layout.is_unsized() {
+                    return None;
+                }
+                pieces = vec![VarPiece::new(pointee_layout.size, Some(VarLoc::Indirect(label, 0)))];
+                layout = pointee_layout;
+            }
+            _ => return None,
+        }
+    }
+
+    Some((layout, pieces))
+}
+
+struct UserVariable<'tcx> {
+    name: Symbol,
+    source_info: mir::SourceInfo,
+    argument_index: Option<u16>,
+    ty: Ty<'tcx>,
+    /// The pieces of all fragments of the variable sorted by offset.
+    fragments: Vec<(Size, Vec<VarPiece>)>,
+}
+
+impl<'tcx> UserVariable<'tcx> {
+    fn into_pieces(self, fx: &FunctionCx<'_, '_, 'tcx>) -> Vec<VarPiece> {
+        let size = fx.layout_of(self.ty).size;
+        let mut pieces = vec![];
+        let mut offset = Size::ZERO;
+        for (fragment_offset, fragment_pieces) in self.fragments {
+            if fragment_offset < offset {
+                // Overlapping fragments. Should never happen.
+                continue;
+            }
+            pieces.push(VarPiece::new(fragment_offset - offset, None));
+            offset = fragment_offset;
+            for piece in fragment_pieces {
+                offset += piece.size;
+                pieces.push(piece);
+            }
+        }
+        if offset < size {
+            pieces.push(VarPiece::new(size - offset, None));
+        }
+        pieces.retain(|piece| piece.size != Size::ZERO);
+        pieces
+    }
+}
+
+fn user_variables<'tcx>(fx: &mut FunctionCx<'_, '_, 'tcx>) -> Vec<UserVariable<'tcx>> {
+    let mut variables: Vec<UserVariable<'tcx>> = vec![];
+    let mut composite_variables = FxHashMap::default();
+
+    let mir = fx.mir;
+    for var in &mir.var_debug_info {
+        let (layout, pieces) = match &var.value {
+            &VarDebugInfoContents::Place(place) => match place_pieces(fx, place) {
+                Some((layout, pieces)) => (layout, pieces),
+                None => {
+                    let ty = fx.monomorphize(place.ty(&fx.mir.local_decls, fx.tcx).ty);
+                    let layout = fx.layout_of(ty);
+                    (layout, vec![VarPiece::new(layout.size, None)])
+                }
+            },
+            // FIXME describe constants using DW_OP_implicit_value
+            VarDebugInfoContents::Const(constant) => {
+                let layout = fx.layout_of(fx.monomorphize(constant.ty()));
+                (layout, vec![VarPiece::new(layout.size, None)])
+            }
+        };
+
+        let Some(composite) = &var.composite else {
+            variables.push(UserVariable {
+                name: var.name,
+                source_info: var.source_info,
+                argument_index: var.argument_index,
+                ty: layout.ty,
+                fragments: vec![(Size::ZERO, pieces)],
+            });
+            continue;
+        };
+
+        let ty = fx.monomorphize(composite.ty);
+        let mut fragment_layout = fx.layout_of(ty);
+        let mut offset = Size::ZERO;
+        for elem in &composite.projection {
+            let PlaceElem::Field(field, _) = *elem else {
+                bug!("unsupported var debuginfo projection `{:?}`", composite.projection);
+            };
+            offset += fragment_layout.fields.offset(field.index());
+            fragment_layout = fragment_layout.field(&*fx, field.index());
+        }
+        let pieces = slice_pieces(&pieces, Size::ZERO, fragment_layout.size);
+
+        // All fragments of a variable share a name and scope.
+        let index =
+            *composite_variables.entry((var.name, var.source_info.scope)).or_insert_with(|| {
+                variables.push(UserVariable {
+                    name: var.name,
+                    source_info: var.source_info,
+                    argument_index: var.argument_index,
+                    ty,
+                    fragments: vec![],
+                });
+                variables.len() - 1
+            });
+        let fragments = &mut variables[index].fragments;
+        let pos = fragments.partition_point(|&(fragment_offset, _)| fragment_offset < offset);
+        fragments.insert(pos, (offset, pieces));
+    }
+
+    variables
+}
+
+impl DebugContext {
+    /// Get or create the abstract `DW_TAG_subprogram` that `DW_TAG_inlined_subroutine` entries
+    /// for `instance` refer to.
+    fn abstract_function<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        instance: Instance<'tcx>,
+    ) -> UnitEntryId {
+        let linkage_name = tcx.symbol_name(instance).name;
+        if let Some(&entry_id) = self.abstract_functions.get(linkage_name) {
+            return entry_id;
+        }
+
+        let scope = self.item_namespace(tcx, tcx.parent(instance.def_id()));
+        let name = super::function_name(tcx, instance);
+        let span = tcx.def_span(instance.def_id());
+        let (file_id, line, _column) = self.get_span_loc(tcx, span, span);
+
+        let entry_id = self.dwarf.unit.add(scope, gimli::DW_TAG_subprogram);
+        let linkage_name_id =
+            if name != linkage_name { Some(self.dwarf.strings.add(linkage_name)) } else { None };
+        let name_id = self.dwarf.strings.add(name);
+        let entry = self.dwarf.unit.get_mut(entry_id);
+        if let Some(linkage_name_id) = linkage_name_id {
+            entry.set(gimli::DW_AT_linkage_name, AttributeValue::StringRef(linkage_name_id));
+        }
+        entry.set(gimli::DW_AT_name, AttributeValue::StringRef(name_id));
+        entry.set(gimli::DW_AT_decl_file, AttributeValue::FileIndex(Some(file_id)));
+        entry.set(gimli::DW_AT_decl_line, AttributeValue::Udata(line));
+        entry.set(gimli::DW_AT_inline, AttributeValue::Inline(gimli::DW_INL_inlined));
+
+        self.abstract_functions.insert(linkage_name.to_owned(), entry_id);
+        entry_id
+    }
+}
+
+impl FunctionDebugContext {
+    fn scope_entry(
+        &mut self,
+        debug_context: &mut DebugContext,
+        fx: &FunctionCx<'_, '_, '_>,
+        scope: SourceScope,
+    ) -> UnitEntryId {
+        if scope == OUTERMOST_SOURCE_SCOPE {
+            return self.entry_id.unwrap();
+        }
+        if let Some(&entry_id) = self.scope_entries.get(&scope) {
+            return entry_id;
+        }
+
+        let scope_data = &fx.mir.source_scopes[scope];
+        let parent = self.scope_entry(debug_context, fx, scope_data.parent_scope.unwrap());
+        let entry_id = if let Some((callee, callsite_span)) = scope_data.inlined {
+            let callee = fx.monomorphize(callee);
+            let origin = debug_context.abstract_function(fx.tcx, callee);
+            let (file_id, line, column) =
+                debug_context.get_span_loc(fx.tcx, fx.mir.span, callsite_span);
+
+            let entry_id = debug_context.dwarf.unit.add(parent, gimli::DW_TAG_inlined_subroutine);
+            let entry = debug_context.dwarf.unit.get_mut(entry_id);
+            entry.set(gimli::DW_AT_abstract_origin, AttributeValue::UnitRef(origin));
+            entry.set(gimli::DW_AT_call_file, AttributeValue::FileIndex(Some(file_id)));
+            entry.set(gimli::DW_AT_call_line, AttributeValue::Udata(line));
+            entry.set(gimli::DW_AT_call_column, AttributeValue::Udata(column));
+            entry_id
+        } else {
+            debug_context.dwarf.unit.add(parent, gimli::DW_TAG_lexical_block)
+        };
+
+        self.scope_entries.insert(scope, entry_id);
+        entry_id
+    }
+
+    /// Define the scopes and user variables of the function. Their code ranges and locations are
+    /// filled in by [`FunctionDebugContext::finalize`] once the function has been compiled.
+    pub(crate) fn define_scopes_and_variables<'tcx>(
+        fx: &mut FunctionCx<'_, '_, 'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+    ) {
+        let Some(debug_context) = fx.debug_context.take() else { return };
+        let mut func_debug_cx = fx.func_debug_cx.take().unwrap();
+
+        if func_debug_cx.entry_id.is_some() {
+            func_debug_cx.scope_parents =
+                fx.mir.source_scopes.iter().map(|scope_data| scope_data.parent_scope).collect();
+
+            // Inlined scopes are always defined to make inlined frames show up in backtraces.
+            for (scope, scope_data) in fx.mir.source_scopes.iter_enumerated() {
+                if scope_data.inlined.is_some() {
+                    func_debug_cx.scope_entry(debug_context, fx, scope);
+                }
+            }
+
+            for var in user_variables(fx) {
+                let scope = func_debug_cx.scope_entry(debug_context, fx, var.source_info.scope);
+                let (file_id, line, _column) =
+                    debug_context.get_span_loc(fx.tcx, fx.mir.span, var.source_info.span);
+                let type_id = debug_context.debug_type(fx.tcx, type_dbg, var.ty);
+
+                let tag = if var.argument_index.is_some() {
+                    gimli::DW_TAG_formal_parameter
+                } else {
+                    gimli::DW_TAG_variable
+                };
+                let entry_id = debug_context.dwarf.unit.add(scope, tag);
+                let name_id = debug_context.dwarf.strings.add(var.name.as_str());
+                let entry = debug_context.dwarf.unit.get_mut(entry_id);
+                entry.set(gimli::DW_AT_name, AttributeValue::StringRef(name_id));
+                entry.set(gimli::DW_AT_decl_file, AttributeValue::FileIndex(Some(file_id)));
+                entry.set(gimli::DW_AT_decl_line, AttributeValue::Udata(line));
+                entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(type_id));
+
+                let pieces = var.into_pieces(fx);
+                func_debug_cx.variables.push((entry_id, pieces));
+            }
+        }
+
+        fx.debug_context = Some(debug_context);
+        fx.func_debug_cx = Some(func_debug_cx);
+    }
+
+    pub(super) fn define_scope_ranges(
+        &self,
+        debug_context: &mut DebugContext,
+        func_id: FuncId,
+        context: &Context,
+    ) {
+        let mut scope_ranges = FxIndexMap::<UnitEntryId, Vec<(u32, u32)>>::default();
+        let mcr = context.compiled_code().unwrap();
+        for &MachSrcLoc { start, end, loc } in mcr.buffer.get_srclocs_sorted() {
+            if loc.is_default() {
+                continue;
+            }
+            let mut scope = Some(self.source_loc_set[loc.bits() as usize].3);
+            while let Some(current) = scope {
+                if let Some(&entry_id) = self.scope_entries.get(&current) {
+                    let ranges = scope_ranges.entry(entry_id).or_default();
+                    match ranges.last_mut() {
+                        Some(last) if last.1 == start => last.1 = end,
+                        _ => ranges.push((start, end)),
+                    }
+                }
+                scope = self.scope_parents[current];
+            }
+        }
+
+        for (entry_id, ranges) in scope_ranges {
+            let entry = debug_context.dwarf.unit.get_mut(entry_id);
+            if let [(start, end)] = ranges[..] {
+                entry.set(
+                    gimli::DW_AT_low_pc,
+                    AttributeValue::Address(func_address(func_id, start)),
+                );
+                entry.set(gimli::DW_AT_high_pc, AttributeValue::Udata(u64::from(end - start)));
+            } else {
+                let range_list = RangeList(
+                    ranges
+                        .into_iter()
+                        .map(|(start, end)| Range::StartLength {
+                            begin: func_address(func_id, start),
+                            length: u64::from(end - start),
+                        })
+                        .collect(),
+                );
+                let range_list_id = debug_context.dwarf.unit.ranges.add(range_list);
+                let entry = debug_context.dwarf.unit.get_mut(entry_id);
+                entry.set(gimli::DW_AT_ranges, AttributeValue::RangeListRef(range_list_id));
+            }
+        }
+    }
+
+    pub(super) fn define_variable_locations(
+        &self,
+        debug_context: &mut DebugContext,
+        func_id: FuncId,
+        isa: &dyn TargetIsa,
+        context: &Context,
+        func_end: u32,
+    ) {
+        let mcr = context.compiled_code().unwrap();
+
+        for (entry_id, pieces) in &self.variables {
+            // Split the function at every point where the location of any piece may change.
+            let mut points = vec![0, func_end];
+            for piece in pieces {
+                if let Some(VarLoc::Value(label) | VarLoc::Indirect(label, _)) = piece.loc {
+                    for range in mcr.value_labels_ranges.get(&label).into_iter().flatten() {
+                        points.push(range.start);
+                        points.push(range.end);
+                    }
+                }
+            }
+            points.sort_unstable();
+            points.dedup();
+
+            let mut locations: Vec<(u32, u32, Expression)> = vec![];
+            for window in points.windows(2) {
+                let &[start, end] = window else { unreachable!() };
+                let Some(expr) = piece_locations(isa, context, pieces, start) else {
+                    continue;
+                };
+                match locations.last_mut() {
+                    Some(last) if last.1 == start && last.2 == expr => last.1 = end,
+                    _ => locations.push((start, end, expr)),
+                }
+            }
+
+            let location = match &locations[..] {
+                [] => continue,
+                [(0, end, expr)] if *end == func_end => AttributeValue::Exprloc(expr.clone()),
+                _ => {
+                    let location_list = LocationList(
+                        locations
+                            .into_iter()
+                            .map(|(start, end, data)| Location::StartLength {
+                                begin: func_address(func_id, start),
+                                length: u64::from(end - start),
+                                data,
+                            })
+                            .collect(),
+                    );
+                    AttributeValue::LocationListRef(
+                        debug_context.dwarf.unit.locations.add(location_list),
+                    )
+                }
+            };
+            debug_context.dwarf.unit.get_mut(*entry_id).set(gimli::DW_AT_location, location);
+        }
+    }
+}
+
+fn func_address(func_id: FuncId, offset: u32) -> Address {
+    match address_for_func(func_id) {
+        Address::Symbol { symbol, addend } => {
+            Address::Symbol { symbol, addend: addend + i64::from(offset) }
+        }
+        Address::Constant(_) => unreachable!(),
+    }
+}
+
+/// Build the location expression for all pieces of a variable at code offset `offset`. Returns
+/// `None` if none of the pieces are available at this point.
+fn piece_locations(
+    isa: &dyn TargetIsa,
+    context: &Context,
+    pieces: &[VarPiece],
+    offset: u32,
+) -> Option<Expression> {
+    let mcr = context.compiled_code().unwrap();
+    let label_loc = |label: ValueLabel| {
+        mcr.value_labels_ranges
+            .get(&label)?
+            .iter()
+            .find(|range| range.start <= offset && offset < range.end)
+            .map(|range| range.loc)
+    };
+    let dwarf_reg = |reg| isa.map_regalloc_reg_to_dwarf(reg).ok().map(Register);
+
+    let mut expr = Expression::new();
+    let mut available = false;
+    for piece in pieces {
+        let piece_available = match piece.loc {
+            None => false,
+            Some(VarLoc::Value(label)) => match label_loc(label) {
+                Some(LabelValueLoc::Reg(reg)) => match dwarf_reg(reg) {
+                    Some(reg) => {
+                        expr.op_reg(reg);
+                        true
+                    }
+                    None => false,
+                },
+                Some(LabelValueLoc::CFAOffset(cfa_offset)) => {
+                    expr.op(gimli::DW_OP_call_frame_cfa);
+                    expr.op_consts(cfa_offset);
+                    expr.op(gimli::DW_OP_plus);
+                    true
+                }
+                None => false,
+            },
+            Some(VarLoc::Indirect(label, offset)) => match label_loc(label) {
+                Some(LabelValueLoc::Reg(reg)) => match dwarf_reg(reg) {
+                    Some(reg) => {
+                        expr.op_breg(reg, offset);
+                        true
+                    }
+                    None => false,
+                },
+                Some(LabelValueLoc::CFAOffset(cfa_offset)) => {
+                    expr.op(gimli::DW_OP_call_frame_cfa);
+                    expr.op_consts(cfa_offset);
+                    expr.op(gimli::DW_OP_plus);
+                    expr.op_deref();
+                    if offset != 0 {
+                        expr.op_consts(offset);
+                        expr.op(gimli::DW_OP_plus);
+                    }
+                    true
+                }
+                None => false,
+            },
+            Some(VarLoc::Stack(stack_slot, offset)) => match mcr.buffer.frame_layout() {
+                Some(frame_layout) => {
+                    // The frame base is the stack pointer, which is also what stack slot offsets
+                    // are relative to.
+                    expr.op_fbreg(i64::from(frame_layout.stackslots[stack_slot].offset) + offset);
+                    true
+                }
+                None => false,
+            },
+        };
+        available |= piece_available;
+        if pieces.len() > 1 {
+            expr.op_piece(piece.size.bytes());
+        }
+    }
+
+    if available { Some(expr) } else { None }
+}
@@ -29,6 +29,7 @@
 use crate::base::CodegenedFunction;
 use crate::concurrency_limiter::{ConcurrencyLimiter, ConcurrencyLimiterToken};
 use crate::debuginfo::TypeDebugContext;
+use crate::fn_cache::FnCache;
 use crate::global_asm::{GlobalAsmConfig, GlobalAsmContext};
 use crate::prelude::*;
 use crate::unwind_module::UnwindModule;
@@ -224,6 +225,10 @@ fn produce_final_output_artifacts(
                 user_wants_objects = true;
                 copy_if_one_unit(OutputType::Object, true);
             }
+            OutputType::SizeReport => {
+                sess.dcx()
+                    .warn("--emit=size-report is not supported by cg_clif, no report is written");
+            }
             OutputType::Mir | OutputType::Metadata | OutputType::Exe | OutputType::DepInfo => {}
         }
     }
@@ -593,12 +598,25 @@ fn module_codegen(
     let output_filenames = tcx.output_filenames(()).clone();
     let should_write_ir = crate::pretty_clif::should_write_ir(tcx.sess);
 
+    // Even if the cgu as a whole can't be reused, most of its functions are likely unchanged.
+    let fn_cache_path = if tcx.sess.opts.incremental.is_some() && !disable_incr_cache() {
+        Some(rustc_incremental::in_incr_comp_dir_sess(
+            tcx.sess,
+            &format!("{cgu_name}.clif-fn-cache"),
+        ))
+    } else {
+        None
+    };
+    let incremental_info = tcx.sess.opts.unstable_opts.incremental_info;
+
     OngoingModuleCodegen::Async(std::thread::spawn(move || {
         profiler.clone().generic_activity_with_arg("compile functions", &*cgu_name).run(|| {
             cranelift_codegen::timing::set_thread_profiler(Box::new(super::MeasuremeProfiler(
                 profiler.clone(),
             )));
 
+            module.fn_cache = fn_cache_path.map(FnCache::load);
+
             let mut cached_context = Context::new();
             for codegened_func in codegened_functions {
                 crate::base::compile_fn(
@@ -612,6 +630,18 @@ fn module_codegen(
                     codegened_func,
                 );
             }
+
+            if let Some(fn_cache) = module.fn_cache.take() {
+                if incremental_info {
+                    let (reused, compiled) = fn_cache.stats();
+                    eprintln!(
+                        "[incremental] cg_clif reused machine code for {reused} of {total} \
+                         functions in `{cgu_name}`",
+                        total = reused + compiled,
+                    );
+                }
+                fn_cache.save();
+            }
         });
 
         let global_asm_object_file =
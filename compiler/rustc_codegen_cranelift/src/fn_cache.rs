@@ -0,0 +1,109 @@
+//! Per-function cache of compiled machine code, reused across incremental compilation sessions.
+//!
+//! Cranelift computes a key for every function from its CLIF IR and the target isa. The CLIF IR is
+//! fully determined by the MIR of the instance and the ABI and layout of all types it uses, so a
+//! function whose key didn't change since the previous session can reuse its machine code even
+//! when other functions in the same codegen unit changed and the codegen unit as a whole has to be
+//! recompiled. The names of referenced functions and data objects are not part of the key. They
+//! are fixed up when the cached code is relinked into the new object file.
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+
+use cranelift_codegen::incremental_cache::CacheKvStore;
+use rustc_data_structures::fx::FxHashSet;
+
+use crate::prelude::*;
+
+const MAGIC: &[u8; 8] = b"clif-fn\0";
+const KEY_LEN: usize = 32;
+
+pub(crate) struct FnCache {
+    path: PathBuf,
+    /// Entries written by the previous session.
+    previous: FxIndexMap<Vec<u8>, Vec<u8>>,
+    /// Keys of entries in `previous` which were looked up by this session.
+    used: RefCell<FxHashSet<Vec<u8>>>,
+    /// Entries for the functions compiled by this session.
+    new: FxIndexMap<Vec<u8>, Vec<u8>>,
+    reused_functions: usize,
+    compiled_functions: usize,
+}
+
+impl FnCache {
+    /// Load the cache at `path`. A missing or corrupt cache file results in an empty cache.
+    pub(crate) fn load(path: PathBuf) -> Self {
+        let previous = fs::read(&path).ok().and_then(|data| parse(&data)).unwrap_or_default();
+        FnCache {
+            path,
+            previous,
+            used: RefCell::default(),
+            new: FxIndexMap::default(),
+            reused_functions: 0,
+            compiled_functions: 0,
+        }
+    }
+
+    pub(crate) fn record_function(&mut self, reused: bool) {
+        if reused {
+            self.reused_functions += 1;
+        } else {
+            self.compiled_functions += 1;
+        }
+    }
+
+    pub(crate) fn stats(&self) -> (usize, usize) {
+        (self.reused_functions, self.compiled_functions)
+    }
+
+    /// Write all entries used by this session back to disk. Entries for functions that no longer
+    /// exist are dropped to prevent the cache from growing without bound.
+    pub(crate) fn save(self) {
+        let used = self.used.into_inner();
+        let previous = self
+            .previous
+            .iter()
+            .filter(|(key, _)| used.contains(*key) && !self.new.contains_key(*key));
+
+        let mut data = MAGIC.to_vec();
+        for (key, value) in previous.chain(&self.new) {
+            data.extend_from_slice(key);
+            data.extend_from_slice(&(value.len() as u64).to_le_bytes());
+            data.extend_from_slice(value);
+        }
+
+        // The cache is only an optimization. Failing to write it shouldn't fail the compilation.
+        let _ = fs::write(&self.path, data);
+    }
+}
+
+impl CacheKvStore for FnCache {
+    fn get(&self, key: &[u8]) -> Option<Cow<'_, [u8]>> {
+        if let Some(value) = self.new.get(key) {
+            return Some(Cow::Borrowed(value));
+        }
+        let value = self.previous.get(key)?;
+        self.used.borrow_mut().insert(key.to_vec());
+        Some(Cow::Borrowed(value))
+    }
+
+    fn insert(&mut self, key: &[u8], val: Vec<u8>) {
+        self.new.insert(key.to_vec(), val);
+    }
+}
+
+fn parse(data: &[u8]) -> Option<FxIndexMap<Vec<u8>, Vec<u8>>> {
+    let mut data = data.strip_prefix(MAGIC)?;
+    let mut entries = FxIndexMap::default();
+    while !data.is_empty() {
+        let (key, rest) = data.split_at_checked(KEY_LEN)?;
+        let (len, rest) = rest.split_at_checked(8)?;
+        let len = usize::try_from(u64::from_le_bytes(len.try_into().unwrap())).ok()?;
+        let (value, rest) = rest.split_at_checked(len)?;
+        entries.insert(key.to_vec(), value.to_vec());
+        data = rest;
+    }
+    Some(entries)
+}
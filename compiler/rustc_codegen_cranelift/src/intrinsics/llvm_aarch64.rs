@@ -242,20 +242,53 @@ pub(super) fn codegen_aarch64_llvm_intrinsic_call<'tcx>(
             );
         }
 
-        "llvm.aarch64.neon.uaddlv.i32.v16i8" => {
+        _ if intrinsic.starts_with("llvm.aarch64.neon.uaddlv.")
+            || intrinsic.starts_with("llvm.aarch64.neon.saddlv.") =>
+        {
             intrinsic_args!(fx, args => (v); intrinsic);
 
-            let mut res_val = fx.bcx.ins().iconst(types::I16, 0);
-            for lane_idx in 0..16 {
+            let signed = intrinsic.starts_with("llvm.aarch64.neon.saddlv.");
+            let (lane_count, _lane_ty) = v.layout().ty.simd_size_and_type(fx.tcx);
+            let res_ty = fx.clif_type(ret.layout().ty).unwrap();
+            let mut res_val = fx.bcx.ins().iconst(res_ty, 0);
+            for lane_idx in 0..lane_count {
                 let lane = v.value_lane(fx, lane_idx).load_scalar(fx);
-                let lane = fx.bcx.ins().uextend(types::I16, lane);
+                let lane = if signed {
+                    fx.bcx.ins().sextend(res_ty, lane)
+                } else {
+                    fx.bcx.ins().uextend(res_ty, lane)
+                };
                 res_val = fx.bcx.ins().iadd(res_val, lane);
             }
-            let res = CValue::by_val(
-                fx.bcx.ins().uextend(types::I32, res_val),
-                fx.layout_of(fx.tcx.types.i32),
-            );
-            ret.write_cvalue(fx, res);
+            ret.write_cvalue(fx, CValue::by_val(res_val, ret.layout()));
+        }
+
+        _ if intrinsic.starts_with("llvm.aarch64.neon.uaddlp.v")
+            || intrinsic.starts_with("llvm.aarch64.neon.saddlp.v") =>
+        {
+            intrinsic_args!(fx, args => (a); intrinsic);
+
+            let signed = intrinsic.starts_with("llvm.aarch64.neon.saddlp.");
+            let (lane_count, _lane_ty) = a.layout().ty.simd_size_and_type(fx.tcx);
+            let (ret_lane_count, ret_lane_ty) = ret.layout().ty.simd_size_and_type(fx.tcx);
+            assert_eq!(lane_count, ret_lane_count * 2);
+
+            let ret_lane_layout = fx.layout_of(ret_lane_ty);
+            let res_ty = fx.clif_type(ret_lane_ty).unwrap();
+            for out_lane_idx in 0..ret_lane_count {
+                let mut res_lane = fx.bcx.ins().iconst(res_ty, 0);
+                for lane_idx in [out_lane_idx * 2, out_lane_idx * 2 + 1] {
+                    let lane = a.value_lane(fx, lane_idx).load_scalar(fx);
+                    let lane = if signed {
+                        fx.bcx.ins().sextend(res_ty, lane)
+                    } else {
+                        fx.bcx.ins().uextend(res_ty, lane)
+                    };
+                    res_lane = fx.bcx.ins().iadd(res_lane, lane);
+                }
+                let res_lane = CValue::by_val(res_lane, ret_lane_layout);
+                ret.place_lane(fx, out_lane_idx).write_cvalue(fx, res_lane);
+            }
         }
 
         _ if intrinsic.starts_with("llvm.aarch64.neon.faddv.f32.v") => {
@@ -414,39 +447,319 @@ pub(super) fn codegen_aarch64_llvm_intrinsic_call<'tcx>(
             );
         }
 
-        // FIXME generalize vector types
-        "llvm.aarch64.neon.tbl1.v8i8" => {
-            intrinsic_args!(fx, args => (t, idx); intrinsic);
+        _ if intrinsic.starts_with("llvm.aarch64.neon.umull.v")
+            || intrinsic.starts_with("llvm.aarch64.neon.smull.v") =>
+        {
+            intrinsic_args!(fx, args => (x, y); intrinsic);
 
-            let zero = fx.bcx.ins().iconst(types::I8, 0);
-            for i in 0..8 {
-                let idx_lane = idx.value_lane(fx, i).load_scalar(fx);
-                let is_zero =
-                    fx.bcx.ins().icmp_imm(IntCC::UnsignedGreaterThanOrEqual, idx_lane, 16);
-                let t_idx = fx.bcx.ins().uextend(fx.pointer_type, idx_lane);
-                let t_lane = t.value_lane_dyn(fx, t_idx).load_scalar(fx);
-                let res = fx.bcx.ins().select(is_zero, zero, t_lane);
-                ret.place_lane(fx, i).to_ptr().store(fx, res, MemFlags::trusted());
-            }
+            let signed = intrinsic.starts_with("llvm.aarch64.neon.smull.");
+            simd_pair_for_each_lane(fx, x, y, ret, &|fx, _lane_ty, res_lane_ty, x_lane, y_lane| {
+                let res_ty = fx.clif_type(res_lane_ty).unwrap();
+                let (x_lane, y_lane) = if signed {
+                    (fx.bcx.ins().sextend(res_ty, x_lane), fx.bcx.ins().sextend(res_ty, y_lane))
+                } else {
+                    (fx.bcx.ins().uextend(res_ty, x_lane), fx.bcx.ins().uextend(res_ty, y_lane))
+                };
+                fx.bcx.ins().imul(x_lane, y_lane)
+            });
+        }
+
+        _ if intrinsic.starts_with("llvm.aarch64.neon.sqdmulh.v")
+            || intrinsic.starts_with("llvm.aarch64.neon.sqrdmulh.v") =>
+        {
+            intrinsic_args!(fx, args => (x, y); intrinsic);
+
+            let rounding = intrinsic.starts_with("llvm.aarch64.neon.sqrdmulh.");
+            simd_pair_for_each_lane(
+                fx,
+                x,
+                y,
+                ret,
+                &|fx, _lane_ty, _res_lane_ty, x_lane, y_lane| {
+                    // (2 * x * y + rounding) >> bits, computed as (x * y + rounding / 2) >> (bits - 1)
+                    // so that it fits in the double width type. It only saturates for MIN * MIN.
+                    let lane_ty = fx.bcx.func.dfg.value_type(x_lane);
+                    let bits = lane_ty.bits();
+                    let wide_ty = lane_ty.double_width().unwrap();
+                    let x_lane = fx.bcx.ins().sextend(wide_ty, x_lane);
+                    let y_lane = fx.bcx.ins().sextend(wide_ty, y_lane);
+                    let mul = fx.bcx.ins().imul(x_lane, y_lane);
+                    let mul =
+                        if rounding { fx.bcx.ins().iadd_imm(mul, 1i64 << (bits - 2)) } else { mul };
+                    let res = fx.bcx.ins().sshr_imm(mul, i64::from(bits - 1));
+                    let max = fx.bcx.ins().iconst(wide_ty, (1i64 << (bits - 1)) - 1);
+                    let res = fx.bcx.ins().smin(res, max);
+                    fx.bcx.ins().ireduce(lane_ty, res)
+                },
+            );
+        }
+
+        _ if intrinsic.starts_with("llvm.aarch64.neon.sabd.v")
+            || intrinsic.starts_with("llvm.aarch64.neon.uabd.v") =>
+        {
+            intrinsic_args!(fx, args => (x, y); intrinsic);
+
+            let signed = intrinsic.starts_with("llvm.aarch64.neon.sabd.");
+            simd_pair_for_each_lane(
+                fx,
+                x,
+                y,
+                ret,
+                &|fx, _lane_ty, _res_lane_ty, x_lane, y_lane| {
+                    let (max, min) = if signed {
+                        (fx.bcx.ins().smax(x_lane, y_lane), fx.bcx.ins().smin(x_lane, y_lane))
+                    } else {
+                        (fx.bcx.ins().umax(x_lane, y_lane), fx.bcx.ins().umin(x_lane, y_lane))
+                    };
+                    fx.bcx.ins().isub(max, min)
+                },
+            );
+        }
+
+        _ if intrinsic.starts_with("llvm.aarch64.neon.fabd.v") => {
+            intrinsic_args!(fx, args => (x, y); intrinsic);
+
+            simd_pair_for_each_lane(
+                fx,
+                x,
+                y,
+                ret,
+                &|fx, _lane_ty, _res_lane_ty, x_lane, y_lane| {
+                    let diff = fx.bcx.ins().fsub(x_lane, y_lane);
+                    fx.bcx.ins().fabs(diff)
+                },
+            );
         }
-        "llvm.aarch64.neon.tbl1.v16i8" => {
-            intrinsic_args!(fx, args => (t, idx); intrinsic);
 
-            let zero = fx.bcx.ins().iconst(types::I8, 0);
-            for i in 0..16 {
+        _ if intrinsic.starts_with("llvm.aarch64.neon.uhadd.v")
+            || intrinsic.starts_with("llvm.aarch64.neon.shadd.v")
+            || intrinsic.starts_with("llvm.aarch64.neon.urhadd.v")
+            || intrinsic.starts_with("llvm.aarch64.neon.srhadd.v") =>
+        {
+            intrinsic_args!(fx, args => (x, y); intrinsic);
+
+            let signed = intrinsic.starts_with("llvm.aarch64.neon.s");
+            let rounding = intrinsic.contains(".urhadd.") || intrinsic.contains(".srhadd.");
+            simd_pair_for_each_lane(
+                fx,
+                x,
+                y,
+                ret,
+                &|fx, _lane_ty, _res_lane_ty, x_lane, y_lane| {
+                    // (x + y + rounding) >> 1
+                    let lane_ty = fx.bcx.func.dfg.value_type(x_lane);
+                    let wide_ty = lane_ty.double_width().unwrap();
+                    let (x_lane, y_lane) = if signed {
+                        (
+                            fx.bcx.ins().sextend(wide_ty, x_lane),
+                            fx.bcx.ins().sextend(wide_ty, y_lane),
+                        )
+                    } else {
+                        (
+                            fx.bcx.ins().uextend(wide_ty, x_lane),
+                            fx.bcx.ins().uextend(wide_ty, y_lane),
+                        )
+                    };
+                    let sum = fx.bcx.ins().iadd(x_lane, y_lane);
+                    let sum = if rounding { fx.bcx.ins().iadd_imm(sum, 1) } else { sum };
+                    let res = fx.bcx.ins().sshr_imm(sum, 1);
+                    fx.bcx.ins().ireduce(lane_ty, res)
+                },
+            );
+        }
+
+        _ if intrinsic.starts_with("llvm.aarch64.neon.sqxtn.v")
+            || intrinsic.starts_with("llvm.aarch64.neon.uqxtn.v")
+            || intrinsic.starts_with("llvm.aarch64.neon.sqxtun.v") =>
+        {
+            intrinsic_args!(fx, args => (a); intrinsic);
+
+            simd_for_each_lane(fx, a, ret, &|fx, _lane_ty, res_lane_ty, lane| {
+                let lane_ty = fx.bcx.func.dfg.value_type(lane);
+                let res_ty = fx.clif_type(res_lane_ty).unwrap();
+                let sat = if intrinsic.starts_with("llvm.aarch64.neon.sqxtn.") {
+                    let (min, max) = type_min_max_value(&mut fx.bcx, res_ty, true);
+                    let min = fx.bcx.ins().sextend(lane_ty, min);
+                    let max = fx.bcx.ins().sextend(lane_ty, max);
+                    let sat = fx.bcx.ins().smax(lane, min);
+                    fx.bcx.ins().smin(sat, max)
+                } else {
+                    let (_min, max) = type_min_max_value(&mut fx.bcx, res_ty, false);
+                    let max = fx.bcx.ins().uextend(lane_ty, max);
+                    if intrinsic.starts_with("llvm.aarch64.neon.sqxtun.") {
+                        let zero = fx.bcx.ins().iconst(lane_ty, 0);
+                        let sat = fx.bcx.ins().smax(lane, zero);
+                        fx.bcx.ins().smin(sat, max)
+                    } else {
+                        fx.bcx.ins().umin(lane, max)
+                    }
+                };
+                fx.bcx.ins().ireduce(res_ty, sat)
+            });
+        }
+
+        _ if intrinsic.starts_with("llvm.aarch64.neon.rshrn.v") => {
+            intrinsic_args!(fx, args => (a, _n); intrinsic);
+
+            let n = if let Some(n) = crate::constant::mir_operand_get_const_val(fx, &args[1].node) {
+                n.to_u32()
+            } else {
+                fx.tcx
+                    .dcx()
+                    .span_fatal(fx.mir.span, "Shift argument for `vrshrn_n` is not a constant");
+            };
+
+            // The bits that are kept do not depend on whether the addition overflows.
+            simd_for_each_lane(fx, a, ret, &|fx, _lane_ty, res_lane_ty, lane| {
+                let res_ty = fx.clif_type(res_lane_ty).unwrap();
+                let rounded = fx.bcx.ins().iadd_imm(lane, 1i64 << (n - 1));
+                let shifted = fx.bcx.ins().ushr_imm(rounded, i64::from(n));
+                fx.bcx.ins().ireduce(res_ty, shifted)
+            });
+        }
+
+        _ if intrinsic.starts_with("llvm.aarch64.neon.sshl.v")
+            || intrinsic.starts_with("llvm.aarch64.neon.ushl.v") =>
+        {
+            intrinsic_args!(fx, args => (x, y); intrinsic);
+
+            let signed = intrinsic.starts_with("llvm.aarch64.neon.sshl.");
+            simd_pair_for_each_lane(
+                fx,
+                x,
+                y,
+                ret,
+                &|fx, _lane_ty, _res_lane_ty, x_lane, y_lane| {
+                    // The shift amount is the signed lowest byte of `y`. Negative amounts shift
+                    // right. Cranelift masks shift amounts, so larger shifts are handled separately.
+                    let lane_ty = fx.bcx.func.dfg.value_type(x_lane);
+                    let bits = i64::from(lane_ty.bits());
+                    let amount = if lane_ty == types::I8 {
+                        y_lane
+                    } else {
+                        fx.bcx.ins().ireduce(types::I8, y_lane)
+                    };
+                    let amount = fx.bcx.ins().sextend(types::I32, amount);
+                    let zero = fx.bcx.ins().iconst(lane_ty, 0);
+
+                    let left = fx.bcx.ins().ishl(x_lane, amount);
+                    let left_overflow =
+                        fx.bcx.ins().icmp_imm(IntCC::SignedGreaterThanOrEqual, amount, bits);
+                    let left = fx.bcx.ins().select(left_overflow, zero, left);
+
+                    let right_amount = fx.bcx.ins().ineg(amount);
+                    let right = if signed {
+                        let max_amount = fx.bcx.ins().iconst(types::I32, bits - 1);
+                        let right_amount = fx.bcx.ins().smin(right_amount, max_amount);
+                        fx.bcx.ins().sshr(x_lane, right_amount)
+                    } else {
+                        let right = fx.bcx.ins().ushr(x_lane, right_amount);
+                        let right_overflow = fx.bcx.ins().icmp_imm(
+                            IntCC::SignedGreaterThanOrEqual,
+                            right_amount,
+                            bits,
+                        );
+                        fx.bcx.ins().select(right_overflow, zero, right)
+                    };
+
+                    let is_right = fx.bcx.ins().icmp_imm(IntCC::SignedLessThan, amount, 0);
+                    fx.bcx.ins().select(is_right, right, left)
+                },
+            );
+        }
+
+        _ if intrinsic.starts_with("llvm.aarch64.neon.frintn.v") => {
+            intrinsic_args!(fx, args => (a); intrinsic);
+
+            simd_for_each_lane(fx, a, ret, &|fx, _lane_ty, _res_lane_ty, lane| {
+                fx.bcx.ins().nearest(lane)
+            });
+        }
+
+        _ if intrinsic.starts_with("llvm.aarch64.neon.tbl")
+            || intrinsic.starts_with("llvm.aarch64.neon.tbx") =>
+        {
+            // `tbl{n}` takes n tables of 16 bytes followed by the indices. `tbx{n}` additionally
+            // takes the values to keep for out of range indices first, instead of using zero.
+            let mut args =
+                args.iter().map(|arg| codegen_operand(fx, &arg.node)).collect::<Vec<_>>();
+            let idx = args.pop().unwrap();
+            let fallback = if intrinsic.starts_with("llvm.aarch64.neon.tbx") {
+                Some(args.remove(0))
+            } else {
+                None
+            };
+            let tables = args;
+
+            let (lane_count, _lane_ty) = idx.layout().ty.simd_size_and_type(fx.tcx);
+            for i in 0..lane_count {
                 let idx_lane = idx.value_lane(fx, i).load_scalar(fx);
-                let is_zero =
-                    fx.bcx.ins().icmp_imm(IntCC::UnsignedGreaterThanOrEqual, idx_lane, 16);
-                let t_idx = fx.bcx.ins().uextend(fx.pointer_type, idx_lane);
-                let t_lane = t.value_lane_dyn(fx, t_idx).load_scalar(fx);
-                let res = fx.bcx.ins().select(is_zero, zero, t_lane);
+                let mut res = match fallback {
+                    Some(fallback) => fallback.value_lane(fx, i).load_scalar(fx),
+                    None => fx.bcx.ins().iconst(types::I8, 0),
+                };
+                for (table_idx, table) in tables.iter().enumerate() {
+                    let t_idx = fx.bcx.ins().iadd_imm(idx_lane, -16 * table_idx as i64);
+                    let in_table = fx.bcx.ins().icmp_imm(IntCC::UnsignedLessThan, t_idx, 16);
+                    let t_idx = fx.bcx.ins().band_imm(t_idx, 0xf);
+                    let t_idx = fx.bcx.ins().uextend(fx.pointer_type, t_idx);
+                    let t_lane = table.value_lane_dyn(fx, t_idx).load_scalar(fx);
+                    res = fx.bcx.ins().select(in_table, t_lane, res);
+                }
                 ret.place_lane(fx, i).to_ptr().store(fx, res, MemFlags::trusted());
             }
         }
 
+        "llvm.aarch64.crc32b"
+        | "llvm.aarch64.crc32h"
+        | "llvm.aarch64.crc32w"
+        | "llvm.aarch64.crc32x"
+        | "llvm.aarch64.crc32cb"
+        | "llvm.aarch64.crc32ch"
+        | "llvm.aarch64.crc32cw"
+        | "llvm.aarch64.crc32cx" => {
+            intrinsic_args!(fx, args => (crc, data); intrinsic);
+
+            let crc = crc.load_scalar(fx);
+            let data = data.load_scalar(fx);
+
+            let asm = match intrinsic {
+                "llvm.aarch64.crc32b" => "crc32b w0, w0, w1",
+                "llvm.aarch64.crc32h" => "crc32h w0, w0, w1",
+                "llvm.aarch64.crc32w" => "crc32w w0, w0, w1",
+                "llvm.aarch64.crc32x" => "crc32x w0, w0, x1",
+                "llvm.aarch64.crc32cb" => "crc32cb w0, w0, w1",
+                "llvm.aarch64.crc32ch" => "crc32ch w0, w0, w1",
+                "llvm.aarch64.crc32cw" => "crc32cw w0, w0, w1",
+                "llvm.aarch64.crc32cx" => "crc32cx w0, w0, x1",
+                _ => unreachable!(),
+            };
+
+            codegen_inline_asm_inner(
+                fx,
+                &[InlineAsmTemplatePiece::String(asm.into())],
+                &[
+                    CInlineAsmOperand::InOut {
+                        reg: InlineAsmRegOrRegClass::Reg(InlineAsmReg::AArch64(
+                            AArch64InlineAsmReg::x0,
+                        )),
+                        _late: true,
+                        in_value: crc,
+                        out_place: Some(ret),
+                    },
+                    CInlineAsmOperand::In {
+                        reg: InlineAsmRegOrRegClass::Reg(InlineAsmReg::AArch64(
+                            AArch64InlineAsmReg::x1,
+                        )),
+                        value: data,
+                    },
+                ],
+                InlineAsmOptions::NOSTACK | InlineAsmOptions::PURE | InlineAsmOptions::NOMEM,
+            );
+        }
+
         /*
-        _ if intrinsic.starts_with("llvm.aarch64.neon.sshl.v")
-            || intrinsic.starts_with("llvm.aarch64.neon.sqshl.v")
+        _ if intrinsic.starts_with("llvm.aarch64.neon.sqshl.v")
             // FIXME split this one out once saturating is implemented
             || intrinsic.starts_with("llvm.aarch64.neon.sqshlu.v") =>
         {
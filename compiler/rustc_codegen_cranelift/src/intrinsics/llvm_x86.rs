@@ -341,8 +341,9 @@ pub(super) fn codegen_x86_llvm_intrinsic_call<'tcx>(
                 }
             }
         }
-        "llvm.x86.avx2.permd" => {
+        "llvm.x86.avx2.permd" | "llvm.x86.avx2.permps" => {
             // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_permutevar8x32_epi32
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_permutevar8x32_ps
             intrinsic_args!(fx, args => (a, idx); intrinsic);
 
             for j in 0..=7 {
@@ -499,18 +500,100 @@ fn select4(
                 },
             );
         }
-        "llvm.x86.sse2.psra.w" => {
+        "llvm.x86.sse2.psll.w"
+        | "llvm.x86.sse2.psll.d"
+        | "llvm.x86.sse2.psll.q"
+        | "llvm.x86.avx2.psll.w"
+        | "llvm.x86.avx2.psll.d"
+        | "llvm.x86.avx2.psll.q"
+        | "llvm.x86.sse2.psrl.w"
+        | "llvm.x86.sse2.psrl.d"
+        | "llvm.x86.sse2.psrl.q"
+        | "llvm.x86.avx2.psrl.w"
+        | "llvm.x86.avx2.psrl.d"
+        | "llvm.x86.avx2.psrl.q"
+        | "llvm.x86.sse2.psra.w"
+        | "llvm.x86.sse2.psra.d"
+        | "llvm.x86.avx2.psra.w"
+        | "llvm.x86.avx2.psra.d" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_sll_epi16
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_srl_epi16
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_sra_epi16
             intrinsic_args!(fx, args => (a, count); intrinsic);
 
+            // All lanes are shifted by the lower 64 bits of `count`.
             let count_lane = count.force_stack(fx).0.load(fx, types::I64, MemFlags::trusted());
             let lane_ty = fx.clif_type(a.layout().ty.simd_size_and_type(fx.tcx).1).unwrap();
-            let max_count = fx.bcx.ins().iconst(types::I64, i64::from(lane_ty.bits() - 1));
-            let saturated_count = fx.bcx.ins().umin(count_lane, max_count);
+            let bits = i64::from(lane_ty.bits());
 
             // FIXME use vector instructions when possible
-            simd_for_each_lane(fx, a, ret, &|fx, _lane_ty, _res_lane_ty, a_lane| {
-                fx.bcx.ins().sshr(a_lane, saturated_count)
-            });
+            if intrinsic.contains(".psra.") {
+                // Arithmetic shifts by more than the lane width fill the lane with the sign bit.
+                let max_count = fx.bcx.ins().iconst(types::I64, bits - 1);
+                let saturated_count = fx.bcx.ins().umin(count_lane, max_count);
+                simd_for_each_lane(fx, a, ret, &|fx, _lane_ty, _res_lane_ty, a_lane| {
+                    fx.bcx.ins().sshr(a_lane, saturated_count)
+                });
+            } else {
+                // Logical shifts by more than the lane width produce zero, but Cranelift masks
+                // the shift amount.
+                let is_overflow =
+                    fx.bcx.ins().icmp_imm(IntCC::UnsignedGreaterThanOrEqual, count_lane, bits);
+                let shift_left = intrinsic.contains(".psll.");
+                simd_for_each_lane(fx, a, ret, &|fx, _lane_ty, _res_lane_ty, a_lane| {
+                    let shifted = if shift_left {
+                        fx.bcx.ins().ishl(a_lane, count_lane)
+                    } else {
+                        fx.bcx.ins().ushr(a_lane, count_lane)
+                    };
+                    let zero = fx.bcx.ins().iconst(lane_ty, 0);
+                    fx.bcx.ins().select(is_overflow, zero, shifted)
+                });
+            }
+        }
+        "llvm.x86.avx2.psllv.d"
+        | "llvm.x86.avx2.psllv.d.256"
+        | "llvm.x86.avx2.psllv.q"
+        | "llvm.x86.avx2.psllv.q.256"
+        | "llvm.x86.avx2.psrlv.d"
+        | "llvm.x86.avx2.psrlv.d.256"
+        | "llvm.x86.avx2.psrlv.q"
+        | "llvm.x86.avx2.psrlv.q.256"
+        | "llvm.x86.avx2.psrav.d"
+        | "llvm.x86.avx2.psrav.d.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_sllv_epi32
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_srlv_epi32
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_srav_epi32
+            intrinsic_args!(fx, args => (a, count); intrinsic);
+
+            simd_pair_for_each_lane(
+                fx,
+                a,
+                count,
+                ret,
+                &|fx, _lane_ty, _res_lane_ty, a_lane, count_lane| {
+                    let lane_ty = fx.bcx.func.dfg.value_type(a_lane);
+                    let bits = i64::from(lane_ty.bits());
+                    if intrinsic.starts_with("llvm.x86.avx2.psrav.") {
+                        let max_count = fx.bcx.ins().iconst(lane_ty, bits - 1);
+                        let saturated_count = fx.bcx.ins().umin(count_lane, max_count);
+                        fx.bcx.ins().sshr(a_lane, saturated_count)
+                    } else {
+                        let is_overflow = fx.bcx.ins().icmp_imm(
+                            IntCC::UnsignedGreaterThanOrEqual,
+                            count_lane,
+                            bits,
+                        );
+                        let shifted = if intrinsic.starts_with("llvm.x86.avx2.psllv.") {
+                            fx.bcx.ins().ishl(a_lane, count_lane)
+                        } else {
+                            fx.bcx.ins().ushr(a_lane, count_lane)
+                        };
+                        let zero = fx.bcx.ins().iconst(lane_ty, 0);
+                        fx.bcx.ins().select(is_overflow, zero, shifted)
+                    }
+                },
+            );
         }
         "llvm.x86.sse2.psad.bw" | "llvm.x86.avx2.psad.bw" => {
             // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_sad_epu8&ig_expand=5770
@@ -624,8 +707,9 @@ fn select4(
             }
         }
 
-        "llvm.x86.ssse3.pmul.hr.sw.128" => {
+        "llvm.x86.ssse3.pmul.hr.sw.128" | "llvm.x86.avx2.pmul.hr.sw" => {
             // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_mulhrs_epi16&ig_expand=4782
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_mulhrs_epi16
             intrinsic_args!(fx, args => (a, b); intrinsic);
 
             assert_eq!(a.layout(), b.layout());
@@ -1283,6 +1367,274 @@ fn select4(
             ret.write_cvalue(fx, res);
         }
 
+        "llvm.x86.sse41.ptestz"
+        | "llvm.x86.sse41.ptestc"
+        | "llvm.x86.sse41.ptestnzc"
+        | "llvm.x86.avx.ptestc.256"
+        | "llvm.x86.avx.ptestnzc.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_testz_si128
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_testc_si128
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_testnzc_si128
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            assert_eq!(a.layout(), b.layout());
+            let (lane_count, lane_ty) = a.layout().ty.simd_size_and_type(fx.tcx);
+            assert_eq!(lane_ty, fx.tcx.types.i64);
+            assert_eq!(ret.layout().ty, fx.tcx.types.i32);
+
+            // ZF is set if `a & b` is zero, CF is set if `!a & b` is zero.
+            let mut and = fx.bcx.ins().iconst(types::I64, 0);
+            let mut and_not = fx.bcx.ins().iconst(types::I64, 0);
+            for lane_idx in 0..lane_count {
+                let a_lane = a.value_lane(fx, lane_idx).load_scalar(fx);
+                let b_lane = b.value_lane(fx, lane_idx).load_scalar(fx);
+                let and_lane = fx.bcx.ins().band(a_lane, b_lane);
+                and = fx.bcx.ins().bor(and, and_lane);
+                let and_not_lane = fx.bcx.ins().band_not(b_lane, a_lane);
+                and_not = fx.bcx.ins().bor(and_not, and_not_lane);
+            }
+            let zf = fx.bcx.ins().icmp_imm(IntCC::Equal, and, 0);
+            let cf = fx.bcx.ins().icmp_imm(IntCC::Equal, and_not, 0);
+
+            let res = match intrinsic {
+                "llvm.x86.sse41.ptestz" => zf,
+                "llvm.x86.sse41.ptestc" | "llvm.x86.avx.ptestc.256" => cf,
+                "llvm.x86.sse41.ptestnzc" | "llvm.x86.avx.ptestnzc.256" => {
+                    let zf_or_cf = fx.bcx.ins().bor(zf, cf);
+                    fx.bcx.ins().bxor_imm(zf_or_cf, 1)
+                }
+                _ => unreachable!(),
+            };
+            let res = CValue::by_val(
+                fx.bcx.ins().uextend(types::I32, res),
+                fx.layout_of(fx.tcx.types.i32),
+            );
+            ret.write_cvalue(fx, res);
+        }
+
+        "llvm.x86.ssse3.phadd.w.128"
+        | "llvm.x86.ssse3.phadd.d.128"
+        | "llvm.x86.ssse3.phadd.sw.128"
+        | "llvm.x86.ssse3.phsub.w.128"
+        | "llvm.x86.ssse3.phsub.d.128"
+        | "llvm.x86.ssse3.phsub.sw.128"
+        | "llvm.x86.avx2.phadd.w"
+        | "llvm.x86.avx2.phadd.d"
+        | "llvm.x86.avx2.phadd.sw"
+        | "llvm.x86.avx2.phsub.w"
+        | "llvm.x86.avx2.phsub.d"
+        | "llvm.x86.avx2.phsub.sw" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_hadd_epi16
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_hadds_epi16
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_hsub_epi16
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            let is_sub = intrinsic.contains(".phsub.");
+            let saturating = intrinsic.contains(".sw");
+            horizontal_instruction(fx, a, b, ret, &|fx, lhs, rhs| {
+                if saturating {
+                    let lhs = fx.bcx.ins().sextend(types::I32, lhs);
+                    let rhs = fx.bcx.ins().sextend(types::I32, rhs);
+                    let res = if is_sub {
+                        fx.bcx.ins().isub(lhs, rhs)
+                    } else {
+                        fx.bcx.ins().iadd(lhs, rhs)
+                    };
+                    let (min, max) = type_min_max_value(&mut fx.bcx, types::I16, true);
+                    let min = fx.bcx.ins().sextend(types::I32, min);
+                    let max = fx.bcx.ins().sextend(types::I32, max);
+                    let res = fx.bcx.ins().smax(res, min);
+                    let res = fx.bcx.ins().smin(res, max);
+                    fx.bcx.ins().ireduce(types::I16, res)
+                } else if is_sub {
+                    fx.bcx.ins().isub(lhs, rhs)
+                } else {
+                    fx.bcx.ins().iadd(lhs, rhs)
+                }
+            });
+        }
+
+        "llvm.x86.sse3.hadd.ps"
+        | "llvm.x86.sse3.hadd.pd"
+        | "llvm.x86.sse3.hsub.ps"
+        | "llvm.x86.sse3.hsub.pd"
+        | "llvm.x86.avx.hadd.ps.256"
+        | "llvm.x86.avx.hadd.pd.256"
+        | "llvm.x86.avx.hsub.ps.256"
+        | "llvm.x86.avx.hsub.pd.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_hadd_ps
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_hsub_ps
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            let is_sub = intrinsic.contains(".hsub.");
+            horizontal_instruction(fx, a, b, ret, &|fx, lhs, rhs| {
+                if is_sub { fx.bcx.ins().fsub(lhs, rhs) } else { fx.bcx.ins().fadd(lhs, rhs) }
+            });
+        }
+
+        "llvm.x86.ssse3.psign.b.128"
+        | "llvm.x86.ssse3.psign.w.128"
+        | "llvm.x86.ssse3.psign.d.128"
+        | "llvm.x86.avx2.psign.b"
+        | "llvm.x86.avx2.psign.w"
+        | "llvm.x86.avx2.psign.d" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_sign_epi8
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            simd_pair_for_each_lane(
+                fx,
+                a,
+                b,
+                ret,
+                &|fx, _lane_ty, _res_lane_ty, a_lane, b_lane| {
+                    let lane_ty = fx.bcx.func.dfg.value_type(a_lane);
+                    let neg = fx.bcx.ins().ineg(a_lane);
+                    let zero = fx.bcx.ins().iconst(lane_ty, 0);
+                    let is_neg = fx.bcx.ins().icmp_imm(IntCC::SignedLessThan, b_lane, 0);
+                    let is_zero = fx.bcx.ins().icmp_imm(IntCC::Equal, b_lane, 0);
+                    let res = fx.bcx.ins().select(is_neg, neg, a_lane);
+                    fx.bcx.ins().select(is_zero, zero, res)
+                },
+            );
+        }
+
+        "llvm.x86.sse2.pmulh.w"
+        | "llvm.x86.sse2.pmulhu.w"
+        | "llvm.x86.avx2.pmulh.w"
+        | "llvm.x86.avx2.pmulhu.w" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_mulhi_epi16
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_mulhi_epu16
+            intrinsic_args!(fx, args => (a, b); intrinsic);
+
+            let signed = !intrinsic.contains(".pmulhu.");
+            simd_pair_for_each_lane(
+                fx,
+                a,
+                b,
+                ret,
+                &|fx, _lane_ty, _res_lane_ty, a_lane, b_lane| {
+                    let (a_lane, b_lane) = if signed {
+                        (
+                            fx.bcx.ins().sextend(types::I32, a_lane),
+                            fx.bcx.ins().sextend(types::I32, b_lane),
+                        )
+                    } else {
+                        (
+                            fx.bcx.ins().uextend(types::I32, a_lane),
+                            fx.bcx.ins().uextend(types::I32, b_lane),
+                        )
+                    };
+                    let mul = fx.bcx.ins().imul(a_lane, b_lane);
+                    let high = fx.bcx.ins().ushr_imm(mul, 16);
+                    fx.bcx.ins().ireduce(types::I16, high)
+                },
+            );
+        }
+
+        "llvm.x86.sse2.pmovmskb.128"
+        | "llvm.x86.avx2.pmovmskb"
+        | "llvm.x86.sse.movmsk.ps"
+        | "llvm.x86.sse2.movmsk.pd"
+        | "llvm.x86.avx.movmsk.ps.256"
+        | "llvm.x86.avx.movmsk.pd.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_movemask_epi8
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_movemask_ps
+            intrinsic_args!(fx, args => (a); intrinsic);
+
+            let (lane_count, _lane_ty) = a.layout().ty.simd_size_and_type(fx.tcx);
+            let mut res = fx.bcx.ins().iconst(types::I32, 0);
+            for lane_idx in 0..lane_count {
+                let lane = a.value_lane(fx, lane_idx).load_scalar(fx);
+                let lane_ty = fx.bcx.func.dfg.value_type(lane);
+                let lane = if lane_ty.is_float() {
+                    fx.bcx.ins().bitcast(lane_ty.as_int(), MemFlags::new(), lane)
+                } else {
+                    lane
+                };
+                let sign = fx.bcx.ins().ushr_imm(lane, i64::from(lane_ty.bits() - 1));
+                let sign = match lane_ty.bits() {
+                    0..32 => fx.bcx.ins().uextend(types::I32, sign),
+                    32 => sign,
+                    _ => fx.bcx.ins().ireduce(types::I32, sign),
+                };
+                let bit = fx.bcx.ins().ishl_imm(sign, lane_idx as i64);
+                res = fx.bcx.ins().bor(res, bit);
+            }
+            ret.write_cvalue(fx, CValue::by_val(res, fx.layout_of(fx.tcx.types.i32)));
+        }
+
+        "llvm.x86.sse41.round.ps"
+        | "llvm.x86.sse41.round.pd"
+        | "llvm.x86.avx.round.ps.256"
+        | "llvm.x86.avx.round.pd.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_round_ps
+            intrinsic_args!(fx, args => (a, _rounding); intrinsic);
+
+            let rounding = if let Some(rounding) =
+                crate::constant::mir_operand_get_const_val(fx, &args[1].node)
+            {
+                rounding.to_u32()
+            } else {
+                fx.tcx
+                    .dcx()
+                    .span_fatal(span, "Rounding argument for `_mm_round_ps` is not a constant");
+            };
+
+            // Bit 2 selects the rounding mode of MXCSR, which we assume to be the default of
+            // rounding to nearest. Bit 3 only suppresses the precision exception.
+            let mode = if rounding & 0b100 != 0 { 0 } else { rounding & 0b11 };
+            simd_for_each_lane(fx, a, ret, &|fx, _lane_ty, _res_lane_ty, lane| match mode {
+                0 => fx.bcx.ins().nearest(lane),
+                1 => fx.bcx.ins().floor(lane),
+                2 => fx.bcx.ins().ceil(lane),
+                3 => fx.bcx.ins().trunc(lane),
+                _ => unreachable!(),
+            });
+        }
+
+        "llvm.x86.sse.rcp.ps"
+        | "llvm.x86.avx.rcp.ps.256"
+        | "llvm.x86.sse.rsqrt.ps"
+        | "llvm.x86.avx.rsqrt.ps.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_rcp_ps
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_rsqrt_ps
+            intrinsic_args!(fx, args => (a); intrinsic);
+
+            // The hardware computes an approximation with a relative error of at most 1.5*2^-12.
+            // The exact result is within these bounds too.
+            let is_sqrt = intrinsic.contains(".rsqrt.");
+            simd_for_each_lane(fx, a, ret, &|fx, _lane_ty, _res_lane_ty, lane| {
+                let lane = if is_sqrt { fx.bcx.ins().sqrt(lane) } else { lane };
+                let one = fx.bcx.ins().f32const(1.0);
+                fx.bcx.ins().fdiv(one, lane)
+            });
+        }
+
+        "llvm.x86.avx.vpermilvar.ps"
+        | "llvm.x86.avx.vpermilvar.ps.256"
+        | "llvm.x86.avx.vpermilvar.pd"
+        | "llvm.x86.avx.vpermilvar.pd.256" => {
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_permutevar_ps
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_permutevar_pd
+            intrinsic_args!(fx, args => (a, idx); intrinsic);
+
+            // Every 128-bit block is permuted separately. For `pd`, bit 1 of the index selects the
+            // lane instead of bit 0.
+            let (lane_count, _lane_ty) = a.layout().ty.simd_size_and_type(fx.tcx);
+            let is_pd = intrinsic.contains(".pd");
+            let lanes_per_block = if is_pd { 2 } else { 4 };
+            for lane_idx in 0..lane_count {
+                let index = idx.value_lane(fx, lane_idx).load_scalar(fx);
+                let index = if is_pd { fx.bcx.ins().ushr_imm(index, 1) } else { index };
+                let index = fx.bcx.ins().band_imm(index, lanes_per_block as i64 - 1);
+                let block_start = lane_idx / lanes_per_block * lanes_per_block;
+                let index = fx.bcx.ins().iadd_imm(index, block_start as i64);
+                let index = fx.bcx.ins().uextend(fx.pointer_type, index);
+                let value = a.value_lane_dyn(fx, index);
+                ret.place_lane(fx, lane_idx).write_cvalue(fx, value);
+            }
+        }
+
         "llvm.x86.rdtsc" => {
             // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_rdtsc&ig_expand=5273
 
@@ -1503,3 +1855,36 @@ fn pack_instruction<'tcx>(
         round(b, 1, 3);
     }
 }
+
+/// Implement an x86 horizontal instruction like `_mm{,256}_hadd_{epi16,epi32,ps,pd}`, which
+/// combines adjacent pairs of lanes. Every 128-bit block of the result first holds the combined
+/// pairs of the same block of `a`, then those of `b`.
+fn horizontal_instruction<'tcx>(
+    fx: &mut FunctionCx<'_, '_, 'tcx>,
+    a: CValue<'tcx>,
+    b: CValue<'tcx>,
+    ret: CPlace<'tcx>,
+    f: &dyn Fn(&mut FunctionCx<'_, '_, 'tcx>, Value, Value) -> Value,
+) {
+    assert_eq!(a.layout(), b.layout());
+    let layout = a.layout();
+
+    let (lane_count, lane_ty) = layout.ty.simd_size_and_type(fx.tcx);
+    let (ret_lane_count, ret_lane_ty) = ret.layout().ty.simd_size_and_type(fx.tcx);
+    assert_eq!(lane_ty, ret_lane_ty);
+    assert_eq!(lane_count, ret_lane_count);
+
+    let lane_layout = fx.layout_of(lane_ty);
+    let lanes_per_block = 16 / lane_layout.size.bytes();
+    for block_start in (0..lane_count).step_by(lanes_per_block as usize) {
+        for (src_idx, src) in [a, b].into_iter().enumerate() {
+            for pair_idx in 0..lanes_per_block / 2 {
+                let lhs = src.value_lane(fx, block_start + pair_idx * 2).load_scalar(fx);
+                let rhs = src.value_lane(fx, block_start + pair_idx * 2 + 1).load_scalar(fx);
+                let res_lane = CValue::by_val(f(fx, lhs, rhs), lane_layout);
+                let dest_idx = block_start + src_idx as u64 * lanes_per_block / 2 + pair_idx;
+                ret.place_lane(fx, dest_idx).write_cvalue(fx, res_lane);
+            }
+        }
+    }
+}
@@ -69,6 +69,7 @@
 mod debuginfo;
 mod discriminant;
 mod driver;
+mod fn_cache;
 mod global_asm;
 mod inline_asm;
 mod intrinsics;
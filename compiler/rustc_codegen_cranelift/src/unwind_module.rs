@@ -9,17 +9,20 @@
 use cranelift_object::{ObjectModule, ObjectProduct};
 
 use crate::UnwindContext;
+use crate::fn_cache::FnCache;
 
 /// A wrapper around a [Module] which adds any defined function to the [UnwindContext].
 pub(crate) struct UnwindModule<T> {
     pub(crate) module: T,
     unwind_context: UnwindContext,
+    /// If set, compiled functions are looked up in and added to this cache.
+    pub(crate) fn_cache: Option<FnCache>,
 }
 
 impl<T: Module> UnwindModule<T> {
     pub(crate) fn new(mut module: T, pic_eh_frame: bool) -> Self {
         let unwind_context = UnwindContext::new(&mut module, pic_eh_frame);
-        UnwindModule { module, unwind_context }
+        UnwindModule { module, unwind_context, fn_cache: None }
     }
 }
 
@@ -90,7 +93,26 @@ fn define_function_with_control_plane(
         ctx: &mut Context,
         ctrl_plane: &mut ControlPlane,
     ) -> ModuleResult<()> {
-        self.module.define_function_with_control_plane(func, ctx, ctrl_plane)?;
+        if let Some(fn_cache) = &mut self.fn_cache {
+            let (_, reused) = ctx.compile_with_cache(self.module.isa(), fn_cache, ctrl_plane)?;
+            fn_cache.record_function(reused);
+
+            let compiled_code = ctx.compiled_code().unwrap();
+            let relocs = compiled_code
+                .buffer
+                .relocs()
+                .iter()
+                .map(|reloc| ModuleReloc::from_mach_reloc(reloc, &ctx.func, func))
+                .collect::<Vec<_>>();
+            self.module.define_function_bytes(
+                func,
+                compiled_code.buffer.alignment as u64,
+                compiled_code.code_buffer(),
+                &relocs,
+            )?;
+        } else {
+            self.module.define_function_with_control_plane(func, ctx, ctrl_plane)?;
+        }
         self.unwind_context.add_function(&mut self.module, func, ctx);
         Ok(())
     }
@@ -1,11 +1,13 @@
 //! Definition of [`CValue`] and [`CPlace`]
 
 use cranelift_codegen::entity::EntityRef;
+use cranelift_codegen::ir::ValueLabel;
 use cranelift_codegen::ir::immediates::Offset32;
 use cranelift_frontend::Variable;
 use rustc_middle::ty::FnSig;
 use rustc_middle::ty::layout::HasTypingEnv;
 
+use crate::debuginfo::{VarLoc, VarPiece};
 use crate::prelude::*;
 
 fn codegen_field<'tcx>(
@@ -433,14 +435,14 @@ pub(crate) fn to_cvalue(self, fx: &mut FunctionCx<'_, '_, 'tcx>) -> CValue<'tcx>
         match self.inner {
             CPlaceInner::Var(_local, var) => {
                 let val = fx.bcx.use_var(var);
-                //fx.bcx.set_val_label(val, cranelift_codegen::ir::ValueLabel::new(var.index()));
+                fx.bcx.set_val_label(val, ValueLabel::new(var.index()));
                 CValue::by_val(val, layout)
             }
             CPlaceInner::VarPair(_local, var1, var2) => {
                 let val1 = fx.bcx.use_var(var1);
-                //fx.bcx.set_val_label(val1, cranelift_codegen::ir::ValueLabel::new(var1.index()));
+                fx.bcx.set_val_label(val1, ValueLabel::new(var1.index()));
                 let val2 = fx.bcx.use_var(var2);
-                //fx.bcx.set_val_label(val2, cranelift_codegen::ir::ValueLabel::new(var2.index()));
+                fx.bcx.set_val_label(val2, ValueLabel::new(var2.index()));
                 CValue::by_val_pair(val1, val2, layout)
             }
             CPlaceInner::Addr(ptr, extra) => {
@@ -477,6 +479,63 @@ pub(crate) fn debug_comment(self) -> (&'static str, String) {
         }
     }
 
+    /// Describe where the bytes of this place live for the purpose of debuginfo. Returns `None`
+    /// for unsized places.
+    pub(crate) fn debug_pieces(self, fx: &mut FunctionCx<'_, '_, 'tcx>) -> Option<Vec<VarPiece>> {
+        let size = self.layout.size;
+        let mut pieces = vec![];
+        match self.inner {
+            CPlaceInner::Var(_local, var) => {
+                let var_size = Size::from_bytes(fx.clif_type(self.layout.ty).unwrap().bytes());
+                pieces.push(VarPiece::new(
+                    var_size,
+                    Some(VarLoc::Value(ValueLabel::new(var.index()))),
+                ));
+                pieces.push(VarPiece::new(size - var_size, None));
+            }
+            CPlaceInner::VarPair(_local, var1, var2) => {
+                let BackendRepr::ScalarPair(a_scalar, b_scalar) = self.layout.backend_repr else {
+                    bug!("Non ScalarPair repr {:?} for VarPair CPlace", self.layout.backend_repr);
+                };
+                let (ty1, ty2) = fx.clif_pair_type(self.layout.ty).unwrap();
+                let b_offset = Size::from_bytes(i64::from(scalar_pair_calculate_b_offset(
+                    fx.tcx, a_scalar, b_scalar,
+                )));
+                let a_size = Size::from_bytes(ty1.bytes());
+                let b_size = Size::from_bytes(ty2.bytes());
+                pieces.push(VarPiece::new(
+                    a_size,
+                    Some(VarLoc::Value(ValueLabel::new(var1.index()))),
+                ));
+                pieces.push(VarPiece::new(b_offset - a_size, None));
+                pieces.push(VarPiece::new(
+                    b_size,
+                    Some(VarLoc::Value(ValueLabel::new(var2.index()))),
+                ));
+                pieces.push(VarPiece::new(size - b_offset - b_size, None));
+            }
+            CPlaceInner::Addr(ptr, None) => match ptr.debug_base_and_offset() {
+                (crate::pointer::PointerBase::Addr(addr), offset) => {
+                    // Give the address a label of its own so that its location can be tracked.
+                    // Variables don't get anywhere close to 2^31 labels, so this can't collide
+                    // with the labels of variables.
+                    let label = ValueLabel::new((1 << 31) + addr.as_u32() as usize);
+                    fx.bcx.set_val_label(addr, label);
+                    pieces.push(VarPiece::new(size, Some(VarLoc::Indirect(label, offset.into()))));
+                }
+                (crate::pointer::PointerBase::Stack(stack_slot), offset) => {
+                    pieces
+                        .push(VarPiece::new(size, Some(VarLoc::Stack(stack_slot, offset.into()))));
+                }
+                (crate::pointer::PointerBase::Dangling(_align), _offset) => {
+                    pieces.push(VarPiece::new(size, None));
+                }
+            },
+            CPlaceInner::Addr(_, Some(_)) => return None,
+        }
+        Some(pieces)
+    }
+
     #[track_caller]
     pub(crate) fn to_ptr(self) -> Pointer {
         match self.inner {
@@ -541,7 +600,7 @@ fn transmute_scalar<'tcx>(
                 dst_ty,
             );
             let data = if src_ty == dst_ty { data } else { codegen_bitcast(fx, dst_ty, data) };
-            //fx.bcx.set_val_label(data, cranelift_codegen::ir::ValueLabel::new(var.index()));
+            fx.bcx.set_val_label(data, ValueLabel::new(var.index()));
             fx.bcx.def_var(var, data);
         }
 
@@ -180,6 +180,10 @@ codegen_ssa_link_exe_status_stack_buffer_overrun = 0xc0000409 is `STATUS_STACK_B
 
 codegen_ssa_link_exe_unexpected_error = `link.exe` returned an unexpected error
 
+codegen_ssa_link_map_report = failed to create the link map report from `{$path}`: {$error}
+
+codegen_ssa_link_map_unsupported = the linker did not write a link map, `-Z link-map-report` is only supported with GNU ld and lld
+
 codegen_ssa_link_script_unavailable = can only use link script when linking with GNU-like linker
 
 codegen_ssa_link_script_write_failure = failed to write link script to {$path}: {$error}
@@ -257,6 +261,8 @@ codegen_ssa_self_contained_linker_missing = the self-contained linker was reques
 
 codegen_ssa_shuffle_indices_evaluation = could not evaluate shuffle_indices at compile time
 
+codegen_ssa_size_report_object = failed to read the symbols of `{$path}` for the size report: {$error}
+
 codegen_ssa_specify_libraries_to_link = use the `-l` flag to specify native libraries to link
 
 codegen_ssa_static_library_native_artifacts = link against the following native artifacts when linking against this static library. The order and any duplication can be significant on some platforms.
@@ -53,6 +53,7 @@
 
 use super::archive::{ArchiveBuilder, ArchiveBuilderBuilder};
 use super::command::Command;
+use super::link_map;
 use super::linker::{self, Linker};
 use super::metadata::{MetadataPosition, create_wrapper_file};
 use super::rpath::{self, RPathConfig};
@@ -729,6 +730,11 @@ fn link_natively(
     // May have not found libraries in the right formats.
     sess.dcx().abort_if_errors();
 
+    // Do not mistake the map of a previous link for one written by a linker that cannot write maps.
+    if sess.opts.unstable_opts.link_map_report {
+        let _ = fs::remove_file(link_map::map_path(temp_filename));
+    }
+
     // Invoke the system linker
     info!("{cmd:?}");
     let unknown_arg_regex =
@@ -953,6 +959,10 @@ fn link_natively(
             if !stdout.is_empty() {
                 lint(format!("linker stdout: {}", stdout))
             }
+
+            if sess.opts.unstable_opts.link_map_report {
+                link_map::write_link_map_report(sess, crate_type, codegen_results, temp_filename);
+            }
         }
         Err(e) => {
             let linker_not_found = e.kind() == io::ErrorKind::NotFound;
@@ -2563,6 +2573,10 @@ fn add_order_independent_options(
         cmd.gc_sections(keep_metadata);
     }
 
+    if sess.opts.unstable_opts.link_map_report {
+        cmd.link_map(&link_map::map_path(out_filename));
+    }
+
     cmd.set_output_kind(link_output_kind, crate_type, out_filename);
 
     add_relro_args(cmd, sess);
@@ -0,0 +1,402 @@
+//! Implements `-Z link-map-report`.
+//!
+//! The linker is asked to write a link map next to its output. After linking, the map is parsed
+//! to find the input sections that ended up in the output and the symbols they define. Comparing
+//! them with the sections of the linked object files gives the size of the code and data that the
+//! linker discarded, e.g. with `--gc-sections`. The retained sections and the retained and
+//! discarded size of every crate are written as JSON.
+//!
+//! Linkers do not record why they kept a section, so the reason we report is a guess based on the
+//! symbols it defines and its name. It is reported as `guessed_reason` to make that clear.
+
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use object::{BinaryFormat, Object, ObjectSection, ObjectSymbol, SectionKind, SymbolKind};
+use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
+use rustc_session::Session;
+use rustc_session::config::CrateType;
+use serde_json::json;
+
+use crate::{CodegenResults, errors};
+
+/// The path of the link map that the linker writes for `out_filename`.
+pub(crate) fn map_path(out_filename: &Path) -> PathBuf {
+    let mut path = OsString::from(out_filename);
+    path.push(".map");
+    path.into()
+}
+
+fn report_path(out_filename: &Path) -> PathBuf {
+    let mut path = OsString::from(out_filename);
+    path.push(".link-map.json");
+    path.into()
+}
+
+/// An object file passed to the linker.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct InputFile {
+    path: String,
+    /// The archive member, if the object file is part of an archive.
+    member: Option<String>,
+}
+
+impl InputFile {
+    /// Parses `path` or `path(member)`.
+    fn parse(s: &str) -> InputFile {
+        if let Some(s) = s.strip_suffix(')')
+            && let Some((path, member)) = s.rsplit_once('(')
+        {
+            InputFile { path: path.to_owned(), member: Some(member.to_owned()) }
+        } else {
+            InputFile { path: s.to_owned(), member: None }
+        }
+    }
+
+    fn display(&self) -> String {
+        match &self.member {
+            Some(member) => format!("{}({member})", self.path),
+            None => self.path.clone(),
+        }
+    }
+
+    /// The crate that the object file belongs to, or the name of the file or archive if it is not
+    /// produced by rustc.
+    fn krate(&self) -> String {
+        let file_name = |path: &str| {
+            Path::new(path).file_name().map_or(path.to_owned(), |n| n.to_string_lossy().into())
+        };
+        let name = self.member.clone().unwrap_or_else(|| file_name(&self.path));
+        // Codegen units are named `{crate}.{cgu}.rcgu.o`, or `{crate}-{hash}.{crate}.{cgu}.rcgu.o`
+        // with `-C extra-filename`. Crate names cannot contain dashes or dots.
+        if name.ends_with(".rcgu.o") {
+            name.split(['-', '.']).next().unwrap().to_owned()
+        } else if self.member.is_some() {
+            file_name(&self.path)
+        } else {
+            name
+        }
+    }
+
+    /// Returns the name and size of each piece of the object file that the linker may discard on
+    /// its own: sections containing code or data, or for wasm, functions and data symbols.
+    fn units(&self) -> Result<Vec<(String, u64)>, String> {
+        let data = fs::read(&self.path).map_err(|e| e.to_string())?;
+        let data = match &self.member {
+            Some(member) => {
+                let archive =
+                    object::read::archive::ArchiveFile::parse(&*data).map_err(|e| e.to_string())?;
+                let mut found = None;
+                for entry in archive.members() {
+                    let entry = entry.map_err(|e| e.to_string())?;
+                    if entry.name() == member.as_bytes() {
+                        found = Some(entry.data(&*data).map_err(|e| e.to_string())?);
+                        break;
+                    }
+                }
+                found.ok_or_else(|| format!("archive member `{member}` not found"))?
+            }
+            None => &*data,
+        };
+        let file = object::File::parse(data).map_err(|e| e.to_string())?;
+
+        let mut units = Vec::new();
+        if file.format() == BinaryFormat::Wasm {
+            for symbol in file.symbols() {
+                if matches!(symbol.kind(), SymbolKind::Text | SymbolKind::Data)
+                    && symbol.is_definition()
+                    && let Ok(name) = symbol.name()
+                {
+                    units.push((name.to_owned(), symbol.size()));
+                }
+            }
+        } else {
+            for section in file.sections() {
+                if matches!(
+                    section.kind(),
+                    SectionKind::Text
+                        | SectionKind::Data
+                        | SectionKind::ReadOnlyData
+                        | SectionKind::ReadOnlyDataWithRel
+                        | SectionKind::ReadOnlyString
+                        | SectionKind::UninitializedData
+                        | SectionKind::Tls
+                        | SectionKind::UninitializedTls
+                ) && let Ok(name) = section.name()
+                {
+                    units.push((name.to_owned(), section.size()));
+                }
+            }
+        }
+        Ok(units)
+    }
+}
+
+/// An input section that is part of the output.
+struct RetainedSection {
+    /// The output section that contains it.
+    output: String,
+    name: String,
+    /// The object file that contains it, or `None` for sections created by the linker.
+    input: Option<InputFile>,
+    size: u64,
+    /// The symbols it defines.
+    symbols: Vec<String>,
+}
+
+/// Parses the map written by lld, for ELF or wasm. It is a table with a header like
+///
+/// ```text
+///              VMA              LMA     Size Align Out     In      Symbol
+/// ```
+///
+/// in which output sections, input sections and symbols are distinguished by the column their
+/// name starts in. Input sections are written as `file:(section)`.
+fn parse_lld_map(map: &str) -> Option<Vec<RetainedSection>> {
+    let mut lines = map.lines();
+    let header = lines.next()?;
+    let out_column = header.find(" Out ")? + 1;
+    let in_column = header.find(" In ")? + 1;
+    let symbol_column = header.find(" Symbol")? + 1;
+    let size_field = header[..out_column].split_whitespace().position(|c| c == "Size")?;
+
+    let mut sections: Vec<RetainedSection> = Vec::new();
+    let mut output = String::new();
+    for line in lines {
+        let (Some(fields), Some(name)) = (line.get(..out_column), line.get(out_column..)) else {
+            continue;
+        };
+        let column = out_column + name.len() - name.trim_start().len();
+        let name = name.trim();
+        if column >= symbol_column {
+            if let Some(section) = sections.last_mut() {
+                section.symbols.push(name.to_owned());
+            }
+        } else if column >= in_column {
+            let Some(size) = fields.split_whitespace().nth(size_field) else { continue };
+            let Ok(size) = u64::from_str_radix(size, 16) else { continue };
+            let Some((input, section)) =
+                name.strip_suffix(')').and_then(|name| name.rsplit_once(":("))
+            else {
+                // Linker script assignments and the like.
+                continue;
+            };
+            let input = (!input.starts_with('<')).then(|| InputFile::parse(input));
+            sections.push(RetainedSection {
+                output: output.clone(),
+                name: section.to_owned(),
+                input,
+                size,
+                symbols: Vec::new(),
+            });
+        } else {
+            output = name.to_owned();
+        }
+    }
+    Some(sections)
+}
+
+/// Parses the map written by GNU ld. After the list of discarded sections, it lists the output
+/// sections at the start of a line, followed by their input sections indented by one space:
+///
+/// ```text
+/// .text           0x0000000000001040      0x1b5
+///  .text._ZN3foo4main17h0123456789abcdefE
+///                 0x0000000000001139       0x2a /tmp/foo.o
+///                 0x0000000000001139                _ZN3foo4main17h0123456789abcdefE
+/// ```
+///
+/// The address, size and file of a section are on the next line if its name is long.
+fn parse_gnu_map(map: &str) -> Option<Vec<RetainedSection>> {
+    let (_, map) = map.split_once("\nLinker script and memory map\n")?;
+
+    let mut sections: Vec<RetainedSection> = Vec::new();
+    let mut output = String::new();
+    let mut lines = map.lines();
+    while let Some(line) = lines.next() {
+        if line.trim().is_empty() {
+            continue;
+        } else if !line.starts_with(' ') {
+            // Skip commands such as `LOAD file`, which are written in uppercase.
+            output = match line.split_whitespace().next() {
+                Some(name) if !name.chars().all(|c| c.is_ascii_uppercase()) => name.to_owned(),
+                _ => String::new(),
+            };
+        } else if !line.starts_with("  ") {
+            // Skip the patterns of the linker script, such as ` *(.text .text.*)`, and padding.
+            if output.is_empty() || line.starts_with(" *") {
+                continue;
+            }
+            let mut fields: Vec<_> = line.split_whitespace().collect();
+            if fields.len() == 1
+                && let Some(next) = lines.next()
+            {
+                fields.extend(next.split_whitespace());
+            }
+            let [name, _address, size, input @ ..] = &fields[..] else { continue };
+            let Some(size) = size.strip_prefix("0x") else { continue };
+            let Ok(size) = u64::from_str_radix(size, 16) else { continue };
+            let input = (!input.is_empty()).then(|| InputFile::parse(&input.join(" ")));
+            sections.push(RetainedSection {
+                output: output.clone(),
+                name: name.to_string(),
+                input,
+                size,
+                symbols: Vec::new(),
+            });
+        } else if let [address, symbol] = line.split_whitespace().collect::<Vec<_>>()[..]
+            && address.starts_with("0x")
+            // The address and size of an output section with a long name.
+            && !symbol.starts_with("0x")
+            && let Some(section) = sections.last_mut()
+        {
+            section.symbols.push(symbol.to_owned());
+        }
+    }
+    Some(sections)
+}
+
+/// Section names that the default linker scripts of GNU ld and lld always keep.
+const KEPT_SECTIONS: &[&str] = &[
+    ".init",
+    ".fini",
+    ".preinit_array",
+    ".init_array",
+    ".fini_array",
+    ".ctors",
+    ".dtors",
+    ".note",
+];
+
+/// Guesses why the linker kept `section`.
+fn root_reason(
+    section: &RetainedSection,
+    entries: &[&str],
+    exported: &FxHashSet<&str>,
+) -> &'static str {
+    if section.input.is_none() {
+        "linker"
+    } else if section.symbols.iter().any(|s| entries.contains(&&**s)) {
+        "entry"
+    } else if section.symbols.iter().any(|s| exported.contains(&**s)) {
+        "exported"
+    } else if KEPT_SECTIONS.iter().any(|kept| {
+        section.name.strip_prefix(kept).is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    }) {
+        "kept"
+    } else {
+        "referenced"
+    }
+}
+
+/// Parses the link map written for `out_filename` and writes the report next to it.
+pub(crate) fn write_link_map_report(
+    sess: &Session,
+    crate_type: CrateType,
+    codegen_results: &CodegenResults,
+    out_filename: &Path,
+) {
+    let map_path = map_path(out_filename);
+    let Ok(map) = fs::read_to_string(&map_path) else {
+        sess.dcx().emit_warn(errors::LinkMapUnsupported);
+        return;
+    };
+    let sections = if map.trim_start().starts_with("VMA") || map.trim_start().starts_with("Addr") {
+        parse_lld_map(&map)
+    } else {
+        parse_gnu_map(&map)
+    };
+    let Some(mut sections) = sections else {
+        let error = "unknown link map format".to_owned();
+        sess.dcx().emit_err(errors::LinkMapReport { path: &map_path, error });
+        return;
+    };
+    sections.retain(|section| section.size != 0);
+    sections.sort_by(|a, b| b.size.cmp(&a.size));
+
+    // The sections that were retained from each input file. Objects of the local crate are always
+    // linked, even if nothing of them was retained.
+    let mut inputs: FxIndexMap<InputFile, FxHashSet<&str>> = FxIndexMap::default();
+    for module in codegen_results.modules.iter().chain(&codegen_results.allocator_module) {
+        if let Some(object) = &module.object {
+            let input = InputFile { path: object.to_string_lossy().into(), member: None };
+            inputs.entry(input).or_default();
+        }
+    }
+    let mut crates: FxIndexMap<String, (u64, u64)> = FxIndexMap::default();
+    let mut retained_size = 0;
+    for section in &sections {
+        retained_size += section.size;
+        let Some(input) = &section.input else { continue };
+        crates.entry(input.krate()).or_default().0 += section.size;
+        let retained = inputs.entry(input.clone()).or_default();
+        retained.insert(&section.name);
+        // wasm-ld lists the functions of an input file, not its sections.
+        retained.extend(section.symbols.iter().map(|s| &**s));
+    }
+
+    // Linkers discard whole sections, or functions for wasm, so everything of the input files
+    // that is not retained was discarded.
+    let mut discarded_size = 0;
+    for (input, retained) in &inputs {
+        let units = match input.units() {
+            Ok(units) => units,
+            Err(error) => {
+                let path = Path::new(&input.path);
+                sess.dcx().emit_err(errors::LinkMapReport { path, error });
+                continue;
+            }
+        };
+        let discarded: u64 = units
+            .iter()
+            .filter(|(name, _)| !retained.contains(&**name))
+            .map(|(_, size)| size)
+            .sum();
+        crates.entry(input.krate()).or_default().1 += discarded;
+        discarded_size += discarded;
+    }
+    crates.sort_by(|_, (a, _), _, (b, _)| b.cmp(a));
+
+    let entries = [&*sess.target.entry_name, "_start"];
+    let exported: FxHashSet<&str> = codegen_results
+        .crate_info
+        .exported_symbols
+        .get(&crate_type)
+        .into_iter()
+        .flatten()
+        .map(|(name, _)| &**name)
+        .collect();
+    let report = json!({
+        "map": map_path,
+        "retained_size": retained_size,
+        "discarded_size": discarded_size,
+        "crates": crates
+            .iter()
+            .map(|(krate, (retained, discarded))| {
+                json!({ "crate": krate, "retained_size": retained, "discarded_size": discarded })
+            })
+            .collect::<Vec<_>>(),
+        "sections": sections
+            .iter()
+            .map(|section| {
+                json!({
+                    "section": section.name,
+                    "output_section": section.output,
+                    "file": section.input.as_ref().map(|input| input.display()),
+                    "crate": section.input.as_ref().map(|input| input.krate()),
+                    "size": section.size,
+                    "guessed_reason": root_reason(section, &entries, &exported),
+                    "symbols": section.symbols,
+                })
+            })
+            .collect::<Vec<_>>(),
+    });
+
+    let report_path = report_path(out_filename);
+    let mut content = serde_json::to_string_pretty(&report).unwrap();
+    content.push('\n');
+    if let Err(error) = fs::write(&report_path, content) {
+        sess.dcx().emit_err(errors::LinkMapReport { path: &report_path, error: error.to_string() });
+    }
+}
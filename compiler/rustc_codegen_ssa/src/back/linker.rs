@@ -330,6 +330,9 @@ fn add_object(&mut self, path: &Path) {
         link_or_cc_args(self, &[path]);
     }
     fn gc_sections(&mut self, keep_metadata: bool);
+    /// Asks the linker to write a link map to `path`, in one of the formats that
+    /// `back::link_map` can read. Linkers that cannot do so ignore this.
+    fn link_map(&mut self, _path: &Path) {}
     fn full_relro(&mut self);
     fn partial_relro(&mut self);
     fn no_relro(&mut self);
@@ -698,6 +701,15 @@ fn gc_sections(&mut self, keep_metadata: bool) {
         }
     }
 
+    fn link_map(&mut self, path: &Path) {
+        // GNU ld, gold and lld all accept `-Map`, but the map of ld64 has another format.
+        if (self.is_gnu || self.sess.target.is_like_wasm) && !self.sess.target.is_like_darwin {
+            let mut arg = OsString::from("-Map=");
+            arg.push(path);
+            self.link_arg(arg);
+        }
+    }
+
     fn optimize(&mut self) {
         if !self.is_gnu && !self.sess.target.is_like_wasm {
             return;
@@ -1388,6 +1400,12 @@ fn gc_sections(&mut self, _keep_metadata: bool) {
         self.link_arg("--gc-sections");
     }
 
+    fn link_map(&mut self, path: &Path) {
+        let mut arg = OsString::from("--Map=");
+        arg.push(path);
+        self.link_arg(arg);
+    }
+
     fn optimize(&mut self) {
         // The -O flag is, as of late 2023, only used for merging of strings and debuginfo, and
         // only differentiates -O0 and -O1. It does not apply to LTO.
@@ -6,10 +6,12 @@
 pub mod archive;
 pub(crate) mod command;
 pub mod link;
+pub(crate) mod link_map;
 pub(crate) mod linker;
 pub mod lto;
 pub mod metadata;
 pub(crate) mod rpath;
+pub(crate) mod size_report;
 pub mod symbol_export;
 pub mod write;
 
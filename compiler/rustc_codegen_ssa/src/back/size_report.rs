@@ -0,0 +1,176 @@
+//! Implements `--emit=size-report`.
+//!
+//! Before codegen, we record which function each symbol name belongs to. After codegen, the size
+//! of each function symbol is read from the produced object files, and attributed to the function,
+//! to the crate that defines it and, for generic functions, to the generic definition. The result
+//! is written as JSON.
+
+use std::fs;
+use std::path::Path;
+
+use object::{Object, ObjectSection, ObjectSymbol, SectionIndex, SymbolKind};
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_session::Session;
+use rustc_session::config::{OutputFilenames, OutputType};
+use serde_json::json;
+
+use crate::{CompiledModule, errors};
+
+/// The function a symbol was generated for.
+pub(crate) struct SymbolOrigin {
+    /// The path of the function, without its generic arguments.
+    path: String,
+    /// The crate that defines the function.
+    krate: String,
+    generic_args: Vec<String>,
+    /// Whether the function is instantiated as a local copy in every codegen unit that uses it,
+    /// like `#[inline]` functions, instead of once in a shared codegen unit. This does not tell
+    /// whether calls to the function were actually inlined.
+    local_copy: bool,
+}
+
+/// Maps the symbol names of all the functions that are codegened in this crate to their origin.
+pub(crate) fn collect_symbol_origins(tcx: TyCtxt<'_>) -> FxHashMap<String, SymbolOrigin> {
+    let mut origins = FxHashMap::default();
+    for cgu in tcx.collect_and_partition_mono_items(()).codegen_units {
+        for (item, data) in cgu.items() {
+            let MonoItem::Fn(instance) = *item else { continue };
+            let def_id = instance.def_id();
+            origins.entry(item.symbol_name(tcx).name.to_owned()).or_insert_with(|| {
+                with_no_trimmed_paths!(SymbolOrigin {
+                    path: tcx.def_path_str(def_id),
+                    krate: tcx.crate_name(def_id.krate).to_string(),
+                    generic_args: instance
+                        .args
+                        .iter()
+                        .filter(|arg| arg.as_region().is_none())
+                        .map(|arg| arg.to_string())
+                        .collect(),
+                    local_copy: data.inlined,
+                })
+            });
+        }
+    }
+    origins
+}
+
+/// The code size of a symbol, summed over all the objects it is defined in.
+#[derive(Default)]
+struct SymbolSize {
+    size: u64,
+    copies: u64,
+}
+
+/// Adds the size of each function symbol defined in the object file at `path` to `sizes`.
+fn read_symbol_sizes(
+    path: &Path,
+    sizes: &mut FxIndexMap<String, SymbolSize>,
+) -> Result<(), String> {
+    let data = fs::read(path).map_err(|e| e.to_string())?;
+    let file = object::File::parse(&*data).map_err(|e| e.to_string())?;
+
+    let mut symbols: Vec<(SectionIndex, u64, u64, String)> = Vec::new();
+    for symbol in file.symbols() {
+        if symbol.kind() != SymbolKind::Text || !symbol.is_definition() {
+            continue;
+        }
+        let (Some(section), Ok(name)) = (symbol.section_index(), symbol.name()) else { continue };
+        symbols.push((section, symbol.address(), symbol.size(), name.to_owned()));
+    }
+
+    // Mach-O and COFF do not record the size of symbols, so we compute it from the address of the
+    // next symbol, or the end of the section.
+    symbols.sort_by_key(|&(section, address, ..)| (section, address));
+    for (i, (section, address, size, name)) in symbols.iter().enumerate() {
+        let size = if *size != 0 {
+            *size
+        } else if let Some((next_section, next_address, ..)) = symbols.get(i + 1)
+            && next_section == section
+        {
+            next_address - address
+        } else {
+            let section = file.section_by_index(*section).map_err(|e| e.to_string())?;
+            (section.address() + section.size()).saturating_sub(*address)
+        };
+        let entry = sizes.entry(name.clone()).or_default();
+        entry.size += size;
+        entry.copies += 1;
+    }
+    Ok(())
+}
+
+pub(crate) fn write_size_report(
+    sess: &Session,
+    origins: &FxHashMap<String, SymbolOrigin>,
+    modules: impl Iterator<Item = &CompiledModule>,
+    outputs: &OutputFilenames,
+) {
+    let mut sizes = FxIndexMap::default();
+    for module in modules {
+        let Some(path) = &module.object else { continue };
+        if let Err(error) = read_symbol_sizes(path, &mut sizes) {
+            sess.dcx().emit_err(errors::SizeReportObject { path, error });
+        }
+    }
+    sizes.sort_by(|name_a, a, name_b, b| b.size.cmp(&a.size).then_with(|| name_a.cmp(name_b)));
+
+    let mut total = 0;
+    let mut crates: FxIndexMap<&str, (u64, u64)> = FxIndexMap::default();
+    let mut generics: FxIndexMap<&str, (u64, u64)> = FxIndexMap::default();
+    let mut symbols = Vec::with_capacity(sizes.len());
+    for (name, SymbolSize { size, copies }) in &sizes {
+        total += size;
+        // Object files for Apple targets prefix all symbol names with an underscore.
+        let origin = origins.get(name).or_else(|| origins.get(name.strip_prefix('_')?));
+        let Some(origin) = origin else {
+            // Symbols that do not come from a Rust function, e.g. compiler builtins.
+            symbols.push(json!({ "symbol": name, "size": size, "copies": copies }));
+            continue;
+        };
+
+        let (crate_size, crate_symbols) = crates.entry(&origin.krate).or_default();
+        *crate_size += size;
+        *crate_symbols += 1;
+        if !origin.generic_args.is_empty() {
+            let (generic_size, instantiations) = generics.entry(&origin.path).or_default();
+            *generic_size += size;
+            *instantiations += 1;
+        }
+
+        symbols.push(json!({
+            "symbol": name,
+            "path": origin.path,
+            "crate": origin.krate,
+            "generic_args": origin.generic_args,
+            "local_copy": origin.local_copy,
+            "size": size,
+            "copies": copies,
+        }));
+    }
+
+    crates.sort_by(|_, (a, _), _, (b, _)| b.cmp(a));
+    generics.sort_by(|_, (a, _), _, (b, _)| b.cmp(a));
+    let report = json!({
+        "total_size": total,
+        "crates": crates
+            .iter()
+            .map(|(krate, (size, symbols))| {
+                json!({ "crate": krate, "size": size, "symbols": symbols })
+            })
+            .collect::<Vec<_>>(),
+        "generics": generics
+            .iter()
+            .map(|(path, (size, instantiations))| {
+                json!({ "path": path, "size": size, "instantiations": instantiations })
+            })
+            .collect::<Vec<_>>(),
+        "symbols": symbols,
+    });
+
+    let mut content = serde_json::to_string_pretty(&report).unwrap();
+    content.push('\n');
+    outputs.path(OutputType::SizeReport).overwrite(&content, sess);
+}
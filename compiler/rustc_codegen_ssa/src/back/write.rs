@@ -8,7 +8,7 @@
 
 use rustc_abi::Size;
 use rustc_ast::attr;
-use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
 use rustc_data_structures::jobserver::{self, Acquired};
 use rustc_data_structures::memmap::Mmap;
 use rustc_data_structures::profiling::{SelfProfilerRef, VerboseTimingGuard};
@@ -37,6 +37,7 @@
 
 use super::link::{self, ensure_removed};
 use super::lto::{self, SerializedModule};
+use super::size_report::{self, SymbolOrigin};
 use crate::back::lto::check_lto_allowed;
 use crate::errors::ErrorCreatingRemarkDir;
 use crate::traits::*;
@@ -132,7 +133,10 @@ macro_rules! if_regular {
 
         let should_emit_obj = sess.opts.output_types.contains_key(&OutputType::Exe)
             || match kind {
-                ModuleKind::Regular => sess.opts.output_types.contains_key(&OutputType::Object),
+                ModuleKind::Regular => {
+                    sess.opts.output_types.contains_key(&OutputType::Object)
+                        || sess.opts.output_types.contains_key(&OutputType::SizeReport)
+                }
                 ModuleKind::Allocator => false,
             };
 
@@ -439,6 +443,13 @@ pub(crate) fn start_async_codegen<B: ExtraBackendMethods>(
 
     let crate_info = CrateInfo::new(tcx, target_cpu);
 
+    let size_report_origins = tcx
+        .sess
+        .opts
+        .output_types
+        .contains_key(&OutputType::SizeReport)
+        .then(|| size_report::collect_symbol_origins(tcx));
+
     let regular_config = ModuleConfig::new(ModuleKind::Regular, tcx, no_builtins);
     let allocator_config = ModuleConfig::new(ModuleKind::Allocator, tcx, no_builtins);
 
@@ -461,6 +472,7 @@ pub(crate) fn start_async_codegen<B: ExtraBackendMethods>(
     OngoingCodegen {
         backend,
         crate_info,
+        size_report_origins,
 
         codegen_worker_receive,
         shared_emitter_main,
@@ -597,7 +609,11 @@ fn produce_final_output_artifacts(
                 user_wants_objects = true;
                 copy_if_one_unit(OutputType::Object, true);
             }
-            OutputType::Mir | OutputType::Metadata | OutputType::Exe | OutputType::DepInfo => {}
+            OutputType::Mir
+            | OutputType::Metadata
+            | OutputType::Exe
+            | OutputType::DepInfo
+            | OutputType::SizeReport => {}
         }
     }
 
@@ -2077,6 +2093,8 @@ fn drop(&mut self) {
 pub struct OngoingCodegen<B: ExtraBackendMethods> {
     pub backend: B,
     pub crate_info: CrateInfo,
+    /// The functions that symbols were generated for, if `--emit=size-report` was requested.
+    pub(crate) size_report_origins: Option<FxHashMap<String, SymbolOrigin>>,
     pub output_filenames: Arc<OutputFilenames>,
     // Field order below is intended to terminate the coordinator thread before two fields below
     // drop and prematurely close channels used by coordinator thread. See `Coordinator`'s
@@ -2104,6 +2122,11 @@ pub fn join(self, sess: &Session) -> (CodegenResults, FxIndexMap<WorkProductId,
 
         let work_products =
             copy_all_cgu_workproducts_to_incr_comp_cache_dir(sess, &compiled_modules);
+        // This must happen before `produce_final_output_artifacts` removes the object files.
+        if let Some(origins) = &self.size_report_origins {
+            let modules = compiled_modules.modules.iter().chain(&compiled_modules.allocator_module);
+            size_report::write_size_report(sess, origins, modules, &self.output_filenames);
+        }
         produce_final_output_artifacts(sess, &compiled_modules, &self.output_filenames);
 
         // FIXME: time_llvm_passes support - does this use a global context or
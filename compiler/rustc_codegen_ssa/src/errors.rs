@@ -656,6 +656,17 @@ pub(crate) struct StaticLibraryNativeArtifactsToFile<'a> {
     pub path: &'a Path,
 }
 
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_link_map_report)]
+pub(crate) struct LinkMapReport<'a> {
+    pub path: &'a Path,
+    pub error: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_link_map_unsupported)]
+pub(crate) struct LinkMapUnsupported;
+
 #[derive(Diagnostic)]
 #[diag(codegen_ssa_link_script_unavailable)]
 pub(crate) struct LinkScriptUnavailable;
@@ -722,6 +733,13 @@ pub(crate) struct ReadFileError {
     pub message: std::io::Error,
 }
 
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_size_report_object)]
+pub(crate) struct SizeReportObject<'a> {
+    pub path: &'a Path,
+    pub error: String,
+}
+
 #[derive(Diagnostic)]
 #[diag(codegen_ssa_unsupported_link_self_contained)]
 pub(crate) struct UnsupportedLinkSelfContained;
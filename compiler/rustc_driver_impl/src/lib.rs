@@ -45,6 +45,7 @@
 use rustc_interface::util::{self, get_codegen_backend};
 use rustc_interface::{Linker, create_and_enter_global_ctxt, interface, passes};
 use rustc_lint::unerased_lint_store;
+use rustc_metadata::DylibError;
 use rustc_metadata::creader::MetadataLoader;
 use rustc_metadata::locator;
 use rustc_middle::ty::TyCtxt;
@@ -214,6 +215,8 @@ pub fn diagnostics_registry() -> Registry {
 
 /// This is the primary entry point for rustc.
 pub fn run_compiler(at_args: &[String], callbacks: &mut (dyn Callbacks + Send)) {
+    run_proc_macro_sandbox_child_if_requested();
+
     let mut default_early_dcx = EarlyDiagCtxt::new(ErrorOutputType::default());
 
     // Throw away the first argument, the name of the binary.
@@ -1328,6 +1331,7 @@ pub fn catch_fatal_errors<F: FnOnce() -> R, R>(f: F) -> Result<R, FatalError> {
 /// Variant of `catch_fatal_errors` for the `interface::Result` return type
 /// that also computes the exit code.
 pub fn catch_with_exit_code(f: impl FnOnce()) -> i32 {
+    run_proc_macro_sandbox_child_if_requested();
     match catch_fatal_errors(f) {
         Ok(()) => EXIT_SUCCESS,
         _ => EXIT_FAILURE,
@@ -1614,7 +1618,27 @@ pub fn install_ctrlc_handler() {
     .expect("Unable to install ctrlc handler");
 }
 
+/// Runs a sandboxed proc macro process instead of the compiler if this process was started as
+/// one. The sandbox re-executes the current executable, so this must be called by every driver
+/// before it does anything else: it is called by `main`, `run_compiler` and
+/// `catch_with_exit_code`, which rustdoc, clippy and other custom drivers go through.
+fn run_proc_macro_sandbox_child_if_requested() {
+    if rustc_expand::proc_macro_sandbox::is_child() {
+        rustc_expand::proc_macro_sandbox::run_child(|path, symbol| unsafe {
+            rustc_metadata::load_symbol_from_dylib::<*const &[_]>(path, symbol)
+                .map(|decls| *decls)
+                .map_err(|err| match err {
+                    DylibError::DlOpen(path, err) | DylibError::DlSym(path, err) => {
+                        format!("couldn't load `{path}`: {err}")
+                    }
+                })
+        });
+    }
+}
+
 pub fn main() -> ! {
+    run_proc_macro_sandbox_child_if_requested();
+
     let start_time = Instant::now();
     let start_rss = get_resident_set_size();
 
@@ -311,6 +311,11 @@ pub fn is_error(&self) -> bool {
         }
     }
 
+    /// The name of the lint that emitted this diagnostic, if any.
+    pub fn lint_name(&self) -> Option<&str> {
+        self.is_lint.as_ref().map(|is_lint| is_lint.name.as_str())
+    }
+
     /// Indicates whether this diagnostic should show up in cargo's future breakage report.
     pub(crate) fn has_future_breakage(&self) -> bool {
         matches!(self.is_lint, Some(IsLint { has_future_breakage: true, .. }))
@@ -618,6 +618,10 @@ struct DiagCtxtInner {
 
     future_breakage_diagnostics: Vec<DiagInner>,
 
+    /// The lints emitted when [`DiagCtxtFlags::collect_lint_diagnostics`] is set, including the
+    /// ones replayed from the incremental cache.
+    lint_diagnostics: Vec<DiagInner>,
+
     /// expected diagnostic will have the level `Expect` which additionally
     /// carries the [`LintExpectationId`] of the expectation that can be
     /// marked as fulfilled. This is a collection of all [`LintExpectationId`]s
@@ -690,6 +694,9 @@ pub struct DiagCtxtFlags {
     pub deduplicate_diagnostics: bool,
     /// Track where errors are created. Enabled with `-Ztrack-diagnostics`.
     pub track_diagnostics: bool,
+    /// Keep the emitted lints for [`DiagCtxt::take_lint_diagnostics`].
+    /// (rustc: see `-Z edition-migration-report`)
+    pub collect_lint_diagnostics: bool,
 }
 
 impl Drop for DiagCtxtInner {
@@ -821,6 +828,7 @@ pub fn reset_err_count(&self) {
             emitted_diagnostics,
             stashed_diagnostics,
             future_breakage_diagnostics,
+            lint_diagnostics,
             fulfilled_expectations,
             ice_file: _,
         } = inner.deref_mut();
@@ -840,6 +848,7 @@ pub fn reset_err_count(&self) {
         *emitted_diagnostics = Default::default();
         *stashed_diagnostics = Default::default();
         *future_breakage_diagnostics = Default::default();
+        *lint_diagnostics = Default::default();
         *fulfilled_expectations = Default::default();
     }
 
@@ -1156,6 +1165,11 @@ pub fn emit_future_breakage_report(&self) {
         }
     }
 
+    /// Takes the lints emitted so far, if [`DiagCtxtFlags::collect_lint_diagnostics`] is set.
+    pub fn take_lint_diagnostics(&self) -> Vec<DiagInner> {
+        std::mem::take(&mut self.inner.borrow_mut().lint_diagnostics)
+    }
+
     pub fn emit_unused_externs(
         &self,
         lint_level: rustc_lint_defs::Level,
@@ -1502,6 +1516,7 @@ fn new(emitter: Box<DynEmitter>) -> Self {
             emitted_diagnostics: Default::default(),
             stashed_diagnostics: Default::default(),
             future_breakage_diagnostics: Vec::new(),
+            lint_diagnostics: Vec::new(),
             fulfilled_expectations: Default::default(),
             ice_file: None,
         }
@@ -1541,6 +1556,13 @@ fn emit_diagnostic(
             self.future_breakage_diagnostics.push(diagnostic.clone());
         }
 
+        if self.flags.collect_lint_diagnostics
+            && diagnostic.is_lint.is_some()
+            && !matches!(diagnostic.level, Allow | Expect)
+        {
+            self.lint_diagnostics.push(diagnostic.clone());
+        }
+
         // We call TRACK_DIAGNOSTIC with an empty closure for the cases that
         // return early *and* have some kind of side-effect, except where
         // noted.
@@ -11,6 +11,9 @@ expand_collapse_debuginfo_illegal =
 expand_count_repetition_misplaced =
     `count` can not be placed inside the innermost repetition
 
+expand_couldnt_dump_macro_rules_trace =
+    unexpected error occurred while dumping the `macro_rules!` trace: {$error}
+
 expand_crate_name_in_cfg_attr =
     `crate_name` within an `#![cfg_attr]` attribute is forbidden
 
@@ -178,6 +181,13 @@ expand_proc_macro_panicked =
     proc macro panicked
     .help = message: {$message}
 
+expand_proc_macro_sandbox_unsupported =
+    `-Zproc-macro-execution-strategy=sandboxed` is not supported: {$reason}
+
+expand_proc_macro_sandbox_violation =
+    sandboxed proc macro {$violation}
+    .help = files read by proc macros must be declared with `proc_macro::tracked::path`
+
 expand_recursion_limit_reached =
     recursion limit reached while expanding `{$descr}`
     .help = consider increasing the recursion limit by adding a `#![recursion_limit = "{$suggested_limit}"]` attribute to your crate (`{$crate_name}`)
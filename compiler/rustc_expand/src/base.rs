@@ -11,7 +11,7 @@
 use rustc_ast::tokenstream::TokenStream;
 use rustc_ast::visit::{AssocCtxt, Visitor};
 use rustc_ast::{self as ast, AttrVec, Attribute, HasAttrs, Item, NodeId, PatKind, Safety};
-use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+use rustc_data_structures::fx::FxIndexMap;
 use rustc_data_structures::sync;
 use rustc_errors::{BufferedEarlyLint, DiagCtxtHandle, ErrorGuaranteed, PResult};
 use rustc_feature::Features;
@@ -24,7 +24,9 @@
 use rustc_parse::MACRO_ARGUMENTS;
 use rustc_parse::parser::{ForceCollect, Parser};
 use rustc_session::Session;
-use rustc_session::config::CollapseMacroDebuginfo;
+use rustc_session::config::{
+    CollapseMacroDebuginfo, ProcMacroExecutionStrategy, SwitchWithOptPath,
+};
 use rustc_session::parse::ParseSess;
 use rustc_span::def_id::{CrateNum, DefId, LocalDefId};
 use rustc_span::edition::Edition;
@@ -38,8 +40,10 @@
 use crate::errors;
 use crate::expand::{self, AstFragment, Invocation};
 use crate::mbe::macro_rules::ParserAnyMacro;
+use crate::mbe::trace::MacroRulesTrace;
 use crate::module::DirOwnership;
-use crate::stats::MacroStat;
+use crate::proc_macro_cache::ProcMacroCache;
+use crate::proc_macro_sandbox::ProcMacroSandbox;
 
 // When adding new variants, make sure to
 // adjust the `visit_*` / `flat_map_*` calls in `InvocationCollector`
@@ -1273,9 +1277,13 @@ pub struct ExtCtxt<'a> {
     /// in the AST, but insert it here so that we know
     /// not to expand it again.
     pub(super) expanded_inert_attrs: MarkedAttrs,
-    /// `-Zmacro-stats` data.
-    pub macro_stats: FxHashMap<(Symbol, MacroKind), MacroStat>,
     pub nb_macro_errors: usize,
+    /// Outputs of `#![proc_macro_pure]` proc macros, persisted across incremental sessions.
+    pub(crate) proc_macro_cache: ProcMacroCache,
+    /// Every `macro_rules!` expansion, recorded when `-Zdump-macro-rules-trace` is enabled.
+    pub(crate) macro_rules_trace: Option<MacroRulesTrace>,
+    /// The sandboxed proc macro processes, with `-Zproc-macro-execution-strategy=sandboxed`.
+    pub(crate) proc_macro_sandbox: Option<Rc<ProcMacroSandbox>>,
 }
 
 impl<'a> ExtCtxt<'a> {
@@ -1305,8 +1313,17 @@ pub fn new(
             expansions: FxIndexMap::default(),
             expanded_inert_attrs: MarkedAttrs::new(),
             buffered_early_lint: vec![],
-            macro_stats: Default::default(),
             nb_macro_errors: 0,
+            proc_macro_cache: Default::default(),
+            macro_rules_trace: matches!(
+                sess.opts.unstable_opts.dump_macro_rules_trace,
+                SwitchWithOptPath::Enabled(_)
+            )
+            .then(Default::default),
+            proc_macro_sandbox: (sess.opts.unstable_opts.proc_macro_execution_strategy
+                == ProcMacroExecutionStrategy::Sandboxed)
+                .then(|| ProcMacroSandbox::new(sess).map(Rc::new))
+                .flatten(),
         }
     }
 
@@ -1324,6 +1341,17 @@ pub fn expander<'b>(&'b mut self) -> expand::MacroExpander<'b, 'a> {
     pub fn monotonic_expander<'b>(&'b mut self) -> expand::MacroExpander<'b, 'a> {
         expand::MacroExpander::new(self, true)
     }
+    /// Saves the outputs of cacheable proc macros for the next incremental session.
+    pub fn save_proc_macro_cache(&self) {
+        self.proc_macro_cache.save(self.sess);
+    }
+    /// Writes the `macro_rules!` trace collected for `-Zdump-macro-rules-trace`.
+    pub fn dump_macro_rules_trace(&mut self) {
+        if let Some(trace) = self.macro_rules_trace.take() {
+            trace.dump(self);
+        }
+    }
+
     pub fn new_parser_from_tts(&self, stream: TokenStream) -> Parser<'a> {
         Parser::new(&self.sess.psess, stream, MACRO_ARGUMENTS)
     }
@@ -78,6 +78,12 @@ pub(crate) struct ResolveRelativePath {
     pub path: String,
 }
 
+#[derive(Diagnostic)]
+#[diag(expand_couldnt_dump_macro_rules_trace)]
+pub(crate) struct CouldntDumpMacroRulesTrace {
+    pub error: String,
+}
+
 #[derive(Diagnostic)]
 #[diag(expand_collapse_debuginfo_illegal)]
 pub(crate) struct CollapseMacroDebuginfoIllegal {
@@ -326,6 +332,22 @@ pub(crate) struct ProcMacroPanickedHelp {
     pub message: String,
 }
 
+#[derive(Diagnostic)]
+#[diag(expand_proc_macro_sandbox_violation)]
+pub(crate) struct ProcMacroSandboxViolation {
+    #[primary_span]
+    pub span: Span,
+    pub violation: String,
+    #[help]
+    pub undeclared_file: bool,
+}
+
+#[derive(Diagnostic)]
+#[diag(expand_proc_macro_sandbox_unsupported)]
+pub(crate) struct ProcMacroSandboxUnsupported {
+    pub reason: String,
+}
+
 #[derive(Diagnostic)]
 #[diag(expand_proc_macro_derive_panicked)]
 pub(crate) struct ProcMacroDerivePanicked {
@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Instant;
 use std::{iter, mem, slice};
 
 use rustc_ast::mut_visit::*;
@@ -736,6 +737,7 @@ fn expand_invoc(
         }
 
         let macro_stats = self.cx.sess.opts.unstable_opts.macro_stats;
+        let start = Instant::now();
 
         let (fragment_kind, span) = (invoc.fragment_kind, invoc.span());
         ExpandResult::Ready(match invoc.kind {
@@ -752,6 +754,7 @@ fn expand_invoc(
                                     span,
                                     mac,
                                     &fragment,
+                                    start.elapsed(),
                                 );
                             }
                             fragment
@@ -771,7 +774,14 @@ fn expand_invoc(
                     };
                     if let Some(fragment) = fragment_kind.make_from(tok_result) {
                         if macro_stats {
-                            update_bang_macro_stats(self.cx, fragment_kind, span, mac, &fragment);
+                            update_bang_macro_stats(
+                                self.cx,
+                                fragment_kind,
+                                span,
+                                mac,
+                                &fragment,
+                                start.elapsed(),
+                            );
                         }
                         fragment
                     } else {
@@ -834,6 +844,7 @@ fn expand_invoc(
                                     &attr,
                                     item,
                                     &fragment,
+                                    start.elapsed(),
                                 );
                             }
                             fragment
@@ -875,6 +886,7 @@ fn expand_invoc(
                                         &attr,
                                         item_clone.unwrap(),
                                         &fragment,
+                                        start.elapsed(),
                                     );
                                 }
                                 fragment
@@ -926,6 +938,7 @@ fn expand_invoc(
                             span,
                             &meta.path,
                             &fragment,
+                            start.elapsed(),
                         );
                     }
                     fragment
@@ -952,6 +965,7 @@ fn expand_invoc(
                                     span,
                                     &path,
                                     &fragment,
+                                    start.elapsed(),
                                 );
                             }
                             fragment
@@ -17,6 +17,7 @@
 #[allow(rustc::untranslatable_diagnostic)]
 mod mbe;
 mod placeholders;
+mod proc_macro_cache;
 mod proc_macro_server;
 mod stats;
 
@@ -28,5 +29,6 @@
 // FIXME(Nilstrieb) Translate proc_macro diagnostics
 #[allow(rustc::untranslatable_diagnostic)]
 pub mod proc_macro;
+pub mod proc_macro_sandbox;
 
 rustc_fluent_macro::fluent_messages! { "../messages.ftl" }
@@ -5,6 +5,7 @@
 
 pub(crate) mod diagnostics;
 pub(crate) mod macro_rules;
+pub(crate) mod trace;
 
 mod macro_check;
 mod macro_parser;
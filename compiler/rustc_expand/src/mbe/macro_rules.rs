@@ -25,12 +25,13 @@
 use rustc_session::Session;
 use rustc_session::parse::{ParseSess, feature_err};
 use rustc_span::edition::Edition;
-use rustc_span::hygiene::Transparency;
+use rustc_span::hygiene::{MacroKind, Transparency};
 use rustc_span::{Ident, Span, Symbol, kw, sym};
 use tracing::{debug, instrument, trace, trace_span};
 
 use super::diagnostics::{FailedMacro, failed_to_match_macro};
 use super::macro_parser::{NamedMatches, NamedParseResult};
+use super::trace::{ExpansionTracer, MATCH_ERROR, NO_ARM_MATCHED, TRANSCRIBE_FAILED};
 use super::{SequenceRepetition, diagnostics};
 use crate::base::{
     AttrProcMacro, BangProcMacro, DummyResult, ExpandResult, ExtCtxt, MacResult,
@@ -184,7 +185,21 @@ pub fn expand_derive(
             trace_macros_note(&mut cx.expansions, sp, msg);
         }
 
-        match try_match_macro_derive(psess, name, body, rules, &mut NoopTracker) {
+        let mut tracer = ExpansionTracer::new(
+            cx,
+            sp,
+            name,
+            MacroKind::Derive,
+            || pprust::tts_to_string(body),
+            rules,
+        );
+
+        let try_success_result = match &mut tracer {
+            Some(tracer) => try_match_macro_derive(psess, name, body, rules, tracer),
+            None => try_match_macro_derive(psess, name, body, rules, &mut NoopTracker),
+        };
+
+        match try_success_result {
             Ok((rule_index, rule, named_matches)) => {
                 let MacroRule::Derive { rhs, .. } = rule else {
                     panic!("try_match_macro_derive returned non-derive rule");
@@ -192,15 +207,36 @@ pub fn expand_derive(
                 let mbe::TokenTree::Delimited(rhs_span, _, rhs) = rhs else {
                     cx.dcx().span_bug(sp, "malformed macro derive rhs");
                 };
+                if let Some(tracer) = &mut tracer {
+                    tracer.matched(rule_index, &named_matches);
+                }
 
                 let id = cx.current_expansion.id;
-                let tts = transcribe(psess, &named_matches, rhs, *rhs_span, self.transparency, id)
-                    .map_err(|e| e.emit())?;
+                let tts = match transcribe(
+                    psess,
+                    &named_matches,
+                    rhs,
+                    *rhs_span,
+                    self.transparency,
+                    id,
+                ) {
+                    Ok(tts) => tts,
+                    Err(err) => {
+                        let guar = err.emit();
+                        if let Some(tracer) = tracer {
+                            tracer.finish(cx, Err(TRANSCRIBE_FAILED));
+                        }
+                        return Err(guar);
+                    }
+                };
 
                 if cx.trace_macros() {
                     let msg = format!("to `{}`", pprust::tts_to_string(&tts));
                     trace_macros_note(&mut cx.expansions, sp, msg);
                 }
+                if let Some(tracer) = tracer {
+                    tracer.finish(cx, Ok(&tts));
+                }
 
                 if is_defined_in_current_crate(node_id) {
                     cx.resolver.record_macro_rule_usage(node_id, rule_index);
@@ -208,8 +244,16 @@ pub fn expand_derive(
 
                 Ok(tts)
             }
-            Err(CanRetry::No(guar)) => Err(guar),
+            Err(CanRetry::No(guar)) => {
+                if let Some(tracer) = tracer {
+                    tracer.finish(cx, Err(MATCH_ERROR));
+                }
+                Err(guar)
+            }
             Err(CanRetry::Yes) => {
+                if let Some(tracer) = tracer {
+                    tracer.finish(cx, Err(NO_ARM_MATCHED));
+                }
                 let (_, guar) = failed_to_match_macro(
                     cx.psess(),
                     sp,
@@ -307,6 +351,9 @@ pub(super) trait Tracker<'matcher> {
     /// The usize is the approximate position of the token in the input token stream.
     fn build_failure(tok: Token, position: u32, msg: &'static str) -> Self::Failure;
 
+    /// This is called before trying to match the rule with the given index.
+    fn before_arm(&mut self, _index: usize) {}
+
     /// This is called before trying to match next MatcherLoc on the current token.
     fn before_match_loc(&mut self, _parser: &TtParser, _matcher: &'matcher MatcherLoc) {}
 
@@ -355,8 +402,14 @@ fn expand_macro<'cx>(
         trace_macros_note(&mut cx.expansions, sp, msg);
     }
 
-    // Track nothing for the best performance.
-    let try_success_result = try_match_macro(psess, name, &arg, rules, &mut NoopTracker);
+    let mut tracer =
+        ExpansionTracer::new(cx, sp, name, MacroKind::Bang, || pprust::tts_to_string(&arg), rules);
+
+    // Track nothing for the best performance, unless the expansion is being traced.
+    let try_success_result = match &mut tracer {
+        Some(tracer) => try_match_macro(psess, name, &arg, rules, tracer),
+        None => try_match_macro(psess, name, &arg, rules, &mut NoopTracker),
+    };
 
     match try_success_result {
         Ok((rule_index, rule, named_matches)) => {
@@ -367,6 +420,9 @@ fn expand_macro<'cx>(
                 cx.dcx().span_bug(sp, "malformed macro rhs");
             };
             let arm_span = rhs_span.entire();
+            if let Some(tracer) = &mut tracer {
+                tracer.matched(rule_index, &named_matches);
+            }
 
             // rhs has holes ( `$id` and `$(...)` that need filled)
             let id = cx.current_expansion.id;
@@ -374,6 +430,9 @@ fn expand_macro<'cx>(
                 Ok(tts) => tts,
                 Err(err) => {
                     let guar = err.emit();
+                    if let Some(tracer) = tracer {
+                        tracer.finish(cx, Err(TRANSCRIBE_FAILED));
+                    }
                     return DummyResult::any(arm_span, guar);
                 }
             };
@@ -382,6 +441,9 @@ fn expand_macro<'cx>(
                 let msg = format!("to `{}`", pprust::tts_to_string(&tts));
                 trace_macros_note(&mut cx.expansions, sp, msg);
             }
+            if let Some(tracer) = tracer {
+                tracer.finish(cx, Ok(&tts));
+            }
 
             let is_local = is_defined_in_current_crate(node_id);
             if is_local {
@@ -393,9 +455,15 @@ fn expand_macro<'cx>(
         }
         Err(CanRetry::No(guar)) => {
             debug!("Will not retry matching as an error was emitted already");
+            if let Some(tracer) = tracer {
+                tracer.finish(cx, Err(MATCH_ERROR));
+            }
             DummyResult::any(sp, guar)
         }
         Err(CanRetry::Yes) => {
+            if let Some(tracer) = tracer {
+                tracer.finish(cx, Err(NO_ARM_MATCHED));
+            }
             // Retry and emit a better error.
             let (span, guar) = failed_to_match_macro(
                 cx.psess(),
@@ -440,8 +508,22 @@ fn expand_macro_attr(
         trace_macros_note(&mut cx.expansions, sp, msg);
     }
 
-    // Track nothing for the best performance.
-    match try_match_macro_attr(psess, name, &args, &body, rules, &mut NoopTracker) {
+    let mut tracer = ExpansionTracer::new(
+        cx,
+        sp,
+        name,
+        MacroKind::Attr,
+        || format!("#[{name}({})] {}", pprust::tts_to_string(&args), pprust::tts_to_string(&body)),
+        rules,
+    );
+
+    // Track nothing for the best performance, unless the expansion is being traced.
+    let try_success_result = match &mut tracer {
+        Some(tracer) => try_match_macro_attr(psess, name, &args, &body, rules, tracer),
+        None => try_match_macro_attr(psess, name, &args, &body, rules, &mut NoopTracker),
+    };
+
+    match try_success_result {
         Ok((i, rule, named_matches)) => {
             let MacroRule::Attr { rhs, unsafe_rule, .. } = rule else {
                 panic!("try_macro_match_attr returned non-attr rule");
@@ -449,6 +531,9 @@ fn expand_macro_attr(
             let mbe::TokenTree::Delimited(rhs_span, _, rhs) = rhs else {
                 cx.dcx().span_bug(sp, "malformed macro rhs");
             };
+            if let Some(tracer) = &mut tracer {
+                tracer.matched(i, &named_matches);
+            }
 
             match (safety, unsafe_rule) {
                 (Safety::Default, false) | (Safety::Unsafe(_), true) => {}
@@ -464,13 +549,24 @@ fn expand_macro_attr(
             }
 
             let id = cx.current_expansion.id;
-            let tts = transcribe(psess, &named_matches, rhs, *rhs_span, transparency, id)
-                .map_err(|e| e.emit())?;
+            let tts = match transcribe(psess, &named_matches, rhs, *rhs_span, transparency, id) {
+                Ok(tts) => tts,
+                Err(err) => {
+                    let guar = err.emit();
+                    if let Some(tracer) = tracer {
+                        tracer.finish(cx, Err(TRANSCRIBE_FAILED));
+                    }
+                    return Err(guar);
+                }
+            };
 
             if cx.trace_macros() {
                 let msg = format!("to `{}`", pprust::tts_to_string(&tts));
                 trace_macros_note(&mut cx.expansions, sp, msg);
             }
+            if let Some(tracer) = tracer {
+                tracer.finish(cx, Ok(&tts));
+            }
 
             if is_local {
                 cx.resolver.record_macro_rule_usage(node_id, i);
@@ -478,8 +574,16 @@ fn expand_macro_attr(
 
             Ok(tts)
         }
-        Err(CanRetry::No(guar)) => Err(guar),
+        Err(CanRetry::No(guar)) => {
+            if let Some(tracer) = tracer {
+                tracer.finish(cx, Err(MATCH_ERROR));
+            }
+            Err(guar)
+        }
         Err(CanRetry::Yes) => {
+            if let Some(tracer) = tracer {
+                tracer.finish(cx, Err(NO_ARM_MATCHED));
+            }
             // Retry and emit a better error.
             let (_, guar) = failed_to_match_macro(
                 cx.psess(),
@@ -538,6 +642,7 @@ pub(super) fn try_match_macro<'matcher, T: Tracker<'matcher>>(
     for (i, rule) in rules.iter().enumerate() {
         let MacroRule::Func { lhs, .. } = rule else { continue };
         let _tracing_span = trace_span!("Matching arm", %i);
+        track.before_arm(i);
 
         // Take a snapshot of the state of pre-expansion gating at this point.
         // This is used so that if a matcher is not `Success(..)`ful,
@@ -600,6 +705,7 @@ pub(super) fn try_match_macro_attr<'matcher, T: Tracker<'matcher>>(
     let mut tt_parser = TtParser::new(name);
     for (i, rule) in rules.iter().enumerate() {
         let MacroRule::Attr { args, body, .. } = rule else { continue };
+        track.before_arm(i);
 
         let mut gated_spans_snapshot = mem::take(&mut *psess.gated_spans.spans.borrow_mut());
 
@@ -653,6 +759,7 @@ pub(super) fn try_match_macro_derive<'matcher, T: Tracker<'matcher>>(
     let mut tt_parser = TtParser::new(name);
     for (i, rule) in rules.iter().enumerate() {
         let MacroRule::Derive { body, .. } = rule else { continue };
+        track.before_arm(i);
 
         let mut gated_spans_snapshot = mem::take(&mut *psess.gated_spans.spans.borrow_mut());
 
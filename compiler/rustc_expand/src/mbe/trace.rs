@@ -0,0 +1,338 @@
+//! Structured tracing of `macro_rules!` expansions for `-Zdump-macro-rules-trace`.
+//!
+//! Unlike `trace_macros!`, which prints a flat list of inputs and outputs, this records every
+//! step of matching: which arms were tried, why each failed arm failed, which metavariables
+//! the matching arm bound and what tokens were produced. Expansions are nested under the
+//! expansion that produced their invocation, so recursive macros show up as a tree.
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use rustc_ast::token::{self, Token};
+use rustc_ast::tokenstream::TokenStream;
+use rustc_ast_pretty::pprust::{self, PrintState, State};
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+use rustc_parse::parser::{ParseNtResult, token_descr};
+use rustc_session::config::SwitchWithOptPath;
+use rustc_span::hygiene::{ExpnId, LocalExpnId, MacroKind};
+use rustc_span::{Ident, Span};
+use serde::Serialize;
+
+use super::macro_parser::{MatcherLoc, NamedMatch, NamedMatches, NamedParseResult, TtParser};
+use super::macro_rules::{MacroRule, Tracker};
+use crate::base::ExtCtxt;
+use crate::errors;
+use crate::mbe::macro_parser::ParseResult::*;
+
+/// Reasons recorded for expansions that did not produce any tokens.
+pub(super) const NO_ARM_MATCHED: &str = "no arm matched the input";
+pub(super) const MATCH_ERROR: &str = "an error was reported while matching";
+pub(super) const TRANSCRIBE_FAILED: &str = "the matched arm could not be transcribed";
+
+/// All `macro_rules!` expansions traced so far, in the order they were expanded.
+#[derive(Default)]
+pub(crate) struct MacroRulesTrace {
+    expansions: FxIndexMap<LocalExpnId, TracedExpansion>,
+}
+
+struct TracedExpansion {
+    /// The closest traced expansion that this one was invoked from.
+    parent: Option<LocalExpnId>,
+    node: ExpansionNode,
+}
+
+#[derive(Serialize)]
+struct ExpansionNode {
+    name: String,
+    kind: &'static str,
+    call_site: String,
+    input: String,
+    arms: Vec<ArmAttempt>,
+    matched_arm: Option<usize>,
+    bindings: Vec<Binding>,
+    output: Option<String>,
+    error: Option<String>,
+    children: Vec<ExpansionNode>,
+}
+
+#[derive(Serialize)]
+struct ArmAttempt {
+    index: usize,
+    matcher: String,
+    outcome: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failure: Option<ArmFailure>,
+}
+
+#[derive(Serialize)]
+struct ArmFailure {
+    /// Whether the failure happened while matching the arguments of an attribute rule, as
+    /// opposed to its body (or the input of a function-like or derive rule).
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    in_attr_args: bool,
+    found: String,
+    expected: Option<String>,
+    message: String,
+    /// Approximate position of `found` in the input token stream.
+    position: u32,
+}
+
+#[derive(Serialize)]
+struct Binding {
+    name: String,
+    value: BoundValue,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum BoundValue {
+    Single(String),
+    Repetition(Vec<BoundValue>),
+}
+
+impl BoundValue {
+    fn new(named_match: &NamedMatch) -> BoundValue {
+        match named_match {
+            NamedMatch::MatchedSeq(matches) => {
+                BoundValue::Repetition(matches.iter().map(BoundValue::new).collect())
+            }
+            NamedMatch::MatchedSingle(pnr) => BoundValue::Single(match pnr {
+                ParseNtResult::Tt(tt) => pprust::tt_to_string(tt),
+                ParseNtResult::Ident(ident, is_raw) => pprust::token_to_string(&Token::new(
+                    token::NtIdent(*ident, *is_raw),
+                    ident.span,
+                ))
+                .into_owned(),
+                ParseNtResult::Lifetime(ident, is_raw) => pprust::token_to_string(&Token::new(
+                    token::NtLifetime(*ident, *is_raw),
+                    ident.span,
+                ))
+                .into_owned(),
+                ParseNtResult::Item(item) => pprust::item_to_string(item),
+                ParseNtResult::Block(block) => State::new().block_to_string(block),
+                ParseNtResult::Stmt(stmt) => pprust::stmt_to_string(stmt),
+                ParseNtResult::Pat(pat, _) => pprust::pat_to_string(pat),
+                ParseNtResult::Expr(expr, _) | ParseNtResult::Literal(expr) => {
+                    pprust::expr_to_string(expr)
+                }
+                ParseNtResult::Ty(ty) => pprust::ty_to_string(ty),
+                ParseNtResult::Meta(attr_item) => State::new().attr_item_to_string(attr_item),
+                ParseNtResult::Path(path) => pprust::path_to_string(path),
+                ParseNtResult::Vis(vis) => pprust::vis_to_string(vis),
+            }),
+        }
+    }
+}
+
+/// Records a single expansion while it is being matched and transcribed. It is also the
+/// [`Tracker`] used for matching, so that failed arms are recorded on the hot path without
+/// having to retry the match.
+pub(super) struct ExpansionTracer<'matcher> {
+    node: ExpansionNode,
+    current_arm: usize,
+    remaining_matcher: Option<&'matcher MatcherLoc>,
+    rules: &'matcher [MacroRule],
+}
+
+impl<'matcher> ExpansionTracer<'matcher> {
+    /// Returns a tracer if `-Zdump-macro-rules-trace` is enabled.
+    pub(super) fn new(
+        cx: &ExtCtxt<'_>,
+        call_site: Span,
+        name: Ident,
+        kind: MacroKind,
+        input: impl FnOnce() -> String,
+        rules: &'matcher [MacroRule],
+    ) -> Option<Self> {
+        if cx.macro_rules_trace.is_none() {
+            return None;
+        }
+        Some(ExpansionTracer {
+            node: ExpansionNode {
+                name: name.to_string(),
+                kind: kind.descr(),
+                call_site: cx.source_map().span_to_diagnostic_string(call_site),
+                input: input(),
+                arms: Vec::new(),
+                matched_arm: None,
+                bindings: Vec::new(),
+                output: None,
+                error: None,
+                children: Vec::new(),
+            },
+            current_arm: 0,
+            remaining_matcher: None,
+            rules,
+        })
+    }
+
+    /// Records the arm that matched and the metavariables it bound.
+    pub(super) fn matched(&mut self, index: usize, named_matches: &NamedMatches) {
+        self.node.matched_arm = Some(index);
+        #[allow(rustc::potential_query_instability)]
+        let mut bindings: Vec<_> = named_matches
+            .iter()
+            .map(|(name, named_match)| Binding {
+                name: format!("${name}"),
+                value: BoundValue::new(named_match),
+            })
+            .collect();
+        bindings.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        self.node.bindings = bindings;
+    }
+
+    /// Finishes the expansion with either the produced tokens or a description of what went
+    /// wrong, and adds it to the trace.
+    pub(super) fn finish(mut self, cx: &mut ExtCtxt<'_>, output: Result<&TokenStream, &str>) {
+        match output {
+            Ok(tts) => self.node.output = Some(pprust::tts_to_string(tts)),
+            Err(error) => self.node.error = Some(error.to_owned()),
+        }
+        let sm = cx.source_map();
+        for arm in &mut self.node.arms {
+            let span = match self.rules[arm.index] {
+                MacroRule::Func { lhs_span, .. } => lhs_span,
+                MacroRule::Attr { args_span, body_span, .. } => args_span.to(body_span),
+                MacroRule::Derive { body_span, .. } => body_span,
+            };
+            arm.matcher =
+                sm.span_to_snippet(span).unwrap_or_else(|_| sm.span_to_diagnostic_string(span));
+        }
+
+        let id = cx.current_expansion.id;
+        let Some(trace) = &mut cx.macro_rules_trace else { return };
+        let mut parent = id.expn_data().parent;
+        let parent = loop {
+            match parent.as_local() {
+                Some(local) if trace.expansions.contains_key(&local) => break Some(local),
+                _ if parent == ExpnId::root() => break None,
+                _ => parent = parent.expn_data().parent,
+            }
+        };
+        trace.expansions.insert(id, TracedExpansion { parent, node: self.node });
+    }
+
+    fn last_arm(&mut self) -> &mut ArmAttempt {
+        if self.node.arms.last().is_none_or(|arm| arm.index != self.current_arm) {
+            self.node.arms.push(ArmAttempt {
+                index: self.current_arm,
+                matcher: String::new(),
+                outcome: "pending",
+                failure: None,
+            });
+        }
+        self.node.arms.last_mut().unwrap()
+    }
+}
+
+impl<'matcher> Tracker<'matcher> for ExpansionTracer<'matcher> {
+    type Failure = (Token, u32, &'static str);
+
+    fn build_failure(tok: Token, position: u32, msg: &'static str) -> Self::Failure {
+        (tok, position, msg)
+    }
+
+    fn before_arm(&mut self, index: usize) {
+        self.current_arm = index;
+        self.remaining_matcher = None;
+    }
+
+    fn before_match_loc(&mut self, parser: &TtParser, matcher: &'matcher MatcherLoc) {
+        // Same heuristic as the diagnostics tracker: remember the first matcher location that
+        // could not make any progress, that is what the arm expected instead of the token.
+        if self.remaining_matcher.is_none()
+            || (parser.has_no_remaining_items_for_step() && *matcher != MatcherLoc::Eof)
+        {
+            self.remaining_matcher = Some(matcher);
+        }
+    }
+
+    fn after_arm(&mut self, in_body: bool, result: &NamedParseResult<Self::Failure>) {
+        let remaining_matcher = self.remaining_matcher.take();
+        let arm = self.last_arm();
+        match result {
+            Success(_) => {
+                // Attribute rules match their arguments first, and only then their body.
+                arm.outcome = if in_body { "matched" } else { "pending" };
+            }
+            Failure((token, position, msg)) => {
+                arm.outcome = "failed";
+                arm.failure = Some(ArmFailure {
+                    in_attr_args: !in_body,
+                    found: token_descr(token),
+                    expected: remaining_matcher.map(|matcher| matcher.to_string()),
+                    message: (*msg).to_owned(),
+                    position: *position,
+                });
+            }
+            Error(_, msg) => {
+                arm.outcome = "error";
+                arm.failure = Some(ArmFailure {
+                    in_attr_args: !in_body,
+                    found: String::new(),
+                    expected: None,
+                    message: msg.clone(),
+                    position: 0,
+                });
+            }
+            ErrorReported(_) => arm.outcome = "error",
+        }
+    }
+
+    fn description() -> &'static str {
+        "trace"
+    }
+}
+
+impl MacroRulesTrace {
+    /// Writes the trace as `<crate>.macro_rules_trace.json` into the directory given to
+    /// `-Zdump-macro-rules-trace`, or the current directory.
+    pub(crate) fn dump(self, cx: &ExtCtxt<'_>) {
+        let SwitchWithOptPath::Enabled(ref dir) = cx.sess.opts.unstable_opts.dump_macro_rules_trace
+        else {
+            return;
+        };
+        if let Err(err) = self.write(dir.as_deref(), cx.ecfg.crate_name.as_str()) {
+            cx.dcx().emit_err(errors::CouldntDumpMacroRulesTrace { error: err.to_string() });
+        }
+    }
+
+    fn write(self, dir: Option<&Path>, crate_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let dir = if let Some(dir) = dir {
+            fs::create_dir_all(dir)?;
+            dir
+        } else {
+            Path::new(".")
+        };
+
+        // Children are always expanded after their parent, so walking the expansions backwards
+        // finishes every node before it is attached to its parent.
+        let mut children: FxHashMap<LocalExpnId, Vec<ExpansionNode>> = Default::default();
+        let mut roots = Vec::new();
+        for (id, TracedExpansion { parent, mut node }) in self.expansions.into_iter().rev() {
+            if let Some(mut nested) = children.remove(&id) {
+                nested.reverse();
+                node.children = nested;
+            }
+            match parent {
+                Some(parent) => children.entry(parent).or_default().push(node),
+                None => roots.push(node),
+            }
+        }
+        roots.reverse();
+
+        #[derive(Serialize)]
+        struct Report<'a> {
+            #[serde(rename = "crate")]
+            krate: &'a str,
+            expansions: Vec<ExpansionNode>,
+        }
+
+        let path = dir.join(format!("{crate_name}.macro_rules_trace.json"));
+        let mut file = File::create_buffered(path)?;
+        serde_json::to_writer_pretty(&mut file, &Report { krate: crate_name, expansions: roots })?;
+        file.flush()?;
+        Ok(())
+    }
+}
@@ -1,12 +1,19 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
 use rustc_ast::tokenstream::TokenStream;
+use rustc_data_structures::svh::Svh;
 use rustc_errors::ErrorGuaranteed;
 use rustc_parse::parser::{ForceCollect, Parser};
 use rustc_session::config::ProcMacroExecutionStrategy;
-use rustc_span::Span;
+use rustc_span::hygiene::MacroKind;
 use rustc_span::profiling::SpannedEventArgRecorder;
+use rustc_span::{Span, Symbol};
 use {rustc_ast as ast, rustc_proc_macro as pm};
 
 use crate::base::{self, *};
+use crate::proc_macro_cache::CacheableExpansion;
+use crate::proc_macro_sandbox::SandboxedExecution;
 use crate::{errors, proc_macro_server};
 
 struct MessagePipe<T> {
@@ -30,15 +37,104 @@ fn recv(&mut self) -> Option<T> {
     }
 }
 
-fn exec_strategy(ecx: &ExtCtxt<'_>) -> impl pm::bridge::server::ExecutionStrategy + 'static {
-    pm::bridge::server::MaybeCrossThread::<MessagePipe<_>>::new(
+enum ExecStrategy<'a> {
+    InProcess(pm::bridge::server::MaybeCrossThread<MessagePipe<pm::bridge::Buffer>>),
+    Sandboxed(SandboxedExecution<'a>),
+}
+
+impl pm::bridge::server::ExecutionStrategy for ExecStrategy<'_> {
+    fn run_bridge_and_client(
+        &self,
+        dispatcher: &mut impl pm::bridge::server::DispatcherTrait,
+        input: pm::bridge::Buffer,
+        run_client: extern "C" fn(pm::bridge::BridgeConfig<'_>) -> pm::bridge::Buffer,
+        force_show_panics: bool,
+    ) -> pm::bridge::Buffer {
+        match self {
+            ExecStrategy::InProcess(strategy) => {
+                strategy.run_bridge_and_client(dispatcher, input, run_client, force_show_panics)
+            }
+            ExecStrategy::Sandboxed(strategy) => {
+                strategy.run_bridge_and_client(dispatcher, input, run_client, force_show_panics)
+            }
+        }
+    }
+}
+
+fn exec_strategy<'a>(
+    ecx: &ExtCtxt<'a>,
+    dylib_entry: Option<&ProcMacroDylibEntry>,
+    span: Span,
+) -> ExecStrategy<'a> {
+    // Proc macros built into the compiler, like `quote!`, are not loaded from a dylib and can't
+    // be sandboxed, but they are trusted anyway.
+    if let Some(sandbox) = &ecx.proc_macro_sandbox
+        && let Some(dylib_entry) = dylib_entry
+    {
+        return ExecStrategy::Sandboxed(SandboxedExecution::new(
+            ecx,
+            sandbox,
+            dylib_entry.clone(),
+            span,
+        ));
+    }
+    ExecStrategy::InProcess(pm::bridge::server::MaybeCrossThread::new(
         ecx.sess.opts.unstable_opts.proc_macro_execution_strategy
             == ProcMacroExecutionStrategy::CrossThread,
-    )
+    ))
+}
+
+/// The dynamic library a proc macro crate was loaded from, and the symbol of its
+/// `&[ProcMacro]` declarations, so that it can be loaded again in a sandboxed process.
+#[derive(Debug)]
+pub struct ProcMacroDylib {
+    pub path: PathBuf,
+    pub decls_symbol: String,
+}
+
+/// A single proc macro of a [`ProcMacroDylib`], identified by its index in the declarations.
+#[derive(Clone, Debug)]
+pub struct ProcMacroDylibEntry {
+    pub dylib: Arc<ProcMacroDylib>,
+    pub index: usize,
+}
+
+/// Identifies a proc macro from a crate marked with `#![proc_macro_pure]`, whose outputs can be
+/// cached across incremental sessions.
+#[derive(Clone, Copy, Debug)]
+pub struct CacheableProcMacro {
+    pub crate_hash: Svh,
+    pub name: Symbol,
+}
+
+/// Runs `expand`, unless the output of an identical earlier expansion can be reused.
+fn expand_cached<E>(
+    ecx: &mut ExtCtxt<'_>,
+    cacheable: Option<CacheableProcMacro>,
+    kind: MacroKind,
+    inputs: &[&TokenStream],
+    expand: impl FnOnce(&mut ExtCtxt<'_>) -> Result<TokenStream, E>,
+) -> Result<TokenStream, E> {
+    let expansion = cacheable.and_then(|mac| CacheableExpansion::new(ecx, mac, kind, inputs));
+    if let Some(expansion) = &expansion
+        && let Some(output) = ecx.proc_macro_cache.lookup(ecx.sess, expansion)
+    {
+        return Ok(output);
+    }
+
+    let outer = ecx.proc_macro_cache.start_tracking(expansion.is_some());
+    let output = expand(ecx);
+    let tracked = ecx.proc_macro_cache.finish_tracking(outer);
+    if let (Some(expansion), Some(tracked), Ok(output)) = (expansion, tracked, &output) {
+        ecx.proc_macro_cache.store(expansion, tracked, output);
+    }
+    output
 }
 
 pub struct BangProcMacro {
     pub client: pm::bridge::client::Client<pm::TokenStream, pm::TokenStream>,
+    pub cacheable: Option<CacheableProcMacro>,
+    pub dylib_entry: Option<ProcMacroDylibEntry>,
 }
 
 impl base::BangProcMacro for BangProcMacro {
@@ -53,15 +149,17 @@ fn expand(
                 recorder.record_arg_with_span(ecx.sess.source_map(), ecx.expansion_descr(), span);
             });
 
-        let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
-        let strategy = exec_strategy(ecx);
-        let server = proc_macro_server::Rustc::new(ecx);
-        self.client.run(&strategy, server, input, proc_macro_backtrace).map_err(|e| {
-            ecx.dcx().emit_err(errors::ProcMacroPanicked {
-                span,
-                message: e
-                    .as_str()
-                    .map(|message| errors::ProcMacroPanickedHelp { message: message.into() }),
+        expand_cached(ecx, self.cacheable, MacroKind::Bang, &[&input], |ecx| {
+            let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
+            let strategy = exec_strategy(ecx, self.dylib_entry.as_ref(), span);
+            let server = proc_macro_server::Rustc::new(ecx);
+            self.client.run(&strategy, server, input.clone(), proc_macro_backtrace).map_err(|e| {
+                ecx.dcx().emit_err(errors::ProcMacroPanicked {
+                    span,
+                    message: e
+                        .as_str()
+                        .map(|message| errors::ProcMacroPanickedHelp { message: message.into() }),
+                })
             })
         })
     }
@@ -69,6 +167,8 @@ fn expand(
 
 pub struct AttrProcMacro {
     pub client: pm::bridge::client::Client<(pm::TokenStream, pm::TokenStream), pm::TokenStream>,
+    pub cacheable: Option<CacheableProcMacro>,
+    pub dylib_entry: Option<ProcMacroDylibEntry>,
 }
 
 impl base::AttrProcMacro for AttrProcMacro {
@@ -84,24 +184,29 @@ fn expand(
                 recorder.record_arg_with_span(ecx.sess.source_map(), ecx.expansion_descr(), span);
             });
 
-        let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
-        let strategy = exec_strategy(ecx);
-        let server = proc_macro_server::Rustc::new(ecx);
-        self.client.run(&strategy, server, annotation, annotated, proc_macro_backtrace).map_err(
-            |e| {
-                ecx.dcx().emit_err(errors::CustomAttributePanicked {
-                    span,
-                    message: e.as_str().map(|message| errors::CustomAttributePanickedHelp {
-                        message: message.into(),
-                    }),
+        let inputs = [&annotation, &annotated];
+        expand_cached(ecx, self.cacheable, MacroKind::Attr, &inputs, |ecx| {
+            let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
+            let strategy = exec_strategy(ecx, self.dylib_entry.as_ref(), span);
+            let server = proc_macro_server::Rustc::new(ecx);
+            self.client
+                .run(&strategy, server, annotation.clone(), annotated.clone(), proc_macro_backtrace)
+                .map_err(|e| {
+                    ecx.dcx().emit_err(errors::CustomAttributePanicked {
+                        span,
+                        message: e.as_str().map(|message| errors::CustomAttributePanickedHelp {
+                            message: message.into(),
+                        }),
+                    })
                 })
-            },
-        )
+        })
     }
 }
 
 pub struct DeriveProcMacro {
     pub client: pm::bridge::client::Client<pm::TokenStream, pm::TokenStream>,
+    pub cacheable: Option<CacheableProcMacro>,
+    pub dylib_entry: Option<ProcMacroDylibEntry>,
 }
 
 impl MultiItemModifier for DeriveProcMacro {
@@ -132,10 +237,13 @@ fn expand(
                         span,
                     );
                 });
-            let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
-            let strategy = exec_strategy(ecx);
-            let server = proc_macro_server::Rustc::new(ecx);
-            match self.client.run(&strategy, server, input, proc_macro_backtrace) {
+            let output = expand_cached(ecx, self.cacheable, MacroKind::Derive, &[&input], |ecx| {
+                let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
+                let strategy = exec_strategy(ecx, self.dylib_entry.as_ref(), span);
+                let server = proc_macro_server::Rustc::new(ecx);
+                self.client.run(&strategy, server, input.clone(), proc_macro_backtrace)
+            });
+            match output {
                 Ok(stream) => stream,
                 Err(e) => {
                     ecx.dcx().emit_err({
@@ -0,0 +1,465 @@
+//! Caching of proc macro outputs across incremental sessions.
+//!
+//! Proc macros from crates marked with `#![proc_macro_pure]` promise that their output only
+//! depends on their input token streams and on the environment variables and files they read
+//! through `proc_macro::tracked`. When compiling incrementally, the output of every expansion of
+//! such a macro is saved into the incremental session directory, keyed by the SVH of the macro's
+//! crate, the macro's name and kind, and the input token streams. Later sessions replay the saved
+//! output instead of running the macro again, as long as the tracked inputs are unchanged.
+//!
+//! Spans cannot be saved as they are: the positions of the input tokens and the syntax contexts of
+//! the expansion both change between sessions. Instead, every output span is saved relative to the
+//! expansion. Its location must be the location of one of the spans the macro received (or of the
+//! call site or definition site), and its syntax context must be the context of one of those spans
+//! or one of the expansion's own contexts. Expansions producing any other span are not cached, and
+//! neither are expansions that emit diagnostics, expand other macros or inspect spans through the
+//! proc macro server, as the saved output could not reproduce those effects.
+
+use std::hash::Hash;
+use std::{env, fs};
+
+use rustc_ast::token::{self, Delimiter, Token, TokenKind};
+use rustc_ast::tokenstream::{DelimSpacing, DelimSpan, Spacing, TokenStream, TokenTree};
+use rustc_ast_pretty::pprust;
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_macros::{Decodable, Encodable};
+use rustc_serialize::opaque::{FileEncoder, MemDecoder};
+use rustc_serialize::{Decodable, Decoder, Encodable, Encoder};
+use rustc_session::Session;
+use rustc_span::hygiene::MacroKind;
+use rustc_span::{BytePos, Pos, Span, Symbol, SyntaxContext};
+use tracing::debug;
+
+use crate::base::ExtCtxt;
+use crate::proc_macro::CacheableProcMacro;
+
+const CACHE_FILENAME: &str = "proc-macro-cache.bin";
+
+const FILE_MAGIC: &[u8] = b"RSPM";
+
+/// The environment variables and files read by the proc macro being expanded.
+#[derive(Default)]
+pub(crate) struct TrackedInputs {
+    env_vars: Vec<(String, Option<String>)>,
+    paths: Vec<String>,
+    /// Set once the macro did something that a cached output could not reproduce.
+    uncacheable: bool,
+}
+
+#[derive(Encodable, Decodable)]
+struct CacheEntry {
+    env_vars: Vec<(String, Option<String>)>,
+    paths: Vec<(String, Fingerprint)>,
+    output: Vec<CachedTree>,
+}
+
+impl CacheEntry {
+    fn is_up_to_date(&self, sess: &Session) -> bool {
+        self.env_vars.iter().all(|(var, value)| env_var(sess, var) == *value)
+            && self.paths.iter().all(|(path, hash)| hash_file(path) == Some(*hash))
+    }
+
+    /// Records the tracked inputs as dependencies of the current crate, as the proc macro would
+    /// have done if it had been run.
+    fn replay_tracked_inputs(&self, sess: &Session) {
+        let mut env_depinfo = sess.psess.env_depinfo.borrow_mut();
+        for (var, value) in &self.env_vars {
+            env_depinfo.insert((Symbol::intern(var), value.as_deref().map(Symbol::intern)));
+        }
+        let mut file_depinfo = sess.psess.file_depinfo.borrow_mut();
+        for (path, _) in &self.paths {
+            file_depinfo.insert(Symbol::intern(path));
+        }
+    }
+}
+
+/// Mirrors how the proc macro bridge resolves environment variables.
+fn env_var(sess: &Session, var: &str) -> Option<String> {
+    sess.opts.logical_env.get(var).cloned().or_else(|| env::var(var).ok())
+}
+
+fn hash_file(path: &str) -> Option<Fingerprint> {
+    let contents = fs::read(path).ok()?;
+    let mut hasher = StableHasher::new();
+    contents.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+#[derive(Encodable, Decodable)]
+enum CachedTree {
+    Token(TokenKind, CachedSpan, Spacing),
+    Delimited(CachedSpan, CachedSpan, DelimSpacing, Delimiter, Vec<CachedTree>),
+}
+
+#[derive(Clone, Copy, Encodable, Decodable)]
+struct CachedSpan {
+    loc: SpanLoc,
+    ctxt: SpanCtxt,
+}
+
+/// Where the location of an output span comes from.
+#[derive(Clone, Copy, Encodable, Decodable)]
+enum SpanLoc {
+    /// The location of the input span with the given index.
+    Input(u32),
+    CallSite,
+    DefSite,
+}
+
+/// Where the syntax context of an output span comes from.
+#[derive(Clone, Copy, Encodable, Decodable)]
+enum SpanCtxt {
+    /// The syntax context of the input span with the given index.
+    Input(u32),
+    CallSite,
+    MixedSite,
+    DefSite,
+}
+
+/// The spans an expansion can refer to in its output.
+struct ExpansionSpans {
+    /// Every span the proc macro can observe in its input, in an order that only depends on the
+    /// input tokens and not on their spans.
+    inputs: Vec<Span>,
+    call_site: Span,
+    mixed_site: Span,
+    def_site: Span,
+}
+
+impl ExpansionSpans {
+    fn encode_stream(&self, stream: &TokenStream) -> Option<Vec<CachedTree>> {
+        let mut locs = FxHashMap::default();
+        let mut ctxts = FxHashMap::default();
+        for (i, span) in self.inputs.iter().enumerate() {
+            locs.entry((span.lo(), span.hi())).or_insert(i as u32);
+            ctxts.entry(span.ctxt()).or_insert(i as u32);
+        }
+        self.encode_trees(stream, &locs, &ctxts)
+    }
+
+    fn encode_trees(
+        &self,
+        stream: &TokenStream,
+        locs: &FxHashMap<(BytePos, BytePos), u32>,
+        ctxts: &FxHashMap<SyntaxContext, u32>,
+    ) -> Option<Vec<CachedTree>> {
+        stream
+            .iter()
+            .map(|tree| {
+                Some(match tree {
+                    TokenTree::Token(token, spacing) => {
+                        // Interpolated identifiers carry a second span, and never result from
+                        // converting a proc macro's output anyway.
+                        if let token::NtIdent(..) | token::NtLifetime(..) = token.kind {
+                            return None;
+                        }
+                        let span = self.encode_span(token.span, locs, ctxts)?;
+                        CachedTree::Token(token.kind, span, *spacing)
+                    }
+                    TokenTree::Delimited(delim_span, delim_spacing, delim, stream) => {
+                        CachedTree::Delimited(
+                            self.encode_span(delim_span.open, locs, ctxts)?,
+                            self.encode_span(delim_span.close, locs, ctxts)?,
+                            *delim_spacing,
+                            *delim,
+                            self.encode_trees(stream, locs, ctxts)?,
+                        )
+                    }
+                })
+            })
+            .collect()
+    }
+
+    fn encode_span(
+        &self,
+        span: Span,
+        locs: &FxHashMap<(BytePos, BytePos), u32>,
+        ctxts: &FxHashMap<SyntaxContext, u32>,
+    ) -> Option<CachedSpan> {
+        let data = span.data();
+        let same_loc = |site: Span| site.lo() == data.lo && site.hi() == data.hi;
+        let loc = if same_loc(self.call_site) {
+            SpanLoc::CallSite
+        } else if same_loc(self.def_site) {
+            SpanLoc::DefSite
+        } else {
+            SpanLoc::Input(*locs.get(&(data.lo, data.hi))?)
+        };
+        let ctxt = if data.ctxt == self.call_site.ctxt() {
+            SpanCtxt::CallSite
+        } else if data.ctxt == self.mixed_site.ctxt() {
+            SpanCtxt::MixedSite
+        } else if data.ctxt == self.def_site.ctxt() {
+            SpanCtxt::DefSite
+        } else {
+            SpanCtxt::Input(*ctxts.get(&data.ctxt)?)
+        };
+        Some(CachedSpan { loc, ctxt })
+    }
+
+    fn decode_stream(&self, trees: &[CachedTree]) -> TokenStream {
+        TokenStream::new(
+            trees
+                .iter()
+                .map(|tree| match tree {
+                    &CachedTree::Token(kind, span, spacing) => {
+                        TokenTree::Token(Token::new(kind, self.decode_span(span)), spacing)
+                    }
+                    &CachedTree::Delimited(open, close, delim_spacing, delim, ref trees) => {
+                        TokenTree::Delimited(
+                            DelimSpan::from_pair(self.decode_span(open), self.decode_span(close)),
+                            delim_spacing,
+                            delim,
+                            self.decode_stream(trees),
+                        )
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    fn decode_span(&self, span: CachedSpan) -> Span {
+        let loc = match span.loc {
+            SpanLoc::Input(i) => self.inputs[i as usize],
+            SpanLoc::CallSite => self.call_site,
+            SpanLoc::DefSite => self.def_site,
+        };
+        let ctxt = match span.ctxt {
+            SpanCtxt::Input(i) => self.inputs[i as usize].ctxt(),
+            SpanCtxt::CallSite => self.call_site.ctxt(),
+            SpanCtxt::MixedSite => self.mixed_site.ctxt(),
+            SpanCtxt::DefSite => self.def_site.ctxt(),
+        };
+        loc.with_ctxt(ctxt)
+    }
+}
+
+/// An expansion of a cacheable proc macro, ready to be looked up in the cache.
+pub(crate) struct CacheableExpansion {
+    key: Fingerprint,
+    spans: ExpansionSpans,
+}
+
+impl CacheableExpansion {
+    /// Returns `None` if outputs of `mac` are not cached in this session.
+    pub(crate) fn new(
+        ecx: &ExtCtxt<'_>,
+        mac: CacheableProcMacro,
+        kind: MacroKind,
+        inputs: &[&TokenStream],
+    ) -> Option<CacheableExpansion> {
+        if ecx.sess.opts.incremental.is_none() {
+            return None;
+        }
+
+        let mut hasher = StableHasher::new();
+        mac.crate_hash.hash(&mut hasher);
+        mac.name.as_str().hash(&mut hasher);
+        kind.descr().hash(&mut hasher);
+        inputs.len().hash(&mut hasher);
+        let mut input_spans = Vec::new();
+        for input in inputs {
+            hash_stream(input, &mut hasher, &mut input_spans);
+        }
+
+        // The same contexts as the ones `proc_macro_server::Rustc` hands out.
+        let expn_data = ecx.current_expansion.id.expn_data();
+        Some(CacheableExpansion {
+            key: hasher.finish(),
+            spans: ExpansionSpans {
+                inputs: input_spans,
+                call_site: ecx.with_call_site_ctxt(expn_data.call_site),
+                mixed_site: ecx.with_mixed_site_ctxt(expn_data.call_site),
+                def_site: ecx.with_def_site_ctxt(expn_data.def_site),
+            },
+        })
+    }
+}
+
+/// Hashes everything a proc macro can observe about `stream` except its spans, and collects the
+/// spans it can observe into `spans`, following how `proc_macro_server` converts token streams.
+fn hash_stream(stream: &TokenStream, hasher: &mut StableHasher, spans: &mut Vec<Span>) {
+    for tree in stream.iter() {
+        match tree {
+            TokenTree::Token(token, spacing) => {
+                0u8.hash(hasher);
+                (*spacing as u8).hash(hasher);
+                let text = pprust::token_kind_to_string(&token.kind);
+                text.hash(hasher);
+
+                spans.push(token.span);
+                match token.kind {
+                    token::NtIdent(ident, _) | token::NtLifetime(ident, _) => {
+                        spans.push(ident.span)
+                    }
+                    // Multi-character operators are split into one `Punct` per character.
+                    _ if token.is_punct() && text.len() > 1 => {
+                        let span = token.span;
+                        let split = (span.hi() - span.lo()).to_usize() == text.len();
+                        spans.extend((0..text.len()).map(|i| {
+                            if split {
+                                let lo = span.lo() + BytePos::from_usize(i);
+                                span.with_lo(lo).with_hi(lo + BytePos(1))
+                            } else {
+                                span
+                            }
+                        }));
+                    }
+                    _ => {}
+                }
+            }
+            TokenTree::Delimited(delim_span, delim_spacing, delim, stream) => {
+                1u8.hash(hasher);
+                (delim_spacing.open as u8).hash(hasher);
+                (delim_spacing.close as u8).hash(hasher);
+                format!("{delim:?}").hash(hasher);
+                spans.extend([delim_span.open, delim_span.close, delim_span.entire()]);
+                hash_stream(stream, hasher, spans);
+                2u8.hash(hasher);
+            }
+        }
+    }
+    3u8.hash(hasher);
+}
+
+#[derive(Default)]
+pub(crate) struct ProcMacroCache {
+    /// Entries saved by the previous session, loaded on first use.
+    previous: Option<FxHashMap<Fingerprint, CacheEntry>>,
+    /// Entries used or created by this session, which get saved for the next one.
+    current: FxIndexMap<Fingerprint, CacheEntry>,
+    /// The inputs tracked by the proc macro currently running, if its output can be cached.
+    tracking: Option<TrackedInputs>,
+    hits: usize,
+    misses: usize,
+}
+
+impl ProcMacroCache {
+    pub(crate) fn lookup(
+        &mut self,
+        sess: &Session,
+        expansion: &CacheableExpansion,
+    ) -> Option<TokenStream> {
+        let previous = self.previous.get_or_insert_with(|| load(sess));
+        if !self.current.contains_key(&expansion.key) {
+            match previous.remove(&expansion.key) {
+                Some(entry) if entry.is_up_to_date(sess) => {
+                    self.current.insert(expansion.key, entry);
+                }
+                _ => {
+                    self.misses += 1;
+                    return None;
+                }
+            }
+        }
+
+        self.hits += 1;
+        let entry = &self.current[&expansion.key];
+        entry.replay_tracked_inputs(sess);
+        Some(expansion.spans.decode_stream(&entry.output))
+    }
+
+    pub(crate) fn store(
+        &mut self,
+        expansion: CacheableExpansion,
+        tracked: TrackedInputs,
+        output: &TokenStream,
+    ) {
+        if tracked.uncacheable {
+            return;
+        }
+        let Some(output) = expansion.spans.encode_stream(output) else {
+            debug!("proc macro output refers to spans outside of its input, not caching it");
+            return;
+        };
+        let Some(paths) =
+            tracked.paths.into_iter().map(|path| Some((hash_file(&path)?, path))).collect()
+        else {
+            return;
+        };
+        self.current
+            .insert(expansion.key, CacheEntry { env_vars: tracked.env_vars, paths, output });
+    }
+
+    /// Starts tracking the inputs of a proc macro about to be run, returning the state of the
+    /// enclosing expansion to be passed to `finish_tracking` afterwards.
+    pub(crate) fn start_tracking(&mut self, cacheable: bool) -> Option<TrackedInputs> {
+        std::mem::replace(&mut self.tracking, cacheable.then(TrackedInputs::default))
+    }
+
+    pub(crate) fn finish_tracking(
+        &mut self,
+        outer: Option<TrackedInputs>,
+    ) -> Option<TrackedInputs> {
+        std::mem::replace(&mut self.tracking, outer)
+    }
+
+    pub(crate) fn track_env_var(&mut self, var: &str, value: Option<&str>) {
+        if let Some(tracking) = &mut self.tracking {
+            tracking.env_vars.push((var.to_owned(), value.map(str::to_owned)));
+        }
+    }
+
+    pub(crate) fn track_path(&mut self, path: &str) {
+        if let Some(tracking) = &mut self.tracking {
+            tracking.paths.push(path.to_owned());
+        }
+    }
+
+    pub(crate) fn mark_uncacheable(&mut self) {
+        if let Some(tracking) = &mut self.tracking {
+            tracking.uncacheable = true;
+        }
+    }
+
+    pub(crate) fn save(&self, sess: &Session) {
+        // Nothing was looked up, keep the file of the previous session around as it is.
+        if self.previous.is_none() {
+            return;
+        }
+
+        if sess.opts.unstable_opts.incremental_info {
+            eprintln!("[incremental] proc macro cache: {} hits, {} misses", self.hits, self.misses);
+        }
+
+        let path = sess.incr_comp_session_dir().join(CACHE_FILENAME);
+        // The file may be hard-linked to the one of the previous session.
+        let _ = fs::remove_file(&path);
+        let Ok(mut encoder) = FileEncoder::new(&path) else {
+            return;
+        };
+        encoder.emit_raw_bytes(FILE_MAGIC);
+        encoder.emit_str(sess.cfg_version);
+        encoder.emit_usize(self.current.len());
+        for (key, entry) in &self.current {
+            key.encode(&mut encoder);
+            entry.encode(&mut encoder);
+        }
+        // The cache is only an optimization, failing to write it is not an error.
+        if let Err((path, err)) = encoder.finish() {
+            debug!("failed to write proc macro cache to {}: {err}", path.display());
+        }
+    }
+}
+
+fn load(sess: &Session) -> FxHashMap<Fingerprint, CacheEntry> {
+    let path = sess.incr_comp_session_dir().join(CACHE_FILENAME);
+    let Ok(data) = fs::read(&path) else {
+        return Default::default();
+    };
+    let Ok(mut decoder) = MemDecoder::new(&data, 0) else {
+        return Default::default();
+    };
+    if decoder.remaining() < FILE_MAGIC.len()
+        || decoder.read_raw_bytes(FILE_MAGIC.len()) != FILE_MAGIC
+        || decoder.read_str() != sess.cfg_version
+    {
+        return Default::default();
+    }
+    let len = decoder.read_usize();
+    (0..len)
+        .map(|_| (Fingerprint::decode(&mut decoder), CacheEntry::decode(&mut decoder)))
+        .collect()
+}
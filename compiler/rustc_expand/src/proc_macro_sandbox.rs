@@ -0,0 +1,370 @@
+//! Running proc macros in a sandboxed process, for `-Zproc-macro-execution-strategy=sandboxed`.
+//!
+//! The compiler re-executes itself as a child process that loads the proc macro dylib and runs
+//! the client side of the bridge, while the server side stays in the compiler. The two exchange
+//! the bridge's buffers over the child's stdin and stdout, so from the server's point of view
+//! this is just another [`ExecutionStrategy`]. Before running any proc macro code, the child
+//! restricts itself so that it can only read the files declared with `proc_macro::tracked::path`,
+//! and can't write files, spawn processes, signal other processes or use the network: all system
+//! calls that are not known to be harmless are denied. Every attempt to make one of them is
+//! reported as an error at the invocation of the proc macro. Proc macros can still start threads.
+//!
+//! Children are kept alive between expansions and reused for the same dylib, since loading a
+//! proc macro crate is much more expensive than a single expansion.
+
+use std::cell::RefCell;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_errors::DiagCtxtHandle;
+use rustc_proc_macro as pm;
+use rustc_proc_macro::bridge::Buffer;
+use rustc_proc_macro::bridge::client::ProcMacro;
+use rustc_proc_macro::bridge::server::{DispatcherTrait, ExecutionStrategy, client_panicked};
+use rustc_session::Session;
+use rustc_span::Span;
+
+use crate::base::ExtCtxt;
+use crate::errors;
+use crate::proc_macro::{ProcMacroDylib, ProcMacroDylibEntry};
+
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod seccomp;
+
+#[cfg(not(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+mod seccomp {
+    use std::fs::File;
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use std::sync::{Arc, Mutex};
+
+    use super::Violation;
+
+    pub(super) fn check_support() -> Result<(), String> {
+        Err("it is only supported on Linux on x86_64 and AArch64".to_owned())
+    }
+
+    fn unsupported<T>() -> io::Result<T> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    pub(super) fn install_filter() -> io::Result<i32> {
+        unsupported()
+    }
+
+    pub(super) fn take_stdout() -> io::Result<File> {
+        unsupported()
+    }
+
+    pub(super) fn supervise(
+        _pid: u32,
+        _listener_fd: i32,
+        _tracked_paths: Arc<Mutex<Vec<PathBuf>>>,
+        _violations: Arc<Mutex<Vec<Violation>>>,
+    ) -> io::Result<()> {
+        unsupported()
+    }
+
+    pub(super) fn normalize_tracked_path(path: &Path) -> PathBuf {
+        path.to_owned()
+    }
+}
+
+/// Set in the environment of the child process, which is the compiler itself.
+pub const CHILD_ENV: &str = "RUSTC_PROC_MACRO_SANDBOX_CHILD";
+
+/// Message tags of the protocol between the compiler and a child. Every message is a tag byte
+/// followed by the little-endian `u64` length of its payload, and the payload itself.
+mod tag {
+    /// Child to compiler, once the sandbox is in place: the seccomp listener fd of the child.
+    pub(super) const READY: u8 = 0;
+    /// Compiler to child: the index of the proc macro, whether to show panics, and the input.
+    pub(super) const EXPAND: u8 = 1;
+    /// Child to compiler: a request of the client to the bridge server.
+    pub(super) const REQUEST: u8 = 2;
+    /// Compiler to child: the response of the bridge server to the last request.
+    pub(super) const REPLY: u8 = 3;
+    /// Child to compiler: the output of the expansion.
+    pub(super) const DONE: u8 = 4;
+}
+
+/// Something a sandboxed proc macro tried to do, and was prevented from doing.
+pub(crate) struct Violation {
+    pub(crate) description: String,
+    /// Whether this was a read of a file that wasn't declared with `proc_macro::tracked::path`.
+    pub(crate) undeclared_file: bool,
+}
+
+/// The sandboxed processes of a compilation session, and the paths they are allowed to read.
+pub(crate) struct ProcMacroSandbox {
+    /// Processes that are not running an expansion, by the dylib they have loaded.
+    idle: RefCell<FxHashMap<PathBuf, Vec<SandboxProcess>>>,
+    /// Paths declared with `proc_macro::tracked::path`, shared with the supervisor threads.
+    tracked_paths: Arc<Mutex<Vec<PathBuf>>>,
+}
+
+impl ProcMacroSandbox {
+    pub(crate) fn new(sess: &Session) -> Option<ProcMacroSandbox> {
+        if let Err(reason) = seccomp::check_support() {
+            sess.dcx().emit_err(errors::ProcMacroSandboxUnsupported { reason });
+            return None;
+        }
+        Some(ProcMacroSandbox { idle: Default::default(), tracked_paths: Default::default() })
+    }
+
+    /// Allows sandboxed proc macros to read `path`, or the files below it if it is a directory.
+    pub(crate) fn track_path(&self, path: &str) {
+        let path = seccomp::normalize_tracked_path(Path::new(path));
+        self.tracked_paths.lock().unwrap().push(path);
+    }
+
+    fn take_process(&self, dylib: &ProcMacroDylib) -> io::Result<SandboxProcess> {
+        if let Some(process) = self.idle.borrow_mut().get_mut(&dylib.path).and_then(Vec::pop) {
+            return Ok(process);
+        }
+        SandboxProcess::spawn(dylib, Arc::clone(&self.tracked_paths))
+    }
+
+    fn return_process(&self, dylib: &ProcMacroDylib, process: SandboxProcess) {
+        self.idle.borrow_mut().entry(dylib.path.clone()).or_default().push(process);
+    }
+}
+
+/// Runs a single proc macro expansion in a sandboxed process.
+pub(crate) struct SandboxedExecution<'a> {
+    sandbox: Rc<ProcMacroSandbox>,
+    entry: ProcMacroDylibEntry,
+    span: Span,
+    dcx: DiagCtxtHandle<'a>,
+}
+
+impl<'a> SandboxedExecution<'a> {
+    pub(crate) fn new(
+        ecx: &ExtCtxt<'a>,
+        sandbox: &Rc<ProcMacroSandbox>,
+        entry: ProcMacroDylibEntry,
+        span: Span,
+    ) -> SandboxedExecution<'a> {
+        SandboxedExecution { sandbox: Rc::clone(sandbox), entry, span, dcx: ecx.dcx() }
+    }
+}
+
+impl ExecutionStrategy for SandboxedExecution<'_> {
+    fn run_bridge_and_client(
+        &self,
+        dispatcher: &mut impl DispatcherTrait,
+        input: Buffer,
+        _run_client: extern "C" fn(pm::bridge::BridgeConfig<'_>) -> Buffer,
+        force_show_panics: bool,
+    ) -> Buffer {
+        let dylib = &*self.entry.dylib;
+        // The process is taken out of the pool for the whole expansion, so that nested
+        // expansions of the same crate, e.g. through `TokenStream::expand_expr`, get another one.
+        let mut process = match self.sandbox.take_process(dylib) {
+            Ok(process) => process,
+            Err(err) => {
+                return client_panicked(format!("failed to start the proc macro sandbox: {err}"));
+            }
+        };
+
+        let output = process.expand(self.entry.index, &input, dispatcher, force_show_panics);
+
+        for violation in process.violations.lock().unwrap().drain(..) {
+            self.dcx.emit_err(errors::ProcMacroSandboxViolation {
+                span: self.span,
+                violation: violation.description,
+                undeclared_file: violation.undeclared_file,
+            });
+        }
+
+        match output {
+            Ok(output) => {
+                self.sandbox.return_process(dylib, process);
+                output
+            }
+            Err(err) => client_panicked(format!("the proc macro sandbox process failed: {err}")),
+        }
+    }
+}
+
+/// A child process running sandboxed proc macros from a single dylib.
+struct SandboxProcess {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: ChildStdout,
+    /// Violations recorded by the supervisor thread since the last expansion.
+    violations: Arc<Mutex<Vec<Violation>>>,
+}
+
+impl SandboxProcess {
+    fn spawn(
+        dylib: &ProcMacroDylib,
+        tracked_paths: Arc<Mutex<Vec<PathBuf>>>,
+    ) -> io::Result<SandboxProcess> {
+        let exe = std::env::current_exe()?;
+        let mut child = Command::new(&exe)
+            .env(CHILD_ENV, "1")
+            .arg(&dylib.path)
+            .arg(&dylib.decls_symbol)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()?;
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let mut process = SandboxProcess { child, stdin, stdout, violations: Default::default() };
+
+        let listener_fd = match read_message(&mut process.stdout) {
+            Ok((tag::READY, payload)) => decode_u64(&payload)? as i32,
+            Ok(_) => return Err(protocol_error()),
+            // The child ran as a compiler, which happens for drivers that neither call
+            // `rustc_driver::run_compiler` nor `rustc_driver::catch_with_exit_code`.
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                return Err(io::Error::other(format!(
+                    "`{}` exited without starting the sandbox, the driver must run the compiler \
+                     through `rustc_driver::run_compiler` or `rustc_driver::catch_with_exit_code`",
+                    exe.display()
+                )));
+            }
+            Err(err) => return Err(err),
+        };
+        seccomp::supervise(
+            process.child.id(),
+            listener_fd,
+            tracked_paths,
+            Arc::clone(&process.violations),
+        )?;
+        Ok(process)
+    }
+
+    fn expand(
+        &mut self,
+        index: usize,
+        input: &[u8],
+        dispatcher: &mut impl DispatcherTrait,
+        force_show_panics: bool,
+    ) -> io::Result<Buffer> {
+        let mut payload = Vec::with_capacity(9 + input.len());
+        payload.extend_from_slice(&(index as u64).to_le_bytes());
+        payload.push(force_show_panics as u8);
+        payload.extend_from_slice(input);
+        write_message(&mut self.stdin, tag::EXPAND, &payload)?;
+
+        loop {
+            match read_message(&mut self.stdout)? {
+                (tag::REQUEST, request) => {
+                    let reply = dispatcher.dispatch(Buffer::from(request));
+                    write_message(&mut self.stdin, tag::REPLY, &reply)?;
+                }
+                (tag::DONE, output) => return Ok(Buffer::from(output)),
+                _ => return Err(protocol_error()),
+            }
+        }
+    }
+}
+
+impl Drop for SandboxProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn protocol_error() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "unexpected message from the sandboxed process")
+}
+
+fn decode_u64(bytes: &[u8]) -> io::Result<u64> {
+    let bytes = bytes.get(..8).ok_or_else(protocol_error)?;
+    Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+fn write_message(w: &mut impl Write, tag: u8, payload: &[u8]) -> io::Result<()> {
+    let mut header = [0; 9];
+    header[0] = tag;
+    header[1..].copy_from_slice(&(payload.len() as u64).to_le_bytes());
+    w.write_all(&header)?;
+    w.write_all(payload)?;
+    w.flush()
+}
+
+fn read_message(r: &mut impl Read) -> io::Result<(u8, Vec<u8>)> {
+    let mut header = [0; 9];
+    r.read_exact(&mut header)?;
+    let len = decode_u64(&header[1..])?;
+    let mut payload = vec![0; len as usize];
+    r.read_exact(&mut payload)?;
+    Ok((header[0], payload))
+}
+
+/// Whether this process was started as a sandboxed proc macro process.
+pub fn is_child() -> bool {
+    std::env::var_os(CHILD_ENV).is_some()
+}
+
+/// The entry point of a sandboxed proc macro process. `load` loads the proc macro declarations
+/// from the dylib at the given path, with the given symbol name.
+pub fn run_child(load: impl FnOnce(&Path, &str) -> Result<&'static [ProcMacro], String>) -> ! {
+    if let Err(err) = child_main(load) {
+        eprintln!("error: sandboxed proc macro process failed: {err}");
+        process::exit(1);
+    }
+    process::exit(0)
+}
+
+fn child_main(
+    load: impl FnOnce(&Path, &str) -> Result<&'static [ProcMacro], String>,
+) -> Result<(), String> {
+    let mut args = std::env::args_os().skip(1);
+    let (Some(path), Some(symbol)) = (args.next(), args.next()) else {
+        return Err("expected the path of a proc macro dylib and its declarations symbol".into());
+    };
+    let symbol = symbol.into_string().map_err(|_| "invalid declarations symbol".to_owned())?;
+
+    let mut output = seccomp::take_stdout().map_err(|err| err.to_string())?;
+    let mut input = io::stdin().lock();
+    // The dylib must be loaded before the sandbox is in place, since it can't be opened anymore
+    // afterwards.
+    let decls = load(Path::new(&path), &symbol)?;
+    let listener_fd = seccomp::install_filter().map_err(|err| err.to_string())?;
+    write_message(&mut output, tag::READY, &(listener_fd as u64).to_le_bytes())
+        .map_err(|err| err.to_string())?;
+
+    loop {
+        let payload = match read_message(&mut input) {
+            Ok((tag::EXPAND, payload)) if payload.len() >= 9 => payload,
+            // The compiler is done with this process.
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Ok(_) => return Err(protocol_error().to_string()),
+            Err(err) => return Err(err.to_string()),
+        };
+        let index = decode_u64(&payload).map_err(|err| err.to_string())? as usize;
+        let force_show_panics = payload[8] != 0;
+        let expand_input = Buffer::from(payload[9..].to_vec());
+
+        // A failure to talk to the compiler in the middle of an expansion can't be reported
+        // through the bridge, the compiler notices that the process exited instead.
+        let dispatch = |request: Buffer| {
+            let reply = write_message(&mut output, tag::REQUEST, &request)
+                .and_then(|()| read_message(&mut input));
+            match reply {
+                Ok((tag::REPLY, reply)) => Buffer::from(reply),
+                _ => process::exit(1),
+            }
+        };
+        let result = match decls.get(index) {
+            Some(ProcMacro::CustomDerive { client, .. } | ProcMacro::Bang { client, .. }) => {
+                client.run_detached(expand_input, dispatch, force_show_panics)
+            }
+            Some(ProcMacro::Attr { client, .. }) => {
+                client.run_detached(expand_input, dispatch, force_show_panics)
+            }
+            None => return Err(format!("no proc macro at index {index}")),
+        };
+        write_message(&mut output, tag::DONE, &result).map_err(|err| err.to_string())?;
+    }
+}
@@ -0,0 +1,603 @@
+//! The Linux implementation of the proc macro sandbox, based on seccomp user notifications.
+//!
+//! The child installs a seccomp filter that allows a fixed set of syscalls, which only operate on
+//! the child's own memory, threads and already open file descriptors, and forwards every other
+//! syscall to a listener that is supervised by a thread in the compiler. The supervisor opens
+//! files the proc macro is allowed to read itself and injects the file descriptor into the child
+//! with `SECCOMP_IOCTL_NOTIF_ADDFD`, so the path it checked is the path that gets opened. Syscalls
+//! that only read the metadata of a declared path, or signal the child itself, are let through.
+//! Everything else fails in the child, and is recorded as a violation.
+//!
+//! Metadata syscalls are let through by the kernel after the supervisor checked their path, so a
+//! proc macro that changes the path in another thread in between can still read the metadata of
+//! an undeclared file. The contents of files are not affected by this, as they are only ever
+//! opened by the supervisor.
+
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::ffi::OsStringExt;
+use std::os::unix::fs::{FileExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::{mem, thread};
+
+use super::Violation;
+
+// From `<linux/seccomp.h>`, `<linux/filter.h>` and `<linux/audit.h>`.
+const SECCOMP_SET_MODE_FILTER: libc::c_ulong = 1;
+const SECCOMP_FILTER_FLAG_NEW_LISTENER: libc::c_ulong = 1 << 3;
+const SECCOMP_RET_KILL_PROCESS: u32 = 0x8000_0000;
+const SECCOMP_RET_USER_NOTIF: u32 = 0x7fc0_0000;
+const SECCOMP_RET_ALLOW: u32 = 0x7fff_0000;
+const SECCOMP_USER_NOTIF_FLAG_CONTINUE: u32 = 1;
+const SECCOMP_ADDFD_FLAG_SEND: u32 = 1 << 1;
+const SECCOMP_IOCTL_NOTIF_RECV: u64 = 0xc050_2100;
+const SECCOMP_IOCTL_NOTIF_SEND: u64 = 0xc018_2101;
+const SECCOMP_IOCTL_NOTIF_ADDFD: u64 = 0x4018_2103;
+
+const BPF_LD_W_ABS: u16 = 0x20;
+const BPF_JMP_JEQ_K: u16 = 0x15;
+const BPF_JMP_JSET_K: u16 = 0x45;
+#[cfg(target_arch = "x86_64")]
+const BPF_JMP_JGE_K: u16 = 0x35;
+const BPF_RET_K: u16 = 0x06;
+
+/// Offsets into `struct seccomp_data`.
+const SECCOMP_DATA_NR: u32 = 0;
+const SECCOMP_DATA_ARCH: u32 = 4;
+/// The low 32 bits of the first argument, on little-endian targets.
+const SECCOMP_DATA_ARG0_LO: u32 = 16;
+
+#[cfg(target_arch = "x86_64")]
+const AUDIT_ARCH: u32 = 0xc000_003e;
+#[cfg(target_arch = "aarch64")]
+const AUDIT_ARCH: u32 = 0xc000_00b7;
+/// Syscall numbers of the x32 ABI, which shares `AUDIT_ARCH_X86_64`, have this bit set.
+#[cfg(target_arch = "x86_64")]
+const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+
+#[repr(C)]
+struct SockFilter {
+    code: u16,
+    jt: u8,
+    jf: u8,
+    k: u32,
+}
+
+#[repr(C)]
+struct SockFprog {
+    len: u16,
+    filter: *const SockFilter,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct SeccompData {
+    nr: i32,
+    arch: u32,
+    instruction_pointer: u64,
+    args: [u64; 6],
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct SeccompNotif {
+    id: u64,
+    pid: u32,
+    flags: u32,
+    data: SeccompData,
+}
+
+#[repr(C)]
+struct SeccompNotifResp {
+    id: u64,
+    val: i64,
+    error: i32,
+    flags: u32,
+}
+
+#[repr(C)]
+struct SeccompNotifAddfd {
+    id: u64,
+    flags: u32,
+    srcfd: u32,
+    newfd: u32,
+    newfd_flags: u32,
+}
+
+/// The first kernel version with `SECCOMP_ADDFD_FLAG_SEND`, which the supervisor uses to
+/// complete `open` syscalls atomically.
+const MIN_KERNEL_VERSION: (u32, u32) = (5, 14);
+
+/// Syscalls that are allowed without asking the supervisor. None of them can name a file by path
+/// or affect another process.
+const ALLOWED_SYSCALLS: &[libc::c_long] = &[
+    // Already open file descriptors, i.e. the standard streams.
+    libc::SYS_read,
+    libc::SYS_write,
+    libc::SYS_readv,
+    libc::SYS_writev,
+    libc::SYS_pread64,
+    libc::SYS_pwrite64,
+    libc::SYS_lseek,
+    libc::SYS_close,
+    libc::SYS_fstat,
+    libc::SYS_fcntl,
+    libc::SYS_dup,
+    libc::SYS_dup3,
+    libc::SYS_getdents64,
+    libc::SYS_pipe2,
+    #[cfg(target_arch = "x86_64")]
+    libc::SYS_dup2,
+    #[cfg(target_arch = "x86_64")]
+    libc::SYS_poll,
+    libc::SYS_ppoll,
+    // Memory.
+    libc::SYS_brk,
+    libc::SYS_mmap,
+    libc::SYS_munmap,
+    libc::SYS_mremap,
+    libc::SYS_mprotect,
+    libc::SYS_madvise,
+    // Threads, which are started with `clone` and checked by the filter itself.
+    libc::SYS_futex,
+    libc::SYS_set_robust_list,
+    libc::SYS_set_tid_address,
+    libc::SYS_rseq,
+    libc::SYS_sched_yield,
+    libc::SYS_sched_getaffinity,
+    libc::SYS_exit,
+    libc::SYS_exit_group,
+    // Signal handling of the process itself.
+    libc::SYS_rt_sigaction,
+    libc::SYS_rt_sigprocmask,
+    libc::SYS_rt_sigreturn,
+    libc::SYS_sigaltstack,
+    // Time, randomness and information about the process itself.
+    libc::SYS_clock_gettime,
+    libc::SYS_clock_getres,
+    libc::SYS_clock_nanosleep,
+    libc::SYS_nanosleep,
+    libc::SYS_gettimeofday,
+    libc::SYS_getrandom,
+    libc::SYS_getpid,
+    libc::SYS_gettid,
+    libc::SYS_getuid,
+    libc::SYS_geteuid,
+    libc::SYS_getgid,
+    libc::SYS_getegid,
+    libc::SYS_getcwd,
+    libc::SYS_uname,
+];
+
+/// Syscalls that open a file by path, and are allowed for files declared through
+/// `proc_macro::tracked::path`.
+const OPEN_SYSCALLS: &[libc::c_long] = &[
+    #[cfg(target_arch = "x86_64")]
+    libc::SYS_open,
+    libc::SYS_openat,
+    libc::SYS_openat2,
+];
+
+/// Descriptions of what a proc macro tried to do with the syscalls that are denied, for the
+/// violations. All syscalls that are not allowed are denied, these are just the likely ones.
+const DENIED_SYSCALLS: &[(libc::c_long, &str)] = &[
+    (libc::SYS_socket, "open a network socket"),
+    (libc::SYS_socketpair, "open a network socket"),
+    (libc::SYS_execve, "execute a program"),
+    (libc::SYS_execveat, "execute a program"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_fork, "spawn a process"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_vfork, "spawn a process"),
+    (libc::SYS_clone, "spawn a process"),
+    (libc::SYS_ptrace, "access another process"),
+    (libc::SYS_process_vm_readv, "access another process"),
+    (libc::SYS_process_vm_writev, "access another process"),
+    (libc::SYS_pidfd_open, "access another process"),
+    (libc::SYS_pidfd_getfd, "access another process"),
+    (libc::SYS_io_uring_setup, "use `io_uring`"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_creat, "create a file"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_mknod, "create a file"),
+    (libc::SYS_mknodat, "create a file"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_mkdir, "create a directory"),
+    (libc::SYS_mkdirat, "create a directory"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_rmdir, "remove a directory"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_unlink, "remove a file"),
+    (libc::SYS_unlinkat, "remove a file"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_rename, "rename a file"),
+    (libc::SYS_renameat, "rename a file"),
+    (libc::SYS_renameat2, "rename a file"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_link, "create a link"),
+    (libc::SYS_linkat, "create a link"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_symlink, "create a link"),
+    (libc::SYS_symlinkat, "create a link"),
+    (libc::SYS_truncate, "truncate a file"),
+    (libc::SYS_ftruncate, "truncate a file"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_chmod, "change the permissions of a file"),
+    (libc::SYS_fchmod, "change the permissions of a file"),
+    (libc::SYS_fchmodat, "change the permissions of a file"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_chown, "change the owner of a file"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_lchown, "change the owner of a file"),
+    (libc::SYS_fchown, "change the owner of a file"),
+    (libc::SYS_fchownat, "change the owner of a file"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_utime, "change the timestamps of a file"),
+    #[cfg(target_arch = "x86_64")]
+    (libc::SYS_utimes, "change the timestamps of a file"),
+    (libc::SYS_utimensat, "change the timestamps of a file"),
+    (libc::SYS_setxattr, "change the extended attributes of a file"),
+    (libc::SYS_lsetxattr, "change the extended attributes of a file"),
+    (libc::SYS_fsetxattr, "change the extended attributes of a file"),
+    (libc::SYS_removexattr, "change the extended attributes of a file"),
+    (libc::SYS_lremovexattr, "change the extended attributes of a file"),
+    (libc::SYS_fremovexattr, "change the extended attributes of a file"),
+    (libc::SYS_kill, "send a signal to another process"),
+    (libc::SYS_tkill, "send a signal to another process"),
+    (libc::SYS_tgkill, "send a signal to another process"),
+];
+
+fn last_error<T>() -> io::Result<T> {
+    Err(io::Error::last_os_error())
+}
+
+/// Checks that the running kernel supports everything the sandbox needs.
+pub(super) fn check_support() -> Result<(), String> {
+    let mut utsname: libc::utsname = unsafe { mem::zeroed() };
+    if unsafe { libc::uname(&mut utsname) } != 0 {
+        return Err(io::Error::last_os_error().to_string());
+    }
+    let release = unsafe { std::ffi::CStr::from_ptr(utsname.release.as_ptr()) };
+    let release = release.to_string_lossy();
+    let mut numbers = release.split(|c: char| !c.is_ascii_digit()).map(|n| n.parse::<u32>());
+    let version = match (numbers.next(), numbers.next()) {
+        (Some(Ok(major)), Some(Ok(minor))) => (major, minor),
+        _ => return Err(format!("the kernel version `{release}` could not be parsed")),
+    };
+    if version < MIN_KERNEL_VERSION {
+        let (major, minor) = MIN_KERNEL_VERSION;
+        return Err(format!("it requires Linux {major}.{minor} or later, but this is `{release}`"));
+    }
+    Ok(())
+}
+
+/// Installs the seccomp filter on the current process, and returns the file descriptor of its
+/// listener. Must be called before the child runs any proc macro code.
+pub(super) fn install_filter() -> io::Result<RawFd> {
+    let stmt = |code, k| SockFilter { code, jt: 0, jf: 0, k };
+    let mut filter = vec![
+        stmt(BPF_LD_W_ABS, SECCOMP_DATA_ARCH),
+        SockFilter { code: BPF_JMP_JEQ_K, jt: 1, jf: 0, k: AUDIT_ARCH },
+        stmt(BPF_RET_K, SECCOMP_RET_KILL_PROCESS),
+        stmt(BPF_LD_W_ABS, SECCOMP_DATA_NR),
+    ];
+    #[cfg(target_arch = "x86_64")]
+    filter.extend([
+        SockFilter { code: BPF_JMP_JGE_K, jt: 0, jf: 1, k: X32_SYSCALL_BIT },
+        stmt(BPF_RET_K, SECCOMP_RET_KILL_PROCESS),
+    ]);
+    // The allowed syscalls jump to the final `ALLOW`, after the five instructions that check
+    // `clone`. Everything else is sent to the supervisor.
+    let allowed = ALLOWED_SYSCALLS.len();
+    for (i, &nr) in ALLOWED_SYSCALLS.iter().enumerate() {
+        let jt = u8::try_from(allowed - i + 4).unwrap();
+        filter.push(SockFilter { code: BPF_JMP_JEQ_K, jt, jf: 0, k: nr as u32 });
+    }
+    // `clone` is allowed for threads, which share everything but the stack with the child.
+    // Processes are denied by the supervisor. `clone3` passes its flags in memory, which the
+    // filter can't read, so it is always sent to the supervisor, which makes it fail with
+    // `ENOSYS` so that the C library falls back to `clone`.
+    filter.extend([
+        SockFilter { code: BPF_JMP_JEQ_K, jt: 1, jf: 0, k: libc::SYS_clone as u32 },
+        stmt(BPF_RET_K, SECCOMP_RET_USER_NOTIF),
+        stmt(BPF_LD_W_ABS, SECCOMP_DATA_ARG0_LO),
+        SockFilter { code: BPF_JMP_JSET_K, jt: 1, jf: 0, k: libc::CLONE_THREAD as u32 },
+        stmt(BPF_RET_K, SECCOMP_RET_USER_NOTIF),
+        stmt(BPF_RET_K, SECCOMP_RET_ALLOW),
+    ]);
+
+    let prog = SockFprog { len: filter.len() as u16, filter: filter.as_ptr() };
+    unsafe {
+        if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+            return last_error();
+        }
+        let fd = libc::syscall(
+            libc::SYS_seccomp,
+            SECCOMP_SET_MODE_FILTER,
+            SECCOMP_FILTER_FLAG_NEW_LISTENER,
+            &prog as *const SockFprog,
+        );
+        if fd < 0 { last_error() } else { Ok(fd as RawFd) }
+    }
+}
+
+/// Takes over the child's stdout for the protocol with the compiler, and redirects the standard
+/// output of proc macros to stderr so that it can't corrupt the protocol.
+pub(super) fn take_stdout() -> io::Result<File> {
+    unsafe {
+        let fd = libc::dup(libc::STDOUT_FILENO);
+        if fd < 0 {
+            return last_error();
+        }
+        if libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) < 0 {
+            return last_error();
+        }
+        Ok(File::from(OwnedFd::from_raw_fd(fd)))
+    }
+}
+
+/// Starts the thread supervising the seccomp listener `listener_fd` of the child `pid`.
+pub(super) fn supervise(
+    pid: u32,
+    listener_fd: RawFd,
+    tracked_paths: Arc<Mutex<Vec<PathBuf>>>,
+    violations: Arc<Mutex<Vec<Violation>>>,
+) -> io::Result<()> {
+    let listener = unsafe {
+        let pidfd = libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0);
+        if pidfd < 0 {
+            return last_error();
+        }
+        let pidfd = OwnedFd::from_raw_fd(pidfd as RawFd);
+        let fd = libc::syscall(libc::SYS_pidfd_getfd, pidfd.as_raw_fd(), listener_fd, 0);
+        if fd < 0 {
+            return last_error();
+        }
+        OwnedFd::from_raw_fd(fd as RawFd)
+    };
+    let mem = File::open(format!("/proc/{pid}/mem"))?;
+    let supervisor = Supervisor { pid, listener, mem, tracked_paths, violations };
+    thread::Builder::new().name("proc-macro-sandbox".to_owned()).spawn(move || supervisor.run())?;
+    Ok(())
+}
+
+struct Supervisor {
+    pid: u32,
+    listener: OwnedFd,
+    mem: File,
+    tracked_paths: Arc<Mutex<Vec<PathBuf>>>,
+    violations: Arc<Mutex<Vec<Violation>>>,
+}
+
+enum Decision {
+    /// Let the kernel run the syscall.
+    Continue,
+    /// Complete an `open` with a file opened by the supervisor.
+    Open {
+        file: File,
+        path: PathBuf,
+        cloexec: bool,
+    },
+    Fail(i32),
+    Deny(i32, Violation),
+}
+
+impl Supervisor {
+    fn run(self) {
+        let fd = self.listener.as_raw_fd();
+        loop {
+            let mut pollfd = libc::pollfd { fd, events: libc::POLLIN, revents: 0 };
+            if unsafe { libc::poll(&mut pollfd, 1, -1) } < 0 {
+                if io::Error::last_os_error().kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+                return;
+            }
+            if pollfd.revents & libc::POLLIN == 0 {
+                // The child exited.
+                return;
+            }
+
+            let mut notif = SeccompNotif::default();
+            if unsafe { libc::ioctl(fd, SECCOMP_IOCTL_NOTIF_RECV as _, &mut notif) } < 0 {
+                // The child may have been killed while the syscall was pending.
+                continue;
+            }
+            self.respond(notif.id, self.decide(&notif.data));
+        }
+    }
+
+    fn decide(&self, data: &SeccompData) -> Decision {
+        let nr = data.nr as libc::c_long;
+        let args = &data.args;
+        match nr {
+            #[cfg(target_arch = "x86_64")]
+            libc::SYS_open => self.decide_open(libc::AT_FDCWD, args[0], args[1]),
+            libc::SYS_openat => self.decide_open(args[0] as i32, args[1], args[2]),
+            libc::SYS_openat2 => match self.read_u64(args[2]) {
+                // `struct open_how` starts with the `u64` flags.
+                Ok(flags) => self.decide_open(args[0] as i32, args[1], flags),
+                Err(_) => Decision::Fail(libc::EFAULT),
+            },
+            #[cfg(target_arch = "x86_64")]
+            libc::SYS_stat | libc::SYS_lstat | libc::SYS_access | libc::SYS_readlink => {
+                self.decide_metadata(libc::AT_FDCWD, args[0])
+            }
+            libc::SYS_newfstatat
+            | libc::SYS_statx
+            | libc::SYS_faccessat
+            | libc::SYS_faccessat2
+            | libc::SYS_readlinkat => self.decide_metadata(args[0] as i32, args[1]),
+            // The standard library asks for the limits of its own stack.
+            libc::SYS_prlimit64
+                if (args[0] == 0 || args[0] == u64::from(self.pid)) && args[2] == 0 =>
+            {
+                Decision::Continue
+            }
+            // `kill` and `tgkill` take the thread group, which is the pid of the child, `tkill`
+            // takes a thread of any process.
+            libc::SYS_kill | libc::SYS_tgkill if args[0] as i32 == self.pid as i32 => {
+                Decision::Continue
+            }
+            libc::SYS_tkill
+                if Path::new(&format!("/proc/{}/task/{}", self.pid, args[0] as i32)).exists() =>
+            {
+                Decision::Continue
+            }
+            // The filter can't check the flags of `clone3`, so the C library has to fall back to
+            // `clone` for threads.
+            libc::SYS_clone3 => Decision::Fail(libc::ENOSYS),
+            // The standard streams are probed for terminals.
+            libc::SYS_ioctl => Decision::Fail(libc::ENOTTY),
+            _ => {
+                let description = match DENIED_SYSCALLS.iter().find(|&&(denied, _)| denied == nr) {
+                    Some((_, action)) => format!("tried to {action}"),
+                    None => format!("tried to make system call {nr}, which is not allowed"),
+                };
+                Decision::Deny(libc::EPERM, Violation { description, undeclared_file: false })
+            }
+        }
+    }
+
+    fn decide_open(&self, dirfd: i32, path: u64, flags: u64) -> Decision {
+        let flags = flags as i32;
+        let Ok(path) = self.resolve_path(dirfd, path) else {
+            return Decision::Fail(libc::EFAULT);
+        };
+
+        if flags & libc::O_ACCMODE != libc::O_RDONLY
+            || flags & (libc::O_CREAT | libc::O_TRUNC | libc::O_TMPFILE) != 0
+        {
+            let description = format!("tried to write to `{}`", path.display());
+            return Decision::Deny(libc::EACCES, Violation { description, undeclared_file: false });
+        }
+
+        let canonical = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        if !self.is_declared(&canonical) {
+            return Self::deny_undeclared(&path);
+        }
+
+        let custom_flags = flags & (libc::O_DIRECTORY | libc::O_NOFOLLOW | libc::O_NONBLOCK);
+        match File::options().read(true).custom_flags(custom_flags).open(&canonical) {
+            Ok(file) => Decision::Open { file, path, cloexec: flags & libc::O_CLOEXEC != 0 },
+            Err(err) => Decision::Fail(err.raw_os_error().unwrap_or(libc::EACCES)),
+        }
+    }
+
+    /// Decides about a syscall that reads the metadata of the file at `path`, relative to
+    /// `dirfd`. An empty path refers to `dirfd` itself, which the child already has open.
+    fn decide_metadata(&self, dirfd: i32, path: u64) -> Decision {
+        let Ok(path) = self.resolve_path(dirfd, path) else {
+            return Decision::Fail(libc::EFAULT);
+        };
+        if path.as_os_str().is_empty() && dirfd != libc::AT_FDCWD {
+            return Decision::Continue;
+        }
+        let canonical = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+        if self.is_declared(&canonical) { Decision::Continue } else { Self::deny_undeclared(&path) }
+    }
+
+    fn is_declared(&self, canonical: &Path) -> bool {
+        self.tracked_paths
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|tracked| canonical == *tracked || canonical.starts_with(tracked))
+    }
+
+    fn deny_undeclared(path: &Path) -> Decision {
+        let description = format!(
+            "tried to read `{}`, which was not declared with `proc_macro::tracked::path`",
+            path.display()
+        );
+        Decision::Deny(libc::EACCES, Violation { description, undeclared_file: true })
+    }
+
+    fn respond(&self, id: u64, decision: Decision) {
+        let fd = self.listener.as_raw_fd();
+        let (error, flags) = match decision {
+            Decision::Continue => (0, SECCOMP_USER_NOTIF_FLAG_CONTINUE),
+            Decision::Open { file, path, cloexec } => {
+                let addfd = SeccompNotifAddfd {
+                    id,
+                    flags: SECCOMP_ADDFD_FLAG_SEND,
+                    srcfd: file.as_raw_fd() as u32,
+                    newfd: 0,
+                    newfd_flags: if cloexec { libc::O_CLOEXEC as u32 } else { 0 },
+                };
+                // With `SECCOMP_ADDFD_FLAG_SEND` this also completes the syscall in the child.
+                if unsafe { libc::ioctl(fd, SECCOMP_IOCTL_NOTIF_ADDFD as _, &addfd) } >= 0 {
+                    return;
+                }
+                let err = io::Error::last_os_error();
+                if err.raw_os_error() == Some(libc::ENOENT) {
+                    // The child was killed while the syscall was pending.
+                    return;
+                }
+                let path = path.display();
+                let description = format!("tried to read `{path}`, but the sandbox failed: {err}");
+                self.violations
+                    .lock()
+                    .unwrap()
+                    .push(Violation { description, undeclared_file: false });
+                (libc::EACCES, 0)
+            }
+            Decision::Fail(error) => (error, 0),
+            Decision::Deny(error, violation) => {
+                // Record the violation before the child can continue, so that it is reported
+                // for the expansion that caused it.
+                self.violations.lock().unwrap().push(violation);
+                (error, 0)
+            }
+        };
+        let resp = SeccompNotifResp { id, val: 0, error: -error, flags };
+        unsafe { libc::ioctl(fd, SECCOMP_IOCTL_NOTIF_SEND as _, &resp) };
+    }
+
+    fn read_u64(&self, addr: u64) -> io::Result<u64> {
+        let mut bytes = [0; 8];
+        self.mem.read_exact_at(&mut bytes, addr)?;
+        Ok(u64::from_ne_bytes(bytes))
+    }
+
+    /// Reads the path at `addr` in the child's memory, and makes it absolute.
+    fn resolve_path(&self, dirfd: i32, addr: u64) -> io::Result<PathBuf> {
+        let mut path = Vec::new();
+        let mut chunk = [0; 256];
+        loop {
+            let read = self.mem.read_at(&mut chunk, addr + path.len() as u64)?;
+            if read == 0 || path.len() > libc::PATH_MAX as usize {
+                return Err(io::ErrorKind::InvalidInput.into());
+            }
+            if let Some(end) = chunk[..read].iter().position(|&b| b == 0) {
+                path.extend_from_slice(&chunk[..end]);
+                break;
+            }
+            path.extend_from_slice(&chunk[..read]);
+        }
+        let path = PathBuf::from(OsString::from_vec(path));
+        if path.is_absolute() {
+            return Ok(path);
+        }
+        let base = if dirfd == libc::AT_FDCWD {
+            fs::read_link(format!("/proc/{}/cwd", self.pid))?
+        } else {
+            fs::read_link(format!("/proc/{}/fd/{dirfd}", self.pid))?
+        };
+        Ok(base.join(path))
+    }
+}
+
+/// Makes `path`, as declared by a proc macro, comparable to the paths resolved by the supervisor.
+pub(super) fn normalize_tracked_path(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| {
+        std::env::current_dir().map(|cwd| cwd.join(path)).unwrap_or_else(|_| path.to_owned())
+    })
+}
+
+const _: () = assert!(mem::size_of::<SeccompNotif>() == 0x50);
+const _: () = assert!(mem::size_of::<SeccompNotifResp>() == 0x18);
+const _: () = assert!(mem::size_of::<SeccompNotifAddfd>() == 0x18);
@@ -473,6 +473,7 @@ fn injected_env_var(&mut self, var: &str) -> Option<String> {
     }
 
     fn track_env_var(&mut self, var: &str, value: Option<&str>) {
+        self.ecx.proc_macro_cache.track_env_var(var, value);
         self.psess()
             .env_depinfo
             .borrow_mut()
@@ -480,6 +481,10 @@ fn track_env_var(&mut self, var: &str, value: Option<&str>) {
     }
 
     fn track_path(&mut self, path: &str) {
+        self.ecx.proc_macro_cache.track_path(path);
+        if let Some(sandbox) = &self.ecx.proc_macro_sandbox {
+            sandbox.track_path(path);
+        }
         self.psess().file_depinfo.borrow_mut().insert(Symbol::intern(path));
     }
 
@@ -543,6 +548,7 @@ fn literal_from_str(&mut self, s: &str) -> Result<Literal<Self::Span, Self::Symb
     }
 
     fn emit_diagnostic(&mut self, diagnostic: Diagnostic<Self::Span>) {
+        self.ecx.proc_macro_cache.mark_uncacheable();
         let message = rustc_errors::DiagMessage::from(diagnostic.message);
         let mut diag: Diag<'_, ()> =
             Diag::new(self.psess().dcx(), diagnostic.level.to_internal(), message);
@@ -576,6 +582,7 @@ fn to_string(&mut self, stream: &Self::TokenStream) -> String {
     }
 
     fn expand_expr(&mut self, stream: &Self::TokenStream) -> Result<Self::TokenStream, ()> {
+        self.ecx.proc_macro_cache.mark_uncacheable();
         // Parse the expression from our tokenstream.
         let expr: PResult<'_, _> = try {
             let mut p = Parser::new(self.psess(), stream.clone(), Some("proc_macro expand expr"));
@@ -679,6 +686,7 @@ fn into_trees(
 
 impl server::Span for Rustc<'_, '_> {
     fn debug(&mut self, span: Self::Span) -> String {
+        self.ecx.proc_macro_cache.mark_uncacheable();
         if self.ecx.ecfg.span_debug {
             format!("{span:?}")
         } else {
@@ -687,6 +695,7 @@ fn debug(&mut self, span: Self::Span) -> String {
     }
 
     fn file(&mut self, span: Self::Span) -> String {
+        self.ecx.proc_macro_cache.mark_uncacheable();
         self.psess()
             .source_map()
             .lookup_char_pos(span.lo())
@@ -697,6 +706,7 @@ fn file(&mut self, span: Self::Span) -> String {
     }
 
     fn local_file(&mut self, span: Self::Span) -> Option<String> {
+        self.ecx.proc_macro_cache.mark_uncacheable();
         self.psess()
             .source_map()
             .lookup_char_pos(span.lo())
@@ -712,14 +722,17 @@ fn local_file(&mut self, span: Self::Span) -> Option<String> {
     }
 
     fn parent(&mut self, span: Self::Span) -> Option<Self::Span> {
+        self.ecx.proc_macro_cache.mark_uncacheable();
         span.parent_callsite()
     }
 
     fn source(&mut self, span: Self::Span) -> Self::Span {
+        self.ecx.proc_macro_cache.mark_uncacheable();
         span.source_callsite()
     }
 
     fn byte_range(&mut self, span: Self::Span) -> Range<usize> {
+        self.ecx.proc_macro_cache.mark_uncacheable();
         let source_map = self.psess().source_map();
 
         let relative_start_pos = source_map.lookup_byte_offset(span.lo()).pos;
@@ -736,16 +749,19 @@ fn end(&mut self, span: Self::Span) -> Self::Span {
     }
 
     fn line(&mut self, span: Self::Span) -> usize {
+        self.ecx.proc_macro_cache.mark_uncacheable();
         let loc = self.psess().source_map().lookup_char_pos(span.lo());
         loc.line
     }
 
     fn column(&mut self, span: Self::Span) -> usize {
+        self.ecx.proc_macro_cache.mark_uncacheable();
         let loc = self.psess().source_map().lookup_char_pos(span.lo());
         loc.col.to_usize() + 1
     }
 
     fn join(&mut self, first: Self::Span, second: Self::Span) -> Option<Self::Span> {
+        self.ecx.proc_macro_cache.mark_uncacheable();
         let self_loc = self.psess().source_map().lookup_char_pos(first.lo());
         let other_loc = self.psess().source_map().lookup_char_pos(second.lo());
 
@@ -762,6 +778,7 @@ fn subspan(
         start: Bound<usize>,
         end: Bound<usize>,
     ) -> Option<Self::Span> {
+        self.ecx.proc_macro_cache.mark_uncacheable();
         let length = span.hi().to_usize() - span.lo().to_usize();
 
         let start = match start {
@@ -797,6 +814,7 @@ fn resolved_at(&mut self, span: Self::Span, at: Self::Span) -> Self::Span {
     }
 
     fn source_text(&mut self, span: Self::Span) -> Option<String> {
+        self.ecx.proc_macro_cache.mark_uncacheable();
         self.psess().source_map().span_to_snippet(span).ok()
     }
 
@@ -1,7 +1,9 @@
 use std::iter;
+use std::time::Duration;
 
 use rustc_ast::{self as ast, DUMMY_NODE_ID, Expr, ExprKind};
 use rustc_ast_pretty::pprust;
+use rustc_session::macro_stats::MacroStat;
 use rustc_span::hygiene::{ExpnKind, MacroKind};
 use rustc_span::{Span, Symbol, kw, sym};
 use smallvec::SmallVec;
@@ -9,18 +11,6 @@
 use crate::base::{Annotatable, ExtCtxt};
 use crate::expand::{AstFragment, AstFragmentKind};
 
-#[derive(Default)]
-pub struct MacroStat {
-    /// Number of uses of the macro.
-    pub uses: usize,
-
-    /// Number of lines of code (when pretty-printed).
-    pub lines: usize,
-
-    /// Number of bytes of code (when pretty-printed).
-    pub bytes: usize,
-}
-
 pub(crate) fn elems_to_string<T>(elems: &SmallVec<[T; 1]>, f: impl Fn(&T) -> String) -> String {
     let mut s = String::new();
     for (i, elem) in elems.iter().enumerate() {
@@ -42,6 +32,7 @@ pub(crate) fn update_bang_macro_stats(
     span: Span,
     mac: Box<ast::MacCall>,
     fragment: &AstFragment,
+    time: Duration,
 ) {
     // Does this path match any of the include macros, e.g. `include!`?
     // Ignore them. They would have large numbers but are entirely
@@ -71,7 +62,16 @@ pub(crate) fn update_bang_macro_stats(
     // Get `mac` back out of `expr`.
     let ast::Expr { kind: ExprKind::MacCall(mac), .. } = expr else { unreachable!() };
 
-    update_macro_stats(ecx, MacroKind::Bang, fragment_kind, span, &mac.path, &input, fragment);
+    update_macro_stats(
+        ecx,
+        MacroKind::Bang,
+        fragment_kind,
+        span,
+        &mac.path,
+        &input,
+        fragment,
+        time,
+    );
 }
 
 pub(crate) fn update_attr_macro_stats(
@@ -82,6 +82,7 @@ pub(crate) fn update_attr_macro_stats(
     attr: &ast::Attribute,
     item: Annotatable,
     fragment: &AstFragment,
+    time: Duration,
 ) {
     // Does this path match `#[derive(...)]` in any of its forms? If so,
     // ignore it because the individual derives will go through the
@@ -100,7 +101,7 @@ pub(crate) fn update_attr_macro_stats(
         pprust::attribute_to_string(attr),
         fragment_kind.expect_from_annotatables(iter::once(item)).to_string(),
     );
-    update_macro_stats(ecx, MacroKind::Attr, fragment_kind, span, path, &input, fragment);
+    update_macro_stats(ecx, MacroKind::Attr, fragment_kind, span, path, &input, fragment, time);
 }
 
 pub(crate) fn update_derive_macro_stats(
@@ -109,12 +110,13 @@ pub(crate) fn update_derive_macro_stats(
     span: Span,
     path: &ast::Path,
     fragment: &AstFragment,
+    time: Duration,
 ) {
     // Use something like `#[derive(Clone)]` for the measured input, even
     // though it may have actually appeared in a multi-derive attribute
     // like `#[derive(Clone, Copy, Debug)]`.
     let input = format!("#[derive({})]", pprust::path_to_string(path));
-    update_macro_stats(ecx, MacroKind::Derive, fragment_kind, span, path, &input, fragment);
+    update_macro_stats(ecx, MacroKind::Derive, fragment_kind, span, path, &input, fragment, time);
 }
 
 pub(crate) fn update_macro_stats(
@@ -125,6 +127,7 @@ pub(crate) fn update_macro_stats(
     path: &ast::Path,
     input: &str,
     fragment: &AstFragment,
+    time: Duration,
 ) {
     // Measure the size of the output by pretty-printing it and counting
     // the lines and bytes.
@@ -145,15 +148,17 @@ pub(crate) fn update_macro_stats(
             {name}: [{crate_name}] ({fragment_kind:?}) {span}\n\
             -------------------------------\n\
             {input}\n\
-            -- {num_lines} lines, {num_bytes} bytes --\n\
+            -- {num_lines} lines, {num_bytes} bytes, {time:.1?} --\n\
             {output}\n\
         "
         );
     }
 
     // The recorded size is the difference between the input and the output.
-    let entry = ecx.macro_stats.entry((name, macro_kind)).or_insert(MacroStat::default());
+    let mut macro_stats = ecx.sess.macro_stats.lock();
+    let entry = macro_stats.entry((name, macro_kind)).or_insert(MacroStat::default());
     entry.uses += 1;
     entry.lines += num_lines;
     entry.bytes += num_bytes;
+    entry.time += time;
 }
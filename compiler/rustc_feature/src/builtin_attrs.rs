@@ -862,6 +862,10 @@ pub struct BuiltinAttribute {
         register_tool, CrateLevel, template!(List: &["tool1, tool2, ..."]), DuplicatesOk,
         EncodeCrossCrate::No, experimental!(register_tool),
     ),
+    gated!(
+        proc_macro_pure, CrateLevel, template!(Word), WarnFollowing,
+        EncodeCrossCrate::Yes, experimental!(proc_macro_pure)
+    ),
 
     // lang-team MCP 147
     gated!(
@@ -1599,6 +1603,9 @@ pub fn is_stable_diagnostic_attribute(sym: Symbol, features: &Features) -> bool
     match sym {
         sym::on_unimplemented | sym::do_not_recommend => true,
         sym::on_const => features.diagnostic_on_const(),
+        sym::on_move => features.diagnostic_on_move(),
+        sym::on_type_mismatch => features.diagnostic_on_type_mismatch(),
+        sym::on_unknown_method => features.diagnostic_on_unknown_method(),
         _ => false,
     }
 }
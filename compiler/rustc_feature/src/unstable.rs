@@ -487,6 +487,15 @@ pub fn internal(&self, feature: Symbol) -> bool {
     (unstable, derive_from, "1.91.0", Some(144889)),
     /// Allows giving non-const impls custom diagnostic messages if attempted to be used as const
     (unstable, diagnostic_on_const, "1.93.0", Some(143874)),
+    /// Allows giving types custom diagnostic messages when a value of the type is used after
+    /// being moved, with `#[diagnostic::on_move]`.
+    (unstable, diagnostic_on_move, "CURRENT_RUSTC_VERSION", None),
+    /// Allows giving types custom diagnostic messages on type mismatches involving them, with
+    /// `#[diagnostic::on_type_mismatch]`.
+    (unstable, diagnostic_on_type_mismatch, "CURRENT_RUSTC_VERSION", None),
+    /// Allows giving types custom diagnostic messages when a method that doesn't exist is called
+    /// on them, with `#[diagnostic::on_unknown_method]`.
+    (unstable, diagnostic_on_unknown_method, "CURRENT_RUSTC_VERSION", None),
     /// Allows `#[doc(cfg(...))]`.
     (unstable, doc_cfg, "1.21.0", Some(43781)),
     /// Allows `#[doc(masked)]`.
@@ -620,6 +629,9 @@ pub fn internal(&self, feature: Symbol) -> bool {
     (unstable, postfix_match, "1.79.0", Some(121618)),
     /// Allows macro attributes on expressions, statements and non-inline modules.
     (unstable, proc_macro_hygiene, "1.30.0", Some(54727)),
+    /// Allows proc macro crates to declare their macros pure with `#![proc_macro_pure]`, letting
+    /// incremental builds reuse their outputs.
+    (unstable, proc_macro_pure, "CURRENT_RUSTC_VERSION", None),
     /// Allows the use of raw-dylibs on ELF platforms
     (incomplete, raw_dylib_elf, "1.87.0", Some(135694)),
     (unstable, reborrow, "1.91.0", Some(145612)),
@@ -783,6 +783,7 @@ pub(crate) fn check_item_type(tcx: TyCtxt<'_>, def_id: LocalDefId) -> Result<(),
             crate::collect::lower_enum_variant_types(tcx, def_id);
             check_enum(tcx, def_id);
             check_variances_for_type_defn(tcx, def_id);
+            check_diagnostic_attrs(tcx, def_id);
         }
         DefKind::Fn => {
             tcx.ensure_ok().generics_of(def_id);
@@ -871,6 +872,7 @@ pub(crate) fn check_item_type(tcx: TyCtxt<'_>, def_id: LocalDefId) -> Result<(),
                 _ => unreachable!(),
             }
             check_variances_for_type_defn(tcx, def_id);
+            check_diagnostic_attrs(tcx, def_id);
         }
         DefKind::OpaqueTy => {
             check_opaque_precise_captures(tcx, def_id);
@@ -1118,6 +1120,11 @@ pub(crate) fn check_item_type(tcx: TyCtxt<'_>, def_id: LocalDefId) -> Result<(),
 pub(super) fn check_diagnostic_attrs(tcx: TyCtxt<'_>, def_id: LocalDefId) {
     // an error would be reported if this fails.
     let _ = OnUnimplementedDirective::of_item(tcx, def_id.to_def_id());
+    if matches!(tcx.def_kind(def_id), DefKind::Struct | DefKind::Enum | DefKind::Union) {
+        for attr in [sym::on_move, sym::on_unknown_method, sym::on_type_mismatch] {
+            let _ = OnUnimplementedDirective::of_adt(tcx, def_id.to_def_id(), attr);
+        }
+    }
 }
 
 pub(super) fn check_specialization_validity<'tcx>(
@@ -293,6 +293,13 @@ hir_typeck_trivial_cast = trivial {$numeric ->
     }: `{$expr_ty}` as `{$cast_ty}`
     .help = cast can be replaced by coercion; this might require a temporary variable
 
+hir_typeck_typed_hole = typed hole of type `{$ty}`
+    .label = a value of type `{$ty}` is expected here
+
+hir_typeck_typed_hole_bindings = a binding in scope has a compatible type
+
+hir_typeck_typed_hole_exprs = an expression built from the bindings in scope has a compatible type
+
 hir_typeck_union_pat_dotdot = `..` cannot be used in union patterns
 
 hir_typeck_union_pat_multiple_fields = union patterns should have exactly one field
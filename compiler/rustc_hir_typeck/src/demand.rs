@@ -1093,7 +1093,7 @@ pub(crate) fn get_conversion_methods_for_diagnostic(
     }
 
     /// This function checks whether the method is not static and does not accept other parameters than `self`.
-    fn has_only_self_parameter(&self, method: &AssocItem) -> bool {
+    pub(crate) fn has_only_self_parameter(&self, method: &AssocItem) -> bool {
         method.is_method()
             && self.tcx.fn_sig(method.def_id).skip_binder().inputs().skip_binder().len() == 1
     }
@@ -702,6 +702,15 @@ pub(crate) struct TrivialCast<'tcx> {
     pub cast_ty: Ty<'tcx>,
 }
 
+#[derive(Diagnostic)]
+#[diag(hir_typeck_typed_hole)]
+pub(crate) struct TypedHoleNote<'tcx> {
+    #[primary_span]
+    #[label]
+    pub span: Span,
+    pub ty: Ty<'tcx>,
+}
+
 pub(crate) struct BreakNonLoop<'a> {
     pub span: Span,
     pub head: Option<Span>,
@@ -257,6 +257,10 @@ pub(super) fn check_expr_with_expectation_and_args(
             }
         }
 
+        if self.tcx.sess.opts.unstable_opts.typed_holes {
+            self.record_typed_hole(expr, expected);
+        }
+
         // True if `expr` is a `Try::from_ok(())` that is a result of desugaring a try block
         // without the final expr (e.g. `try { return; }`). We don't want to generate an
         // unreachable_code lint for it since warnings for autogenerated code are confusing.
@@ -36,9 +36,13 @@
 mod pat;
 mod place_op;
 mod typeck_root_ctxt;
+mod typed_holes;
 mod upvar;
 mod writeback;
 
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
 pub use coercion::can_coerce;
 use fn_ctxt::FnCtxt;
 use rustc_data_structures::unord::UnordSet;
@@ -55,6 +59,7 @@
 use rustc_middle::ty::{self, Ty, TyCtxt};
 use rustc_middle::{bug, span_bug};
 use rustc_session::config;
+use rustc_session::macro_stats::expanded_macro;
 use rustc_span::Span;
 use rustc_span::def_id::LocalDefId;
 use tracing::{debug, instrument};
@@ -86,8 +91,36 @@ fn used_trait_imports(tcx: TyCtxt<'_>, def_id: LocalDefId) -> &UnordSet<LocalDef
     &tcx.typeck(def_id).used_trait_imports
 }
 
+thread_local! {
+    /// The time spent type checking the bodies whose type checking was started while type
+    /// checking another body on this thread, e.g. to reveal an opaque type, for `-Zmacro-stats`.
+    static NESTED_TYPECK_TIME: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
 fn typeck<'tcx>(tcx: TyCtxt<'tcx>, def_id: LocalDefId) -> &'tcx ty::TypeckResults<'tcx> {
-    typeck_with_inspect(tcx, def_id, None)
+    // With incremental compilation, type checking is not timed, as the results of the previous
+    // session are loaded from the cache without running this.
+    if !tcx.sess.macro_stats_costs()
+        || tcx.sess.opts.incremental.is_some()
+        || tcx.is_typeck_child(def_id.to_def_id())
+    {
+        return typeck_with_inspect(tcx, def_id, None);
+    }
+
+    // `-Zmacro-stats` attributes the time spent type checking a body to the macro expansion the
+    // body comes from. The bodies type checked in the meantime are attributed to their own macro,
+    // so their time is subtracted.
+    let outer_nested_time = NESTED_TYPECK_TIME.replace(Duration::ZERO);
+    let start = Instant::now();
+    let typeck_results = typeck_with_inspect(tcx, def_id, None);
+    let time = start.elapsed();
+    let nested_time = NESTED_TYPECK_TIME.replace(outer_nested_time + time);
+    if let Some(expanded_macro) = expanded_macro(tcx.def_span(def_id))
+        && let Some(stat) = tcx.sess.macro_stats.lock().get_mut(&expanded_macro)
+    {
+        stat.typeck_time += time.saturating_sub(nested_time);
+    }
+    typeck_results
 }
 
 /// Same as `typeck` but `inspect` is invoked on evaluation of each root obligation.
@@ -271,6 +304,10 @@ fn typeck_with_inspect<'tcx>(
 
     fcx.check_asms();
 
+    if tcx.sess.opts.unstable_opts.typed_holes {
+        fcx.report_typed_holes();
+    }
+
     let typeck_results = fcx.resolve_type_vars_in_body(body);
 
     fcx.detect_opaque_types_added_during_writeback();
@@ -1162,7 +1162,7 @@ fn report_no_match_method_error(
             restrict_type_params,
             suggested_derive,
             unsatisfied_bounds,
-            custom_span_label,
+            mut custom_span_label,
             bound_spans,
         )) = self.suggest_unsatisfied_ty_or_trait(
             &mut err,
@@ -1178,6 +1178,24 @@ fn report_no_match_method_error(
             return err.emit();
         };
 
+        if unsatisfied_predicates.is_empty() {
+            let OnUnimplementedNote { message, label, notes, .. } = self.err_ctxt().on_type_note(
+                sym::on_unknown_method,
+                rcvr_ty.peel_refs(),
+                err.long_ty_path(),
+            );
+            if let Some(message) = message {
+                err.primary_message(message);
+            }
+            if let Some(label) = label {
+                custom_span_label = true;
+                err.span_label(item_ident.span, label);
+            }
+            for note in notes {
+                err.note(note);
+            }
+        }
+
         let similar_candidate = no_match_data.similar_candidate;
         let should_label_not_found = self.suggest_surround_method_call(
             &mut err,
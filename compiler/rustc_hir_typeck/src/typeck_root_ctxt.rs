@@ -13,6 +13,7 @@
 use tracing::instrument;
 
 use super::callee::DeferredCallResolution;
+use super::typed_holes::TypedHole;
 
 /// Data shared between a "typeck root" and its nested bodies,
 /// e.g. closures defined within the function. For example:
@@ -67,6 +68,10 @@ pub(crate) struct TypeckRootCtxt<'tcx> {
     /// we record that type variable here. This is later used to inform
     /// fallback. See the `fallback` module for details.
     pub(super) diverging_type_vars: RefCell<UnordSet<Ty<'tcx>>>,
+
+    /// The `todo!()` and `_` holes found when `-Ztyped-holes` is enabled, reported once the
+    /// expected types have been inferred.
+    pub(super) typed_holes: RefCell<Vec<TypedHole<'tcx>>>,
 }
 
 impl<'tcx> Deref for TypeckRootCtxt<'tcx> {
@@ -101,6 +106,7 @@ pub(crate) fn new(tcx: TyCtxt<'tcx>, def_id: LocalDefId) -> Self {
             deferred_asm_checks: RefCell::new(Vec::new()),
             deferred_repeat_expr_checks: RefCell::new(Vec::new()),
             diverging_type_vars: RefCell::new(Default::default()),
+            typed_holes: RefCell::new(Vec::new()),
         }
     }
 
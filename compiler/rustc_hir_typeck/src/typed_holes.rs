@@ -0,0 +1,303 @@
+//! Typed holes for `-Ztyped-holes`.
+//!
+//! With the flag, `todo!()` and `_` in expression position are reported with a note giving the
+//! type that their context expects, along with the bindings in scope and short expressions built
+//! from them (`x.field`, `x.method()`, `x.field.method()`) that have that type. Holes are
+//! collected while checking the body and reported once it has been checked, as the expected type
+//! is often only inferred from later uses.
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_errors::{Applicability, SuggestionStyle};
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_hir::{self as hir, HirId};
+use rustc_middle::hir::nested_filter;
+use rustc_middle::ty::{self, Ty, TyCtxt, TypeVisitableExt};
+use rustc_span::{Ident, Span, Symbol, sym};
+
+use crate::method::probe;
+use crate::{Expectation, FnCtxt, errors, fluent_generated as fluent};
+
+/// The most candidates of each kind listed for a hole.
+const MAX_CANDIDATES: usize = 8;
+
+pub(crate) struct TypedHole<'tcx> {
+    hir_id: HirId,
+    /// The span replaced by the candidates, which is the whole invocation for `todo!(..)`.
+    span: Span,
+    expected: Ty<'tcx>,
+}
+
+impl<'a, 'tcx> FnCtxt<'a, 'tcx> {
+    /// Records `expr` as a typed hole if it is `_` or the expansion of `todo!()` and its context
+    /// expects a type.
+    pub(crate) fn record_typed_hole(
+        &self,
+        expr: &'tcx hir::Expr<'tcx>,
+        expected: Expectation<'tcx>,
+    ) {
+        let Some(expected) = expected.only_has_type(self) else { return };
+        let span = match expr.kind {
+            // `_` in expression position has already been rejected when lowering to HIR.
+            hir::ExprKind::Err(_)
+                if !expr.span.from_expansion()
+                    && self
+                        .tcx
+                        .sess
+                        .source_map()
+                        .span_to_snippet(expr.span)
+                        .is_ok_and(|snippet| snippet == "_") =>
+            {
+                expr.span
+            }
+            _ => {
+                let Some(call_site) = self.todo_call_site(expr.span) else { return };
+                // Only the outermost expression of the expansion is the hole.
+                if let hir::Node::Expr(parent) = self.tcx.parent_hir_node(expr.hir_id)
+                    && self.todo_call_site(parent.span) == Some(call_site)
+                {
+                    return;
+                }
+                call_site
+            }
+        };
+        self.typed_holes.borrow_mut().push(TypedHole { hir_id: expr.hir_id, span, expected });
+    }
+
+    /// Returns the span of the `todo!()` invocation that `span` was expanded from, if any.
+    fn todo_call_site(&self, span: Span) -> Option<Span> {
+        let mut ctxt = span.ctxt();
+        while !ctxt.is_root() {
+            let expn_data = ctxt.outer_expn_data();
+            if expn_data
+                .macro_def_id
+                .is_some_and(|def_id| self.tcx.is_diagnostic_item(sym::todo_macro, def_id))
+            {
+                return Some(expn_data.call_site);
+            }
+            ctxt = expn_data.call_site.ctxt();
+        }
+        None
+    }
+
+    pub(crate) fn report_typed_holes(&self) {
+        let holes = std::mem::take(&mut *self.typed_holes.borrow_mut());
+        if holes.is_empty() {
+            return;
+        }
+
+        let mut collector = BindingsInScope {
+            tcx: self.tcx,
+            holes: holes.iter().map(|hole| hole.hir_id).collect(),
+            scope: Vec::new(),
+            in_scope: FxHashMap::default(),
+        };
+        collector.visit_body(self.tcx.hir_body_owned_by(self.body_id));
+
+        for hole in holes {
+            let expected = self.resolve_vars_if_possible(hole.expected);
+            // A unit hole is almost always a statement, which needs no filling.
+            if expected.references_error() || expected.is_unit() {
+                continue;
+            }
+            let mut diag =
+                self.dcx().create_note(errors::TypedHoleNote { span: hole.span, ty: expected });
+            // Nothing is known about the type yet, so anything would do.
+            if !expected.is_ty_var() {
+                let bindings = collector.in_scope.get(&hole.hir_id).map_or(&[][..], |b| &b[..]);
+                let (bindings, exprs) = self.typed_hole_candidates(&hole, expected, bindings);
+                if !bindings.is_empty() {
+                    diag.span_suggestions_with_style(
+                        hole.span,
+                        fluent::hir_typeck_typed_hole_bindings,
+                        bindings,
+                        Applicability::MaybeIncorrect,
+                        SuggestionStyle::ShowAlways,
+                    );
+                }
+                if !exprs.is_empty() {
+                    diag.span_suggestions_with_style(
+                        hole.span,
+                        fluent::hir_typeck_typed_hole_exprs,
+                        exprs,
+                        Applicability::MaybeIncorrect,
+                        SuggestionStyle::ShowAlways,
+                    );
+                }
+            }
+            diag.emit();
+        }
+    }
+
+    /// Returns the bindings in `bindings` (innermost last) that can fill the hole, possibly
+    /// borrowed, and the field accesses and method calls on them that can.
+    fn typed_hole_candidates(
+        &self,
+        hole: &TypedHole<'tcx>,
+        expected: Ty<'tcx>,
+        bindings: &[(Ident, HirId)],
+    ) -> (Vec<String>, Vec<String>) {
+        let tcx = self.tcx;
+        let module = tcx.parent_module(hole.hir_id);
+        let mut shadowed = FxHashSet::default();
+        let mut direct = Vec::new();
+        let mut exprs = Vec::new();
+        for &(ident, hir_id) in bindings.iter().rev() {
+            if !shadowed.insert(ident.name) {
+                continue;
+            }
+            let Some(ty) = self.typeck_results.borrow().node_type_opt(hir_id) else { continue };
+            let ty = self.resolve_vars_if_possible(ty);
+            if ty.is_ty_var() || ty.is_never() || ty.references_error() {
+                continue;
+            }
+
+            if self.may_coerce(ty, expected) {
+                direct.push(ident.to_string());
+                continue;
+            }
+            if let ty::Ref(_, _, mutbl) = *expected.kind()
+                && self.may_coerce(Ty::new_ref(tcx, tcx.lifetimes.re_static, ty, mutbl), expected)
+            {
+                direct.push(format!("{}{ident}", mutbl.ref_prefix_str()));
+                continue;
+            }
+
+            for method in self.typed_hole_methods(hole, expected, ty) {
+                exprs.push(format!("{ident}.{method}()"));
+            }
+            if let ty::Adt(adt, args) = *ty.peel_refs().kind()
+                && adt.is_struct()
+            {
+                for field in &adt.non_enum_variant().fields {
+                    let field_ty = field.ty(tcx, args);
+                    if !field.vis.is_accessible_from(module, tcx) || field_ty.has_aliases() {
+                        continue;
+                    }
+                    let receiver = format!("{ident}.{}", field.name);
+                    if self.may_coerce(field_ty, expected) {
+                        exprs.push(receiver);
+                        continue;
+                    }
+                    for method in self.typed_hole_methods(hole, expected, field_ty) {
+                        exprs.push(format!("{receiver}.{method}()"));
+                    }
+                }
+            }
+        }
+        direct.truncate(MAX_CANDIDATES);
+        exprs.truncate(MAX_CANDIDATES);
+        (direct, exprs)
+    }
+
+    /// Returns the methods without arguments other than `self` that can be called on `self_ty`
+    /// to get a value of the `expected` type.
+    fn typed_hole_methods(
+        &self,
+        hole: &TypedHole<'tcx>,
+        expected: Ty<'tcx>,
+        self_ty: Ty<'tcx>,
+    ) -> Vec<Symbol> {
+        self.probe_for_return_type_for_diagnostic(
+            hole.span,
+            probe::Mode::MethodCall,
+            expected,
+            self_ty,
+            hole.hir_id,
+            |item| {
+                // Methods generic over their return type, like `Into::into`, match any type.
+                self.has_only_self_parameter(item)
+                    && !matches!(
+                        self.tcx.fn_sig(item.def_id).skip_binder().output().skip_binder().kind(),
+                        ty::Param(_)
+                    )
+            },
+        )
+        .into_iter()
+        .map(|item| item.name())
+        .collect()
+    }
+}
+
+/// Collects the bindings in scope at each hole, innermost last.
+struct BindingsInScope<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    holes: FxHashSet<HirId>,
+    scope: Vec<(Ident, HirId)>,
+    in_scope: FxHashMap<HirId, Vec<(Ident, HirId)>>,
+}
+
+impl BindingsInScope<'_> {
+    fn bind(&mut self, pat: &hir::Pat<'_>) {
+        pat.each_binding(|_, hir_id, _, ident| {
+            // Skip the bindings introduced by desugarings, like the iterator of a `for` loop.
+            if !ident.span.from_expansion() {
+                self.scope.push((ident, hir_id));
+            }
+        });
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for BindingsInScope<'tcx> {
+    type NestedFilter = nested_filter::OnlyBodies;
+
+    fn maybe_tcx(&mut self) -> Self::MaybeTyCtxt {
+        self.tcx
+    }
+
+    fn visit_param(&mut self, param: &'tcx hir::Param<'tcx>) {
+        self.bind(param.pat);
+        intravisit::walk_param(self, param);
+    }
+
+    fn visit_block(&mut self, block: &'tcx hir::Block<'tcx>) {
+        let len = self.scope.len();
+        intravisit::walk_block(self, block);
+        self.scope.truncate(len);
+    }
+
+    fn visit_local(&mut self, local: &'tcx hir::LetStmt<'tcx>) {
+        // The bindings of a `let` are only in scope after it.
+        if let Some(init) = local.init {
+            self.visit_expr(init);
+        }
+        if let Some(els) = local.els {
+            self.visit_block(els);
+        }
+        self.bind(local.pat);
+    }
+
+    fn visit_arm(&mut self, arm: &'tcx hir::Arm<'tcx>) {
+        let len = self.scope.len();
+        self.bind(arm.pat);
+        intravisit::walk_arm(self, arm);
+        self.scope.truncate(len);
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr<'tcx>) {
+        if self.holes.contains(&expr.hir_id) {
+            self.in_scope.insert(expr.hir_id, self.scope.clone());
+        }
+        match expr.kind {
+            // The bindings of `if let` are only in scope in the `then` branch.
+            hir::ExprKind::If(cond, then, els) => {
+                let len = self.scope.len();
+                self.visit_expr(cond);
+                self.visit_expr(then);
+                self.scope.truncate(len);
+                if let Some(els) = els {
+                    self.visit_expr(els);
+                }
+            }
+            hir::ExprKind::Let(let_expr) => {
+                self.visit_expr(let_expr.init);
+                self.bind(let_expr.pat);
+            }
+            hir::ExprKind::Closure(..) => {
+                let len = self.scope.len();
+                intravisit::walk_expr(self, expr);
+                self.scope.truncate(len);
+            }
+            _ => intravisit::walk_expr(self, expr),
+        }
+    }
+}
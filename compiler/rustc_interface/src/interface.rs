@@ -20,6 +20,7 @@
 use rustc_query_impl::QueryCtxt;
 use rustc_query_system::query::print_query_stack;
 use rustc_session::config::{self, Cfg, CheckCfg, ExpectedValues, Input, OutFileName};
+use rustc_session::mir_pass_stats::print_mir_pass_stats;
 use rustc_session::parse::ParseSess;
 use rustc_session::{CompilerIO, EarlyDiagCtxt, Session, lint};
 use rustc_span::source_map::{FileLoader, RealFileLoader, SourceMapInputs};
@@ -544,6 +545,12 @@ pub fn run_compiler<R: Send>(config: Config, f: impl FnOnce(&Compiler) -> R + Se
 
             compiler.sess.finish_diagnostics();
 
+            // The MIR passes run on demand, from analysis to codegen, so the statistics can only
+            // be printed once the compilation is over, however it ended.
+            if compiler.sess.opts.unstable_opts.mir_pass_stats {
+                print_mir_pass_stats(&compiler.sess.mir_pass_stats.lock());
+            }
+
             // If error diagnostics have been emitted, we can't return an
             // error directly, because the return type of this function
             // is `R`, not `Result<R, E>`. But we need to communicate the
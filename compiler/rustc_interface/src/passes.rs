@@ -3,17 +3,19 @@
 use std::io::{self, BufWriter, Write};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, LazyLock, OnceLock};
-use std::{env, fs, iter};
+use std::{cmp, env, fs, iter};
 
 use rustc_ast as ast;
 use rustc_attr_parsing::{AttributeParser, ShouldEmit};
 use rustc_codegen_ssa::traits::CodegenBackend;
 use rustc_codegen_ssa::{CodegenResults, CrateInfo};
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
 use rustc_data_structures::jobserver::Proxy;
 use rustc_data_structures::steal::Steal;
 use rustc_data_structures::sync::{AppendOnlyIndexVec, FreezeLock, WorkerLocal};
 use rustc_data_structures::{parallel, thousands};
 use rustc_errors::timings::TimingSection;
+use rustc_errors::{Applicability, CodeSuggestion, Suggestions};
 use rustc_expand::base::{ExtCtxt, LintStoreExpand};
 use rustc_feature::Features;
 use rustc_fs_util::try_canonicalize;
@@ -21,12 +23,14 @@
 use rustc_hir::def_id::{LOCAL_CRATE, StableCrateId, StableCrateIdMap};
 use rustc_hir::definitions::Definitions;
 use rustc_hir::limit::Limit;
+use rustc_hir::{HirId, intravisit};
 use rustc_incremental::setup_dep_graph;
 use rustc_lint::{BufferedEarlyLint, EarlyCheckNode, LintStore, unerased_lint_store};
 use rustc_metadata::EncodedMetadata;
 use rustc_metadata::creader::CStore;
 use rustc_middle::arena::Arena;
 use rustc_middle::dep_graph::DepsType;
+use rustc_middle::hir::nested_filter;
 use rustc_middle::ty::{self, CurrentGcx, GlobalCtxt, RegisteredTools, TyCtxt};
 use rustc_middle::util::Providers;
 use rustc_parse::lexer::StripTokens;
@@ -34,11 +38,17 @@
 use rustc_passes::{abi_test, input_stats, layout_test};
 use rustc_resolve::{Resolver, ResolverOutputs};
 use rustc_session::Session;
-use rustc_session::config::{CrateType, Input, OutFileName, OutputFilenames, OutputType};
+use rustc_session::config::{
+    CrateType, Input, OutFileName, OutputFilenames, OutputType, SwitchWithOptPath,
+};
 use rustc_session::cstore::Untracked;
+use rustc_session::macro_stats::{MacroStat, expanded_macro};
 use rustc_session::output::{collect_crate_types, filename_for_input};
 use rustc_session::parse::feature_err;
+use rustc_session::proof_trees::{ProofTreeCutOff, ProofTreeGoal};
 use rustc_session::search_paths::PathKind;
+use rustc_span::edition::Edition;
+use rustc_span::hygiene::MacroKind;
 use rustc_span::{
     DUMMY_SP, ErrorGuaranteed, ExpnKind, SourceFileHash, SourceFileHashAlgorithm, Span, Symbol, sym,
 };
@@ -217,10 +227,17 @@ fn configure_and_expand(
         // Expand macros now!
         let krate = sess.time("expand_crate", || ecx.monotonic_expander().expand_crate(krate));
 
+        // Dump the trace before aborting, failed expansions are the most interesting ones.
+        ecx.dump_macro_rules_trace();
+
         if ecx.nb_macro_errors > 0 {
             sess.dcx().abort_if_errors();
         }
 
+        if sess.opts.incremental.is_some() {
+            sess.time("save_proc_macro_cache", || ecx.save_proc_macro_cache());
+        }
+
         // The rest is error reporting and stats
 
         sess.psess.buffered_lints.with_lock(|buffered_lints: &mut Vec<BufferedEarlyLint>| {
@@ -311,20 +328,12 @@ fn configure_and_expand(
 fn print_macro_stats(ecx: &ExtCtxt<'_>) {
     use std::fmt::Write;
 
-    let crate_name = ecx.ecfg.crate_name.as_str();
-    let crate_name = if crate_name == "build_script_build" {
-        // This is a build script. Get the package name from the environment.
-        let pkg_name =
-            std::env::var("CARGO_PKG_NAME").unwrap_or_else(|_| "<unknown crate>".to_string());
-        format!("{pkg_name} build script")
-    } else {
-        crate_name.to_string()
-    };
+    let crate_name = macro_stats_crate_name(ecx.ecfg.crate_name.as_str());
+    let stats = ecx.sess.macro_stats.lock();
 
     // No instability because we immediately sort the produced vector.
     #[allow(rustc::potential_query_instability)]
-    let mut macro_stats: Vec<_> = ecx
-        .macro_stats
+    let mut macro_stats: Vec<_> = stats
         .iter()
         .map(|((name, kind), stat)| {
             // This gives the desired sort order: sort by bytes, then lines, etc.
@@ -398,6 +407,365 @@ fn print_macro_stats(ecx: &ExtCtxt<'_>) {
     eprint!("{s}");
 }
 
+/// The name of the crate in the `-Zmacro-stats` output.
+fn macro_stats_crate_name(crate_name: &str) -> String {
+    if crate_name == "build_script_build" {
+        // This is a build script. Get the package name from the environment.
+        let pkg_name =
+            std::env::var("CARGO_PKG_NAME").unwrap_or_else(|_| "<unknown crate>".to_string());
+        format!("{pkg_name} build script")
+    } else {
+        crate_name.to_string()
+    }
+}
+
+/// Counts the HIR nodes coming from each macro expansion recorded by `-Zmacro-stats`.
+struct MacroHirNodeCounter<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    macro_stats: &'a mut FxHashMap<(Symbol, MacroKind), MacroStat>,
+}
+
+impl<'a, 'tcx> intravisit::Visitor<'tcx> for MacroHirNodeCounter<'a, 'tcx> {
+    type NestedFilter = nested_filter::All;
+
+    fn maybe_tcx(&mut self) -> Self::MaybeTyCtxt {
+        self.tcx
+    }
+
+    fn visit_id(&mut self, hir_id: HirId) {
+        if let Some(expanded_macro) = expanded_macro(self.tcx.hir_span(hir_id))
+            && let Some(stat) = self.macro_stats.get_mut(&expanded_macro)
+        {
+            stat.hir_nodes += 1;
+        }
+    }
+}
+
+/// Prints the costs of the macro expansions for `-Zmacro-stats-costs`, and writes all the
+/// statistics for `-Zmacro-stats-json`. Unlike the expansion statistics, the costs are only known
+/// once all bodies have been type checked.
+fn print_macro_costs(tcx: TyCtxt<'_>) {
+    use std::fmt::Write;
+
+    let mut macro_stats = tcx.sess.macro_stats.lock();
+    tcx.hir_walk_toplevel_module(&mut MacroHirNodeCounter { tcx, macro_stats: &mut macro_stats });
+
+    let crate_name = macro_stats_crate_name(tcx.crate_name(LOCAL_CRATE).as_str());
+    // See `typeck` in `rustc_hir_typeck`.
+    let typeck_timed = tcx.sess.opts.incremental.is_none();
+
+    // No instability because we immediately sort the produced vector.
+    #[allow(rustc::potential_query_instability)]
+    let mut sorted_stats: Vec<_> =
+        macro_stats.iter().map(|(&(name, kind), stat)| (name, kind, stat)).collect();
+    // Sort by HIR nodes, then by the same keys as the expansion statistics, as the times vary
+    // between runs.
+    sorted_stats.sort_unstable_by_key(|&(name, kind, stat)| {
+        cmp::Reverse((stat.hir_nodes, stat.bytes, stat.lines, stat.uses, name, kind))
+    });
+
+    if tcx.sess.opts.unstable_opts.macro_stats_costs {
+        let prefix = "macro-stats";
+        let name_w = 32;
+        let time_w = 16;
+        let hir_nodes_w = 11;
+        let typeck_w = 13;
+        let banner_w = name_w + time_w + hir_nodes_w + typeck_w;
+
+        let mut s = String::new();
+        _ = writeln!(s, "{prefix} {}", "=".repeat(banner_w));
+        _ = writeln!(s, "{prefix} MACRO COST STATS: {}", crate_name);
+        _ = writeln!(
+            s,
+            "{prefix} {:<name_w$}{:>time_w$}{:>hir_nodes_w$}{:>typeck_w$}",
+            "Macro Name", "Expansion Time", "HIR Nodes", "Typeck Time",
+        );
+        _ = writeln!(s, "{prefix} {}", "-".repeat(banner_w));
+        if sorted_stats.is_empty() {
+            _ = writeln!(s, "{prefix} (none)");
+        }
+        for &(name, kind, stat) in &sorted_stats {
+            let mut name = ExpnKind::Macro(kind, name).descr();
+            if name.len() >= name_w {
+                _ = writeln!(s, "{prefix} {:<name_w$}", name);
+                name = String::new();
+            }
+            let typeck_time =
+                if typeck_timed { format!("{:.1?}", stat.typeck_time) } else { "-".to_owned() };
+            _ = writeln!(
+                s,
+                "{prefix} {:<name_w$}{:>time_w$}{:>hir_nodes_w$}{:>typeck_w$}",
+                name,
+                format!("{:.1?}", stat.time),
+                thousands::usize_with_underscores(stat.hir_nodes),
+                typeck_time,
+            );
+        }
+        if !typeck_timed {
+            _ = writeln!(s, "{prefix} (type checking is not timed with incremental compilation)");
+        }
+        _ = writeln!(s, "{prefix} {}", "=".repeat(banner_w));
+        eprint!("{s}");
+    }
+
+    if tcx.sess.opts.unstable_opts.macro_stats_json {
+        let macros: Vec<_> = sorted_stats
+            .iter()
+            .map(|&(name, kind, stat)| {
+                serde_json::json!({
+                    "name": name.as_str(),
+                    "kind": kind.descr(),
+                    "uses": stat.uses,
+                    "lines": stat.lines,
+                    "bytes": stat.bytes,
+                    "time_ns": stat.time.as_nanos() as u64,
+                    "hir_nodes": stat.hir_nodes,
+                    "typeck_time_ns": typeck_timed.then(|| stat.typeck_time.as_nanos() as u64),
+                })
+            })
+            .collect();
+        let report = serde_json::json!({ "crate": crate_name, "macros": macros });
+        let path = tcx
+            .output_filenames(())
+            .out_directory
+            .join(format!("{}.macro_stats.json", tcx.crate_name(LOCAL_CRATE)));
+        if let Err(error) = fs::write(&path, serde_json::to_string_pretty(&report).unwrap()) {
+            tcx.dcx().emit_fatal(errors::FailedWritingFile { path: &path, error });
+        }
+    }
+}
+
+/// A hit of an edition migration lint, for `-Zedition-migration-report`.
+struct EditionMigrationHit {
+    lint: String,
+    span: Option<Span>,
+    /// Whether the lint suggested a fix that can be applied without review.
+    machine_applicable: bool,
+    /// Whether the affected code would compile in the target edition but behave differently, for
+    /// example because values are dropped in a different order, rather than be rejected.
+    changes_semantics: bool,
+    /// The documentation of the change, usually a page of the edition guide.
+    reference: String,
+}
+
+/// Writes the hits of the migration lints of `target_edition` to
+/// `<crate_name>.edition_migration.json`. The hits are taken from the lints collected by the
+/// `DiagCtxt` for `-Zedition-migration-report`, so that the lints of queries loaded from the
+/// incremental cache, which are replayed rather than emitted again, are reported as well.
+fn write_edition_migration_report(tcx: TyCtxt<'_>, target_edition: Edition) {
+    let lint_store = unerased_lint_store(tcx.sess);
+    let mut hits: Vec<_> = tcx
+        .dcx()
+        .take_lint_diagnostics()
+        .into_iter()
+        .filter_map(|diag| {
+            let &[lint_id] = lint_store.find_lints(diag.lint_name()?)? else { return None };
+            let reason = lint_id.lint.future_incompatible?.reason;
+            if reason.edition() != Some(target_edition) {
+                return None;
+            }
+            let suggestions: &[CodeSuggestion] = match &diag.suggestions {
+                Suggestions::Enabled(suggestions) => suggestions,
+                Suggestions::Sealed(suggestions) => suggestions,
+                Suggestions::Disabled => &[],
+            };
+            Some(EditionMigrationHit {
+                lint: lint_id.lint.name_lower(),
+                span: diag.span.primary_span(),
+                machine_applicable: suggestions
+                    .iter()
+                    .any(|suggestion| suggestion.applicability == Applicability::MachineApplicable),
+                changes_semantics: reason.changes_semantics(),
+                reference: reason.reference(),
+            })
+        })
+        .collect();
+    hits.sort_by_key(|hit| (hit.span.map(|span| span.lo()), hit.lint.clone()));
+
+    let source_map = tcx.sess.source_map();
+    let fix = |hit: &EditionMigrationHit| {
+        if hit.machine_applicable { "machine-applicable" } else { "needs-review" }
+    };
+    let risk = |hit: &EditionMigrationHit| {
+        if hit.changes_semantics { "semantic" } else { "syntactic" }
+    };
+
+    // The totals for each lint, in the order in which the lints were first hit.
+    let mut lints: FxIndexMap<&str, (usize, &EditionMigrationHit)> = FxIndexMap::default();
+    for hit in hits.iter() {
+        lints.entry(hit.lint.as_str()).or_insert((0, hit)).0 += 1;
+    }
+    let lints: Vec<_> = lints
+        .into_iter()
+        .map(|(name, (count, hit))| {
+            serde_json::json!({
+                "name": name,
+                "hits": count,
+                "risk": risk(hit),
+                "reference": hit.reference,
+            })
+        })
+        .collect();
+    let count = |f: &dyn Fn(&EditionMigrationHit) -> bool| hits.iter().filter(|hit| f(hit)).count();
+    let report = serde_json::json!({
+        "crate": tcx.crate_name(LOCAL_CRATE).as_str(),
+        "edition": tcx.sess.edition().to_string(),
+        "target_edition": target_edition.to_string(),
+        "summary": {
+            "hits": hits.len(),
+            "machine_applicable": count(&|hit| hit.machine_applicable),
+            "needs_review": count(&|hit| !hit.machine_applicable),
+            "semantic": count(&|hit| hit.changes_semantics),
+            "syntactic": count(&|hit| !hit.changes_semantics),
+        },
+        "lints": lints,
+        "hits": hits
+            .iter()
+            .map(|hit| {
+                serde_json::json!({
+                    "lint": hit.lint,
+                    "span": hit.span.map(|span| source_map.span_to_diagnostic_string(span)),
+                    "fix": fix(hit),
+                    "risk": risk(hit),
+                })
+            })
+            .collect::<Vec<_>>(),
+    });
+
+    let path = tcx
+        .output_filenames(())
+        .out_directory
+        .join(format!("{}.edition_migration.json", tcx.crate_name(LOCAL_CRATE)));
+    if let Err(error) = fs::write(&path, serde_json::to_string_pretty(&report).unwrap()) {
+        tcx.dcx().emit_fatal(errors::FailedWritingFile { path: &path, error });
+    }
+}
+
+/// Writes the proof trees recorded for `-Zdump-proof-trees` as `<crate_name>.proof_trees.json` and
+/// as `<crate_name>.proof_trees.html`, in which the goals and candidates that failed are expanded
+/// and all others are collapsed.
+fn write_proof_trees(tcx: TyCtxt<'_>, dir: Option<&Path>) {
+    let mut trees = tcx.sess.proof_trees.lock();
+    trees.sort_by_key(|tree| tree.span.lo());
+
+    let source_map = tcx.sess.source_map();
+
+    fn goal_json(goal: &ProofTreeGoal, span: &dyn Fn(Span) -> String) -> serde_json::Value {
+        let candidates: Vec<_> = goal
+            .candidates
+            .iter()
+            .map(|candidate| {
+                serde_json::json!({
+                    "kind": candidate.kind,
+                    "span": candidate.span.map(span),
+                    "result": candidate.result,
+                    "nested": candidate
+                        .nested
+                        .iter()
+                        .map(|nested| goal_json(nested, span))
+                        .collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "goal": goal.goal,
+            "source": goal.source,
+            "result": goal.result,
+            "cut_off": goal.cut_off.map(ProofTreeCutOff::as_str),
+            "candidates": candidates,
+        })
+    }
+
+    fn goal_html(html: &mut String, goal: &ProofTreeGoal, span: &dyn Fn(Span) -> String) {
+        let open = if goal.result == "yes" { "" } else { " open" };
+        html.push_str(&format!(
+            "<li><details{open}><summary><code>{}</code> <span class=\"{}\">{}</span> \
+             <span class=\"source\">({})</span>",
+            escape_html(&goal.goal),
+            goal.result,
+            goal.result,
+            goal.source,
+        ));
+        if let Some(cut_off) = goal.cut_off {
+            html.push_str(&format!(
+                " <span class=\"cut-off\">not expanded: {}</span>",
+                cut_off.as_str()
+            ));
+        }
+        html.push_str("</summary><ul>\n");
+        for candidate in &goal.candidates {
+            let open = if candidate.result == "yes" { "" } else { " open" };
+            html.push_str(&format!(
+                "<li><details{open}><summary>{} <span class=\"{}\">{}</span>",
+                escape_html(&candidate.kind),
+                candidate.result,
+                candidate.result,
+            ));
+            if let Some(candidate_span) = candidate.span {
+                html.push_str(&format!(
+                    " <span class=\"source\">{}</span>",
+                    escape_html(&span(candidate_span))
+                ));
+            }
+            html.push_str("</summary><ul>\n");
+            for nested in &candidate.nested {
+                goal_html(html, nested, span);
+            }
+            html.push_str("</ul></details></li>\n");
+        }
+        html.push_str("</ul></details></li>\n");
+    }
+
+    fn escape_html(s: &str) -> String {
+        s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+    }
+
+    let span = |span: Span| source_map.span_to_diagnostic_string(span);
+    let crate_name = tcx.crate_name(LOCAL_CRATE);
+    let report = serde_json::json!({
+        "crate": crate_name.as_str(),
+        "errors": trees
+            .iter()
+            .map(|tree| {
+                serde_json::json!({
+                    "span": span(tree.span),
+                    "item": tree.item,
+                    "root": goal_json(&tree.root, &span),
+                })
+            })
+            .collect::<Vec<_>>(),
+    });
+
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Proof trees of `{crate_name}`</title>\n<style>\n\
+         ul {{ list-style: none; padding-left: 1.5em; }}\n\
+         .yes {{ color: green; }}\n.no, .overflow {{ color: red; }}\n\
+         .ambiguous {{ color: orange; }}\n\
+         .source, .cut-off {{ color: gray; }}\n</style>\n</head>\n<body>\n\
+         <h1>Proof trees of <code>{crate_name}</code></h1>\n"
+    );
+    for tree in trees.iter() {
+        html.push_str(&format!(
+            "<h2><code>{}</code> in <code>{}</code></h2>\n<ul>\n",
+            escape_html(&span(tree.span)),
+            escape_html(&tree.item),
+        ));
+        goal_html(&mut html, &tree.root, &span);
+        html.push_str("</ul>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+
+    let dir = dir.unwrap_or(Path::new("."));
+    let files = [("json", serde_json::to_string_pretty(&report).unwrap()), ("html", html)];
+    for (extension, contents) in files {
+        let path = dir.join(format!("{crate_name}.proof_trees.{extension}"));
+        if let Err(error) = fs::create_dir_all(dir).and_then(|()| fs::write(&path, contents)) {
+            tcx.dcx().emit_err(errors::FailedWritingFile { path: &path, error });
+        }
+    }
+}
+
 fn early_lint_checks(tcx: TyCtxt<'_>, (): ()) {
     let sess = tcx.sess;
     let (resolver, krate) = &*tcx.resolver_for_lowering().borrow();
@@ -1145,6 +1513,16 @@ fn analysis(tcx: TyCtxt<'_>, (): ()) {
 
     let sess = tcx.sess;
 
+    // Printed after type checking, which `-Zmacro-stats` attributes to macros as well.
+    if sess.macro_stats_costs() {
+        print_macro_costs(tcx);
+    }
+
+    // Written here, as the trait errors reported so far abort the compilation below.
+    if let SwitchWithOptPath::Enabled(ref dir) = sess.opts.unstable_opts.dump_proof_trees {
+        write_proof_trees(tcx, dir.as_deref());
+    }
+
     // Avoid overwhelming user with errors if borrow checking failed.
     // I'm not sure how helpful this is, to be honest, but it avoids a
     // lot of annoying errors in the ui tests (basically,
@@ -1172,6 +1550,9 @@ fn analysis(tcx: TyCtxt<'_>, (): ()) {
                         tcx.par_hir_for_each_module(|module| {
                             tcx.ensure_ok().check_mod_deathness(module)
                         });
+                        if sess.opts.unstable_opts.report_unused_workspace_items {
+                            tcx.ensure_ok().check_unused_workspace_items(());
+                        }
                     },
                     {
                         sess.time("lint_checking", || {
@@ -1202,6 +1583,11 @@ fn analysis(tcx: TyCtxt<'_>, (): ()) {
         let _ = tcx.all_diagnostic_items(());
     });
 
+    // All lints have been emitted at this point.
+    if let Some(target_edition) = sess.opts.unstable_opts.edition_migration_report {
+        write_edition_migration_report(tcx, target_edition);
+    }
+
     // If `-Zvalidate-mir` is set, we also want to compute the final MIR for each item
     // (either its `mir_for_ctfe` or `optimized_mir`) since that helps uncover any bugs
     // in MIR optimizations that may only be reachable through codegen, or other codepaths
@@ -693,6 +693,8 @@ macro_rules! untracked {
     untracked!(codegen_source_order, true);
     untracked!(deduplicate_diagnostics, false);
     untracked!(dump_dep_graph, true);
+    untracked!(dump_import_graph, SwitchWithOptPath::Enabled(Some("import-graph-dir/".into())));
+    untracked!(dump_macro_rules_trace, SwitchWithOptPath::Enabled(Some("macro-trace-dir/".into())));
     untracked!(dump_mir, Some(String::from("abc")));
     untracked!(dump_mir_dataflow, true);
     untracked!(dump_mir_dir, String::from("abc"));
@@ -701,6 +703,7 @@ macro_rules! untracked {
     untracked!(dump_mir_graphviz, true);
     untracked!(dump_mono_stats, SwitchWithOptPath::Enabled(Some("mono-items-dir/".into())));
     untracked!(dump_mono_stats_format, DumpMonoStatsFormat::Json);
+    untracked!(dump_proof_trees, SwitchWithOptPath::Enabled(Some("proof-trees-dir/".into())));
     untracked!(dylib_lto, true);
     untracked!(emit_stack_sizes, true);
     untracked!(future_incompat_test, true);
@@ -708,13 +711,17 @@ macro_rules! untracked {
     untracked!(incremental_info, true);
     untracked!(incremental_verify_ich, true);
     untracked!(input_stats, true);
+    untracked!(link_map_report, true);
     untracked!(link_native_libraries, false);
     untracked!(llvm_time_trace, true);
     untracked!(ls, vec!["all".to_owned()]);
     untracked!(macro_backtrace, true);
     untracked!(macro_stats, true);
+    untracked!(macro_stats_costs, true);
+    untracked!(macro_stats_json, true);
     untracked!(meta_stats, true);
     untracked!(mir_include_spans, MirIncludeSpans::On);
+    untracked!(mir_pass_stats, true);
     untracked!(nll_facts, true);
     untracked!(no_analysis, true);
     untracked!(no_leak_check, true);
@@ -827,6 +834,8 @@ macro_rules! tracked {
     tracked!(min_function_alignment, Some(Align::EIGHT));
     tracked!(mir_emit_retag, true);
     tracked!(mir_enable_passes, vec![("DestProp".to_string(), false)]);
+    tracked!(mir_opt_bisect_filter, Some("foo::bar".to_string()));
+    tracked!(mir_opt_bisect_limit, Some(10));
     tracked!(mir_opt_level, Some(4));
     tracked!(mir_preserve_ub, true);
     tracked!(move_size_limit, Some(4096));
@@ -853,10 +862,12 @@ macro_rules! tracked {
     tracked!(precise_enum_drop_elaboration, false);
     tracked!(profile_sample_use, Some(PathBuf::from("abc")));
     tracked!(profiler_runtime, "abc".to_string());
+    tracked!(record_upstream_uses, true);
     tracked!(reg_struct_return, true);
     tracked!(regparm, Some(3));
     tracked!(relax_elf_relocations, Some(true));
     tracked!(remap_cwd_prefix, Some(PathBuf::from("abc")));
+    tracked!(report_unused_workspace_items, true);
     tracked!(sanitizer, SanitizerSet::ADDRESS);
     tracked!(sanitizer_cfi_canonical_jump_tables, None);
     tracked!(sanitizer_cfi_generalize_pointers, Some(true));
@@ -880,6 +891,7 @@ macro_rules! tracked {
     tracked!(trap_unreachable, Some(false));
     tracked!(treat_err_as_bug, NonZero::new(1));
     tracked!(tune_cpu, Some(String::from("abc")));
+    tracked!(typed_holes, true);
     tracked!(ub_checks, Some(false));
     tracked!(uninit_const_chunk_threshold, 123);
     tracked!(unleash_the_miri_inside_of_you, true);
@@ -898,6 +910,7 @@ macro_rules! tracked_no_crate_hash {
             assert_non_crate_hash_different(&reference, &opts);
         };
     }
+    tracked_no_crate_hash!(edition_migration_report, Some(Edition::Edition2024));
     tracked_no_crate_hash!(no_codegen, true);
     tracked_no_crate_hash!(verbose_internals, true);
 }
@@ -143,6 +143,7 @@
         UNUSED_UNSAFE,
         UNUSED_VARIABLES,
         UNUSED_VISIBILITIES,
+        UNUSED_WORKSPACE_ITEMS,
         USELESS_DEPRECATED,
         VARARGS_WITHOUT_PATTERN,
         WARNINGS,
@@ -710,6 +711,52 @@
     "detect visibility qualifiers on `const _` items"
 }
 
+declare_lint! {
+    /// The `unused_workspace_items` lint detects public items of upstream
+    /// crates that no crate in the crate graph uses.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs multiple crates)
+    /// // a.rs, compiled with `-Z record-upstream-uses`
+    /// pub fn used() {}
+    /// pub fn unused() {}
+    ///
+    /// // main.rs, compiled with `-Z report-unused-workspace-items`
+    /// fn main() {
+    ///     a::used();
+    /// }
+    /// ```
+    ///
+    /// This will produce:
+    ///
+    /// ```text
+    /// warning: function `a::unused` is public but not used by any downstream crate
+    ///  --> a.rs:3:1
+    ///   |
+    /// 3 | pub fn unused() {}
+    ///   | ^^^^^^^^^^^^^^^
+    ///   |
+    ///   = note: `#[warn(unused_workspace_items)]` on by default
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// The `dead_code` lint treats every public item as used, since it
+    /// cannot know about the crates depending on the one being compiled. In
+    /// a workspace, many public items are only meant to be used by sibling
+    /// crates, and can become dead without any warning. Crates compiled with
+    /// `-Z record-upstream-uses` record which items of their dependencies
+    /// they use, and compiling the top-level crate with
+    /// `-Z report-unused-workspace-items` checks the public items of those
+    /// crates against the uses recorded by every crate in the crate graph.
+    ///
+    /// This lint is only emitted with `-Z report-unused-workspace-items`.
+    pub UNUSED_WORKSPACE_ITEMS,
+    Warn,
+    "detect public items of workspace crates that no crate uses"
+}
+
 declare_lint! {
     /// The `unused_assignments` lint detects assignments that will never be read.
     ///
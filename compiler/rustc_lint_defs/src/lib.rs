@@ -516,6 +516,22 @@ pub fn edition(self) -> Option<Edition> {
         }
     }
 
+    /// Whether affected code keeps compiling but behaves differently after the change, as opposed
+    /// to being rejected.
+    pub fn changes_semantics(self) -> bool {
+        match self {
+            Self::FutureReleaseSemanticsChange(_)
+            | Self::EditionSemanticsChange(_)
+            | Self::EditionAndFutureReleaseSemanticsChange(_) => true,
+
+            Self::FutureReleaseError(_)
+            | Self::EditionError(_)
+            | Self::EditionAndFutureReleaseError(_)
+            | Self::Custom(_, _) => false,
+            Self::Unreachable => unreachable!(),
+        }
+    }
+
     pub fn reference(&self) -> String {
         match self {
             Self::FutureReleaseSemanticsChange(release_fcw)
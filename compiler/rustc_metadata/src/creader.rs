@@ -3,6 +3,7 @@
 use std::error::Error;
 use std::path::Path;
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 use std::{cmp, env, iter};
 
@@ -14,6 +15,7 @@
 use rustc_data_structures::sync::{self, FreezeReadGuard, FreezeWriteGuard};
 use rustc_data_structures::unord::UnordMap;
 use rustc_expand::base::SyntaxExtension;
+use rustc_expand::proc_macro::ProcMacroDylib;
 use rustc_fs_util::try_canonicalize;
 use rustc_hir as hir;
 use rustc_hir::def_id::{CrateNum, LOCAL_CRATE, LocalDefId, StableCrateId};
@@ -607,7 +609,7 @@ fn register_crate<'tcx>(
             private_dep,
         )?;
 
-        let raw_proc_macros = if crate_root.is_proc_macro_crate() {
+        let (raw_proc_macros, proc_macro_dylib) = if crate_root.is_proc_macro_crate() {
             let temp_root;
             let (dlsym_source, dlsym_root) = match &host_lib {
                 Some(host_lib) => (&host_lib.source, {
@@ -617,9 +619,11 @@ fn register_crate<'tcx>(
                 None => (&source, &crate_root),
             };
             let dlsym_dylib = dlsym_source.dylib.as_ref().expect("no dylib for a proc-macro crate");
-            Some(self.dlsym_proc_macros(tcx.sess, dlsym_dylib, dlsym_root.stable_crate_id())?)
+            let (raw_proc_macros, proc_macro_dylib) =
+                self.dlsym_proc_macros(tcx.sess, dlsym_dylib, dlsym_root.stable_crate_id())?;
+            (Some(raw_proc_macros), Some(Arc::new(proc_macro_dylib)))
         } else {
-            None
+            (None, None)
         };
 
         let crate_metadata = CrateMetadata::new(
@@ -628,6 +632,7 @@ fn register_crate<'tcx>(
             metadata,
             crate_root,
             raw_proc_macros,
+            proc_macro_dylib,
             cnum,
             cnum_map,
             dep_kind,
@@ -909,7 +914,7 @@ fn dlsym_proc_macros(
         sess: &Session,
         path: &Path,
         stable_crate_id: StableCrateId,
-    ) -> Result<&'static [ProcMacro], CrateError> {
+    ) -> Result<(&'static [ProcMacro], ProcMacroDylib), CrateError> {
         let sym_name = sess.generate_proc_macro_decls_symbol(stable_crate_id);
         debug!("trying to dlsym proc_macros {} for symbol `{}`", path.display(), sym_name);
 
@@ -918,7 +923,10 @@ fn dlsym_proc_macros(
             match result {
                 Ok(result) => {
                     debug!("loaded dlsym proc_macros {} for symbol `{}`", path.display(), sym_name);
-                    Ok(*result)
+                    Ok((
+                        *result,
+                        ProcMacroDylib { path: path.to_path_buf(), decls_symbol: sym_name },
+                    ))
                 }
                 Err(err) => {
                     debug!(
@@ -14,7 +14,10 @@
 use rustc_data_structures::sync::Lock;
 use rustc_data_structures::unhash::UnhashMap;
 use rustc_expand::base::{SyntaxExtension, SyntaxExtensionKind};
-use rustc_expand::proc_macro::{AttrProcMacro, BangProcMacro, DeriveProcMacro};
+use rustc_expand::proc_macro::{
+    AttrProcMacro, BangProcMacro, CacheableProcMacro, DeriveProcMacro, ProcMacroDylib,
+    ProcMacroDylibEntry,
+};
 use rustc_hir::Safety;
 use rustc_hir::def::Res;
 use rustc_hir::def_id::{CRATE_DEF_INDEX, LOCAL_CRATE};
@@ -34,7 +37,7 @@
 use rustc_span::hygiene::HygieneDecodeContext;
 use rustc_span::{
     BlobDecoder, BytePos, ByteSymbol, DUMMY_SP, Pos, RemapPathScopeComponents, SpanData,
-    SpanDecoder, Symbol, SyntaxContext, kw,
+    SpanDecoder, Symbol, SyntaxContext, kw, sym,
 };
 use tracing::debug;
 
@@ -99,6 +102,8 @@ pub(crate) struct CrateMetadata {
     incoherent_impls: FxIndexMap<SimplifiedType, LazyArray<DefIndex>>,
     /// Proc macro descriptions for this crate, if it's a proc macro crate.
     raw_proc_macros: Option<&'static [ProcMacro]>,
+    /// The dylib `raw_proc_macros` were loaded from.
+    proc_macro_dylib: Option<Arc<ProcMacroDylib>>,
     /// Source maps for code from the crate.
     source_map_import_info: Lock<Vec<Option<ImportedSourceFile>>>,
     /// For every definition in this crate, maps its `DefPathHash` to its `DefIndex`.
@@ -966,7 +971,7 @@ fn missing(self, descr: &str, id: DefIndex) -> ! {
         bug!("missing `{descr}` for {:?}", self.local_def_id(id))
     }
 
-    fn raw_proc_macro(self, tcx: TyCtxt<'_>, id: DefIndex) -> &'a ProcMacro {
+    fn raw_proc_macro(self, tcx: TyCtxt<'_>, id: DefIndex) -> (usize, &'a ProcMacro) {
         // DefIndex's in root.proc_macro_data have a one-to-one correspondence
         // with items in 'raw_proc_macros'.
         let pos = self
@@ -978,7 +983,7 @@ fn raw_proc_macro(self, tcx: TyCtxt<'_>, id: DefIndex) -> &'a ProcMacro {
             .decode((self, tcx))
             .position(|i| i == id)
             .unwrap();
-        &self.raw_proc_macros.unwrap()[pos]
+        (pos, &self.raw_proc_macros.unwrap()[pos])
     }
 
     fn opt_item_name(self, item_index: DefIndex) -> Option<Symbol> {
@@ -1036,21 +1041,57 @@ fn get_span(self, tcx: TyCtxt<'_>, index: DefIndex) -> Span {
     }
 
     fn load_proc_macro<'tcx>(self, tcx: TyCtxt<'tcx>, id: DefIndex) -> SyntaxExtension {
-        let (name, kind, helper_attrs) = match *self.raw_proc_macro(tcx, id) {
+        let cacheable = |name| {
+            self.get_item_attrs(tcx, CRATE_DEF_INDEX)
+                .any(|attr| attr.has_name(sym::proc_macro_pure))
+                .then(|| CacheableProcMacro {
+                    crate_hash: self.root.hash,
+                    name: Symbol::intern(name),
+                })
+        };
+        let (index, raw_proc_macro) = self.raw_proc_macro(tcx, id);
+        let dylib_entry = self
+            .proc_macro_dylib
+            .as_ref()
+            .map(|dylib| ProcMacroDylibEntry { dylib: Arc::clone(dylib), index });
+        let (name, kind, helper_attrs) = match *raw_proc_macro {
             ProcMacro::CustomDerive { trait_name, attributes, client } => {
                 let helper_attrs =
                     attributes.iter().cloned().map(Symbol::intern).collect::<Vec<_>>();
+                let cacheable = cacheable(trait_name);
                 (
                     trait_name,
-                    SyntaxExtensionKind::Derive(Arc::new(DeriveProcMacro { client })),
+                    SyntaxExtensionKind::Derive(Arc::new(DeriveProcMacro {
+                        client,
+                        cacheable,
+                        dylib_entry,
+                    })),
                     helper_attrs,
                 )
             }
             ProcMacro::Attr { name, client } => {
-                (name, SyntaxExtensionKind::Attr(Arc::new(AttrProcMacro { client })), Vec::new())
+                let cacheable = cacheable(name);
+                (
+                    name,
+                    SyntaxExtensionKind::Attr(Arc::new(AttrProcMacro {
+                        client,
+                        cacheable,
+                        dylib_entry,
+                    })),
+                    Vec::new(),
+                )
             }
             ProcMacro::Bang { name, client } => {
-                (name, SyntaxExtensionKind::Bang(Arc::new(BangProcMacro { client })), Vec::new())
+                let cacheable = cacheable(name);
+                (
+                    name,
+                    SyntaxExtensionKind::Bang(Arc::new(BangProcMacro {
+                        client,
+                        cacheable,
+                        dylib_entry,
+                    })),
+                    Vec::new(),
+                )
             }
         };
 
@@ -1413,6 +1454,11 @@ fn get_traits(self, tcx: TyCtxt<'_>) -> impl Iterator<Item = DefId> {
         self.root.traits.decode((self, tcx)).map(move |index| self.local_def_id(index))
     }
 
+    /// Decodes the items of upstream crates used by the crate, if it records them.
+    fn get_upstream_uses(self, tcx: TyCtxt<'_>) -> Option<impl Iterator<Item = DefId>> {
+        self.root.records_upstream_uses.then(|| self.root.upstream_uses.decode((self, tcx)))
+    }
+
     /// Decodes all trait impls in the crate (for rustdoc).
     fn get_trait_impls(self, tcx: TyCtxt<'_>) -> impl Iterator<Item = DefId> {
         self.cdata.trait_impls.values().flat_map(move |impls| {
@@ -1880,6 +1926,7 @@ pub(crate) fn new(
         blob: MetadataBlob,
         root: CrateRoot,
         raw_proc_macros: Option<&'static [ProcMacro]>,
+        proc_macro_dylib: Option<Arc<ProcMacroDylib>>,
         cnum: CrateNum,
         cnum_map: CrateNumMap,
         dep_kind: CrateDepKind,
@@ -1905,6 +1952,7 @@ pub(crate) fn new(
             trait_impls,
             incoherent_impls: Default::default(),
             raw_proc_macros,
+            proc_macro_dylib,
             source_map_import_info: Lock::new(Vec::new()),
             def_path_hash_map,
             expn_hash_map: Default::default(),
@@ -379,6 +379,9 @@ fn into_args(self) -> (DefId, SimplifiedType) {
 
     traits => { tcx.arena.alloc_from_iter(cdata.get_traits(tcx)) }
     trait_impls_in_crate => { tcx.arena.alloc_from_iter(cdata.get_trait_impls(tcx)) }
+    upstream_uses => {
+        cdata.get_upstream_uses(tcx).map(|uses| &*tcx.arena.alloc_from_iter(uses))
+    }
     implementations_of_trait => { cdata.get_implementations_of_trait(tcx, other) }
     crate_incoherent_impls => { cdata.get_incoherent_impls(tcx, other) }
 
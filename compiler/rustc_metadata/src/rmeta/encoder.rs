@@ -692,6 +692,8 @@ macro_rules! stat {
         let stable_order_of_exportable_impls =
             stat!("exportable-items", || self.encode_stable_order_of_exportable_impls());
 
+        let upstream_uses = stat!("upstream-uses", || self.encode_upstream_uses());
+
         // Encode exported symbols info. This is prefetched in `encode_metadata`.
         let (exported_non_generic_symbols, exported_generic_symbols) =
             stat!("exported-symbols", || {
@@ -766,6 +768,9 @@ macro_rules! stat {
                 incoherent_impls,
                 exportable_items,
                 stable_order_of_exportable_impls,
+                records_upstream_uses: !self.is_proc_macro
+                    && tcx.sess.opts.unstable_opts.record_upstream_uses,
+                upstream_uses,
                 exported_non_generic_symbols,
                 exported_generic_symbols,
                 interpret_alloc_index,
@@ -2231,6 +2236,16 @@ fn encode_exportable_items(&mut self) -> LazyArray<DefIndex> {
         self.lazy_array(self.tcx.exportable_items(LOCAL_CRATE).iter().map(|def_id| def_id.index))
     }
 
+    /// Encodes the items of upstream crates used by this crate, for
+    /// `-Zreport-unused-workspace-items` in downstream crates.
+    fn encode_upstream_uses(&mut self) -> LazyArray<DefId> {
+        empty_proc_macro!(self);
+        if !self.tcx.sess.opts.unstable_opts.record_upstream_uses {
+            return LazyArray::default();
+        }
+        self.lazy_array(self.tcx.upstream_uses(LOCAL_CRATE).unwrap_or_default())
+    }
+
     fn encode_stable_order_of_exportable_impls(&mut self) -> LazyArray<(DefIndex, usize)> {
         empty_proc_macro!(self);
         let stable_order_of_exportable_impls =
@@ -274,6 +274,9 @@ pub(crate) struct CrateRoot {
 
     exportable_items: LazyArray<DefIndex>,
     stable_order_of_exportable_impls: LazyArray<(DefIndex, usize)>,
+    /// Whether the crate was compiled with `-Zrecord-upstream-uses`.
+    records_upstream_uses: bool,
+    upstream_uses: LazyArray<DefId>,
     exported_non_generic_symbols: LazyArray<(ExportedSymbol<'static>, SymbolExportInfo)>,
     exported_generic_symbols: LazyArray<(ExportedSymbol<'static>, SymbolExportInfo)>,
 
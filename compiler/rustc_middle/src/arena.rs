@@ -31,6 +31,7 @@ macro_rules! arena_types {
                 rustc_hir::def_id::LocalDefId,
                 rustc_middle::ty::DefinitionSiteHiddenType<'tcx>,
             >,
+            [] borrowck_facts: rustc_middle::mir::borrowck_facts::BorrowckFacts<'tcx>,
             [] resolver: rustc_data_structures::steal::Steal<(
                 rustc_middle::ty::ResolverAstLowering,
                 std::sync::Arc<rustc_ast::Crate>,
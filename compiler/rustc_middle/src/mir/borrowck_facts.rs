@@ -0,0 +1,101 @@
+//! Borrow checker results that are kept for external tools.
+//!
+//! The borrow checker drops its results once it is done with a body. Tools that need them, like
+//! rustc_public, override the `mir_borrowck` query to record the [`BorrowckFacts`] of every
+//! borrow checked body in [`GlobalCtxt::borrowck_facts`](crate::ty::GlobalCtxt::borrowck_facts),
+//! where they live as long as the compiler session.
+
+use rustc_index::IndexVec;
+
+use super::{Body, BorrowKind, Location, Place};
+use crate::ty::RegionVid;
+
+/// The results of borrow checking a body.
+#[derive(Debug)]
+pub struct BorrowckFacts<'tcx> {
+    /// The body that was borrow checked. Its types contain region variables.
+    pub body: Body<'tcx>,
+    /// All the borrows in the body, indexed by the `BorrowIndex` of the borrow checker.
+    pub borrows: Vec<BorrowFact<'tcx>>,
+    /// The indices of the borrows that go out of scope at each location, i.e., the loans that
+    /// are killed.
+    pub borrows_out_of_scope: Vec<(Location, Vec<usize>)>,
+    /// The outlives constraints between region variables.
+    pub outlives_constraints: Vec<OutlivesFact>,
+    /// The universal regions of the body, i.e., its lifetime parameters and free regions.
+    pub universal_regions: Vec<RegionVid>,
+    /// The index in `sccs` of the strongly connected component of each region variable.
+    pub region_sccs: IndexVec<RegionVid, usize>,
+    /// The strongly connected components of the outlives constraint graph, with the values that
+    /// region inference computed for them.
+    pub sccs: Vec<RegionSccFact>,
+    /// The move paths of the body. The root paths are the locals of the body.
+    pub move_paths: Vec<MovePathFact<'tcx>>,
+    /// All the moves out of a move path in the body.
+    pub moves: Vec<MoveFact>,
+}
+
+/// A strongly connected component of region variables, which all have the same value.
+#[derive(Clone, Debug)]
+pub struct RegionSccFact {
+    /// The components that this component outlives.
+    pub successors: Vec<usize>,
+    /// The locations of the body contained in the inferred value.
+    pub locations: Vec<Location>,
+    /// The universal regions contained in the inferred value, i.e., outlived by the component.
+    pub universal_regions: Vec<RegionVid>,
+}
+
+/// Location where a two-phase borrow is activated, if a borrow
+/// is in fact a two-phase borrow.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TwoPhaseActivation {
+    NotTwoPhase,
+    NotActivated,
+    ActivatedAt(Location),
+}
+
+/// A borrow that occurs in a borrow checked body.
+#[derive(Clone, Debug)]
+pub struct BorrowFact<'tcx> {
+    /// Location where the borrow reservation starts.
+    pub reserve_location: Location,
+    /// Location where the borrow is activated.
+    pub activation: TwoPhaseActivation,
+    /// What kind of borrow this is.
+    pub kind: BorrowKind,
+    /// The region variable of the borrow.
+    pub region: RegionVid,
+    /// Place from which we are borrowing.
+    pub borrowed_place: Place<'tcx>,
+    /// Place to which the borrow was stored.
+    pub assigned_place: Place<'tcx>,
+}
+
+/// A constraint that `sup` outlives `sub`.
+#[derive(Copy, Clone, Debug)]
+pub struct OutlivesFact {
+    pub sup: RegionVid,
+    pub sub: RegionVid,
+    /// The location that introduced the constraint, or `None` if it holds at all locations.
+    pub location: Option<Location>,
+}
+
+/// A path that can be moved out of, such as `x`, `x.f` or `*x`.
+#[derive(Clone, Debug)]
+pub struct MovePathFact<'tcx> {
+    pub place: Place<'tcx>,
+    /// The index of the parent path in [`BorrowckFacts::move_paths`].
+    pub parent: Option<usize>,
+    /// The indices of the child paths in [`BorrowckFacts::move_paths`].
+    pub children: Vec<usize>,
+}
+
+/// A move out of a move path.
+#[derive(Copy, Clone, Debug)]
+pub struct MoveFact {
+    /// The index of the path in [`BorrowckFacts::move_paths`].
+    pub path: usize,
+    /// The location of the move.
+    pub source: Location,
+}
@@ -38,6 +38,7 @@
 };
 
 mod basic_blocks;
+pub mod borrowck_facts;
 mod consts;
 pub mod coverage;
 mod generic_graph;
@@ -1230,6 +1230,19 @@
         desc { |tcx| "checking deathness of variables in {}", describe_as_module(key, tcx) }
     }
 
+    /// The items of upstream crates used by a crate. This is `None` unless the crate was compiled
+    /// with `-Zrecord-upstream-uses`, which records them in its metadata.
+    query upstream_uses(_: CrateNum) -> Option<&'tcx [DefId]> {
+        desc { "collecting the items of upstream crates used by a crate" }
+        separate_provide_extern
+    }
+
+    /// Warns about the public items of crates recording their upstream uses which no crate in the
+    /// crate graph uses. Only run with `-Zreport-unused-workspace-items`.
+    query check_unused_workspace_items(_: ()) {
+        desc { "checking for public items of workspace crates that no crate uses" }
+    }
+
     query check_type_wf(key: ()) -> Result<(), ErrorGuaranteed> {
         desc { "checking that types are well-formed" }
         return_result_from_ensure_ok
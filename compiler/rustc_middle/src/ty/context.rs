@@ -66,6 +66,7 @@
 use crate::metadata::ModChild;
 use crate::middle::codegen_fn_attrs::{CodegenFnAttrs, TargetFeature};
 use crate::middle::resolve_bound_vars;
+use crate::mir::borrowck_facts::BorrowckFacts;
 use crate::mir::interpret::{self, Allocation, ConstAllocation};
 use crate::mir::{Body, Local, Place, PlaceElem, ProjectionKind, Promoted};
 use crate::query::plumbing::QuerySystem;
@@ -1622,6 +1623,14 @@ pub struct GlobalCtxt<'tcx> {
     /// Stores memory for globals (statics/consts).
     pub(crate) alloc_map: interpret::AllocMap<'tcx>,
 
+    /// Monomorphized MIR bodies that external tools registered to replace the MIR of instances
+    /// during mono item collection and codegen. See [`TyCtxt::codegen_instance_mir`].
+    pub instance_mir_overrides: Lock<FxHashMap<ty::Instance<'tcx>, &'tcx Body<'tcx>>>,
+
+    /// Borrow checker results that external tools recorded while the bodies were borrow checked.
+    /// See [`mir::borrowck_facts`](crate::mir::borrowck_facts).
+    pub borrowck_facts: Lock<FxHashMap<LocalDefId, &'tcx BorrowckFacts<'tcx>>>,
+
     current_gcx: CurrentGcx,
 
     /// A jobserver reference used to release then acquire a token while waiting on a query.
@@ -1851,6 +1860,8 @@ pub fn create_global_ctxt<T>(
             clauses_cache: Default::default(),
             data_layout,
             alloc_map: interpret::AllocMap::new(),
+            instance_mir_overrides: Default::default(),
+            borrowck_facts: Default::default(),
             current_gcx,
             jobserver_proxy,
         });
@@ -1797,6 +1797,16 @@ pub fn instance_mir(self, instance: ty::InstanceKind<'tcx>) -> &'tcx Body<'tcx>
         }
     }
 
+    /// Returns the MIR that should be code generated for an [`ty::Instance`].
+    ///
+    /// This is the MIR returned by [`TyCtxt::instance_mir`], unless an external tool registered
+    /// a replacement in [`GlobalCtxt::instance_mir_overrides`]. Replacements are already
+    /// monomorphized, so instantiating them with the instance's arguments is a no-op.
+    pub fn codegen_instance_mir(self, instance: ty::Instance<'tcx>) -> &'tcx Body<'tcx> {
+        let body = self.instance_mir_overrides.lock().get(&instance).copied();
+        body.unwrap_or_else(|| self.instance_mir(instance.def))
+    }
+
     /// Gets all attributes with the given name.
     pub fn get_attrs(
         self,
@@ -24,8 +24,8 @@
 use rustc_index::IndexVec;
 use rustc_middle::mir::{
     AnalysisPhase, Body, CallSource, ClearCrossCrate, ConstOperand, ConstQualifs, LocalDecl,
-    MirPhase, Operand, Place, ProjectionElem, Promoted, RuntimePhase, Rvalue, START_BLOCK,
-    SourceInfo, Statement, StatementKind, TerminatorKind,
+    Location, MirPhase, Operand, Place, ProjectionElem, Promoted, RuntimePhase, Rvalue,
+    START_BLOCK, SourceInfo, Statement, StatementKind, TerminatorKind,
 };
 use rustc_middle::ty::{self, TyCtxt, TypeVisitableExt};
 use rustc_middle::util::Providers;
@@ -52,6 +52,7 @@
 mod lint;
 mod lint_tail_expr_drop_order;
 mod liveness;
+mod loops;
 mod patch;
 mod shim;
 mod ssa;
@@ -154,6 +155,7 @@ mod dead_store_elimination : DeadStoreElimination {
     mod jump_threading : JumpThreading;
     mod known_panics_lint : KnownPanicsLint;
     mod large_enums : EnumSizeOpt;
+    mod loop_invariant_code_motion : LoopInvariantCodeMotion;
     mod lower_intrinsics : LowerIntrinsics;
     mod lower_slice_len : LowerSliceLenCalls;
     mod match_branches : MatchBranchSimplification;
@@ -162,6 +164,7 @@ mod dead_store_elimination : DeadStoreElimination {
     mod post_drop_elaboration : CheckLiveDrops;
     mod prettify : ReorderBasicBlocks, ReorderLocals;
     mod promote_consts : PromoteTemps;
+    mod range_check_elimination : RangeCheckElimination;
     mod ref_prop : ReferencePropagation;
     mod remove_noop_landing_pads : RemoveNoopLandingPads;
     mod remove_place_mention : RemovePlaceMention;
@@ -203,6 +206,16 @@ mod simplify_branches : SimplifyConstCondition {
     mod validate : Validator;
 }
 
+/// Validates a body that is code generated without going through the MIR pipeline, like the
+/// bodies that tools replace through rustc_public. Returns the failures instead of reporting them
+/// as bugs.
+pub fn validate_external_body<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+) -> Vec<(Location, String)> {
+    validate::validate_body(tcx, body)
+}
+
 rustc_fluent_macro::fluent_messages! { "../messages.ftl" }
 
 pub fn provide(providers: &mut Providers) {
@@ -741,8 +754,10 @@ fn o1<T>(x: T) -> WithMinOptLevel<T> {
             &dead_store_elimination::DeadStoreElimination::Initial,
             &gvn::GVN,
             &simplify::SimplifyLocals::AfterGVN,
+            &loop_invariant_code_motion::LoopInvariantCodeMotion,
             &match_branches::MatchBranchSimplification,
             &dataflow_const_prop::DataflowConstProp,
+            &range_check_elimination::RangeCheckElimination,
             &single_use_consts::SingleUseConsts,
             &o1(simplify_branches::SimplifyConstCondition::AfterConstProp),
             &jump_threading::JumpThreading,
@@ -0,0 +1,211 @@
+//! Loop-invariant code motion.
+//!
+//! This pass moves assignments whose value does not change between iterations of a loop to the
+//! block right before the loop. To stay conservative, it only moves assignments that
+//! - assign to an SSA local, so the assignment still dominates all uses after moving it;
+//! - compute an rvalue that has no side effects and cannot cause UB, so it can be executed even
+//!   if the loop would not have executed it;
+//! - only read constants and SSA locals (without projections) that are defined outside the loop,
+//!   or by assignments that were moved out of the loop as well.
+//!
+//! Hoisted locals are live for the whole body afterwards, so their storage statements are removed.
+//! As the loop may now read them repeatedly, moves out of them are turned into copies.
+//!
+//! The pass is not enabled by default, it has to be requested with `-Zmir-enable-passes`.
+
+use rustc_index::bit_set::DenseBitSet;
+use rustc_middle::mir::visit::{MutVisitor, PlaceContext, Visitor};
+use rustc_middle::mir::*;
+use rustc_middle::ty::TyCtxt;
+use tracing::{debug, instrument};
+
+use crate::loops::natural_loops;
+use crate::ssa::SsaLocals;
+
+pub(super) struct LoopInvariantCodeMotion;
+
+impl<'tcx> crate::MirPass<'tcx> for LoopInvariantCodeMotion {
+    fn is_enabled(&self, _sess: &rustc_session::Session) -> bool {
+        // Only enabled with `-Zmir-enable-passes=+LoopInvariantCodeMotion` for now, until its
+        // effect on the optimized MIR of existing tests and on compile times has been evaluated.
+        false
+    }
+
+    #[instrument(level = "trace", skip(self, tcx, body))]
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        debug!(def_id = ?body.source.def_id());
+        let loops = natural_loops(body);
+        if loops.is_empty() {
+            return;
+        }
+
+        let typing_env = body.typing_env(tcx);
+        let ssa = SsaLocals::new(tcx, body, typing_env);
+
+        let mut hoisted = DenseBitSet::new_empty(body.local_decls.len());
+        for l in &loops {
+            if body[l.header].is_cleanup {
+                continue;
+            }
+            let Some(preheader) = l.preheader(body) else { continue };
+
+            // The locals that are assigned to in the loop, including by nested loops that were
+            // already processed.
+            let mut assigned = DenseBitSet::new_empty(body.local_decls.len());
+            for bb in l.blocks.iter() {
+                AssignedLocals(&mut assigned).visit_basic_block_data(bb, &body[bb]);
+            }
+
+            // Visit the blocks in reverse postorder, so that an assignment is moved before the
+            // assignments that use it.
+            let loop_blocks: Vec<_> = body
+                .basic_blocks
+                .reverse_postorder()
+                .iter()
+                .copied()
+                .filter(|&bb| l.blocks.contains(bb))
+                .collect();
+            let basic_blocks = body.basic_blocks.as_mut_preserves_cfg();
+            for bb in loop_blocks {
+                for statement_index in 0..basic_blocks[bb].statements.len() {
+                    let statement = &basic_blocks[bb].statements[statement_index];
+                    let StatementKind::Assign(box (place, rvalue)) = &statement.kind else {
+                        continue;
+                    };
+                    let Some(local) = place.as_local() else { continue };
+                    if !ssa.is_ssa(local) || !is_invariant(rvalue, &ssa, &assigned, &hoisted) {
+                        continue;
+                    }
+
+                    debug!(?bb, ?statement, ?preheader, "hoisting");
+                    let statement = &mut basic_blocks[bb].statements[statement_index];
+                    let kind = std::mem::replace(&mut statement.kind, StatementKind::Nop);
+                    let source_info = statement.source_info;
+                    basic_blocks[preheader].statements.push(Statement::new(source_info, kind));
+                    assigned.remove(local);
+                    hoisted.insert(local);
+                }
+            }
+        }
+
+        if !hoisted.is_empty() {
+            HoistedLocals { tcx, hoisted }.visit_body_preserves_cfg(body);
+        }
+    }
+
+    fn is_required(&self) -> bool {
+        false
+    }
+}
+
+/// Returns whether `rvalue` can be computed before the loop.
+fn is_invariant<'tcx>(
+    rvalue: &Rvalue<'tcx>,
+    ssa: &SsaLocals,
+    assigned: &DenseBitSet<Local>,
+    hoisted: &DenseBitSet<Local>,
+) -> bool {
+    // Projections may read through pointers or read inactive variants and union fields, which
+    // is only fine if the loop would have done so as well.
+    let invariant_place = |place: &Place<'tcx>| {
+        place.as_local().is_some_and(|local| ssa.is_ssa(local) && !assigned.contains(local))
+    };
+    let invariant_operand = |operand: &Operand<'tcx>| match operand {
+        Operand::Constant(_) => true,
+        Operand::Copy(place) => invariant_place(place),
+        // Moves of hoisted locals are turned into copies afterwards. Other moves may deinitialize
+        // their operand, so they cannot be moved out of the loop.
+        Operand::Move(place) => {
+            invariant_place(place) && place.as_local().is_some_and(|local| hoisted.contains(local))
+        }
+        Operand::RuntimeChecks(_) => false,
+    };
+
+    match rvalue {
+        Rvalue::Use(operand) | Rvalue::Repeat(operand, _) => invariant_operand(operand),
+        Rvalue::UnaryOp(UnOp::Not | UnOp::Neg | UnOp::PtrMetadata, operand) => {
+            invariant_operand(operand)
+        }
+        Rvalue::Cast(
+            CastKind::IntToInt
+            | CastKind::IntToFloat
+            | CastKind::FloatToInt
+            | CastKind::FloatToFloat
+            | CastKind::PtrToPtr,
+            operand,
+            _,
+        ) => invariant_operand(operand),
+        Rvalue::BinaryOp(op, box (left, right)) => {
+            // Division by zero, unchecked arithmetic that overflows, and out of bounds pointer
+            // offsets are UB, so we cannot execute them speculatively.
+            let can_be_ub = matches!(
+                op,
+                BinOp::Div
+                    | BinOp::Rem
+                    | BinOp::AddUnchecked
+                    | BinOp::SubUnchecked
+                    | BinOp::MulUnchecked
+                    | BinOp::ShlUnchecked
+                    | BinOp::ShrUnchecked
+                    | BinOp::Offset
+            );
+            !can_be_ub && invariant_operand(left) && invariant_operand(right)
+        }
+        Rvalue::Discriminant(place) => invariant_place(place),
+        Rvalue::Aggregate(box kind, operands) => {
+            matches!(kind, AggregateKind::Tuple | AggregateKind::Array(_))
+                && operands.iter().all(invariant_operand)
+        }
+        Rvalue::Ref(..)
+        | Rvalue::RawPtr(..)
+        | Rvalue::ThreadLocalRef(..)
+        | Rvalue::Cast(..)
+        | Rvalue::UnaryOp(..)
+        | Rvalue::ShallowInitBox(..)
+        | Rvalue::CopyForDeref(..)
+        | Rvalue::WrapUnsafeBinder(..) => false,
+    }
+}
+
+/// Collects the locals that are assigned to, or whose storage is changed.
+struct AssignedLocals<'a>(&'a mut DenseBitSet<Local>);
+
+impl<'tcx> Visitor<'tcx> for AssignedLocals<'_> {
+    fn visit_local(&mut self, local: Local, context: PlaceContext, _: Location) {
+        if context.is_mutating_use() || context.is_storage_marker() {
+            self.0.insert(local);
+        }
+    }
+}
+
+/// Removes the storage statements of hoisted locals, and turns moves out of them into copies.
+struct HoistedLocals<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    hoisted: DenseBitSet<Local>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for HoistedLocals<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, _: Location) {
+        if let Operand::Move(place) = *operand
+            && !place.is_indirect_first_projection()
+            && self.hoisted.contains(place.local)
+        {
+            *operand = Operand::Copy(place);
+        }
+    }
+
+    fn visit_statement(&mut self, stmt: &mut Statement<'tcx>, loc: Location) {
+        match stmt.kind {
+            StatementKind::StorageLive(l) | StatementKind::StorageDead(l)
+                if self.hoisted.contains(l) =>
+            {
+                stmt.make_nop(true)
+            }
+            _ => self.super_statement(stmt, loc),
+        }
+    }
+}
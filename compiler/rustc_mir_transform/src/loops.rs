@@ -0,0 +1,67 @@
+//! Natural loops of the control-flow graph.
+//!
+//! An edge `latch -> header` is a back edge if `header` dominates `latch`. The natural loop of
+//! `header` consists of the header and all the blocks that can reach one of its latches without
+//! going through the header. Loops that share a header are merged.
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_index::bit_set::DenseBitSet;
+use rustc_middle::mir::*;
+
+pub(super) struct Loop {
+    pub(super) header: BasicBlock,
+    /// The blocks of the loop, including the header and the blocks of nested loops.
+    pub(super) blocks: DenseBitSet<BasicBlock>,
+}
+
+impl Loop {
+    /// Returns the block that is executed right before entering the loop, if there is a unique
+    /// such block and it only jumps to the header.
+    pub(super) fn preheader(&self, body: &Body<'_>) -> Option<BasicBlock> {
+        let mut outside = body.basic_blocks.predecessors()[self.header]
+            .iter()
+            .filter(|&&bb| !self.blocks.contains(bb));
+        let preheader = *outside.next()?;
+        if outside.next().is_some() {
+            return None;
+        }
+        match body[preheader].terminator().kind {
+            TerminatorKind::Goto { target } if target == self.header => Some(preheader),
+            _ => None,
+        }
+    }
+}
+
+/// Computes the natural loops of `body`, with inner loops before the loops containing them.
+pub(super) fn natural_loops(body: &Body<'_>) -> Vec<Loop> {
+    let dominators = body.basic_blocks.dominators();
+
+    // We only visit reachable blocks: computing `dominates` on an unreachable block ICEs.
+    let mut latches: FxIndexMap<BasicBlock, Vec<BasicBlock>> = FxIndexMap::default();
+    for &bb in body.basic_blocks.reverse_postorder() {
+        for header in body[bb].terminator().successors() {
+            if dominators.dominates(header, bb) {
+                latches.entry(header).or_default().push(bb);
+            }
+        }
+    }
+
+    let predecessors = body.basic_blocks.predecessors();
+    let mut loops: Vec<_> = latches
+        .into_iter()
+        .map(|(header, mut worklist)| {
+            let mut blocks = DenseBitSet::new_empty(body.basic_blocks.len());
+            blocks.insert(header);
+            while let Some(bb) = worklist.pop() {
+                if blocks.insert(bb) {
+                    worklist.extend(predecessors[bb].iter().copied());
+                }
+            }
+            Loop { header, blocks }
+        })
+        .collect();
+
+    // A loop that is nested in another one has fewer blocks.
+    loops.sort_by_cached_key(|l| l.blocks.count());
+    loops
+}
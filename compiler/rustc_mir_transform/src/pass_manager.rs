@@ -1,10 +1,13 @@
 use std::cell::RefCell;
 use std::collections::hash_map::Entry;
+use std::time::Instant;
 
 use rustc_data_structures::fx::{FxHashMap, FxIndexSet};
 use rustc_middle::mir::{Body, MirDumper, MirPhase, RuntimePhase};
 use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_session::Session;
+use rustc_session::mir_pass_stats::MirPassStats;
 use tracing::trace;
 
 use crate::lint::lint_body;
@@ -238,6 +241,55 @@ pub(super) fn should_run_pass<'tcx, P>(
     overridden.unwrap_or_else(|| !suppressed && pass.is_enabled(tcx.sess))
 }
 
+/// Implements `-Zmir-opt-bisect-limit`, in the style of LLVM's `-opt-bisect-limit`: the
+/// optimization passes that are about to run on a body are numbered, and only the first `limit`
+/// of them actually run. Each decision is printed together with the pass name and the function
+/// path, so that the pass that introduces a miscompilation can be found by bisecting `limit`, and
+/// the function it miscompiles by narrowing down `-Zmir-opt-bisect-filter`.
+///
+/// Required passes are always run and not counted. `count` numbers the passes of `body` only, so
+/// that whether a pass runs on a function does not depend on the other functions of the crate or
+/// on the order in which the compiler threads optimize them.
+fn check_mir_opt_bisect_limit<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    pass_name: &str,
+    count: &mut usize,
+) -> bool {
+    let opts = &tcx.sess.opts.unstable_opts;
+    let Some(limit) = opts.mir_opt_bisect_limit else {
+        return true;
+    };
+    let path = body_path(tcx, body);
+    if let Some(filter) = &opts.mir_opt_bisect_filter
+        && !path.contains(filter.as_str())
+    {
+        return true;
+    }
+    *count += 1;
+    let run = *count <= limit;
+    eprintln!(
+        "BISECT: {} pass ({count}) {pass_name} on {path}",
+        if run { "running" } else { "NOT running" },
+    );
+    run
+}
+
+/// The path of the function `body` belongs to, used to report statistics and bisection.
+fn body_path<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>) -> String {
+    let path = with_no_trimmed_paths!(tcx.def_path_str(body.source.def_id()));
+    match body.source.promoted {
+        Some(promoted) => format!("{path}::{{promoted#{}}}", promoted.as_usize()),
+        None => path,
+    }
+}
+
+/// The size of `body`, as `(statements, blocks, locals)`, for `-Zmir-pass-stats`.
+fn body_size(body: &Body<'_>) -> (i64, i64, i64) {
+    let statements = body.basic_blocks.iter().map(|data| data.statements.len()).sum::<usize>();
+    (statements as i64, body.basic_blocks.len() as i64, body.local_decls.len() as i64)
+}
+
 fn run_passes_inner<'tcx>(
     tcx: TyCtxt<'tcx>,
     body: &mut Body<'tcx>,
@@ -279,6 +331,8 @@ fn run_passes_inner<'tcx>(
     if !body.should_skip() {
         let validate = validate_each & tcx.sess.opts.unstable_opts.validate_mir;
         let lint = tcx.sess.opts.unstable_opts.lint_mir;
+        let stats = tcx.sess.opts.unstable_opts.mir_pass_stats;
+        let mut bisect_count = 0;
 
         for pass in passes {
             let pass_name = pass.name();
@@ -287,6 +341,12 @@ fn run_passes_inner<'tcx>(
                 continue;
             };
 
+            if !pass.is_required()
+                && !check_mir_opt_bisect_limit(tcx, body, pass_name, &mut bisect_count)
+            {
+                continue;
+            }
+
             let dumper = if pass.is_mir_dump_enabled()
                 && let Some(dumper) = MirDumper::new(tcx, pass_name, body)
             {
@@ -299,6 +359,8 @@ fn run_passes_inner<'tcx>(
                 dumper.dump_mir(body);
             }
 
+            let size_before = stats.then(|| (body_size(body), Instant::now()));
+
             if let Some(prof_arg) = &prof_arg {
                 tcx.sess
                     .prof
@@ -308,6 +370,19 @@ fn run_passes_inner<'tcx>(
                 pass.run_pass(tcx, body);
             }
 
+            if let Some(((statements, blocks, locals), start)) = size_before {
+                let time = start.elapsed();
+                let (statements_after, blocks_after, locals_after) = body_size(body);
+                tcx.sess.mir_pass_stats.lock().push(MirPassStats {
+                    pass: pass_name,
+                    body: body_path(tcx, body),
+                    statements_removed: statements - statements_after,
+                    blocks_removed: blocks - blocks_after,
+                    locals_removed: locals - locals_after,
+                    time,
+                });
+            }
+
             if let Some(dumper) = dumper {
                 dumper.set_disambiguator(&"after").dump_mir(body);
             }
@@ -0,0 +1,956 @@
+//! Removes overflow and bounds checks that are proven to always succeed.
+//!
+//! This pass tracks an interval of possible values for every integer place. The intervals are
+//! narrowed on the edges of `SwitchInt` terminators that branch on a comparison, so that the
+//! bounds check in
+//!
+//! ```ignore (illustrative)
+//! if i < 8 { a[i] } else { 0 }
+//! ```
+//!
+//! is known to always succeed. Proven `Assert` terminators are replaced by a `Goto`, and the
+//! comparisons they check are replaced by a constant.
+
+use std::assert_matches::assert_matches;
+use std::fmt::{Debug, Formatter};
+
+use rustc_middle::bug;
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, ScalarInt, Ty, TyCtxt};
+use rustc_mir_dataflow::fmt::DebugWithContext;
+use rustc_mir_dataflow::lattice::{HasBottom, HasTop, JoinSemiLattice};
+use rustc_mir_dataflow::value_analysis::{
+    Map, PlaceCollectionMode, PlaceIndex, State, TrackElem, ValueOrPlace, debug_with_context,
+};
+use rustc_mir_dataflow::{Analysis, ResultsVisitor, visit_reachable_results};
+use rustc_span::DUMMY_SP;
+use tracing::{debug, debug_span, instrument};
+
+// These constants are somewhat random guesses and have not been optimized.
+// If `tcx.sess.mir_opt_level() >= 4`, we ignore the limits (this can become very expensive).
+const BLOCK_LIMIT: usize = 100;
+const PLACE_LIMIT: usize = 100;
+
+/// The number of times an interval may grow at a join before it is widened to the full range of
+/// its type. This bounds the height of the lattice, so that the analysis terminates on loops.
+const WIDENING_LIMIT: u8 = 4;
+
+pub(super) struct RangeCheckElimination;
+
+impl<'tcx> crate::MirPass<'tcx> for RangeCheckElimination {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        // Enabled in debug builds as well, which are the builds that keep overflow checks.
+        sess.mir_opt_level() >= 1
+    }
+
+    #[instrument(skip_all level = "debug")]
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        debug!(def_id = ?body.source.def_id());
+        if tcx.sess.mir_opt_level() < 4 && body.basic_blocks.len() > BLOCK_LIMIT {
+            debug!("aborted range check elimination due to too many basic blocks");
+            return;
+        }
+
+        // See `DataflowConstProp` for why we limit the number of tracked places.
+        let value_limit = if tcx.sess.mir_opt_level() < 4 { Some(PLACE_LIMIT) } else { None };
+        let map = Map::new(tcx, body, PlaceCollectionMode::Full { value_limit });
+
+        let ranges = debug_span!("analyze")
+            .in_scope(|| RangeAnalysis::new(tcx, body, map).iterate_to_fixpoint(tcx, body, None));
+
+        let mut collector = Collector::default();
+        debug_span!("collect").in_scope(|| visit_reachable_results(body, &ranges, &mut collector));
+
+        debug_span!("patch").in_scope(|| {
+            let basic_blocks = body.basic_blocks.as_mut_preserves_cfg();
+            for (location, value) in collector.comparisons {
+                let statement =
+                    &mut basic_blocks[location.block].statements[location.statement_index];
+                let StatementKind::Assign(box (_, rvalue)) = &mut statement.kind else {
+                    bug!("found comparison info for non-assign statement");
+                };
+                *rvalue = Rvalue::Use(Operand::Constant(Box::new(ConstOperand {
+                    span: DUMMY_SP,
+                    user_ty: None,
+                    const_: Const::from_bool(tcx, value),
+                })));
+            }
+
+            if !collector.asserts.is_empty() {
+                let basic_blocks = body.basic_blocks_mut();
+                for (block, target) in collector.asserts {
+                    basic_blocks[block].terminator_mut().kind = TerminatorKind::Goto { target };
+                }
+            }
+        });
+    }
+
+    fn is_required(&self) -> bool {
+        false
+    }
+}
+
+/// The set of values an integer place may hold.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum IntRange {
+    Bottom,
+    /// All values in `lo..=hi`. `widenings` counts how often the interval grew at a join.
+    Interval {
+        lo: i128,
+        hi: i128,
+        widenings: u8,
+    },
+    /// Any value of the type of the place.
+    Top,
+}
+
+impl IntRange {
+    fn new(lo: i128, hi: i128) -> Self {
+        if lo > hi { IntRange::Bottom } else { IntRange::Interval { lo, hi, widenings: 0 } }
+    }
+
+    fn singleton(value: i128) -> Self {
+        IntRange::new(value, value)
+    }
+
+    fn from_bool(value: Option<bool>) -> Self {
+        value.map_or(IntRange::Top, |value| IntRange::singleton(value.into()))
+    }
+
+    /// Returns the range if it lies within `ty_bounds`, and `Top` otherwise.
+    fn within(lo: i128, hi: i128, ty_bounds: (i128, i128)) -> Self {
+        if ty_bounds.0 <= lo && hi <= ty_bounds.1 { IntRange::new(lo, hi) } else { IntRange::Top }
+    }
+
+    fn as_singleton(self) -> Option<i128> {
+        match self {
+            IntRange::Interval { lo, hi, .. } if lo == hi => Some(lo),
+            _ => None,
+        }
+    }
+
+    /// Returns the bounds of this range, where `Top` is bounded by `ty_bounds`.
+    fn bounds(self, ty_bounds: (i128, i128)) -> Option<(i128, i128)> {
+        match self {
+            IntRange::Bottom => None,
+            IntRange::Interval { lo, hi, .. } => Some((lo, hi)),
+            IntRange::Top => Some(ty_bounds),
+        }
+    }
+
+    /// Narrows this range to `lo..=hi`, which must be a subset of it.
+    fn narrow(self, lo: i128, hi: i128) -> Self {
+        match self {
+            IntRange::Interval { lo: old_lo, hi: old_hi, .. } if (lo, hi) == (old_lo, old_hi) => {
+                self
+            }
+            // Keep the number of widenings, so that narrowing cannot prevent termination.
+            IntRange::Interval { widenings, .. } if lo <= hi => {
+                IntRange::Interval { lo, hi, widenings }
+            }
+            _ => IntRange::new(lo, hi),
+        }
+    }
+}
+
+impl JoinSemiLattice for IntRange {
+    fn join(&mut self, other: &Self) -> bool {
+        let result = match (*self, *other) {
+            (IntRange::Top, _) | (_, IntRange::Bottom) => return false,
+            (IntRange::Bottom, other) => other,
+            (_, IntRange::Top) => IntRange::Top,
+            (
+                IntRange::Interval { lo, hi, widenings },
+                IntRange::Interval { lo: other_lo, hi: other_hi, widenings: other_widenings },
+            ) => {
+                if lo <= other_lo && other_hi <= hi {
+                    return false;
+                }
+                let widenings = widenings.max(other_widenings) + 1;
+                if widenings > WIDENING_LIMIT {
+                    IntRange::Top
+                } else {
+                    IntRange::Interval { lo: lo.min(other_lo), hi: hi.max(other_hi), widenings }
+                }
+            }
+        };
+        *self = result;
+        true
+    }
+}
+
+impl HasBottom for IntRange {
+    const BOTTOM: Self = IntRange::Bottom;
+
+    fn is_bottom(&self) -> bool {
+        matches!(self, IntRange::Bottom)
+    }
+}
+
+impl HasTop for IntRange {
+    const TOP: Self = IntRange::Top;
+}
+
+impl Debug for IntRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntRange::Bottom => write!(f, "⊥"),
+            IntRange::Interval { lo, hi, .. } if lo == hi => write!(f, "{lo}"),
+            IntRange::Interval { lo, hi, .. } => write!(f, "{lo}..={hi}"),
+            IntRange::Top => write!(f, "⊤"),
+        }
+    }
+}
+
+/// A comparison that determines the discriminant of a `SwitchInt`.
+struct Comparison<'tcx> {
+    op: BinOp,
+    left: Operand<'tcx>,
+    right: Operand<'tcx>,
+    /// The places that hold the value of `left` when reaching the terminator.
+    left_places: Vec<Place<'tcx>>,
+    /// The places that hold the value of `right` when reaching the terminator.
+    right_places: Vec<Place<'tcx>>,
+}
+
+struct SwitchIntData<'tcx> {
+    discr: Place<'tcx>,
+    comparison: Option<Comparison<'tcx>>,
+}
+
+// Note: Like in `DataflowConstProp`, places that have their reference taken are not tracked, so the
+// value of a tracked place can only be changed by direct assignment.
+struct RangeAnalysis<'a, 'tcx> {
+    map: Map<'tcx>,
+    tcx: TyCtxt<'tcx>,
+    body: &'a Body<'tcx>,
+    typing_env: ty::TypingEnv<'tcx>,
+}
+
+impl<'tcx> Analysis<'tcx> for RangeAnalysis<'_, 'tcx> {
+    type Domain = State<IntRange>;
+
+    type SwitchIntData = SwitchIntData<'tcx>;
+
+    const NAME: &'static str = "RangeAnalysis";
+
+    fn bottom_value(&self, _body: &Body<'tcx>) -> Self::Domain {
+        State::Unreachable
+    }
+
+    fn initialize_start_block(&self, body: &Body<'tcx>, state: &mut Self::Domain) {
+        // The initial state maps all tracked places of argument projections to ⊤ and the rest to ⊥.
+        assert_matches!(state, State::Unreachable);
+        *state = State::new_reachable();
+        for arg in body.args_iter() {
+            state.flood(PlaceRef { local: arg, projection: &[] }, &self.map);
+        }
+    }
+
+    fn apply_primary_statement_effect(
+        &self,
+        state: &mut Self::Domain,
+        statement: &Statement<'tcx>,
+        _location: Location,
+    ) {
+        if state.is_reachable() {
+            self.handle_statement(statement, state);
+        }
+    }
+
+    fn apply_primary_terminator_effect<'mir>(
+        &self,
+        state: &mut Self::Domain,
+        terminator: &'mir Terminator<'tcx>,
+        _location: Location,
+    ) -> TerminatorEdges<'mir, 'tcx> {
+        if state.is_reachable() {
+            self.handle_terminator(terminator, state)
+        } else {
+            TerminatorEdges::None
+        }
+    }
+
+    fn apply_call_return_effect(
+        &self,
+        state: &mut Self::Domain,
+        _block: BasicBlock,
+        return_places: CallReturnPlaces<'_, 'tcx>,
+    ) {
+        if state.is_reachable() {
+            return_places.for_each(|place| state.flood(place.as_ref(), &self.map));
+        }
+    }
+
+    fn get_switch_int_data(
+        &self,
+        block: BasicBlock,
+        discr: &Operand<'tcx>,
+    ) -> Option<Self::SwitchIntData> {
+        let discr = discr.place()?;
+        self.map.find(discr.as_ref())?;
+
+        // Look for the comparison that computes the discriminant, e.g.
+        // ```text
+        // _3 = copy _1;
+        // _2 = Lt(move _3, const 8_usize);
+        // switchInt(move _2) -> [0: bb2, otherwise: bb1];
+        // ```
+        // Its operands, and the places they were copied from, must not be modified afterwards.
+        let statements = &self.body[block].statements;
+        let mut comparison = None;
+        for (index, statement) in statements.iter().enumerate().rev() {
+            if let StatementKind::Assign(box (place, Rvalue::BinaryOp(op, box (left, right)))) =
+                &statement.kind
+                && *place == discr
+                && matches!(
+                    op,
+                    BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge | BinOp::Eq | BinOp::Ne
+                )
+            {
+                comparison = Some(Comparison {
+                    op: *op,
+                    left: left.clone(),
+                    right: right.clone(),
+                    left_places: equal_places(left, statements, index),
+                    right_places: equal_places(right, statements, index),
+                });
+                break;
+            }
+            if writes_local(statement, discr.local) {
+                break;
+            }
+        }
+        Some(SwitchIntData { discr, comparison })
+    }
+
+    fn apply_switch_int_edge_effect(
+        &self,
+        data: &mut Self::SwitchIntData,
+        state: &mut Self::Domain,
+        value: SwitchTargetValue,
+        targets: &SwitchTargets,
+    ) {
+        if !state.is_reachable() {
+            return;
+        }
+        let discr_ty = data.discr.ty(self.body, self.tcx).ty;
+        let value = match value {
+            SwitchTargetValue::Normal(bits) => {
+                if let Some(discr) = self.map.find(data.discr.as_ref()) {
+                    let value = self.bits_to_range(bits, discr_ty);
+                    state.insert_value_idx(discr, value, &self.map);
+                }
+                Some(bits)
+            }
+            // A boolean that is not equal to the only listed value is equal to the other one.
+            SwitchTargetValue::Otherwise if discr_ty.is_bool() => match targets.all_values() {
+                [value] => Some(1 - value.get()),
+                _ => None,
+            },
+            SwitchTargetValue::Otherwise => None,
+        };
+        if discr_ty.is_bool()
+            && let Some(value) = value
+            && let Some(comparison) = &data.comparison
+        {
+            self.refine(comparison, value != 0, state);
+        }
+    }
+}
+
+impl<'a, 'tcx> RangeAnalysis<'a, 'tcx> {
+    fn new(tcx: TyCtxt<'tcx>, body: &'a Body<'tcx>, map: Map<'tcx>) -> Self {
+        Self { map, tcx, body, typing_env: body.typing_env(tcx) }
+    }
+
+    fn handle_statement(&self, statement: &Statement<'tcx>, state: &mut State<IntRange>) {
+        match &statement.kind {
+            StatementKind::Assign(box (place, rvalue)) => {
+                self.handle_assign(*place, rvalue, state);
+            }
+            StatementKind::SetDiscriminant { box place, .. } => {
+                state.flood_discr(place.as_ref(), &self.map);
+            }
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local) => {
+                // StorageLive leaves the local in an uninitialized state.
+                // StorageDead makes it UB to access the local afterwards.
+                state.flood_with(Place::from(*local).as_ref(), &self.map, IntRange::BOTTOM);
+            }
+            StatementKind::Intrinsic(..) | StatementKind::Retag(..) => {
+                // These cannot modify tracked places.
+            }
+            StatementKind::ConstEvalCounter
+            | StatementKind::Nop
+            | StatementKind::FakeRead(..)
+            | StatementKind::PlaceMention(..)
+            | StatementKind::Coverage(..)
+            | StatementKind::BackwardIncompatibleDropHint { .. }
+            | StatementKind::AscribeUserType(..) => {}
+        }
+    }
+
+    fn handle_terminator<'mir>(
+        &self,
+        terminator: &'mir Terminator<'tcx>,
+        state: &mut State<IntRange>,
+    ) -> TerminatorEdges<'mir, 'tcx> {
+        match &terminator.kind {
+            TerminatorKind::Call { .. } | TerminatorKind::InlineAsm { .. } => {
+                // Effect is applied by `apply_call_return_effect`.
+            }
+            TerminatorKind::Drop { place, .. } => {
+                state.flood_with(place.as_ref(), &self.map, IntRange::BOTTOM);
+            }
+            TerminatorKind::Yield { .. } => {
+                // They would have an effect, but are not allowed in this phase.
+                bug!("encountered disallowed terminator");
+            }
+            TerminatorKind::SwitchInt { discr, targets } => {
+                return match self.eval_operand(discr, state) {
+                    // We are branching on uninitialized data, this is UB, treat it as unreachable.
+                    IntRange::Bottom => TerminatorEdges::None,
+                    value => match value.as_singleton() {
+                        Some(value) => {
+                            let size = discr.ty(self.body, self.tcx).primitive_size(self.tcx);
+                            TerminatorEdges::Single(
+                                targets.target_for_value(size.truncate(value as u128)),
+                            )
+                        }
+                        None => TerminatorEdges::SwitchInt { discr, targets },
+                    },
+                };
+            }
+            TerminatorKind::TailCall { .. }
+            | TerminatorKind::Goto { .. }
+            | TerminatorKind::UnwindResume
+            | TerminatorKind::UnwindTerminate(_)
+            | TerminatorKind::Return
+            | TerminatorKind::Unreachable
+            | TerminatorKind::Assert { .. }
+            | TerminatorKind::CoroutineDrop
+            | TerminatorKind::FalseEdge { .. }
+            | TerminatorKind::FalseUnwind { .. } => {
+                // These terminators have no effect on the analysis.
+            }
+        }
+        terminator.edges()
+    }
+
+    fn handle_assign(
+        &self,
+        target: Place<'tcx>,
+        rvalue: &Rvalue<'tcx>,
+        state: &mut State<IntRange>,
+    ) {
+        match rvalue {
+            Rvalue::Use(operand) => {
+                state.flood(target.as_ref(), &self.map);
+                if let Some(target) = self.map.find(target.as_ref()) {
+                    self.assign_operand(state, target, operand);
+                }
+            }
+            Rvalue::BinaryOp(op, box (left, right)) if op.is_overflowing() => {
+                // Flood everything now, so we can use `insert_value_idx` directly later.
+                state.flood(target.as_ref(), &self.map);
+
+                let Some(target) = self.map.find(target.as_ref()) else { return };
+
+                let value_target = self.map.apply(target, TrackElem::Field(0_u32.into()));
+                let overflow_target = self.map.apply(target, TrackElem::Field(1_u32.into()));
+
+                if value_target.is_some() || overflow_target.is_some() {
+                    let (val, overflow) = self.binary_op(state, *op, left, right);
+
+                    if let Some(value_target) = value_target {
+                        // We have flooded `target` earlier.
+                        state.insert_value_idx(value_target, val, &self.map);
+                    }
+                    if let Some(overflow_target) = overflow_target {
+                        // We have flooded `target` earlier.
+                        state.insert_value_idx(overflow_target, overflow, &self.map);
+                    }
+                }
+            }
+            Rvalue::Cast(
+                CastKind::PointerCoercion(ty::adjustment::PointerCoercion::Unsize, _),
+                operand,
+                _,
+            ) => {
+                let pointer = self.handle_operand(operand, state);
+                state.assign(target.as_ref(), pointer, &self.map);
+
+                if let Some(target_len) = self.map.find_len(target.as_ref())
+                    && let operand_ty = operand.ty(self.body, self.tcx)
+                    && let Some(operand_ty) = operand_ty.builtin_deref(true)
+                    && let ty::Array(_, len) = operand_ty.kind()
+                    && let Some(len) = Const::Ty(self.tcx.types.usize, *len)
+                        .try_eval_scalar_int(self.tcx, self.typing_env)
+                {
+                    let len = self.scalar_to_range(len, self.tcx.types.usize);
+                    state.insert_value_idx(target_len, len, &self.map);
+                }
+            }
+            _ => {
+                let result = self.handle_rvalue(rvalue, state);
+                state.assign(target.as_ref(), result, &self.map);
+            }
+        }
+    }
+
+    fn handle_rvalue(
+        &self,
+        rvalue: &Rvalue<'tcx>,
+        state: &mut State<IntRange>,
+    ) -> ValueOrPlace<IntRange> {
+        let val = match rvalue {
+            Rvalue::Cast(CastKind::IntToInt, operand, ty) => {
+                let operand_ty = operand.ty(self.body, self.tcx);
+                match (self.type_bounds(operand_ty), self.type_bounds(*ty)) {
+                    (Some(operand_bounds), Some(ty_bounds)) => {
+                        match self.eval_operand(operand, state).bounds(operand_bounds) {
+                            Some((lo, hi)) => IntRange::within(lo, hi, ty_bounds),
+                            None => IntRange::Bottom,
+                        }
+                    }
+                    _ => IntRange::Top,
+                }
+            }
+            Rvalue::BinaryOp(op, box (left, right)) if !op.is_overflowing() => {
+                let (val, _overflow) = self.binary_op(state, *op, left, right);
+                val
+            }
+            Rvalue::UnaryOp(op, operand) => {
+                if let UnOp::PtrMetadata = op
+                    && let Some(place) = operand.place()
+                    && let Some(len) = self.map.find_len(place.as_ref())
+                {
+                    return ValueOrPlace::Place(len);
+                }
+                self.unary_op(state, *op, operand)
+            }
+            Rvalue::Discriminant(place) => state.get_discr(place.as_ref(), &self.map),
+            Rvalue::Use(operand) => return self.handle_operand(operand, state),
+            Rvalue::CopyForDeref(_) => bug!("`CopyForDeref` in runtime MIR"),
+            Rvalue::ShallowInitBox(..) => bug!("`ShallowInitBox` in runtime MIR"),
+            Rvalue::Ref(..)
+            | Rvalue::RawPtr(..)
+            | Rvalue::Repeat(..)
+            | Rvalue::ThreadLocalRef(..)
+            | Rvalue::Cast(..)
+            | Rvalue::BinaryOp(..)
+            | Rvalue::Aggregate(..)
+            | Rvalue::WrapUnsafeBinder(..) => return ValueOrPlace::TOP,
+        };
+        ValueOrPlace::Value(val)
+    }
+
+    /// The caller must have flooded `place`.
+    fn assign_operand(
+        &self,
+        state: &mut State<IntRange>,
+        place: PlaceIndex,
+        operand: &Operand<'tcx>,
+    ) {
+        match operand {
+            Operand::RuntimeChecks(_) => {}
+            Operand::Copy(rhs) | Operand::Move(rhs) => {
+                if let Some(rhs) = self.map.find(rhs.as_ref()) {
+                    state.insert_place_idx(place, rhs, &self.map);
+                }
+            }
+            Operand::Constant(box constant) => {
+                state.insert_value_idx(place, self.handle_constant(constant), &self.map);
+            }
+        }
+    }
+
+    fn handle_operand(
+        &self,
+        operand: &Operand<'tcx>,
+        state: &mut State<IntRange>,
+    ) -> ValueOrPlace<IntRange> {
+        match operand {
+            Operand::RuntimeChecks(_) => ValueOrPlace::TOP,
+            Operand::Constant(box constant) => ValueOrPlace::Value(self.handle_constant(constant)),
+            Operand::Copy(place) | Operand::Move(place) => {
+                self.map.find(place.as_ref()).map(ValueOrPlace::Place).unwrap_or(ValueOrPlace::TOP)
+            }
+        }
+    }
+
+    fn handle_constant(&self, constant: &ConstOperand<'tcx>) -> IntRange {
+        constant
+            .const_
+            .try_eval_scalar_int(self.tcx, self.typing_env)
+            .map_or(IntRange::Top, |scalar| self.scalar_to_range(scalar, constant.ty()))
+    }
+
+    fn eval_operand(&self, operand: &Operand<'tcx>, state: &State<IntRange>) -> IntRange {
+        match operand {
+            Operand::RuntimeChecks(_) => IntRange::Top,
+            Operand::Constant(box constant) => self.handle_constant(constant),
+            Operand::Copy(place) | Operand::Move(place) => self
+                .map
+                .find(place.as_ref())
+                .map_or(IntRange::Top, |place| state.get_idx(place, &self.map)),
+        }
+    }
+
+    /// Returns the bounds of `operand`, or `None` if it is not an integer or uninitialized.
+    fn operand_bounds(
+        &self,
+        operand: &Operand<'tcx>,
+        state: &State<IntRange>,
+    ) -> Option<(i128, i128)> {
+        let ty_bounds = self.type_bounds(operand.ty(self.body, self.tcx))?;
+        self.eval_operand(operand, state).bounds(ty_bounds)
+    }
+
+    /// Returns the minimum and maximum value of an integer type, or `None` if it is not an
+    /// integer or its values do not fit into an `i128`.
+    fn type_bounds(&self, ty: Ty<'tcx>) -> Option<(i128, i128)> {
+        match ty.kind() {
+            ty::Bool => Some((0, 1)),
+            ty::Char => Some((0, char::MAX as i128)),
+            ty::Int(_) => {
+                let size = ty.primitive_size(self.tcx);
+                Some((size.signed_int_min(), size.signed_int_max()))
+            }
+            ty::Uint(_) => {
+                let size = ty.primitive_size(self.tcx);
+                i128::try_from(size.unsigned_int_max()).ok().map(|max| (0, max))
+            }
+            _ => None,
+        }
+    }
+
+    fn scalar_to_range(&self, scalar: ScalarInt, ty: Ty<'tcx>) -> IntRange {
+        self.bits_to_range(scalar.to_bits_unchecked(), ty)
+    }
+
+    fn bits_to_range(&self, bits: u128, ty: Ty<'tcx>) -> IntRange {
+        match ty.kind() {
+            ty::Int(_) => IntRange::singleton(ty.primitive_size(self.tcx).sign_extend(bits)),
+            ty::Uint(_) | ty::Bool | ty::Char => {
+                i128::try_from(bits).map_or(IntRange::Top, IntRange::singleton)
+            }
+            _ => IntRange::Top,
+        }
+    }
+
+    fn unary_op(&self, state: &State<IntRange>, op: UnOp, operand: &Operand<'tcx>) -> IntRange {
+        let ty = operand.ty(self.body, self.tcx);
+        let Some(ty_bounds) = self.type_bounds(ty) else { return IntRange::Top };
+        let Some((lo, hi)) = self.eval_operand(operand, state).bounds(ty_bounds) else {
+            return IntRange::Bottom;
+        };
+        match op {
+            UnOp::Not if ty.is_bool() => IntRange::new(1 - hi, 1 - lo),
+            // `!x` is `-x - 1` for signed integers, and `MAX - x` for unsigned integers.
+            UnOp::Not if ty.is_signed() => IntRange::new(!hi, !lo),
+            UnOp::Not if ty.is_integral() => IntRange::new(ty_bounds.1 - hi, ty_bounds.1 - lo),
+            UnOp::Neg if ty.is_signed() => match (hi.checked_neg(), lo.checked_neg()) {
+                (Some(lo), Some(hi)) => IntRange::within(lo, hi, ty_bounds),
+                _ => IntRange::Top,
+            },
+            UnOp::Not | UnOp::Neg | UnOp::PtrMetadata => IntRange::Top,
+        }
+    }
+
+    /// Evaluates a binary operation. The second element of the result is the overflow flag of
+    /// overflowing operations.
+    fn binary_op(
+        &self,
+        state: &State<IntRange>,
+        op: BinOp,
+        left: &Operand<'tcx>,
+        right: &Operand<'tcx>,
+    ) -> (IntRange, IntRange) {
+        let left_ty = left.ty(self.body, self.tcx);
+        let Some(ty_bounds) = self.type_bounds(left_ty) else {
+            return (IntRange::Top, IntRange::Top);
+        };
+        let (l, r) = match (self.eval_operand(left, state), self.eval_operand(right, state)) {
+            (IntRange::Bottom, _) | (_, IntRange::Bottom) => {
+                return (IntRange::Bottom, IntRange::Bottom);
+            }
+            _ => match (self.operand_bounds(left, state), self.operand_bounds(right, state)) {
+                (Some(l), Some(r)) => (l, r),
+                _ => return (IntRange::Top, IntRange::Top),
+            },
+        };
+
+        // The exact result of arithmetic operations, which may not fit into the type.
+        let exact = match op.overflowing_to_wrapping().unwrap_or(op) {
+            BinOp::Add | BinOp::AddUnchecked => l.0.checked_add(r.0).zip(l.1.checked_add(r.1)),
+            BinOp::Sub | BinOp::SubUnchecked => l.0.checked_sub(r.1).zip(l.1.checked_sub(r.0)),
+            BinOp::Mul | BinOp::MulUnchecked => {
+                let products = [
+                    l.0.checked_mul(r.0),
+                    l.0.checked_mul(r.1),
+                    l.1.checked_mul(r.0),
+                    l.1.checked_mul(r.1),
+                ];
+                products.into_iter().collect::<Option<Vec<_>>>().map(|products| {
+                    (*products.iter().min().unwrap(), *products.iter().max().unwrap())
+                })
+            }
+            _ => None,
+        };
+        if let Some((lo, hi)) = exact {
+            let overflow = if ty_bounds.0 <= lo && hi <= ty_bounds.1 {
+                Some(false)
+            } else if hi < ty_bounds.0 || ty_bounds.1 < lo {
+                Some(true)
+            } else {
+                None
+            };
+            let value = match op {
+                // Overflowing an unchecked operation is UB.
+                BinOp::AddUnchecked | BinOp::SubUnchecked | BinOp::MulUnchecked => {
+                    IntRange::new(lo.max(ty_bounds.0), hi.min(ty_bounds.1))
+                }
+                _ => IntRange::within(lo, hi, ty_bounds),
+            };
+            return (value, IntRange::from_bool(overflow));
+        }
+
+        let value = match op {
+            BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge | BinOp::Eq | BinOp::Ne => {
+                IntRange::from_bool(compare(op, l, r))
+            }
+            // For non-negative values, the result is at most the smaller operand.
+            BinOp::BitAnd if l.0 >= 0 && r.0 >= 0 => match (l, r) {
+                ((a, b), (c, d)) if a == b && c == d => IntRange::singleton(a & c),
+                _ => IntRange::new(0, l.1.min(r.1)),
+            },
+            BinOp::BitOr | BinOp::BitXor if l.0 >= 0 && l.0 == l.1 && r.0 >= 0 && r.0 == r.1 => {
+                let value = if op == BinOp::BitOr { l.0 | r.0 } else { l.0 ^ r.0 };
+                IntRange::within(value, value, ty_bounds)
+            }
+            BinOp::Div if l.0 >= 0 && r.0 > 0 => IntRange::new(l.0 / r.1, l.1 / r.0),
+            BinOp::Rem if l.0 >= 0 && r.0 > 0 => IntRange::new(0, l.1.min(r.1 - 1)),
+            // `Shr` masks the shift amount by the bit width of the type, so `x >> 9` is `x >> 1`
+            // for a `u8`, and shifting by the bit width or more is UB for `ShrUnchecked`. Only
+            // shift amounts below the bit width are folded.
+            BinOp::Shr | BinOp::ShrUnchecked
+                if l.0 >= 0
+                    && r.0 >= 0
+                    && r.1 < i128::from(left_ty.primitive_size(self.tcx).bits()) =>
+            {
+                IntRange::new(l.0 >> r.1, l.1 >> r.0)
+            }
+            _ => IntRange::Top,
+        };
+        (value, IntRange::Top)
+    }
+
+    /// Narrows the operands of `comparison`, knowing that it evaluated to `holds`.
+    fn refine(&self, comparison: &Comparison<'tcx>, holds: bool, state: &mut State<IntRange>) {
+        let op = if holds { comparison.op } else { negate(comparison.op) };
+        let left = self.eval_operand(&comparison.left, state);
+        let right = self.eval_operand(&comparison.right, state);
+        let (Some(l), Some(r)) = (
+            self.operand_bounds(&comparison.left, state),
+            self.operand_bounds(&comparison.right, state),
+        ) else {
+            return;
+        };
+
+        let (new_l, new_r) = match op {
+            BinOp::Lt => {
+                ((l.0, l.1.min(r.1.saturating_sub(1))), (r.0.max(l.0.saturating_add(1)), r.1))
+            }
+            BinOp::Le => ((l.0, l.1.min(r.1)), (r.0.max(l.0), r.1)),
+            BinOp::Gt => {
+                ((l.0.max(r.0.saturating_add(1)), l.1), (r.0, r.1.min(l.1.saturating_sub(1))))
+            }
+            BinOp::Ge => ((l.0.max(r.0), l.1), (r.0, r.1.min(l.1))),
+            BinOp::Eq => {
+                let both = (l.0.max(r.0), l.1.min(r.1));
+                (both, both)
+            }
+            // We can only exclude a value at the boundary of an interval.
+            BinOp::Ne => (exclude(l, r), exclude(r, l)),
+            _ => bug!("unexpected comparison {op:?}"),
+        };
+        if new_l.0 > new_l.1 || new_r.0 > new_r.1 {
+            // The comparison cannot have this result.
+            *state = State::Unreachable;
+            return;
+        }
+
+        let left = left.narrow(new_l.0, new_l.1);
+        for place in &comparison.left_places {
+            if let Some(place) = self.map.find(place.as_ref()) {
+                state.insert_value_idx(place, left, &self.map);
+            }
+        }
+        let right = right.narrow(new_r.0, new_r.1);
+        for place in &comparison.right_places {
+            if let Some(place) = self.map.find(place.as_ref()) {
+                state.insert_value_idx(place, right, &self.map);
+            }
+        }
+    }
+}
+
+/// Decides the comparison `l op r`, if possible.
+fn compare(op: BinOp, l: (i128, i128), r: (i128, i128)) -> Option<bool> {
+    match op {
+        BinOp::Lt if l.1 < r.0 => Some(true),
+        BinOp::Lt if l.0 >= r.1 => Some(false),
+        BinOp::Le if l.1 <= r.0 => Some(true),
+        BinOp::Le if l.0 > r.1 => Some(false),
+        BinOp::Gt => compare(BinOp::Lt, r, l),
+        BinOp::Ge => compare(BinOp::Le, r, l),
+        BinOp::Eq if l.0 == l.1 && l == r => Some(true),
+        BinOp::Eq if l.1 < r.0 || r.1 < l.0 => Some(false),
+        BinOp::Ne => compare(BinOp::Eq, l, r).map(|eq| !eq),
+        _ => None,
+    }
+}
+
+fn negate(op: BinOp) -> BinOp {
+    match op {
+        BinOp::Lt => BinOp::Ge,
+        BinOp::Le => BinOp::Gt,
+        BinOp::Gt => BinOp::Le,
+        BinOp::Ge => BinOp::Lt,
+        BinOp::Eq => BinOp::Ne,
+        BinOp::Ne => BinOp::Eq,
+        _ => bug!("unexpected comparison {op:?}"),
+    }
+}
+
+/// Removes the value of the singleton `other` from `range`, if it is at one of its boundaries.
+/// `range` is returned unchanged if that would overflow, which can happen for `i128` values.
+fn exclude(range: (i128, i128), other: (i128, i128)) -> (i128, i128) {
+    let (value, other_hi) = other;
+    if value != other_hi {
+        return range;
+    }
+    let excluded = if value == range.0 {
+        range.0.checked_add(1).map(|lo| (lo, range.1))
+    } else if value == range.1 {
+        range.1.checked_sub(1).map(|hi| (range.0, hi))
+    } else {
+        None
+    };
+    excluded.unwrap_or(range)
+}
+
+/// Returns whether `statement` may modify `local`.
+fn writes_local(statement: &Statement<'_>, local: Local) -> bool {
+    match &statement.kind {
+        StatementKind::Assign(box (place, _))
+        | StatementKind::SetDiscriminant { box place, .. } => place.local == local,
+        StatementKind::StorageLive(l) | StatementKind::StorageDead(l) => *l == local,
+        _ => false,
+    }
+}
+
+/// Returns the places that hold the value of `operand` after `statements[index]`, up to the end
+/// of the block. This includes the place the operand was copied from earlier in the block.
+fn equal_places<'tcx>(
+    operand: &Operand<'tcx>,
+    statements: &[Statement<'tcx>],
+    index: usize,
+) -> Vec<Place<'tcx>> {
+    let Some(place) = operand.place() else { return Vec::new() };
+    let later = &statements[index + 1..];
+    if later.iter().any(|statement| writes_local(statement, place.local)) {
+        return Vec::new();
+    }
+
+    let mut places = vec![place];
+    for (source_index, statement) in statements[..index].iter().enumerate().rev() {
+        if let StatementKind::Assign(box (
+            target,
+            Rvalue::Use(Operand::Copy(source) | Operand::Move(source)),
+        )) = &statement.kind
+            && *target == place
+        {
+            if !statements[source_index + 1..]
+                .iter()
+                .any(|statement| writes_local(statement, source.local))
+            {
+                places.push(*source);
+            }
+            break;
+        }
+        if writes_local(statement, place.local) {
+            break;
+        }
+    }
+    places
+}
+
+/// This is used to visualize the dataflow analysis.
+impl<'tcx> DebugWithContext<RangeAnalysis<'_, 'tcx>> for State<IntRange> {
+    fn fmt_with(&self, ctxt: &RangeAnalysis<'_, 'tcx>, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            State::Reachable(values) => debug_with_context(values, None, &ctxt.map, f),
+            State::Unreachable => write!(f, "unreachable"),
+        }
+    }
+
+    fn fmt_diff_with(
+        &self,
+        old: &Self,
+        ctxt: &RangeAnalysis<'_, 'tcx>,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        match (self, old) {
+            (State::Reachable(this), State::Reachable(old)) => {
+                debug_with_context(this, Some(old), &ctxt.map, f)
+            }
+            _ => Ok(()), // Consider printing something here.
+        }
+    }
+}
+
+/// Collects the comparisons and assertions that are known to succeed.
+#[derive(Default)]
+struct Collector {
+    /// Comparisons with a known result.
+    comparisons: Vec<(Location, bool)>,
+    /// `Assert` terminators that always succeed, and their success targets.
+    asserts: Vec<(BasicBlock, BasicBlock)>,
+}
+
+impl<'tcx> ResultsVisitor<'tcx, RangeAnalysis<'_, 'tcx>> for Collector {
+    #[instrument(level = "trace", skip(self, analysis, statement))]
+    fn visit_after_early_statement_effect(
+        &mut self,
+        analysis: &RangeAnalysis<'_, 'tcx>,
+        state: &State<IntRange>,
+        statement: &Statement<'tcx>,
+        location: Location,
+    ) {
+        if let StatementKind::Assign(box (_, Rvalue::BinaryOp(op, box (left, right)))) =
+            &statement.kind
+            && matches!(op, BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge | BinOp::Eq | BinOp::Ne)
+            && let Some(l) = analysis.operand_bounds(left, state)
+            && let Some(r) = analysis.operand_bounds(right, state)
+            && let Some(value) = compare(*op, l, r)
+        {
+            self.comparisons.push((location, value));
+        }
+    }
+
+    fn visit_after_early_terminator_effect(
+        &mut self,
+        analysis: &RangeAnalysis<'_, 'tcx>,
+        state: &State<IntRange>,
+        terminator: &Terminator<'tcx>,
+        location: Location,
+    ) {
+        if let TerminatorKind::Assert { cond, expected, target, .. } = &terminator.kind
+            && analysis.eval_operand(cond, state).as_singleton() == Some((*expected).into())
+        {
+            self.asserts.push((location.block, *target));
+        }
+    }
+}
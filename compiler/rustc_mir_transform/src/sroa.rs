@@ -1,4 +1,4 @@
-use rustc_abi::FieldIdx;
+use rustc_abi::{FieldIdx, VariantIdx};
 use rustc_data_structures::flat_map_in_place::FlatMapInPlace;
 use rustc_hir::LangItem;
 use rustc_index::IndexVec;
@@ -36,7 +36,7 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
             debug!(?escaping);
             let replacements = compute_flattening(tcx, typing_env, body, escaping);
             debug!(?replacements);
-            let all_dead_locals = replace_flattened_locals(tcx, body, replacements);
+            let all_dead_locals = replace_flattened_locals(tcx, typing_env, body, replacements);
             if !all_dead_locals.is_empty() {
                 excluded.union(&all_dead_locals);
                 excluded = {
@@ -59,16 +59,21 @@ fn is_required(&self) -> bool {
 ///
 /// There are 3 cases:
 /// - the aggregated local is used or passed to other code (function parameters and arguments);
-/// - the locals is a union or an enum;
+/// - the locals is a union;
 /// - the local's address is taken, and thus the relative addresses of the fields are observable to
 ///   client code.
+///
+/// Enums are split into their discriminant and the fields of all their variants, so they also have
+/// to be assigned to by aggregates only, and must not appear as a whole in debuginfo: copying the
+/// whole enum would read the fields of inactive variants, and debuginfo fragments cannot describe
+/// variants.
 fn escaping_locals<'tcx>(
     tcx: TyCtxt<'tcx>,
     excluded: &DenseBitSet<Local>,
     body: &Body<'tcx>,
 ) -> DenseBitSet<Local> {
     let is_excluded_ty = |ty: Ty<'tcx>| {
-        if ty.is_union() || ty.is_enum() {
+        if ty.is_union() {
             return true;
         }
         if let ty::Adt(def, _args) = ty.kind()
@@ -93,37 +98,69 @@ fn escaping_locals<'tcx>(
             set.insert(local);
         }
     }
-    let mut visitor = EscapeVisitor { set };
+    for var_debug_info in &body.var_debug_info {
+        if let VarDebugInfoContents::Place(place) = var_debug_info.value
+            && body.local_decls[place.local].ty.is_enum()
+            && !matches!(place.projection[..], [PlaceElem::Downcast(..), PlaceElem::Field(..), ..])
+        {
+            set.insert(place.local);
+        }
+    }
+    let mut visitor = EscapeVisitor { set, local_decls: &body.local_decls };
     visitor.visit_body(body);
     return visitor.set;
 
-    struct EscapeVisitor {
+    struct EscapeVisitor<'a, 'tcx> {
         set: DenseBitSet<Local>,
+        local_decls: &'a LocalDecls<'tcx>,
     }
 
-    impl<'tcx> Visitor<'tcx> for EscapeVisitor {
+    impl<'tcx> Visitor<'tcx> for EscapeVisitor<'_, 'tcx> {
         fn visit_local(&mut self, local: Local, _: PlaceContext, _: Location) {
             self.set.insert(local);
         }
 
         fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
             // Mirror the implementation in PreFlattenVisitor.
-            if let &[PlaceElem::Field(..), ..] = &place.projection[..] {
-                return;
+            match place.projection[..] {
+                [PlaceElem::Field(..), ..] => return,
+                [PlaceElem::Downcast(..), PlaceElem::Field(..), ..]
+                    if self.local_decls[place.local].ty.is_enum() =>
+                {
+                    return;
+                }
+                _ => {}
             }
             self.super_place(place, context, location);
         }
 
+        fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+            // Reading the discriminant of an enum is replaced by reading its split discriminant.
+            if let Rvalue::Discriminant(place) = rvalue
+                && let Some(local) = place.as_local()
+                && self.local_decls[local].ty.is_enum()
+            {
+                return;
+            }
+            self.super_rvalue(rvalue, location)
+        }
+
         fn visit_assign(
             &mut self,
             lvalue: &Place<'tcx>,
             rvalue: &Rvalue<'tcx>,
             location: Location,
         ) {
-            if lvalue.as_local().is_some() {
+            if let Some(local) = lvalue.as_local() {
                 match rvalue {
                     // Aggregate assignments are expanded in run_pass.
-                    Rvalue::Aggregate(..) | Rvalue::Use(..) => {
+                    Rvalue::Aggregate(..) => {
+                        self.visit_rvalue(rvalue, location);
+                        return;
+                    }
+                    // We do not know which variant an enum is assigned, so we cannot tell which
+                    // fields can be read.
+                    Rvalue::Use(..) if !self.local_decls[local].ty.is_enum() => {
                         self.visit_rvalue(rvalue, location);
                         return;
                     }
@@ -152,15 +189,31 @@ struct ReplacementMap<'tcx> {
     /// Pre-computed list of all "new" locals for each "old" local. This is used to expand storage
     /// and deinit statement and debuginfo.
     fragments: IndexVec<Local, Option<IndexVec<FieldIdx, Option<(Ty<'tcx>, Local)>>>>,
+    /// Same as `fragments`, for enum locals.
+    enum_fragments: IndexVec<Local, Option<EnumFragments<'tcx>>>,
+}
+
+#[derive(Clone, Debug)]
+struct EnumFragments<'tcx> {
+    /// The local that holds the discriminant of the enum.
+    discriminant: Local,
+    /// The new locals for the fields of each variant.
+    variants: IndexVec<VariantIdx, IndexVec<FieldIdx, Option<(Ty<'tcx>, Local)>>>,
 }
 
 impl<'tcx> ReplacementMap<'tcx> {
     fn replace_place(&self, tcx: TyCtxt<'tcx>, place: PlaceRef<'tcx>) -> Option<Place<'tcx>> {
-        let &[PlaceElem::Field(f, _), ref rest @ ..] = place.projection else {
-            return None;
+        let (new_local, rest) = match place.projection {
+            &[PlaceElem::Field(f, _), ref rest @ ..] => {
+                let fields = self.fragments[place.local].as_ref()?;
+                (fields[f]?.1, rest)
+            }
+            &[PlaceElem::Downcast(_, v), PlaceElem::Field(f, _), ref rest @ ..] => {
+                let variants = &self.enum_fragments[place.local].as_ref()?.variants;
+                (variants[v].get(f).copied().flatten()?.1, rest)
+            }
+            _ => return None,
         };
-        let fields = self.fragments[place.local].as_ref()?;
-        let (_, new_local) = fields[f]?;
         Some(Place { local: new_local, projection: tcx.mk_place_elems(rest) })
     }
 
@@ -175,6 +228,16 @@ fn place_fragments(
             Some((field, ty, local))
         }))
     }
+
+    /// Returns all the locals that `local` is split into, including the discriminant of enums.
+    fn fragment_locals(&self, local: Local) -> Option<Vec<Local>> {
+        if let Some(fields) = &self.fragments[local] {
+            return Some(fields.iter().flatten().map(|&(_, new_local)| new_local).collect());
+        }
+        let EnumFragments { discriminant, variants } = self.enum_fragments[local].as_ref()?;
+        let fields = variants.iter().flatten().flatten().map(|&(_, new_local)| new_local);
+        Some(std::iter::once(*discriminant).chain(fields).collect())
+    }
 }
 
 /// Compute the replacement of flattened places into locals.
@@ -188,6 +251,7 @@ fn compute_flattening<'tcx>(
     escaping: DenseBitSet<Local>,
 ) -> ReplacementMap<'tcx> {
     let mut fragments = IndexVec::from_elem(None, &body.local_decls);
+    let mut enum_fragments = IndexVec::from_elem(None, &body.local_decls);
 
     for local in body.local_decls.indices() {
         if escaping.contains(local) {
@@ -195,6 +259,27 @@ fn compute_flattening<'tcx>(
         }
         let decl = body.local_decls[local].clone();
         let ty = decl.ty;
+        if let ty::Adt(def, _) = ty.kind()
+            && def.is_enum()
+        {
+            let discriminant = body.local_decls.push(LocalDecl {
+                ty: ty.discriminant_ty(tcx),
+                user_ty: None,
+                ..decl.clone()
+            });
+            let mut variants = IndexVec::from_elem_n(IndexVec::new(), def.variants().len());
+            iter_fields(ty, tcx, typing_env, |variant, field, field_ty| {
+                let Some(variant) = variant else { bug!("enum field without variant") };
+                let new_local = body.local_decls.push(LocalDecl {
+                    ty: field_ty,
+                    user_ty: None,
+                    ..decl.clone()
+                });
+                variants[variant].insert(field, (field_ty, new_local));
+            });
+            enum_fragments[local] = Some(EnumFragments { discriminant, variants });
+            continue;
+        }
         iter_fields(ty, tcx, typing_env, |variant, field, field_ty| {
             if variant.is_some() {
                 // Downcasts are currently not supported.
@@ -205,18 +290,19 @@ fn compute_flattening<'tcx>(
             fragments.get_or_insert_with(local, IndexVec::new).insert(field, (field_ty, new_local));
         });
     }
-    ReplacementMap { fragments }
+    ReplacementMap { fragments, enum_fragments }
 }
 
 /// Perform the replacement computed by `compute_flattening`.
 fn replace_flattened_locals<'tcx>(
     tcx: TyCtxt<'tcx>,
+    typing_env: ty::TypingEnv<'tcx>,
     body: &mut Body<'tcx>,
     replacements: ReplacementMap<'tcx>,
 ) -> DenseBitSet<Local> {
     let mut all_dead_locals = DenseBitSet::new_empty(replacements.fragments.len());
-    for (local, replacements) in replacements.fragments.iter_enumerated() {
-        if replacements.is_some() {
+    for local in replacements.fragments.indices() {
+        if replacements.fragments[local].is_some() || replacements.enum_fragments[local].is_some() {
             all_dead_locals.insert(local);
         }
     }
@@ -227,6 +313,7 @@ fn replace_flattened_locals<'tcx>(
 
     let mut visitor = ReplacementVisitor {
         tcx,
+        typing_env,
         local_decls: &body.local_decls,
         replacements: &replacements,
         all_dead_locals,
@@ -249,6 +336,8 @@ fn replace_flattened_locals<'tcx>(
 
 struct ReplacementVisitor<'tcx, 'll> {
     tcx: TyCtxt<'tcx>,
+    /// This is only used to build the discriminant constants of enums.
+    typing_env: ty::TypingEnv<'tcx>,
     /// This is only used to compute the type for `VarDebugInfoFragment`.
     local_decls: &'ll LocalDecls<'tcx>,
     /// Work to do.
@@ -312,8 +401,8 @@ fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Locatio
         match statement.kind {
             // Duplicate storage and deinit statements, as they pretty much apply to all fields.
             StatementKind::StorageLive(l) => {
-                if let Some(final_locals) = self.replacements.place_fragments(l.into()) {
-                    for (_, _, fl) in final_locals {
+                if let Some(final_locals) = self.replacements.fragment_locals(l) {
+                    for fl in final_locals {
                         self.patch.add_statement(location, StatementKind::StorageLive(fl));
                     }
                     statement.make_nop(true);
@@ -321,8 +410,8 @@ fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Locatio
                 return;
             }
             StatementKind::StorageDead(l) => {
-                if let Some(final_locals) = self.replacements.place_fragments(l.into()) {
-                    for (_, _, fl) in final_locals {
+                if let Some(final_locals) = self.replacements.fragment_locals(l) {
+                    for fl in final_locals {
                         self.patch.add_statement(location, StatementKind::StorageDead(fl));
                     }
                     statement.make_nop(true);
@@ -337,7 +426,52 @@ fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Locatio
             // a_1 = y
             // ...
             // ```
-            StatementKind::Assign(box (place, Rvalue::Aggregate(_, ref mut operands))) => {
+            StatementKind::Assign(box (place, Rvalue::Aggregate(ref kind, ref mut operands))) => {
+                // For enums, we have `a = Enum::Variant { 0: x, 1: y, .. }`.
+                // We replace it by
+                // ```
+                // a_discr = const discriminant_of_variant
+                // a_variant_0 = x
+                // a_variant_1 = y
+                // ...
+                // ```
+                if let Some(local) = place.as_local()
+                    && let Some(EnumFragments { discriminant, variants }) =
+                        &self.replacements.enum_fragments[local]
+                {
+                    let &AggregateKind::Adt(_, variant, ..) = &**kind else {
+                        bug!("enum assigned from {kind:?}")
+                    };
+                    let ty = self.local_decls[local].ty;
+                    let discr = ty.discriminant_for_variant(self.tcx, variant).unwrap();
+                    let discr = Const::from_bits(self.tcx, discr.val, self.typing_env, discr.ty);
+                    let discr = ConstOperand {
+                        span: statement.source_info.span,
+                        user_ty: None,
+                        const_: discr,
+                    };
+                    self.patch.add_statement(
+                        location,
+                        StatementKind::Assign(Box::new((
+                            (*discriminant).into(),
+                            Rvalue::Use(Operand::Constant(Box::new(discr))),
+                        ))),
+                    );
+                    let operands = std::mem::take(operands);
+                    for (&opt_ty_local, mut operand) in variants[variant].iter().zip(operands) {
+                        if let Some((_, new_local)) = opt_ty_local {
+                            self.visit_operand(&mut operand, location);
+                            let rvalue = Rvalue::Use(operand);
+                            self.patch.add_statement(
+                                location,
+                                StatementKind::Assign(Box::new((new_local.into(), rvalue))),
+                            );
+                        }
+                    }
+                    statement.make_nop(true);
+                    return;
+                }
+
                 if let Some(local) = place.as_local()
                     && let Some(final_locals) = &self.replacements.fragments[local]
                 {
@@ -430,6 +564,18 @@ fn visit_statement(&mut self, statement: &mut Statement<'tcx>, location: Locatio
         self.super_statement(statement, location)
     }
 
+    fn visit_rvalue(&mut self, rvalue: &mut Rvalue<'tcx>, location: Location) {
+        if let Rvalue::Discriminant(place) = rvalue
+            && let Some(local) = place.as_local()
+            && let Some(EnumFragments { discriminant, .. }) =
+                &self.replacements.enum_fragments[local]
+        {
+            *rvalue = Rvalue::Use(Operand::Copy((*discriminant).into()));
+            return;
+        }
+        self.super_rvalue(rvalue, location)
+    }
+
     fn visit_local(&mut self, local: &mut Local, _: PlaceContext, _: Location) {
         assert!(!self.all_dead_locals.contains(*local));
     }
@@ -1,5 +1,7 @@
 //! Validates the MIR to ensure that invariants are upheld.
 
+use std::cell::RefCell;
+
 use rustc_abi::{ExternAbi, FIRST_VARIANT, Size};
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_hir::LangItem;
@@ -42,63 +44,81 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
         if matches!(body.source.instance, InstanceKind::Intrinsic(..) | InstanceKind::Virtual(..)) {
             return;
         }
-        let def_id = body.source.def_id();
-        let typing_env = body.typing_env(tcx);
-        let can_unwind = if body.phase <= MirPhase::Runtime(RuntimePhase::Initial) {
-            // In this case `AbortUnwindingCalls` haven't yet been executed.
-            true
-        } else if !tcx.def_kind(def_id).is_fn_like() {
-            true
-        } else {
-            let body_ty = tcx.type_of(def_id).skip_binder();
-            let body_abi = match body_ty.kind() {
-                ty::FnDef(..) => body_ty.fn_sig(tcx).abi(),
-                ty::Closure(..) => ExternAbi::RustCall,
-                ty::CoroutineClosure(..) => ExternAbi::RustCall,
-                ty::Coroutine(..) => ExternAbi::Rust,
-                // No need to do MIR validation on error bodies
-                ty::Error(_) => return,
-                _ => span_bug!(body.span, "unexpected body ty: {body_ty}"),
-            };
+        check_body(tcx, &self.when, body, None);
+    }
 
-            ty::layout::fn_can_unwind(tcx, Some(def_id), body_abi)
-        };
+    fn is_required(&self) -> bool {
+        true
+    }
+}
 
-        let mut cfg_checker = CfgChecker {
-            when: &self.when,
-            body,
-            tcx,
-            unwind_edge_count: 0,
-            reachable_blocks: traversal::reachable_as_bitset(body),
-            value_cache: FxHashSet::default(),
-            can_unwind,
-        };
-        cfg_checker.visit_body(body);
-        cfg_checker.check_cleanup_control_flow();
+/// Validates a body that did not go through the MIR pipeline, and returns the failures instead of
+/// reporting them as bugs.
+pub(super) fn validate_body<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>) -> Vec<(Location, String)> {
+    let failures = RefCell::new(Vec::new());
+    check_body(tcx, "external body", body, Some(&failures));
+    failures.into_inner()
+}
 
-        // Also run the TypeChecker.
-        for (location, msg) in validate_types(tcx, typing_env, body, body) {
-            cfg_checker.fail(location, msg);
-        }
+/// Runs all checks on `body`, and pushes the failures to `failures`, or reports them as bugs if
+/// it is `None`.
+fn check_body<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    when: &str,
+    body: &Body<'tcx>,
+    failures: Option<&RefCell<Vec<(Location, String)>>>,
+) {
+    let def_id = body.source.def_id();
+    let typing_env = body.typing_env(tcx);
+    let can_unwind = if body.phase <= MirPhase::Runtime(RuntimePhase::Initial) {
+        // In this case `AbortUnwindingCalls` haven't yet been executed.
+        true
+    } else if !tcx.def_kind(def_id).is_fn_like() {
+        true
+    } else {
+        let body_ty = tcx.type_of(def_id).skip_binder();
+        let body_abi = match body_ty.kind() {
+            ty::FnDef(..) => body_ty.fn_sig(tcx).abi(),
+            ty::Closure(..) => ExternAbi::RustCall,
+            ty::CoroutineClosure(..) => ExternAbi::RustCall,
+            ty::Coroutine(..) => ExternAbi::Rust,
+            // No need to do MIR validation on error bodies
+            ty::Error(_) => return,
+            _ => span_bug!(body.span, "unexpected body ty: {body_ty}"),
+        };
 
-        // Ensure that debuginfo records are not emitted for locals that are not in debuginfo.
-        for (location, msg) in validate_debuginfos(body) {
-            cfg_checker.fail(location, msg);
-        }
+        ty::layout::fn_can_unwind(tcx, Some(def_id), body_abi)
+    };
+
+    let mut cfg_checker = CfgChecker {
+        when,
+        body,
+        tcx,
+        unwind_edge_count: 0,
+        reachable_blocks: traversal::reachable_as_bitset(body),
+        value_cache: FxHashSet::default(),
+        can_unwind,
+        failures,
+    };
+    cfg_checker.visit_body(body);
+    cfg_checker.check_cleanup_control_flow();
+
+    // Also run the TypeChecker.
+    for (location, msg) in validate_types(tcx, typing_env, body, body) {
+        cfg_checker.fail(location, msg);
+    }
 
-        if let MirPhase::Runtime(_) = body.phase
-            && let ty::InstanceKind::Item(_) = body.source.instance
-            && body.has_free_regions()
-        {
-            cfg_checker.fail(
-                Location::START,
-                format!("Free regions in optimized {} MIR", body.phase.name()),
-            );
-        }
+    // Ensure that debuginfo records are not emitted for locals that are not in debuginfo.
+    for (location, msg) in validate_debuginfos(body) {
+        cfg_checker.fail(location, msg);
     }
 
-    fn is_required(&self) -> bool {
-        true
+    if let MirPhase::Runtime(_) = body.phase
+        && let ty::InstanceKind::Item(_) = body.source.instance
+        && body.has_free_regions()
+    {
+        cfg_checker
+            .fail(Location::START, format!("Free regions in optimized {} MIR", body.phase.name()));
     }
 }
 
@@ -118,11 +138,17 @@ struct CfgChecker<'a, 'tcx> {
     // If `false`, then the MIR must not contain `UnwindAction::Continue` or
     // `TerminatorKind::Resume`.
     can_unwind: bool,
+    /// Where to collect the failures, if they should not be reported as bugs.
+    failures: Option<&'a RefCell<Vec<(Location, String)>>>,
 }
 
 impl<'a, 'tcx> CfgChecker<'a, 'tcx> {
     #[track_caller]
     fn fail(&self, location: Location, msg: impl AsRef<str>) {
+        if let Some(failures) = self.failures {
+            failures.borrow_mut().push((location, msg.as_ref().to_owned()));
+            return;
+        }
         // We might see broken MIR when other errors have already occurred.
         if self.tcx.dcx().has_errors().is_none() {
             span_bug!(
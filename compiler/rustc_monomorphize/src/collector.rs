@@ -1309,7 +1309,7 @@ fn collect_items_of_instance<'tcx>(
     mode: CollectionMode,
 ) -> Result<(MonoItems<'tcx>, MonoItems<'tcx>), NormalizationErrorInMono> {
     // This item is getting monomorphized, do mono-time checks.
-    let body = tcx.instance_mir(instance.def);
+    let body = tcx.codegen_instance_mir(instance);
     // Plenty of code paths later assume that everything can be normalized. So we have to check
     // normalization first.
     // We choose to emit the error outside to provide helpful diagnostics.
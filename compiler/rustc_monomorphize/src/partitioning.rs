@@ -1320,7 +1320,7 @@ pub(crate) fn provide(providers: &mut Providers) {
             InstanceKind::Item(..)
             | InstanceKind::DropGlue(..)
             | InstanceKind::AsyncDropGlueCtorShim(..) => {
-                let mir = tcx.instance_mir(instance.def);
+                let mir = tcx.codegen_instance_mir(instance);
                 mir.basic_blocks.iter().map(|bb| bb.statements.len() + 1).sum()
             }
             // Other compiler-generated shims size estimate: 1
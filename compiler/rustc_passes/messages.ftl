@@ -100,6 +100,9 @@ passes_diagnostic_diagnostic_on_const_only_for_trait_impls =
     `#[diagnostic::on_const]` can only be applied to trait impls
     .label = not a trait impl
 
+passes_diagnostic_diagnostic_on_type_only_for_adts =
+    `#[diagnostic::{$attr_name}]` can only be applied to structs, enums and unions
+
 passes_diagnostic_diagnostic_on_unimplemented_only_for_traits =
     `#[diagnostic::on_unimplemented]` can only be applied to trait definitions
 
@@ -587,6 +590,9 @@ passes_unused_multiple =
 passes_unused_no_lints_note =
     attribute `{$name}` without any lints has no effect
 
+passes_unused_workspace_item =
+    {$descr} `{$path}` is public but not used by any downstream crate
+
 passes_useless_assignment =
     useless assignment of {$is_field_assign ->
         [true] field
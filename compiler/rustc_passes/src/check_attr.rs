@@ -60,6 +60,12 @@
 #[diag(passes_diagnostic_diagnostic_on_unimplemented_only_for_traits)]
 struct DiagnosticOnUnimplementedOnlyForTraits;
 
+#[derive(LintDiagnostic)]
+#[diag(passes_diagnostic_diagnostic_on_type_only_for_adts)]
+struct DiagnosticOnTypeOnlyForAdts {
+    attr_name: Symbol,
+}
+
 #[derive(LintDiagnostic)]
 #[diag(passes_diagnostic_diagnostic_on_const_only_for_trait_impls)]
 struct DiagnosticOnConstOnlyForTraitImpls {
@@ -316,6 +322,13 @@ fn check_attributes(
                         [sym::diagnostic, sym::on_const, ..] => {
                             self.check_diagnostic_on_const(attr.span(), hir_id, target, item)
                         }
+                        [
+                            sym::diagnostic,
+                            attr_name @ (sym::on_move
+                            | sym::on_unknown_method
+                            | sym::on_type_mismatch),
+                            ..,
+                        ] => self.check_diagnostic_on_type(attr.span(), hir_id, target, *attr_name),
                         [sym::rustc_clean, ..]
                         | [sym::rustc_dirty, ..]
                         | [sym::rustc_if_this_changed, ..]
@@ -575,6 +588,25 @@ fn check_diagnostic_on_unimplemented(&self, attr_span: Span, hir_id: HirId, targ
         }
     }
 
+    /// Checks if `#[diagnostic::on_move]`, `#[diagnostic::on_unknown_method]` or
+    /// `#[diagnostic::on_type_mismatch]` is applied to a struct, enum or union
+    fn check_diagnostic_on_type(
+        &self,
+        attr_span: Span,
+        hir_id: HirId,
+        target: Target,
+        attr_name: Symbol,
+    ) {
+        if !matches!(target, Target::Struct | Target::Enum | Target::Union) {
+            self.tcx.emit_node_span_lint(
+                MISPLACED_DIAGNOSTIC_ATTRIBUTES,
+                hir_id,
+                attr_span,
+                DiagnosticOnTypeOnlyForAdts { attr_name },
+            );
+        }
+    }
+
     /// Checks if `#[diagnostic::on_const]` is applied to a trait impl
     fn check_diagnostic_on_const(
         &self,
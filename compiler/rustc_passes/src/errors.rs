@@ -786,6 +786,13 @@ pub(crate) struct UselessAssignment<'a> {
     pub ty: Ty<'a>,
 }
 
+#[derive(LintDiagnostic)]
+#[diag(passes_unused_workspace_item)]
+pub(crate) struct UnusedWorkspaceItem<'a> {
+    pub descr: &'a str,
+    pub path: String,
+}
+
 #[derive(LintDiagnostic)]
 #[diag(passes_inline_ignored_for_exported)]
 #[help]
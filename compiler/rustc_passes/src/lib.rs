@@ -27,6 +27,7 @@
 mod lib_features;
 mod reachable;
 pub mod stability;
+mod upstream_uses;
 mod upvars;
 mod weak_lang_items;
 
@@ -42,6 +43,7 @@ pub fn provide(providers: &mut Providers) {
     lib_features::provide(providers);
     reachable::provide(providers);
     stability::provide(providers);
+    upstream_uses::provide(providers);
     upvars::provide(providers);
     check_export::provide(providers);
     providers.check_externally_implementable_items = eii::check_externally_implementable_items;
@@ -0,0 +1,195 @@
+//! Detection of public items of workspace crates that no crate uses.
+//!
+//! The dead-code pass has to treat every public item as used, as it cannot know about the crates
+//! that depend on the one being compiled. Crates compiled with `-Zrecord-upstream-uses` record
+//! the items of their dependencies they use in their metadata, so that a crate depending on all of
+//! them (usually the top-level binary) can be compiled with `-Zreport-unused-workspace-items` to
+//! find the public items of those crates that no crate uses.
+
+use rustc_data_structures::fx::{FxHashSet, FxIndexSet};
+use rustc_hir::def::{DefKind, Res};
+use rustc_hir::def_id::{CrateNum, DefId, LOCAL_CRATE};
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_hir::{self as hir, CRATE_HIR_ID, HirId};
+use rustc_middle::hir::nested_filter;
+use rustc_middle::query::{LocalCrate, Providers};
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_session::lint::builtin::UNUSED_WORKSPACE_ITEMS;
+
+use crate::errors::UnusedWorkspaceItem;
+
+/// Collects the items of upstream crates referred to in the HIR of the local crate.
+struct UpstreamUseCollector<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    maybe_typeck_results: Option<&'tcx ty::TypeckResults<'tcx>>,
+    uses: FxIndexSet<DefId>,
+}
+
+impl<'tcx> UpstreamUseCollector<'tcx> {
+    fn record(&mut self, def_id: DefId) {
+        if def_id.is_local() || !self.uses.insert(def_id) {
+            return;
+        }
+        let tcx = self.tcx;
+        match tcx.def_kind(def_id) {
+            // Using a constructor or a variant uses the type it belongs to.
+            DefKind::Ctor(..) | DefKind::Variant => self.record(tcx.parent(def_id)),
+            // Using an associated item uses its trait, or the type of its inherent impl, which
+            // may never be named when values of it only come from calls.
+            DefKind::AssocFn | DefKind::AssocConst | DefKind::AssocTy => {
+                let parent = tcx.parent(def_id);
+                match tcx.def_kind(parent) {
+                    DefKind::Trait => self.record(parent),
+                    DefKind::Impl { of_trait: false } => {
+                        if let ty::Adt(adt, _) = tcx.type_of(parent).instantiate_identity().kind() {
+                            self.record(adt.did());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Records the method or associated item that a type-relative path or a method call at
+    /// `hir_id` resolved to.
+    fn record_type_dependent_def(&mut self, hir_id: HirId) {
+        if let Some(typeck_results) = self.maybe_typeck_results
+            && let Some(def_id) = typeck_results.type_dependent_def_id(hir_id)
+        {
+            self.record(def_id);
+        }
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for UpstreamUseCollector<'tcx> {
+    type NestedFilter = nested_filter::OnlyBodies;
+
+    fn maybe_tcx(&mut self) -> Self::MaybeTyCtxt {
+        self.tcx
+    }
+
+    fn visit_nested_body(&mut self, body: hir::BodyId) {
+        let old_maybe_typeck_results =
+            self.maybe_typeck_results.replace(self.tcx.typeck_body(body));
+        self.visit_body(self.tcx.hir_body(body));
+        self.maybe_typeck_results = old_maybe_typeck_results;
+    }
+
+    fn visit_path(&mut self, path: &hir::Path<'tcx>, _: HirId) {
+        for segment in path.segments {
+            if let Res::Def(_, def_id) = segment.res {
+                self.record(def_id);
+            }
+        }
+        if let Res::Def(_, def_id) = path.res {
+            self.record(def_id);
+        }
+        intravisit::walk_path(self, path);
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr<'tcx>) {
+        self.record_type_dependent_def(expr.hir_id);
+        // Values of upstream types are often used without ever naming the type.
+        if let Some(typeck_results) = self.maybe_typeck_results
+            && let Some(ty) = typeck_results.node_type_opt(expr.hir_id)
+            && let ty::Adt(adt, _) = ty.peel_refs().kind()
+        {
+            self.record(adt.did());
+        }
+        intravisit::walk_expr(self, expr);
+    }
+
+    fn visit_pat(&mut self, pat: &'tcx hir::Pat<'tcx>) {
+        self.record_type_dependent_def(pat.hir_id);
+        intravisit::walk_pat(self, pat);
+    }
+}
+
+fn upstream_uses_provider_local(tcx: TyCtxt<'_>, _: LocalCrate) -> Option<&[DefId]> {
+    let opts = &tcx.sess.opts.unstable_opts;
+    if !opts.record_upstream_uses && !opts.report_unused_workspace_items {
+        return None;
+    }
+
+    let mut collector =
+        UpstreamUseCollector { tcx, maybe_typeck_results: None, uses: FxIndexSet::default() };
+    tcx.hir_visit_all_item_likes_in_crate(&mut collector);
+    Some(tcx.arena.alloc_from_iter(collector.uses))
+}
+
+/// Returns the items of `krate` that can be named through public paths, along with the public
+/// associated items of the inherent impls of its public types.
+fn public_items(tcx: TyCtxt<'_>, krate: CrateNum) -> Vec<DefId> {
+    let mut items = Vec::new();
+    let mut seen = FxHashSet::default();
+    let mut modules = vec![krate.as_def_id()];
+    while let Some(module) = modules.pop() {
+        for child in tcx.module_children(module) {
+            let Res::Def(def_kind, def_id) = child.res else { continue };
+            // Re-exports of items of other crates are checked with their own crate.
+            if !child.vis.is_public() || def_id.krate != krate || !seen.insert(def_id) {
+                continue;
+            }
+            match def_kind {
+                DefKind::Mod => modules.push(def_id),
+                DefKind::Struct | DefKind::Enum | DefKind::Union => {
+                    items.push(def_id);
+                    for &impl_def_id in tcx.inherent_impls(def_id) {
+                        items.extend(
+                            tcx.associated_item_def_ids(impl_def_id)
+                                .iter()
+                                .filter(|&&item| tcx.visibility(item).is_public()),
+                        );
+                    }
+                }
+                DefKind::Trait
+                | DefKind::TyAlias
+                | DefKind::Fn
+                | DefKind::Const
+                | DefKind::Static { .. } => items.push(def_id),
+                _ => {}
+            }
+        }
+    }
+    items.sort_by_key(|def_id| def_id.index);
+    items
+}
+
+fn check_unused_workspace_items(tcx: TyCtxt<'_>, (): ()) {
+    let mut used = FxHashSet::default();
+    let mut workspace_crates = Vec::new();
+    for &cnum in std::iter::once(&LOCAL_CRATE).chain(tcx.crates(())) {
+        let Some(uses) = tcx.upstream_uses(cnum) else { continue };
+        used.extend(uses.iter().copied());
+        if cnum != LOCAL_CRATE {
+            workspace_crates.push(cnum);
+        }
+    }
+
+    for cnum in workspace_crates {
+        for def_id in public_items(tcx, cnum) {
+            if used.contains(&def_id) || tcx.item_name(def_id).as_str().starts_with('_') {
+                continue;
+            }
+            tcx.emit_node_span_lint(
+                UNUSED_WORKSPACE_ITEMS,
+                CRATE_HIR_ID,
+                tcx.def_span(def_id),
+                UnusedWorkspaceItem {
+                    descr: tcx.def_descr(def_id),
+                    path: tcx.def_path_str(def_id),
+                },
+            );
+        }
+    }
+}
+
+pub(crate) fn provide(providers: &mut Providers) {
+    *providers = Providers {
+        upstream_uses: upstream_uses_provider_local,
+        check_unused_workspace_items,
+        ..*providers
+    };
+}
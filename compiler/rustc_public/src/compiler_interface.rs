@@ -13,15 +13,18 @@
 use crate::abi::{FnAbi, Layout, LayoutShape, ReprOptions};
 use crate::crate_def::Attribute;
 use crate::mir::alloc::{AllocId, GlobalAlloc};
+use crate::mir::borrowck::BorrowckFacts;
 use crate::mir::mono::{Instance, InstanceDef, StaticDef};
 use crate::mir::{BinOp, Body, Place, UnOp};
 use crate::target::{MachineInfo, MachineSize};
+use crate::traits::{EvaluationResult, ImplSource, ParamEnv};
 use crate::ty::{
     AdtDef, AdtKind, Allocation, ClosureDef, ClosureKind, CoroutineDef, Discr, FieldDef, FnDef,
     ForeignDef, ForeignItemKind, ForeignModule, ForeignModuleDef, GenericArgs, GenericPredicates,
     Generics, ImplDef, ImplTrait, IntrinsicDef, LineInfo, MirConst, PolyFnSig, RigidTy, Span,
-    TraitDecl, TraitDef, Ty, TyConst, TyConstId, TyKind, UintTy, VariantDef, VariantIdx,
+    TraitDecl, TraitDef, TraitRef, Ty, TyConst, TyConstId, TyKind, UintTy, VariantDef, VariantIdx,
 };
+use crate::unstable::convert::check_body_translatable;
 use crate::unstable::{RustcInternal, Stable, new_item_kind};
 use crate::{
     AssocItems, Crate, CrateDef, CrateItem, CrateItems, CrateNum, DefId, Error, Filename,
@@ -101,6 +104,23 @@ pub(crate) fn mir_body(&self, item: DefId) -> mir::Body {
         cx.mir_body(did).stable(&mut *tables, cx)
     }
 
+    /// Retrieve the borrow checker facts of a function.
+    pub(crate) fn borrowck_facts(&self, item: CrateItem) -> Result<BorrowckFacts, Error> {
+        let mut tables = self.tables.borrow_mut();
+        let cx = &*self.cx.borrow();
+        let did = tables[item.0];
+        cx.borrowck_facts(did).map(|facts| facts.stable(&mut *tables, cx))
+    }
+
+    /// Retrieve the HIR body of an item and its type checking results.
+    pub(crate) fn hir_body(&self, item: CrateItem) -> Result<crate::hir::Body, Error> {
+        let mut tables = self.tables.borrow_mut();
+        let cx = &*self.cx.borrow();
+        let did = tables[item.0];
+        let (body, typeck_results) = cx.hir_body(did)?;
+        Ok((body, typeck_results).stable(&mut *tables, cx))
+    }
+
     /// Check whether the body of a function is available.
     pub(crate) fn has_body(&self, item: DefId) -> bool {
         let mut tables = self.tables.borrow_mut();
@@ -126,6 +146,14 @@ pub(crate) fn crate_functions(&self, crate_num: CrateNum) -> Vec<FnDef> {
         cx.crate_functions(krate).iter().map(|did| tables.fn_def(*did)).collect()
     }
 
+    /// Retrieve all items defined in this crate that have a MIR available.
+    pub(crate) fn crate_items(&self, crate_num: CrateNum) -> CrateItems {
+        let mut tables = self.tables.borrow_mut();
+        let cx = &*self.cx.borrow();
+        let krate = crate_num.internal(&mut *tables, cx.tcx);
+        cx.crate_items(krate).iter().map(|did| tables.crate_item(*did)).collect()
+    }
+
     /// Retrieve all static items defined in this crate.
     pub(crate) fn crate_statics(&self, crate_num: CrateNum) -> Vec<StaticDef> {
         let mut tables = self.tables.borrow_mut();
@@ -606,6 +634,22 @@ pub(crate) fn instance_body(&self, instance: InstanceDef) -> Option<Body> {
         cx.instance_body(instance).map(|body| body.stable(&mut *tables, cx))
     }
 
+    /// Replace the body that is code generated for an Instance.
+    pub(crate) fn replace_instance_body(
+        &self,
+        instance: InstanceDef,
+        body: &Body,
+    ) -> Result<(), Error> {
+        check_body_translatable(body)?;
+        let mut tables = self.tables.borrow_mut();
+        let cx = &*self.cx.borrow();
+        let instance = tables.instances[instance];
+        let blocks = body.blocks.internal(&mut *tables, cx.tcx);
+        let locals = body.locals().iter().map(|decl| decl.internal(&mut *tables, cx.tcx)).collect();
+        let var_debug_info = body.var_debug_info.internal(&mut *tables, cx.tcx);
+        cx.replace_instance_body(instance, blocks, locals, var_debug_info, body.spread_arg())
+    }
+
     /// Get the instance type with generic instantiations applied and lifetimes erased.
     pub(crate) fn instance_ty(&self, instance: InstanceDef) -> Ty {
         let mut tables = self.tables.borrow_mut();
@@ -672,6 +716,42 @@ pub(crate) fn resolve_instance(&self, def: FnDef, args: &GenericArgs) -> Option<
         cx.resolve_instance(def_id, args_ref).map(|inst| inst.stable(&mut *tables, cx))
     }
 
+    /// Evaluate whether a trait reference holds in the given environment.
+    pub(crate) fn evaluate_trait_ref(
+        &self,
+        trait_ref: &TraitRef,
+        param_env: ParamEnv,
+    ) -> Result<EvaluationResult, Error> {
+        let mut tables = self.tables.borrow_mut();
+        let cx = &*self.cx.borrow();
+        let trait_ref = trait_ref.internal(&mut *tables, cx.tcx);
+        let item = param_env.internal(&mut *tables, cx.tcx);
+        cx.evaluate_trait_ref(trait_ref, item).map(|result| result.stable(&mut *tables, cx))
+    }
+
+    /// Select the implementation that proves a trait reference in the given environment.
+    pub(crate) fn select_trait_impl(
+        &self,
+        trait_ref: &TraitRef,
+        param_env: ParamEnv,
+    ) -> Result<Option<ImplSource>, Error> {
+        let mut tables = self.tables.borrow_mut();
+        let cx = &*self.cx.borrow();
+        let trait_ref = trait_ref.internal(&mut *tables, cx.tcx);
+        let item = param_env.internal(&mut *tables, cx.tcx);
+        cx.select_trait_impl(trait_ref, item)
+            .map(|impl_source| impl_source.map(|impl_source| impl_source.stable(&mut *tables, cx)))
+    }
+
+    /// Normalize a type in the given environment.
+    pub(crate) fn normalize_ty(&self, ty: Ty, param_env: ParamEnv) -> Result<Ty, Error> {
+        let mut tables = self.tables.borrow_mut();
+        let cx = &*self.cx.borrow();
+        let internal_ty = ty.internal(&mut *tables, cx.tcx);
+        let item = param_env.internal(&mut *tables, cx.tcx);
+        cx.normalize_ty(internal_ty, item).map(|ty| ty.stable(&mut *tables, cx))
+    }
+
     /// Resolve an instance for drop_in_place for the given type.
     pub(crate) fn resolve_drop_in_place(&self, ty: Ty) -> Instance {
         let mut tables = self.tables.borrow_mut();
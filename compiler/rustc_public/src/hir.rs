@@ -0,0 +1,332 @@
+//! rustc_public's view of source-level function bodies.
+//!
+//! This module exposes the expressions, patterns and statements of a body, as written by the
+//! user after macro expansion and desugaring, together with the results of type checking them.
+//!
+//! The nodes of a [Body] are stored in flat vectors, and they refer to each other by their
+//! index. Use the spans of the nodes to relate them to the MIR of the same item.
+
+use serde::Serialize;
+
+use crate::compiler_interface::with;
+use crate::mir::{Mutability, PointerCoercion};
+use crate::ty::{ClosureDef, FnDef, GenericArgs, Span, Ty};
+use crate::{CrateItem, DefId, Error, Opaque, Symbol};
+
+/// The index of an expression in [Body::exprs].
+pub type ExprId = usize;
+
+/// The index of a pattern in [Body::pats].
+pub type PatId = usize;
+
+/// The index of a statement in [Body::stmts].
+pub type StmtId = usize;
+
+/// The index of a block in [Body::blocks].
+pub type BlockId = usize;
+
+/// Retrieve the source-level body of the given item and its type checking results.
+///
+/// This will fail if the item is not a local item with a body.
+pub fn body(item: CrateItem) -> Result<Body, Error> {
+    with(|cx| cx.hir_body(item))
+}
+
+/// The source-level body of a function, closure, constant or static.
+#[derive(Clone, Debug, Serialize)]
+pub struct Body {
+    /// The patterns of the function parameters.
+    pub params: Vec<PatId>,
+    /// The expression that computes the value of the body.
+    pub value: ExprId,
+    pub exprs: Vec<Expr>,
+    pub pats: Vec<Pat>,
+    pub stmts: Vec<Stmt>,
+    pub blocks: Vec<Block>,
+    pub typeck_results: TypeckResults,
+}
+
+impl Body {
+    pub fn expr(&self, expr: ExprId) -> &Expr {
+        &self.exprs[expr]
+    }
+
+    pub fn pat(&self, pat: PatId) -> &Pat {
+        &self.pats[pat]
+    }
+
+    pub fn stmt(&self, stmt: StmtId) -> &Stmt {
+        &self.stmts[stmt]
+    }
+
+    pub fn block(&self, block: BlockId) -> &Block {
+        &self.blocks[block]
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum ExprKind {
+    /// An array, e.g., `[a, b, c]`.
+    Array(Vec<ExprId>),
+    /// A function call, e.g., `foo(a, b)`.
+    Call(ExprId, Vec<ExprId>),
+    /// A method call, e.g., `x.foo(a, b)`. The resolved method can be retrieved with
+    /// [TypeckResults::method_resolution].
+    MethodCall {
+        method: Symbol,
+        receiver: ExprId,
+        args: Vec<ExprId>,
+    },
+    Tup(Vec<ExprId>),
+    Binary(BinOp, ExprId, ExprId),
+    Unary(UnOp, ExprId),
+    Lit(LitKind),
+    /// A cast, e.g., `x as u64`. The target type is the type of this expression.
+    Cast(ExprId),
+    /// A scrutinee that is dropped at the end of the enclosing statement.
+    DropTemps(ExprId),
+    /// A `let` in a condition, e.g., `if let Some(x) = y`.
+    Let {
+        pat: PatId,
+        init: ExprId,
+    },
+    If(ExprId, ExprId, Option<ExprId>),
+    Loop(BlockId, LoopSource),
+    Match(ExprId, Vec<Arm>),
+    /// A closure. Its body can be retrieved with [body] for the closure item.
+    Closure(ClosureDef),
+    Block(BlockId),
+    Assign(ExprId, ExprId),
+    /// A compound assignment, e.g., `a += b`.
+    AssignOp(BinOp, ExprId, ExprId),
+    Field(ExprId, Symbol),
+    Index(ExprId, ExprId),
+    Path(PathRes),
+    AddrOf(AddrOfKind, Mutability, ExprId),
+    Break(Option<ExprId>),
+    Continue,
+    Ret(Option<ExprId>),
+    Struct {
+        fields: Vec<(Symbol, ExprId)>,
+        base: Option<ExprId>,
+    },
+    Repeat(ExprId),
+    /// Other expressions, such as inline assembly or `yield`, which are not yet supported.
+    Other(Opaque),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Arm {
+    pub pat: PatId,
+    pub guard: Option<ExprId>,
+    pub body: ExprId,
+    pub span: Span,
+}
+
+/// What a path expression resolves to.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum PathRes {
+    /// A local variable, declared by the given binding pattern.
+    Local(PatId),
+    /// A variable captured by a closure, which is declared in the body of the parent item.
+    Upvar(Symbol),
+    /// An item, such as a function, constant or constructor.
+    Def(DefId),
+    /// Other resolutions, such as `Self` constructors.
+    Other(Opaque),
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum AddrOfKind {
+    /// A reference, e.g., `&x`.
+    Ref,
+    /// A raw pointer, e.g., `&raw const x`.
+    Raw,
+    /// A pinned reference, e.g., `&pin mut x`.
+    Pin,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum LoopSource {
+    Loop,
+    While,
+    ForLoop,
+}
+
+/// A binary operator as written in the source, including the lazy boolean operators.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    And,
+    Or,
+    BitXor,
+    BitAnd,
+    BitOr,
+    Shl,
+    Shr,
+    Eq,
+    Lt,
+    Le,
+    Ne,
+    Ge,
+    Gt,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum UnOp {
+    Deref,
+    Not,
+    Neg,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum LitKind {
+    Str(Symbol),
+    ByteStr(Vec<u8>),
+    Byte(u8),
+    Char(char),
+    Int(u128),
+    /// A float literal, as written in the source.
+    Float(Symbol),
+    Bool(bool),
+    Other(Opaque),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Pat {
+    pub kind: PatKind,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum PatKind {
+    Wild,
+    /// A binding, e.g., `ref mut x` or `x @ Some(_)`.
+    Binding {
+        name: Symbol,
+        by_ref: Option<Mutability>,
+        mutability: Mutability,
+        subpat: Option<PatId>,
+    },
+    Struct(PathRes, Vec<(Symbol, PatId)>),
+    TupleStruct(PathRes, Vec<PatId>),
+    Or(Vec<PatId>),
+    Tuple(Vec<PatId>),
+    Box(PatId),
+    Deref(PatId),
+    Ref(PatId, Mutability),
+    /// A literal or a path to a constant.
+    Expr(Opaque),
+    Range(Option<Opaque>, Option<Opaque>),
+    Slice(Vec<PatId>, Option<PatId>, Vec<PatId>),
+    Never,
+    Other(Opaque),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Stmt {
+    pub kind: StmtKind,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum StmtKind {
+    /// A `let` statement, e.g., `let x = y;` or `let Some(x) = y else { .. };`.
+    Let { pat: PatId, init: Option<ExprId>, els: Option<BlockId> },
+    /// An item declaration.
+    Item,
+    /// An expression without a trailing semicolon.
+    Expr(ExprId),
+    /// An expression with a trailing semicolon.
+    Semi(ExprId),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Block {
+    pub stmts: Vec<StmtId>,
+    /// The trailing expression of the block, if any.
+    pub expr: Option<ExprId>,
+    /// Whether this is an `unsafe` block.
+    pub is_unsafe: bool,
+    pub span: Span,
+}
+
+/// The results of type checking a [Body].
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct TypeckResults {
+    pub(crate) expr_tys: Vec<Option<Ty>>,
+    pub(crate) expr_adjustments: Vec<Vec<Adjustment>>,
+    pub(crate) method_resolutions: Vec<Option<MethodResolution>>,
+    pub(crate) pat_tys: Vec<Option<Ty>>,
+}
+
+impl TypeckResults {
+    /// The type of an expression before adjustments, if it was computed.
+    pub fn expr_ty(&self, expr: ExprId) -> Option<Ty> {
+        self.expr_tys[expr]
+    }
+
+    /// The type of an expression after adjustments, if it was computed.
+    pub fn expr_ty_adjusted(&self, expr: ExprId) -> Option<Ty> {
+        self.expr_adjustments[expr]
+            .last()
+            .map(|adjustment| adjustment.target)
+            .or(self.expr_ty(expr))
+    }
+
+    /// The adjustments applied to an expression, such as auto-borrows and coercions.
+    pub fn expr_adjustments(&self, expr: ExprId) -> &[Adjustment] {
+        &self.expr_adjustments[expr]
+    }
+
+    /// The method invoked by a method call or an overloaded operator expression.
+    pub fn method_resolution(&self, expr: ExprId) -> Option<&MethodResolution> {
+        self.method_resolutions[expr].as_ref()
+    }
+
+    /// The type of a pattern, if it was computed.
+    pub fn pat_ty(&self, pat: PatId) -> Option<Ty> {
+        self.pat_tys[pat]
+    }
+}
+
+/// A method resolved by the type checker.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct MethodResolution {
+    pub def: FnDef,
+    pub args: GenericArgs,
+}
+
+/// An implicit conversion applied to an expression.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Adjustment {
+    pub kind: Adjust,
+    /// The type of the expression after the adjustment.
+    pub target: Ty,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum Adjust {
+    /// Go from `!` to any type.
+    NeverToAny,
+    /// Dereference once. If the dereference is overloaded, this contains the mutability of
+    /// the `Deref` or `DerefMut` call.
+    Deref(Option<Mutability>),
+    /// Take a reference of the given mutability.
+    Borrow(Mutability),
+    /// Take a raw pointer of the given mutability.
+    RawBorrow(Mutability),
+    Pointer(PointerCoercion),
+    /// Reborrow a pinned reference.
+    ReborrowPin(Mutability),
+}
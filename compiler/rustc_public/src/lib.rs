@@ -48,10 +48,12 @@
 pub mod compiler_interface;
 #[macro_use]
 pub mod error;
+pub mod hir;
 pub mod mir;
 pub mod target;
 #[cfg(test)]
 mod tests;
+pub mod traits;
 pub mod ty;
 pub mod visitor;
 
@@ -110,6 +112,16 @@ pub fn fn_defs(&self) -> Vec<FnDef> {
         with(|cx| cx.crate_functions(self.id))
     }
 
+    /// Return the items defined in this crate that have a MIR available.
+    ///
+    /// For external crates, this only includes the items whose MIR was encoded in the crate
+    /// metadata. By default, that is only the case for generic and inline items. Compile
+    /// dependencies with `-Zalways-encode-mir` to include all of their items, e.g., by running
+    /// them through [run_crate_graph!]; passing the flag to the local crate is not enough.
+    pub fn items(&self) -> CrateItems {
+        with(|cx| cx.crate_items(self.id))
+    }
+
     /// Return a list of static items defined in this crate independent on their visibility.
     pub fn statics(&self) -> Vec<StaticDef> {
         with(|cx| cx.crate_statics(self.id))
@@ -209,6 +221,18 @@ pub fn all_local_items() -> CrateItems {
     with(|cx| cx.all_local_items())
 }
 
+/// Retrieve all items in the crate graph that have a MIR available.
+///
+/// This includes the items of the local crate, followed by the items of its dependencies.
+/// See [Crate::items] for which items of a dependency are available.
+pub fn all_items() -> CrateItems {
+    let mut items = all_local_items();
+    for krate in external_crates() {
+        items.extend(krate.items());
+    }
+    items
+}
+
 pub fn all_trait_decls() -> TraitDecls {
     with(|cx| cx.all_trait_decls())
 }
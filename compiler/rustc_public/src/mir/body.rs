@@ -39,6 +39,15 @@ pub struct Body {
 
 pub type BasicBlockIdx = usize;
 
+/// The location of a statement or terminator in a body.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord, Serialize)]
+pub struct BodyLocation {
+    pub block: BasicBlockIdx,
+    /// The index of the statement in the block. Equal to the number of statements of
+    /// the block for the terminator.
+    pub statement_index: usize,
+}
+
 impl Body {
     /// Constructs a `Body`.
     ///
@@ -119,6 +128,36 @@ pub fn dump<W: io::Write>(&self, w: &mut W, fn_name: &str) -> io::Result<()> {
     pub fn spread_arg(&self) -> Option<Local> {
         self.spread_arg
     }
+
+    /// Add a new local to this body and return it.
+    ///
+    /// The new local is an inner local, since the return and argument locals are fixed.
+    pub fn new_local(&mut self, ty: Ty, span: Span, mutability: Mutability) -> Local {
+        self.locals.push(LocalDecl { ty, span, mutability });
+        self.locals.len() - 1
+    }
+
+    /// Add a new basic block to this body and return its index.
+    pub fn new_block(&mut self, block: BasicBlock) -> BasicBlockIdx {
+        self.blocks.push(block);
+        self.blocks.len() - 1
+    }
+
+    /// Split the block at the given location and return the index of the new block.
+    ///
+    /// The statements starting at `location` and the terminator are moved to a new block,
+    /// and the original block jumps to the new one. This can be used to insert a new
+    /// terminator, such as a call, in the middle of a block.
+    pub fn split_block(&mut self, location: BodyLocation) -> BasicBlockIdx {
+        let block = &mut self.blocks[location.block];
+        let span = block.terminator.span;
+        let statements = block.statements.split_off(location.statement_index);
+        let terminator = std::mem::replace(
+            &mut block.terminator,
+            Terminator { kind: TerminatorKind::Goto { target: self.blocks.len() }, span },
+        );
+        self.new_block(BasicBlock { statements, terminator })
+    }
 }
 
 type LocalDecls = Vec<LocalDecl>;
@@ -0,0 +1,170 @@
+//! Borrow checker results for a function body.
+//!
+//! Borrowck facts are only available if the compiler was instructed to record them
+//! before it ran. To do that, invoke [enable_borrowck_facts] before starting the compiler
+//! with the `run!` macro.
+//!
+//! All locations in these facts refer to the body returned in [BorrowckFacts::body],
+//! which is the MIR that was borrow checked. This body is different from the one
+//! returned by [crate::CrateItem::body], which has already been optimized.
+
+use serde::Serialize;
+
+use crate::compiler_interface::with;
+use crate::mir::{Body, BodyLocation, BorrowKind, Local, Place};
+use crate::ty::RegionVid;
+use crate::{CrateItem, Error};
+
+/// Request the compiler to record the borrow checker facts of every body.
+///
+/// This must be invoked before the compiler starts, and it will slow down borrow checking.
+pub fn enable_borrowck_facts() {
+    rustc_public_bridge::borrowck::enable_borrowck_facts()
+}
+
+/// Retrieve the borrow checker facts of the given item.
+///
+/// This will fail if the item is not a local item with a body, or if the facts were not
+/// recorded. See [enable_borrowck_facts] for more details.
+pub fn borrowck_facts(item: CrateItem) -> Result<BorrowckFacts, Error> {
+    with(|cx| cx.borrowck_facts(item))
+}
+
+/// The index of a borrow in [BorrowckFacts::borrows].
+pub type BorrowIndex = usize;
+
+/// The index of a move path in [BorrowckFacts::move_paths].
+pub type MovePathIndex = usize;
+
+/// The index of a strongly connected component of region variables in [BorrowckFacts::sccs].
+pub type RegionSccIndex = usize;
+
+/// The information computed by the borrow checker for a function body.
+#[derive(Clone, Debug, Serialize)]
+pub struct BorrowckFacts {
+    /// The body that was borrow checked.
+    ///
+    /// Unlike optimized bodies, the types in this body contain region variables.
+    pub body: Body,
+    /// All the borrows in the body.
+    pub borrows: Vec<BorrowData>,
+    /// The borrows that go out of scope at each location, i.e., the loans that are killed.
+    pub borrows_out_of_scope: Vec<(BodyLocation, Vec<BorrowIndex>)>,
+    /// The outlives constraints between region variables, which were used to
+    /// compute the region inference results.
+    pub outlives_constraints: Vec<OutlivesConstraint>,
+    /// The region variables that are universal, i.e., that represent the lifetime
+    /// parameters and the free regions of the body.
+    pub universal_regions: Vec<RegionVid>,
+    /// The strongly connected component of each region variable, indexed by [RegionVid].
+    pub region_sccs: Vec<RegionSccIndex>,
+    /// The strongly connected components of the outlives constraints, with the values that
+    /// region inference computed for them.
+    pub sccs: Vec<RegionScc>,
+    /// The move paths of the body. The root paths are the locals of the body.
+    pub move_paths: Vec<MovePath>,
+    /// All the moves out of a move path in the body.
+    pub moves: Vec<MoveOut>,
+}
+
+impl BorrowckFacts {
+    /// Retrieve the borrows that are activated at the given location.
+    pub fn activations_at(&self, location: BodyLocation) -> impl Iterator<Item = BorrowIndex> {
+        self.borrows.iter().enumerate().filter_map(move |(idx, borrow)| {
+            (borrow.activation == TwoPhaseActivation::ActivatedAt(location)).then_some(idx)
+        })
+    }
+
+    /// Retrieve the value that region inference computed for the given region variable.
+    pub fn region_value(&self, region: RegionVid) -> &RegionValue {
+        &self.sccs[self.region_sccs[region as usize]].value
+    }
+
+    /// Retrieve the borrows that go out of scope at the given location.
+    pub fn borrows_out_of_scope_at(&self, location: BodyLocation) -> &[BorrowIndex] {
+        self.borrows_out_of_scope
+            .iter()
+            .find_map(|(loc, borrows)| (*loc == location).then_some(borrows.as_slice()))
+            .unwrap_or_default()
+    }
+}
+
+/// A borrow that occurs in the body.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct BorrowData {
+    /// Location where the borrow reservation starts.
+    /// In many cases, this will be equal to the activation location but not always.
+    pub reserve_location: BodyLocation,
+    /// Location where the borrow is activated.
+    pub activation: TwoPhaseActivation,
+    /// What kind of borrow this is.
+    pub kind: BorrowKind,
+    /// The region variable of the borrow.
+    pub region: RegionVid,
+    /// Place from which we are borrowing.
+    pub borrowed_place: Place,
+    /// Place to which the borrow was stored.
+    pub assigned_place: Place,
+}
+
+/// Information about the activation of a two-phase borrow.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize)]
+pub enum TwoPhaseActivation {
+    /// This is not a two-phase borrow.
+    NotTwoPhase,
+    /// This is a two-phase borrow that was never activated.
+    NotActivated,
+    /// This is a two-phase borrow that is activated at the given location.
+    ActivatedAt(BodyLocation),
+}
+
+/// A constraint that `sup` outlives `sub`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize)]
+pub struct OutlivesConstraint {
+    pub sup: RegionVid,
+    pub sub: RegionVid,
+    /// The location that introduced this constraint, if it was introduced by a statement
+    /// or terminator of the body.
+    pub location: Option<BodyLocation>,
+}
+
+/// A strongly connected component of the outlives constraints. All the region variables in
+/// a component are equal.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct RegionScc {
+    /// The components that this component outlives.
+    pub successors: Vec<RegionSccIndex>,
+    /// The value that region inference computed for the region variables of this component.
+    pub value: RegionValue,
+}
+
+/// The value of a region variable, i.e., the parts of the program where it must be valid.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct RegionValue {
+    /// The locations of the body that the region contains.
+    pub locations: Vec<BodyLocation>,
+    /// The universal regions that the region outlives, i.e., whose values it contains.
+    pub universal_regions: Vec<RegionVid>,
+}
+
+/// A canonicalized path that can be moved out of, such as `x`, `x.f` or `*x`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct MovePath {
+    pub place: Place,
+    pub parent: Option<MovePathIndex>,
+    pub children: Vec<MovePathIndex>,
+}
+
+/// A move out of a move path at a given location.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct MoveOut {
+    pub path: MovePathIndex,
+    pub source: BodyLocation,
+}
+
+impl MovePath {
+    /// The local at the root of this move path.
+    pub fn local(&self) -> Local {
+        self.place.local
+    }
+}
@@ -54,6 +54,22 @@ pub fn body(&self) -> Option<Body> {
         with(|context| context.instance_body(self.def))
     }
 
+    /// Replace the body that is code generated for this instance.
+    ///
+    /// The body must be monomorphic, and its return and argument locals must have the same types
+    /// as the ones in [Instance::body]. Further calls to [Instance::body] return the new body.
+    ///
+    /// The replacement must happen before the compiler starts code generation, e.g., inside the
+    /// callback of the `run!` macro. Note that:
+    /// - Borrow checker only constructs, such as `FakeRead` statements and user type annotations,
+    ///   are dropped. Bodies with inline assembly or coverage statements cannot be translated.
+    /// - Callers that already inlined the original body are not affected.
+    /// - The ABI of the function is still computed from the original body. For instance, an
+    ///   argument that was read-only in the original body must not be modified by the new body.
+    pub fn replace_body(&self, body: &Body) -> Result<(), Error> {
+        with(|context| context.replace_instance_body(self.def, body))
+    }
+
     /// Check whether this instance has a body available.
     ///
     /// For intrinsics with fallback body, this will return `true`. It is up to the user to decide
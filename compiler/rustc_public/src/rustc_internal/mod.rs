@@ -6,8 +6,10 @@
 use std::cell::RefCell;
 
 use rustc_middle::ty::TyCtxt;
+use rustc_middle::util::Providers;
 use rustc_public_bridge::Tables;
 use rustc_public_bridge::context::CompilerCtxt;
+use rustc_session::Session;
 use rustc_span::def_id::CrateNum;
 
 use crate::Error;
@@ -68,6 +70,14 @@ pub(crate) fn with_bridge<R>(
     })
 }
 
+/// Override the compiler queries that rustc_public relies on.
+///
+/// This must be installed as the `override_queries` callback of the compiler configuration.
+/// It is required to collect the facts returned by [crate::mir::borrowck::borrowck_facts].
+pub fn override_queries(session: &Session, providers: &mut Providers) {
+    rustc_public_bridge::override_queries(session, providers)
+}
+
 pub fn run<F, T>(tcx: TyCtxt<'_>, f: F) -> Result<T, Error>
 where
     F: FnOnce() -> T,
@@ -149,6 +159,32 @@ macro_rules! run_with_tcx {
     };
 }
 
+/// Instantiate and run the compiler with the provided arguments and callback, making the MIR
+/// of every item available to dependent crates.
+///
+/// This behaves like [run!], but it compiles the crate with `-Zalways-encode-mir`. Use it to
+/// compile every crate of a crate graph, e.g., as a `RUSTC_WRAPPER`. The callback of the last
+/// crate can then iterate over the bodies of the whole crate graph with [crate::all_items],
+/// and resolve instances across crates.
+///
+/// Note that the flag is only added to the crate compiled by this invocation. The MIR of a
+/// dependency is read from its metadata, so every dependency must itself have been compiled
+/// with `-Zalways-encode-mir`, either through this macro or by passing the flag explicitly.
+/// This includes the standard library, which is not built with the flag by default.
+/// Items of dependencies compiled without it are silently missing from [crate::all_items],
+/// and their instances have no body.
+#[macro_export]
+macro_rules! run_crate_graph {
+    ($args:expr, $callback_fn:ident) => {
+        $crate::run_crate_graph!($args, || $callback_fn())
+    };
+    ($args:expr, $callback:expr) => {{
+        let mut args = $args.to_vec();
+        args.push("-Zalways-encode-mir".to_string());
+        $crate::run_driver!(&args, $callback)
+    }};
+}
+
 /// Optionally include an ident. This is needed due to macro hygiene.
 #[macro_export]
 #[doc(hidden)]
@@ -226,6 +262,12 @@ impl<B, C, F> Callbacks for RustcPublic<B, C, F>
             C: Send,
             F: FnOnce($($crate::optional!($with_tcx TyCtxt))?) -> ControlFlow<B, C> + Send,
         {
+            /// Install the query overrides required by rustc_public. Queries are only overridden
+            /// if borrowck facts were requested.
+            fn config(&mut self, config: &mut interface::Config) {
+                config.override_queries = Some(rustc_internal::override_queries);
+            }
+
             /// Called after analysis. Return value instructs the compiler whether to
             /// continue the compilation afterwards (defaults to `Compilation::Continue`)
             fn after_analysis<'tcx>(
@@ -0,0 +1,93 @@
+//! Trait solving queries.
+//!
+//! This module allows tools to check whether a type implements a trait, to find out which
+//! implementation is used to prove it, and to normalize associated types.
+//!
+//! All queries are solved in a [ParamEnv], which defines the where-clauses that can be
+//! assumed to hold.
+
+use serde::Serialize;
+
+use crate::compiler_interface::with;
+use crate::ty::{GenericArgs, ImplDef, PredicateKind, TraitRef, Ty};
+use crate::{DefId, Error};
+
+/// The where-clauses that are assumed to hold while solving a trait query.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum ParamEnv {
+    /// No where-clauses. Use this for queries that don't involve generic parameters.
+    Empty,
+    /// The where-clauses of the given item, e.g., the bounds of a generic function.
+    /// Types in the query may refer to the generic parameters of this item.
+    Item(DefId),
+}
+
+/// Evaluate whether the given trait reference holds.
+pub fn evaluate(trait_ref: &TraitRef, param_env: ParamEnv) -> Result<EvaluationResult, Error> {
+    with(|cx| cx.evaluate_trait_ref(trait_ref, param_env))
+}
+
+/// Select the implementation used to prove the given trait reference.
+///
+/// Returns `None` if the selection is ambiguous, and an error if the trait is not implemented.
+pub fn select(trait_ref: &TraitRef, param_env: ParamEnv) -> Result<Option<ImplSource>, Error> {
+    with(|cx| cx.select_trait_impl(trait_ref, param_env))
+}
+
+/// Normalize the associated types and other aliases in the given type.
+///
+/// This will fail if an alias cannot be normalized, e.g., if the trait it refers to is not
+/// implemented.
+pub fn normalize(ty: Ty, param_env: ParamEnv) -> Result<Ty, Error> {
+    with(|cx| cx.normalize_ty(ty, param_env))
+}
+
+/// The result of evaluating a trait reference.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum EvaluationResult {
+    /// The trait reference holds.
+    Holds,
+    /// The trait reference holds, as long as some lifetime constraints are satisfied.
+    HoldsModuloRegions,
+    /// The trait reference holds, as long as the hidden types of some opaque types are
+    /// compatible.
+    HoldsModuloOpaqueTypes,
+    /// It is not possible to tell whether the trait reference holds, e.g., because there are
+    /// multiple candidates that may apply.
+    Ambiguous,
+    /// The trait reference does not hold.
+    Fails,
+}
+
+impl EvaluationResult {
+    /// Whether the trait reference may hold, i.e., it is not known to fail.
+    pub fn may_hold(&self) -> bool {
+        !matches!(self, EvaluationResult::Fails)
+    }
+
+    /// Whether the trait reference is known to hold without any constraints.
+    pub fn holds(&self) -> bool {
+        matches!(self, EvaluationResult::Holds)
+    }
+}
+
+/// The source that proves a trait reference.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ImplSource {
+    pub kind: ImplSourceKind,
+    /// The predicates that must hold for this source to apply, e.g., the where-clauses of
+    /// the selected impl.
+    pub nested: Vec<PredicateKind>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub enum ImplSourceKind {
+    /// An `impl` block, instantiated with the given arguments.
+    UserDefined { impl_def: ImplDef, args: GenericArgs },
+    /// A where-clause of the [ParamEnv].
+    Param,
+    /// A trait object, whose methods start at the given index in the vtable.
+    Object { vtable_base: usize },
+    /// An implementation provided by the compiler, e.g., for `Copy` of a tuple.
+    Builtin,
+}
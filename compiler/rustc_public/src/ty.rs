@@ -228,11 +228,16 @@ pub enum RegionKind {
     ReBound(DebruijnIndex, BoundRegion),
     ReStatic,
     RePlaceholder(Placeholder<BoundRegion>),
+    /// A region variable, which is only present in bodies that are being borrow checked.
+    ReVar(RegionVid),
     ReErased,
 }
 
 pub(crate) type DebruijnIndex = u32;
 
+/// The index of a region variable used by the borrow checker.
+pub type RegionVid = u32;
+
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize)]
 pub struct EarlyParamRegion {
     pub index: u32,
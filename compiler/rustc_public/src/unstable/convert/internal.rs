@@ -12,7 +12,15 @@
 use crate::compiler_interface::BridgeTys;
 use crate::mir::alloc::AllocId;
 use crate::mir::mono::{Instance, MonoItem, StaticDef};
-use crate::mir::{BinOp, Mutability, Place, ProjectionElem, RawPtrKind, Safety, UnOp};
+use crate::mir::{
+    AggregateKind, AssertMessage, BasicBlock, BasicBlockIdx, BinOp, Body, BorrowKind, CastKind,
+    ConstOperand, CoroutineDesugaring, CoroutineKind, CoroutineSource, FakeBorrowKind, LocalDecl,
+    MutBorrowKind, Mutability, NonDivergingIntrinsic, Operand, Place, PointerCoercion,
+    ProjectionElem, RawPtrKind, RetagKind, RuntimeChecks, Rvalue, Safety, Statement, StatementKind,
+    SwitchTargets, Terminator, TerminatorKind, UnOp, UnwindAction, VarDebugInfo,
+    VarDebugInfoContents,
+};
+use crate::traits::ParamEnv;
 use crate::ty::{
     Abi, AdtDef, Binder, BoundRegionKind, BoundTyKind, BoundVariableKind, ClosureKind,
     ExistentialPredicate, ExistentialProjection, ExistentialTraitRef, FloatTy, FnSig,
@@ -20,7 +28,7 @@
     TermKind, TraitRef, Ty, TyConst, UintTy, VariantDef, VariantIdx,
 };
 use crate::unstable::{InternalCx, RustcInternal};
-use crate::{CrateItem, CrateNum, DefId, IndexedVal};
+use crate::{CrateItem, CrateNum, DefId, Error, IndexedVal};
 
 impl RustcInternal for CrateItem {
     type T<'tcx> = rustc_span::def_id::DefId;
@@ -55,6 +63,21 @@ fn internal<'tcx>(
     }
 }
 
+impl RustcInternal for ParamEnv {
+    /// The item whose where-clauses are assumed to hold, if any.
+    type T<'tcx> = Option<rustc_span::def_id::DefId>;
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            ParamEnv::Empty => None,
+            ParamEnv::Item(def_id) => Some(def_id.internal(tables, tcx)),
+        }
+    }
+}
+
 impl RustcInternal for GenericArgs {
     type T<'tcx> = rustc_ty::GenericArgsRef<'tcx>;
     fn internal<'tcx>(
@@ -759,6 +782,602 @@ fn internal<'tcx>(
     }
 }
 
+impl RustcInternal for BasicBlock {
+    type T<'tcx> = rustc_middle::mir::BasicBlockData<'tcx>;
+
+    /// The block is not marked as a cleanup block. That is computed once the entire body is
+    /// translated, since it depends on the unwind edges of the other blocks.
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        rustc_middle::mir::BasicBlockData::new_stmts(
+            self.statements.internal(tables, tcx),
+            Some(self.terminator.internal(tables, tcx)),
+            false,
+        )
+    }
+}
+
+impl RustcInternal for Statement {
+    type T<'tcx> = rustc_middle::mir::Statement<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        rustc_middle::mir::Statement::new(
+            rustc_middle::mir::SourceInfo::outermost(self.span.internal(tables, tcx)),
+            self.kind.internal(tables, tcx),
+        )
+    }
+}
+
+impl RustcInternal for StatementKind {
+    type T<'tcx> = rustc_middle::mir::StatementKind<'tcx>;
+
+    /// Statements that only matter before borrow checking, i.e., `FakeRead` and
+    /// `AscribeUserType`, are translated to `Nop`.
+    ///
+    /// Coverage statements cannot be translated, see `check_body_translatable`.
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        use rustc_middle::mir::StatementKind as Internal;
+        match self {
+            StatementKind::Assign(place, rvalue) => Internal::Assign(Box::new((
+                place.internal(tables, tcx),
+                rvalue.internal(tables, tcx),
+            ))),
+            StatementKind::SetDiscriminant { place, variant_index } => Internal::SetDiscriminant {
+                place: Box::new(place.internal(tables, tcx)),
+                variant_index: variant_index.internal(tables, tcx),
+            },
+            StatementKind::StorageLive(local) => {
+                Internal::StorageLive(rustc_middle::mir::Local::from_usize(*local))
+            }
+            StatementKind::StorageDead(local) => {
+                Internal::StorageDead(rustc_middle::mir::Local::from_usize(*local))
+            }
+            StatementKind::Retag(kind, place) => {
+                Internal::Retag(kind.internal(tables, tcx), Box::new(place.internal(tables, tcx)))
+            }
+            StatementKind::PlaceMention(place) => {
+                Internal::PlaceMention(Box::new(place.internal(tables, tcx)))
+            }
+            StatementKind::Intrinsic(intrinsic) => {
+                Internal::Intrinsic(Box::new(intrinsic.internal(tables, tcx)))
+            }
+            StatementKind::ConstEvalCounter => Internal::ConstEvalCounter,
+            StatementKind::FakeRead(..)
+            | StatementKind::AscribeUserType { .. }
+            | StatementKind::Nop => Internal::Nop,
+            StatementKind::Coverage(_) => {
+                unreachable!("Coverage statements cannot be translated: {self:?}")
+            }
+        }
+    }
+}
+
+impl RustcInternal for RetagKind {
+    type T<'tcx> = rustc_middle::mir::RetagKind;
+
+    fn internal<'tcx>(
+        &self,
+        _tables: &mut Tables<'_, BridgeTys>,
+        _tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            RetagKind::FnEntry => rustc_middle::mir::RetagKind::FnEntry,
+            RetagKind::TwoPhase => rustc_middle::mir::RetagKind::TwoPhase,
+            RetagKind::Raw => rustc_middle::mir::RetagKind::Raw,
+            RetagKind::Default => rustc_middle::mir::RetagKind::Default,
+        }
+    }
+}
+
+impl RustcInternal for NonDivergingIntrinsic {
+    type T<'tcx> = rustc_middle::mir::NonDivergingIntrinsic<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            NonDivergingIntrinsic::Assume(op) => {
+                rustc_middle::mir::NonDivergingIntrinsic::Assume(op.internal(tables, tcx))
+            }
+            NonDivergingIntrinsic::CopyNonOverlapping(copy) => {
+                rustc_middle::mir::NonDivergingIntrinsic::CopyNonOverlapping(
+                    rustc_middle::mir::CopyNonOverlapping {
+                        src: copy.src.internal(tables, tcx),
+                        dst: copy.dst.internal(tables, tcx),
+                        count: copy.count.internal(tables, tcx),
+                    },
+                )
+            }
+        }
+    }
+}
+
+impl RustcInternal for Rvalue {
+    type T<'tcx> = rustc_middle::mir::Rvalue<'tcx>;
+
+    /// `Len` cannot be translated, see `check_body_translatable`.
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        use rustc_middle::mir::Rvalue as Internal;
+        match self {
+            Rvalue::AddressOf(kind, place) => {
+                Internal::RawPtr(kind.internal(tables, tcx), place.internal(tables, tcx))
+            }
+            Rvalue::Aggregate(kind, operands) => Internal::Aggregate(
+                Box::new(kind.internal(tables, tcx)),
+                operands.iter().map(|op| op.internal(tables, tcx)).collect(),
+            ),
+            Rvalue::BinaryOp(op, lhs, rhs) => Internal::BinaryOp(
+                op.internal(tables, tcx),
+                Box::new((lhs.internal(tables, tcx), rhs.internal(tables, tcx))),
+            ),
+            Rvalue::CheckedBinaryOp(op, lhs, rhs) => Internal::BinaryOp(
+                op.internal(tables, tcx).wrapping_to_overflowing().unwrap(),
+                Box::new((lhs.internal(tables, tcx), rhs.internal(tables, tcx))),
+            ),
+            Rvalue::Cast(kind, op, ty) => Internal::Cast(
+                kind.internal(tables, tcx),
+                op.internal(tables, tcx),
+                ty.internal(tables, tcx),
+            ),
+            Rvalue::CopyForDeref(place) => Internal::CopyForDeref(place.internal(tables, tcx)),
+            Rvalue::Discriminant(place) => Internal::Discriminant(place.internal(tables, tcx)),
+            Rvalue::Ref(region, kind, place) => Internal::Ref(
+                region.internal(tables, tcx),
+                kind.internal(tables, tcx),
+                place.internal(tables, tcx),
+            ),
+            Rvalue::Repeat(op, len) => {
+                Internal::Repeat(op.internal(tables, tcx), len.internal(tables, tcx))
+            }
+            Rvalue::ShallowInitBox(op, ty) => {
+                Internal::ShallowInitBox(op.internal(tables, tcx), ty.internal(tables, tcx))
+            }
+            Rvalue::ThreadLocalRef(item) => Internal::ThreadLocalRef(item.internal(tables, tcx)),
+            Rvalue::UnaryOp(op, operand) => {
+                Internal::UnaryOp(op.internal(tables, tcx), operand.internal(tables, tcx))
+            }
+            Rvalue::Use(op) => Internal::Use(op.internal(tables, tcx)),
+            Rvalue::Len(_) => unreachable!("`Len` cannot be translated: {self:?}"),
+        }
+    }
+}
+
+impl RustcInternal for AggregateKind {
+    type T<'tcx> = rustc_middle::mir::AggregateKind<'tcx>;
+
+    /// User type annotations are dropped, since they are only used by the borrow checker.
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        use rustc_middle::mir::AggregateKind as Internal;
+        match self {
+            AggregateKind::Array(ty) => Internal::Array(ty.internal(tables, tcx)),
+            AggregateKind::Tuple => Internal::Tuple,
+            AggregateKind::Adt(def, variant, args, _user_ty, field) => Internal::Adt(
+                def.0.internal(tables, tcx),
+                variant.internal(tables, tcx),
+                args.internal(tables, tcx),
+                None,
+                field.map(rustc_abi::FieldIdx::from_usize),
+            ),
+            AggregateKind::Closure(def, args) => {
+                Internal::Closure(def.0.internal(tables, tcx), args.internal(tables, tcx))
+            }
+            AggregateKind::Coroutine(def, args) => {
+                Internal::Coroutine(def.0.internal(tables, tcx), args.internal(tables, tcx))
+            }
+            AggregateKind::CoroutineClosure(def, args) => {
+                Internal::CoroutineClosure(def.0.internal(tables, tcx), args.internal(tables, tcx))
+            }
+            AggregateKind::RawPtr(ty, mutability) => {
+                Internal::RawPtr(ty.internal(tables, tcx), mutability.internal(tables, tcx))
+            }
+        }
+    }
+}
+
+impl RustcInternal for CastKind {
+    type T<'tcx> = rustc_middle::mir::CastKind;
+
+    /// Pointer coercions are assumed to be implicit, since the source of the coercion
+    /// is not part of rustc_public's IR.
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        use rustc_middle::mir::CastKind as Internal;
+        match self {
+            CastKind::PointerExposeAddress => Internal::PointerExposeProvenance,
+            CastKind::PointerWithExposedProvenance => Internal::PointerWithExposedProvenance,
+            CastKind::PointerCoercion(coercion) => Internal::PointerCoercion(
+                coercion.internal(tables, tcx),
+                rustc_middle::mir::CoercionSource::Implicit,
+            ),
+            CastKind::IntToInt => Internal::IntToInt,
+            CastKind::FloatToInt => Internal::FloatToInt,
+            CastKind::FloatToFloat => Internal::FloatToFloat,
+            CastKind::IntToFloat => Internal::IntToFloat,
+            CastKind::PtrToPtr => Internal::PtrToPtr,
+            CastKind::FnPtrToPtr => Internal::FnPtrToPtr,
+            CastKind::Transmute => Internal::Transmute,
+            CastKind::Subtype => Internal::Subtype,
+        }
+    }
+}
+
+impl RustcInternal for PointerCoercion {
+    type T<'tcx> = rustc_ty::adjustment::PointerCoercion;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        use rustc_ty::adjustment::PointerCoercion as Internal;
+        match self {
+            PointerCoercion::ReifyFnPointer(safety) => {
+                Internal::ReifyFnPointer(safety.internal(tables, tcx))
+            }
+            PointerCoercion::UnsafeFnPointer => Internal::UnsafeFnPointer,
+            PointerCoercion::ClosureFnPointer(safety) => {
+                Internal::ClosureFnPointer(safety.internal(tables, tcx))
+            }
+            PointerCoercion::MutToConstPointer => Internal::MutToConstPointer,
+            PointerCoercion::ArrayToPointer => Internal::ArrayToPointer,
+            PointerCoercion::Unsize => Internal::Unsize,
+        }
+    }
+}
+
+impl RustcInternal for BorrowKind {
+    type T<'tcx> = rustc_middle::mir::BorrowKind;
+
+    fn internal<'tcx>(
+        &self,
+        _tables: &mut Tables<'_, BridgeTys>,
+        _tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        use rustc_middle::mir::{
+            BorrowKind as Internal, FakeBorrowKind as InternalFake, MutBorrowKind as InternalMut,
+        };
+        match self {
+            BorrowKind::Shared => Internal::Shared,
+            BorrowKind::Fake(FakeBorrowKind::Deep) => Internal::Fake(InternalFake::Deep),
+            BorrowKind::Fake(FakeBorrowKind::Shallow) => Internal::Fake(InternalFake::Shallow),
+            BorrowKind::Mut { kind } => Internal::Mut {
+                kind: match kind {
+                    MutBorrowKind::Default => InternalMut::Default,
+                    MutBorrowKind::TwoPhaseBorrow => InternalMut::TwoPhaseBorrow,
+                    MutBorrowKind::ClosureCapture => InternalMut::ClosureCapture,
+                },
+            },
+        }
+    }
+}
+
+impl RustcInternal for Operand {
+    type T<'tcx> = rustc_middle::mir::Operand<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            Operand::Copy(place) => rustc_middle::mir::Operand::Copy(place.internal(tables, tcx)),
+            Operand::Move(place) => rustc_middle::mir::Operand::Move(place.internal(tables, tcx)),
+            Operand::Constant(constant) => {
+                rustc_middle::mir::Operand::Constant(Box::new(constant.internal(tables, tcx)))
+            }
+            Operand::RuntimeChecks(checks) => {
+                rustc_middle::mir::Operand::RuntimeChecks(match checks {
+                    RuntimeChecks::UbChecks => rustc_middle::mir::RuntimeChecks::UbChecks,
+                    RuntimeChecks::ContractChecks => {
+                        rustc_middle::mir::RuntimeChecks::ContractChecks
+                    }
+                    RuntimeChecks::OverflowChecks => {
+                        rustc_middle::mir::RuntimeChecks::OverflowChecks
+                    }
+                })
+            }
+        }
+    }
+}
+
+impl RustcInternal for ConstOperand {
+    type T<'tcx> = rustc_middle::mir::ConstOperand<'tcx>;
+
+    /// User type annotations are dropped, since they are only used by the borrow checker.
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        rustc_middle::mir::ConstOperand {
+            span: self.span.internal(tables, tcx),
+            user_ty: None,
+            const_: self.const_.internal(tables, tcx),
+        }
+    }
+}
+
+impl RustcInternal for Terminator {
+    type T<'tcx> = rustc_middle::mir::Terminator<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        use rustc_middle::mir::TerminatorKind as Internal;
+        let span = self.span.internal(tables, tcx);
+        let kind = match &self.kind {
+            TerminatorKind::Goto { target } => Internal::Goto { target: block_idx(*target) },
+            TerminatorKind::SwitchInt { discr, targets } => Internal::SwitchInt {
+                discr: discr.internal(tables, tcx),
+                targets: targets.internal(tables, tcx),
+            },
+            TerminatorKind::Resume => Internal::UnwindResume,
+            TerminatorKind::Abort => {
+                Internal::UnwindTerminate(rustc_middle::mir::UnwindTerminateReason::Abi)
+            }
+            TerminatorKind::Return => Internal::Return,
+            TerminatorKind::Unreachable => Internal::Unreachable,
+            TerminatorKind::Drop { place, target, unwind } => Internal::Drop {
+                place: place.internal(tables, tcx),
+                target: block_idx(*target),
+                unwind: unwind.internal(tables, tcx),
+                replace: false,
+                drop: None,
+                async_fut: None,
+            },
+            TerminatorKind::Call { func, args, destination, target, unwind } => Internal::Call {
+                func: func.internal(tables, tcx),
+                args: args
+                    .iter()
+                    .map(|arg| rustc_span::source_map::Spanned {
+                        node: arg.internal(tables, tcx),
+                        span,
+                    })
+                    .collect(),
+                destination: destination.internal(tables, tcx),
+                target: target.map(block_idx),
+                unwind: unwind.internal(tables, tcx),
+                call_source: rustc_middle::mir::CallSource::Normal,
+                fn_span: span,
+            },
+            TerminatorKind::Assert { cond, expected, msg, target, unwind } => Internal::Assert {
+                cond: cond.internal(tables, tcx),
+                expected: *expected,
+                msg: Box::new(msg.internal(tables, tcx)),
+                target: block_idx(*target),
+                unwind: unwind.internal(tables, tcx),
+            },
+            TerminatorKind::InlineAsm { .. } => {
+                unreachable!("Inline assembly cannot be translated: {self:?}")
+            }
+        };
+        rustc_middle::mir::Terminator {
+            source_info: rustc_middle::mir::SourceInfo::outermost(span),
+            kind,
+        }
+    }
+}
+
+fn block_idx(idx: BasicBlockIdx) -> rustc_middle::mir::BasicBlock {
+    rustc_middle::mir::BasicBlock::from_usize(idx)
+}
+
+impl RustcInternal for SwitchTargets {
+    type T<'tcx> = rustc_middle::mir::SwitchTargets;
+
+    fn internal<'tcx>(
+        &self,
+        _tables: &mut Tables<'_, BridgeTys>,
+        _tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        rustc_middle::mir::SwitchTargets::new(
+            self.branches().map(|(value, target)| (value, block_idx(target))),
+            block_idx(self.otherwise()),
+        )
+    }
+}
+
+impl RustcInternal for UnwindAction {
+    type T<'tcx> = rustc_middle::mir::UnwindAction;
+
+    fn internal<'tcx>(
+        &self,
+        _tables: &mut Tables<'_, BridgeTys>,
+        _tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            UnwindAction::Continue => rustc_middle::mir::UnwindAction::Continue,
+            UnwindAction::Unreachable => rustc_middle::mir::UnwindAction::Unreachable,
+            UnwindAction::Terminate => rustc_middle::mir::UnwindAction::Terminate(
+                rustc_middle::mir::UnwindTerminateReason::Abi,
+            ),
+            UnwindAction::Cleanup(target) => {
+                rustc_middle::mir::UnwindAction::Cleanup(block_idx(*target))
+            }
+        }
+    }
+}
+
+impl RustcInternal for AssertMessage {
+    type T<'tcx> = rustc_middle::mir::AssertMessage<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        use rustc_middle::mir::AssertKind;
+        match self {
+            AssertMessage::BoundsCheck { len, index } => AssertKind::BoundsCheck {
+                len: len.internal(tables, tcx),
+                index: index.internal(tables, tcx),
+            },
+            AssertMessage::Overflow(op, lhs, rhs) => AssertKind::Overflow(
+                op.internal(tables, tcx),
+                lhs.internal(tables, tcx),
+                rhs.internal(tables, tcx),
+            ),
+            AssertMessage::OverflowNeg(op) => AssertKind::OverflowNeg(op.internal(tables, tcx)),
+            AssertMessage::DivisionByZero(op) => {
+                AssertKind::DivisionByZero(op.internal(tables, tcx))
+            }
+            AssertMessage::RemainderByZero(op) => {
+                AssertKind::RemainderByZero(op.internal(tables, tcx))
+            }
+            AssertMessage::ResumedAfterReturn(kind) => {
+                AssertKind::ResumedAfterReturn(kind.internal(tables, tcx))
+            }
+            AssertMessage::ResumedAfterPanic(kind) => {
+                AssertKind::ResumedAfterPanic(kind.internal(tables, tcx))
+            }
+            AssertMessage::ResumedAfterDrop(kind) => {
+                AssertKind::ResumedAfterDrop(kind.internal(tables, tcx))
+            }
+            AssertMessage::MisalignedPointerDereference { required, found } => {
+                AssertKind::MisalignedPointerDereference {
+                    required: required.internal(tables, tcx),
+                    found: found.internal(tables, tcx),
+                }
+            }
+            AssertMessage::NullPointerDereference => AssertKind::NullPointerDereference,
+            AssertMessage::InvalidEnumConstruction(op) => {
+                AssertKind::InvalidEnumConstruction(op.internal(tables, tcx))
+            }
+        }
+    }
+}
+
+impl RustcInternal for CoroutineKind {
+    type T<'tcx> = rustc_hir::CoroutineKind;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        match self {
+            CoroutineKind::Desugared(desugaring, source) => {
+                let desugaring = match desugaring {
+                    CoroutineDesugaring::Async => rustc_hir::CoroutineDesugaring::Async,
+                    CoroutineDesugaring::Gen => rustc_hir::CoroutineDesugaring::Gen,
+                    CoroutineDesugaring::AsyncGen => rustc_hir::CoroutineDesugaring::AsyncGen,
+                };
+                let source = match source {
+                    CoroutineSource::Block => rustc_hir::CoroutineSource::Block,
+                    CoroutineSource::Closure => rustc_hir::CoroutineSource::Closure,
+                    CoroutineSource::Fn => rustc_hir::CoroutineSource::Fn,
+                };
+                rustc_hir::CoroutineKind::Desugared(desugaring, source)
+            }
+            CoroutineKind::Coroutine(movability) => {
+                rustc_hir::CoroutineKind::Coroutine(movability.internal(tables, tcx))
+            }
+        }
+    }
+}
+
+impl RustcInternal for LocalDecl {
+    type T<'tcx> = rustc_middle::mir::LocalDecl<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        let mut decl = rustc_middle::mir::LocalDecl::new(
+            self.ty.internal(tables, tcx),
+            self.span.internal(tables, tcx),
+        );
+        decl.mutability = self.mutability.internal(tables, tcx);
+        decl
+    }
+}
+
+impl RustcInternal for VarDebugInfo {
+    type T<'tcx> = rustc_middle::mir::VarDebugInfo<'tcx>;
+
+    fn internal<'tcx>(
+        &self,
+        tables: &mut Tables<'_, BridgeTys>,
+        tcx: impl InternalCx<'tcx>,
+    ) -> Self::T<'tcx> {
+        let value = match &self.value {
+            VarDebugInfoContents::Place(place) => {
+                rustc_middle::mir::VarDebugInfoContents::Place(place.internal(tables, tcx))
+            }
+            VarDebugInfoContents::Const(constant) => {
+                rustc_middle::mir::VarDebugInfoContents::Const(constant.internal(tables, tcx))
+            }
+        };
+        rustc_middle::mir::VarDebugInfo {
+            name: rustc_span::Symbol::intern(&self.name),
+            source_info: rustc_middle::mir::SourceInfo {
+                span: self.source_info.span.internal(tables, tcx),
+                scope: rustc_middle::mir::SourceScope::from_u32(self.source_info.scope),
+            },
+            composite: self.composite.as_ref().map(|fragment| {
+                Box::new(rustc_middle::mir::VarDebugInfoFragment {
+                    ty: fragment.ty.internal(tables, tcx),
+                    projection: fragment.projection.internal(tables, tcx),
+                })
+            }),
+            value,
+            argument_index: self.argument_index,
+        }
+    }
+}
+
+/// Check that a body only contains constructs that can be translated back to the compiler.
+///
+/// Coverage statements are opaque, and `Len` and inline assembly do not carry enough
+/// information to be reconstructed.
+pub(crate) fn check_body_translatable(body: &Body) -> Result<(), Error> {
+    for (idx, block) in body.blocks.iter().enumerate() {
+        for statement in &block.statements {
+            let unsupported = match &statement.kind {
+                StatementKind::Coverage(_) => true,
+                StatementKind::Assign(_, Rvalue::Len(_)) => true,
+                _ => false,
+            };
+            if unsupported {
+                return Err(error!(
+                    "Statement `{statement:?}` in block `{idx}` cannot be translated"
+                ));
+            }
+        }
+        if let TerminatorKind::InlineAsm { .. } = block.terminator.kind {
+            return Err(error!("Inline assembly in block `{idx}` cannot be translated"));
+        }
+    }
+    Ok(())
+}
+
 impl<T> RustcInternal for &T
 where
     T: RustcInternal,
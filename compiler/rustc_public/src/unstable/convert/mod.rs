@@ -17,6 +17,8 @@
 mod internal;
 mod stable;
 
+pub(crate) use internal::check_body_translatable;
+
 impl<'tcx, T> Stable<'tcx> for &T
 where
     T: Stable<'tcx>,
@@ -0,0 +1,149 @@
+//! Conversion of internal Rust compiler borrow checker results to stable ones.
+
+use rustc_middle::mir;
+use rustc_middle::mir::borrowck_facts::{
+    BorrowFact, BorrowckFacts, MoveFact, MovePathFact, OutlivesFact, RegionSccFact,
+    TwoPhaseActivation,
+};
+use rustc_public_bridge::Tables;
+use rustc_public_bridge::context::CompilerCtxt;
+
+use crate::compiler_interface::BridgeTys;
+use crate::mir::BodyLocation;
+use crate::unstable::Stable;
+
+impl<'tcx> Stable<'tcx> for mir::Location {
+    type T = BodyLocation;
+    fn stable(&self, _: &mut Tables<'_, BridgeTys>, _: &CompilerCtxt<'_, BridgeTys>) -> Self::T {
+        BodyLocation { block: self.block.as_usize(), statement_index: self.statement_index }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for BorrowckFacts<'tcx> {
+    type T = crate::mir::borrowck::BorrowckFacts;
+    fn stable<'cx>(
+        &self,
+        tables: &mut Tables<'cx, BridgeTys>,
+        cx: &CompilerCtxt<'cx, BridgeTys>,
+    ) -> Self::T {
+        let mut borrows_out_of_scope: Vec<_> = self
+            .borrows_out_of_scope
+            .iter()
+            .map(|(location, borrows)| (location.stable(tables, cx), borrows.clone()))
+            .collect();
+        borrows_out_of_scope.sort_by_key(|(location, _)| *location);
+        crate::mir::borrowck::BorrowckFacts {
+            body: self.body.stable(tables, cx),
+            borrows: self.borrows.iter().map(|borrow| borrow.stable(tables, cx)).collect(),
+            borrows_out_of_scope,
+            outlives_constraints: self
+                .outlives_constraints
+                .iter()
+                .map(|constraint| constraint.stable(tables, cx))
+                .collect(),
+            universal_regions: self.universal_regions.iter().map(|r| r.as_u32()).collect(),
+            region_sccs: self.region_sccs.iter().copied().collect(),
+            sccs: self.sccs.iter().map(|scc| scc.stable(tables, cx)).collect(),
+            move_paths: self.move_paths.iter().map(|path| path.stable(tables, cx)).collect(),
+            moves: self.moves.iter().map(|move_out| move_out.stable(tables, cx)).collect(),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for RegionSccFact {
+    type T = crate::mir::borrowck::RegionScc;
+    fn stable<'cx>(
+        &self,
+        tables: &mut Tables<'cx, BridgeTys>,
+        cx: &CompilerCtxt<'cx, BridgeTys>,
+    ) -> Self::T {
+        crate::mir::borrowck::RegionScc {
+            successors: self.successors.clone(),
+            value: crate::mir::borrowck::RegionValue {
+                locations: self
+                    .locations
+                    .iter()
+                    .map(|location| location.stable(tables, cx))
+                    .collect(),
+                universal_regions: self.universal_regions.iter().map(|r| r.as_u32()).collect(),
+            },
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for BorrowFact<'tcx> {
+    type T = crate::mir::borrowck::BorrowData;
+    fn stable<'cx>(
+        &self,
+        tables: &mut Tables<'cx, BridgeTys>,
+        cx: &CompilerCtxt<'cx, BridgeTys>,
+    ) -> Self::T {
+        crate::mir::borrowck::BorrowData {
+            reserve_location: self.reserve_location.stable(tables, cx),
+            activation: self.activation.stable(tables, cx),
+            kind: self.kind.stable(tables, cx),
+            region: self.region.as_u32(),
+            borrowed_place: self.borrowed_place.stable(tables, cx),
+            assigned_place: self.assigned_place.stable(tables, cx),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for TwoPhaseActivation {
+    type T = crate::mir::borrowck::TwoPhaseActivation;
+    fn stable<'cx>(
+        &self,
+        tables: &mut Tables<'cx, BridgeTys>,
+        cx: &CompilerCtxt<'cx, BridgeTys>,
+    ) -> Self::T {
+        use crate::mir::borrowck::TwoPhaseActivation as Activation;
+        match self {
+            TwoPhaseActivation::NotTwoPhase => Activation::NotTwoPhase,
+            TwoPhaseActivation::NotActivated => Activation::NotActivated,
+            TwoPhaseActivation::ActivatedAt(location) => {
+                Activation::ActivatedAt(location.stable(tables, cx))
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for OutlivesFact {
+    type T = crate::mir::borrowck::OutlivesConstraint;
+    fn stable<'cx>(
+        &self,
+        tables: &mut Tables<'cx, BridgeTys>,
+        cx: &CompilerCtxt<'cx, BridgeTys>,
+    ) -> Self::T {
+        crate::mir::borrowck::OutlivesConstraint {
+            sup: self.sup.as_u32(),
+            sub: self.sub.as_u32(),
+            location: self.location.stable(tables, cx),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for MovePathFact<'tcx> {
+    type T = crate::mir::borrowck::MovePath;
+    fn stable<'cx>(
+        &self,
+        tables: &mut Tables<'cx, BridgeTys>,
+        cx: &CompilerCtxt<'cx, BridgeTys>,
+    ) -> Self::T {
+        crate::mir::borrowck::MovePath {
+            place: self.place.stable(tables, cx),
+            parent: self.parent,
+            children: self.children.clone(),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for MoveFact {
+    type T = crate::mir::borrowck::MoveOut;
+    fn stable<'cx>(
+        &self,
+        tables: &mut Tables<'cx, BridgeTys>,
+        cx: &CompilerCtxt<'cx, BridgeTys>,
+    ) -> Self::T {
+        crate::mir::borrowck::MoveOut { path: self.path, source: self.source.stable(tables, cx) }
+    }
+}
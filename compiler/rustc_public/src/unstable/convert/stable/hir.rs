@@ -0,0 +1,389 @@
+//! Conversion of internal Rust compiler HIR bodies and type checking results to stable ones.
+
+use rustc_ast::LitKind;
+use rustc_hir as hir;
+use rustc_hir::def::Res;
+use rustc_middle::ty::TypeckResults;
+use rustc_middle::ty::adjustment::{Adjust, AutoBorrow};
+use rustc_public_bridge::Tables;
+use rustc_public_bridge::context::CompilerCtxt;
+
+use crate::compiler_interface::BridgeTys;
+use crate::hir::{
+    AddrOfKind, Arm, BinOp, Block, BlockId, Body, Expr, ExprId, ExprKind, LoopSource,
+    MethodResolution, Pat, PatId, PatKind, PathRes, Stmt, StmtId, StmtKind, UnOp,
+};
+use crate::opaque;
+use crate::unstable::Stable;
+
+impl<'tcx> Stable<'tcx> for (&hir::Body<'tcx>, &TypeckResults<'tcx>) {
+    type T = Body;
+    fn stable<'cx>(
+        &self,
+        tables: &mut Tables<'cx, BridgeTys>,
+        cx: &CompilerCtxt<'cx, BridgeTys>,
+    ) -> Self::T {
+        let (body, typeck_results) = *self;
+        let mut builder = HirBodyBuilder {
+            tables,
+            cx,
+            typeck_results,
+            locals: Default::default(),
+            body: Body {
+                params: vec![],
+                value: 0,
+                exprs: vec![],
+                pats: vec![],
+                stmts: vec![],
+                blocks: vec![],
+                typeck_results: Default::default(),
+            },
+        };
+        builder.body.params = body.params.iter().map(|param| builder.pat(param.pat)).collect();
+        builder.body.value = builder.expr(body.value);
+        builder.body
+    }
+}
+
+/// Flattens a HIR body into a [Body], one node at a time.
+///
+/// Nodes are added after their children, so every reference points to an existing node.
+/// Patterns are converted before the expressions in their scope, so paths to local
+/// variables can refer to the pattern that declares them.
+struct HirBodyBuilder<'a, 'tcx, 'cx> {
+    tables: &'a mut Tables<'cx, BridgeTys>,
+    cx: &'a CompilerCtxt<'cx, BridgeTys>,
+    typeck_results: &'a TypeckResults<'tcx>,
+    /// The binding patterns of this body.
+    locals: hir::HirIdMap<PatId>,
+    body: Body,
+}
+
+impl<'a, 'tcx, 'cx> HirBodyBuilder<'a, 'tcx, 'cx> {
+    fn expr(&mut self, expr: &hir::Expr<'tcx>) -> ExprId {
+        let kind = self.expr_kind(expr);
+        let typeck_results = self.typeck_results;
+        let ty = typeck_results.expr_ty_opt(expr).map(|ty| ty.stable(self.tables, self.cx));
+        let adjustments = typeck_results
+            .expr_adjustments(expr)
+            .iter()
+            .map(|adjustment| adjustment.stable(self.tables, self.cx))
+            .collect();
+        let method = match typeck_results.type_dependent_def(expr.hir_id) {
+            Some((hir::def::DefKind::AssocFn, def_id)) => Some(MethodResolution {
+                def: self.tables.fn_def(def_id),
+                args: typeck_results.node_args(expr.hir_id).stable(self.tables, self.cx),
+            }),
+            _ => None,
+        };
+        let results = &mut self.body.typeck_results;
+        results.expr_tys.push(ty);
+        results.expr_adjustments.push(adjustments);
+        results.method_resolutions.push(method);
+        self.body.exprs.push(Expr { kind, span: expr.span.stable(self.tables, self.cx) });
+        self.body.exprs.len() - 1
+    }
+
+    fn exprs(&mut self, exprs: &[hir::Expr<'tcx>]) -> Vec<ExprId> {
+        exprs.iter().map(|expr| self.expr(expr)).collect()
+    }
+
+    fn expr_kind(&mut self, expr: &hir::Expr<'tcx>) -> ExprKind {
+        match expr.kind {
+            hir::ExprKind::Array(elems) => ExprKind::Array(self.exprs(elems)),
+            hir::ExprKind::Call(func, args) => ExprKind::Call(self.expr(func), self.exprs(args)),
+            hir::ExprKind::MethodCall(segment, receiver, args, _) => ExprKind::MethodCall {
+                method: segment.ident.to_string(),
+                receiver: self.expr(receiver),
+                args: self.exprs(args),
+            },
+            hir::ExprKind::Tup(elems) => ExprKind::Tup(self.exprs(elems)),
+            hir::ExprKind::Binary(op, lhs, rhs) => ExprKind::Binary(
+                op.node.stable(self.tables, self.cx),
+                self.expr(lhs),
+                self.expr(rhs),
+            ),
+            hir::ExprKind::Unary(op, operand) => {
+                ExprKind::Unary(op.stable(self.tables, self.cx), self.expr(operand))
+            }
+            hir::ExprKind::Lit(lit) => ExprKind::Lit(lit.node.stable(self.tables, self.cx)),
+            hir::ExprKind::Cast(operand, _) => ExprKind::Cast(self.expr(operand)),
+            hir::ExprKind::DropTemps(inner) => ExprKind::DropTemps(self.expr(inner)),
+            hir::ExprKind::Let(let_expr) => {
+                let pat = self.pat(let_expr.pat);
+                ExprKind::Let { pat, init: self.expr(let_expr.init) }
+            }
+            hir::ExprKind::If(cond, then, els) => {
+                ExprKind::If(self.expr(cond), self.expr(then), els.map(|els| self.expr(els)))
+            }
+            hir::ExprKind::Loop(block, _, source, _) => {
+                ExprKind::Loop(self.block(block), source.stable(self.tables, self.cx))
+            }
+            hir::ExprKind::Match(scrutinee, arms, _) => {
+                let scrutinee = self.expr(scrutinee);
+                let arms = arms
+                    .iter()
+                    .map(|arm| {
+                        let pat = self.pat(arm.pat);
+                        Arm {
+                            pat,
+                            guard: arm.guard.map(|guard| self.expr(guard)),
+                            body: self.expr(arm.body),
+                            span: arm.span.stable(self.tables, self.cx),
+                        }
+                    })
+                    .collect();
+                ExprKind::Match(scrutinee, arms)
+            }
+            hir::ExprKind::Closure(closure) => {
+                ExprKind::Closure(self.tables.closure_def(closure.def_id.to_def_id()))
+            }
+            hir::ExprKind::Block(block, _) => ExprKind::Block(self.block(block)),
+            hir::ExprKind::Assign(lhs, rhs, _) => ExprKind::Assign(self.expr(lhs), self.expr(rhs)),
+            hir::ExprKind::AssignOp(op, lhs, rhs) => {
+                let op = hir::BinOpKind::from(op.node).stable(self.tables, self.cx);
+                ExprKind::AssignOp(op, self.expr(lhs), self.expr(rhs))
+            }
+            hir::ExprKind::Field(base, ident) => {
+                ExprKind::Field(self.expr(base), ident.to_string())
+            }
+            hir::ExprKind::Index(base, index, _) => {
+                ExprKind::Index(self.expr(base), self.expr(index))
+            }
+            hir::ExprKind::Path(ref qpath) => ExprKind::Path(self.path_res(qpath, expr.hir_id)),
+            hir::ExprKind::AddrOf(kind, mutability, operand) => {
+                let kind = match kind {
+                    hir::BorrowKind::Ref => AddrOfKind::Ref,
+                    hir::BorrowKind::Raw => AddrOfKind::Raw,
+                    hir::BorrowKind::Pin => AddrOfKind::Pin,
+                };
+                ExprKind::AddrOf(kind, mutability.stable(self.tables, self.cx), self.expr(operand))
+            }
+            hir::ExprKind::Break(_, value) => ExprKind::Break(value.map(|value| self.expr(value))),
+            hir::ExprKind::Continue(_) => ExprKind::Continue,
+            hir::ExprKind::Ret(value) => ExprKind::Ret(value.map(|value| self.expr(value))),
+            hir::ExprKind::Struct(_, fields, tail) => {
+                let fields = fields
+                    .iter()
+                    .map(|field| (field.ident.to_string(), self.expr(field.expr)))
+                    .collect();
+                let base = match tail {
+                    hir::StructTailExpr::Base(base) => Some(self.expr(base)),
+                    hir::StructTailExpr::None | hir::StructTailExpr::DefaultFields(_) => None,
+                };
+                ExprKind::Struct { fields, base }
+            }
+            hir::ExprKind::Repeat(elem, _) => ExprKind::Repeat(self.expr(elem)),
+            hir::ExprKind::ConstBlock(..)
+            | hir::ExprKind::Use(..)
+            | hir::ExprKind::Type(..)
+            | hir::ExprKind::Become(..)
+            | hir::ExprKind::InlineAsm(..)
+            | hir::ExprKind::OffsetOf(..)
+            | hir::ExprKind::Yield(..)
+            | hir::ExprKind::UnsafeBinderCast(..)
+            | hir::ExprKind::Err(..) => ExprKind::Other(opaque(&expr.kind)),
+        }
+    }
+
+    fn path_res(&mut self, qpath: &hir::QPath<'tcx>, hir_id: hir::HirId) -> PathRes {
+        match self.typeck_results.qpath_res(qpath, hir_id) {
+            Res::Local(local) => match self.locals.get(&local) {
+                Some(pat) => PathRes::Local(*pat),
+                None => PathRes::Upvar(match qpath {
+                    hir::QPath::Resolved(_, path) => path
+                        .segments
+                        .last()
+                        .map(|segment| segment.ident.to_string())
+                        .unwrap_or_default(),
+                    hir::QPath::TypeRelative(_, segment) => segment.ident.to_string(),
+                }),
+            },
+            Res::Def(_, def_id) => PathRes::Def(self.tables.create_def_id(def_id)),
+            res => PathRes::Other(opaque(&res)),
+        }
+    }
+
+    fn pat(&mut self, pat: &hir::Pat<'tcx>) -> PatId {
+        let kind = match pat.kind {
+            hir::PatKind::Wild => PatKind::Wild,
+            hir::PatKind::Binding(hir::BindingMode(by_ref, mutability), _, ident, subpat) => {
+                PatKind::Binding {
+                    name: ident.to_string(),
+                    by_ref: match by_ref {
+                        hir::ByRef::Yes(_, mutability) => {
+                            Some(mutability.stable(self.tables, self.cx))
+                        }
+                        hir::ByRef::No => None,
+                    },
+                    mutability: mutability.stable(self.tables, self.cx),
+                    subpat: subpat.map(|subpat| self.pat(subpat)),
+                }
+            }
+            hir::PatKind::Struct(ref qpath, fields, _) => {
+                let res = self.path_res(qpath, pat.hir_id);
+                let fields = fields
+                    .iter()
+                    .map(|field| (field.ident.to_string(), self.pat(field.pat)))
+                    .collect();
+                PatKind::Struct(res, fields)
+            }
+            hir::PatKind::TupleStruct(ref qpath, pats, _) => {
+                let res = self.path_res(qpath, pat.hir_id);
+                PatKind::TupleStruct(res, self.pats(pats))
+            }
+            hir::PatKind::Or(pats) => PatKind::Or(self.pats(pats)),
+            hir::PatKind::Tuple(pats, _) => PatKind::Tuple(self.pats(pats)),
+            hir::PatKind::Box(inner) => PatKind::Box(self.pat(inner)),
+            hir::PatKind::Deref(inner) => PatKind::Deref(self.pat(inner)),
+            hir::PatKind::Ref(inner, _, mutability) => {
+                PatKind::Ref(self.pat(inner), mutability.stable(self.tables, self.cx))
+            }
+            hir::PatKind::Expr(expr) => PatKind::Expr(opaque(&expr.kind)),
+            hir::PatKind::Range(start, end, _) => PatKind::Range(
+                start.map(|start| opaque(&start.kind)),
+                end.map(|end| opaque(&end.kind)),
+            ),
+            hir::PatKind::Slice(before, slice, after) => {
+                let before = self.pats(before);
+                let slice = slice.map(|slice| self.pat(slice));
+                PatKind::Slice(before, slice, self.pats(after))
+            }
+            hir::PatKind::Never => PatKind::Never,
+            hir::PatKind::Missing | hir::PatKind::Guard(..) | hir::PatKind::Err(_) => {
+                PatKind::Other(opaque(&pat.kind))
+            }
+        };
+        let ty = self.typeck_results.node_type_opt(pat.hir_id);
+        self.body.typeck_results.pat_tys.push(ty.map(|ty| ty.stable(self.tables, self.cx)));
+        self.body.pats.push(Pat { kind, span: pat.span.stable(self.tables, self.cx) });
+        let id = self.body.pats.len() - 1;
+        if let hir::PatKind::Binding(_, hir_id, ..) = pat.kind {
+            self.locals.insert(hir_id, id);
+        }
+        id
+    }
+
+    fn pats(&mut self, pats: &[hir::Pat<'tcx>]) -> Vec<PatId> {
+        pats.iter().map(|pat| self.pat(pat)).collect()
+    }
+
+    fn stmt(&mut self, stmt: &hir::Stmt<'tcx>) -> StmtId {
+        let kind = match stmt.kind {
+            hir::StmtKind::Let(local) => {
+                // Convert the initializer first, since it cannot refer to the new bindings.
+                let init = local.init.map(|init| self.expr(init));
+                let els = local.els.map(|els| self.block(els));
+                StmtKind::Let { pat: self.pat(local.pat), init, els }
+            }
+            hir::StmtKind::Item(_) => StmtKind::Item,
+            hir::StmtKind::Expr(expr) => StmtKind::Expr(self.expr(expr)),
+            hir::StmtKind::Semi(expr) => StmtKind::Semi(self.expr(expr)),
+        };
+        self.body.stmts.push(Stmt { kind, span: stmt.span.stable(self.tables, self.cx) });
+        self.body.stmts.len() - 1
+    }
+
+    fn block(&mut self, block: &hir::Block<'tcx>) -> BlockId {
+        let stmts = block.stmts.iter().map(|stmt| self.stmt(stmt)).collect();
+        let expr = block.expr.map(|expr| self.expr(expr));
+        self.body.blocks.push(Block {
+            stmts,
+            expr,
+            is_unsafe: matches!(block.rules, hir::BlockCheckMode::UnsafeBlock(_)),
+            span: block.span.stable(self.tables, self.cx),
+        });
+        self.body.blocks.len() - 1
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::BinOpKind {
+    type T = BinOp;
+    fn stable(&self, _: &mut Tables<'_, BridgeTys>, _: &CompilerCtxt<'_, BridgeTys>) -> Self::T {
+        match self {
+            hir::BinOpKind::Add => BinOp::Add,
+            hir::BinOpKind::Sub => BinOp::Sub,
+            hir::BinOpKind::Mul => BinOp::Mul,
+            hir::BinOpKind::Div => BinOp::Div,
+            hir::BinOpKind::Rem => BinOp::Rem,
+            hir::BinOpKind::And => BinOp::And,
+            hir::BinOpKind::Or => BinOp::Or,
+            hir::BinOpKind::BitXor => BinOp::BitXor,
+            hir::BinOpKind::BitAnd => BinOp::BitAnd,
+            hir::BinOpKind::BitOr => BinOp::BitOr,
+            hir::BinOpKind::Shl => BinOp::Shl,
+            hir::BinOpKind::Shr => BinOp::Shr,
+            hir::BinOpKind::Eq => BinOp::Eq,
+            hir::BinOpKind::Lt => BinOp::Lt,
+            hir::BinOpKind::Le => BinOp::Le,
+            hir::BinOpKind::Ne => BinOp::Ne,
+            hir::BinOpKind::Ge => BinOp::Ge,
+            hir::BinOpKind::Gt => BinOp::Gt,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::UnOp {
+    type T = UnOp;
+    fn stable(&self, _: &mut Tables<'_, BridgeTys>, _: &CompilerCtxt<'_, BridgeTys>) -> Self::T {
+        match self {
+            hir::UnOp::Deref => UnOp::Deref,
+            hir::UnOp::Not => UnOp::Not,
+            hir::UnOp::Neg => UnOp::Neg,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::LoopSource {
+    type T = LoopSource;
+    fn stable(&self, _: &mut Tables<'_, BridgeTys>, _: &CompilerCtxt<'_, BridgeTys>) -> Self::T {
+        match self {
+            hir::LoopSource::Loop => LoopSource::Loop,
+            hir::LoopSource::While => LoopSource::While,
+            hir::LoopSource::ForLoop => LoopSource::ForLoop,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for LitKind {
+    type T = crate::hir::LitKind;
+    fn stable(&self, _: &mut Tables<'_, BridgeTys>, _: &CompilerCtxt<'_, BridgeTys>) -> Self::T {
+        use crate::hir::LitKind as Stable;
+        match self {
+            LitKind::Str(symbol, _) => Stable::Str(symbol.to_string()),
+            LitKind::ByteStr(bytes, _) => Stable::ByteStr(bytes.as_byte_str().to_vec()),
+            LitKind::Byte(byte) => Stable::Byte(*byte),
+            LitKind::Char(c) => Stable::Char(*c),
+            LitKind::Int(value, _) => Stable::Int(value.get()),
+            LitKind::Float(symbol, _) => Stable::Float(symbol.to_string()),
+            LitKind::Bool(value) => Stable::Bool(*value),
+            LitKind::CStr(..) | LitKind::Err(_) => Stable::Other(opaque(self)),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for rustc_middle::ty::adjustment::Adjustment<'tcx> {
+    type T = crate::hir::Adjustment;
+    fn stable<'cx>(
+        &self,
+        tables: &mut Tables<'cx, BridgeTys>,
+        cx: &CompilerCtxt<'cx, BridgeTys>,
+    ) -> Self::T {
+        use crate::hir::Adjust as Stable;
+        let kind = match &self.kind {
+            Adjust::NeverToAny => Stable::NeverToAny,
+            Adjust::Deref(overloaded) => {
+                Stable::Deref(overloaded.as_ref().map(|deref| deref.mutbl.stable(tables, cx)))
+            }
+            Adjust::Borrow(AutoBorrow::Ref(mutability)) => {
+                Stable::Borrow(hir::Mutability::from(*mutability).stable(tables, cx))
+            }
+            Adjust::Borrow(AutoBorrow::RawPtr(mutability)) => {
+                Stable::RawBorrow(mutability.stable(tables, cx))
+            }
+            Adjust::Pointer(coercion) => Stable::Pointer(coercion.stable(tables, cx)),
+            Adjust::ReborrowPin(mutability) => Stable::ReborrowPin(mutability.stable(tables, cx)),
+        };
+        crate::hir::Adjustment { kind, target: self.target.stable(tables, cx) }
+    }
+}
@@ -8,7 +8,10 @@
 use crate::compiler_interface::BridgeTys;
 
 mod abi;
+mod borrowck;
+mod hir;
 mod mir;
+mod traits;
 mod ty;
 
 impl<'tcx> Stable<'tcx> for rustc_hir::Safety {
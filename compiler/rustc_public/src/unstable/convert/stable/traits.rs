@@ -0,0 +1,56 @@
+//! Conversion of internal Rust compiler trait solving results to stable ones.
+
+use rustc_middle::traits::{BuiltinImplSource, EvaluationResult, ImplSource};
+use rustc_middle::ty;
+use rustc_public_bridge::Tables;
+use rustc_public_bridge::context::CompilerCtxt;
+
+use crate::compiler_interface::BridgeTys;
+use crate::traits::ImplSourceKind;
+use crate::unstable::Stable;
+
+impl<'tcx> Stable<'tcx> for EvaluationResult {
+    type T = crate::traits::EvaluationResult;
+    fn stable(&self, _: &mut Tables<'_, BridgeTys>, _: &CompilerCtxt<'_, BridgeTys>) -> Self::T {
+        use crate::traits::EvaluationResult as Stable;
+        match self {
+            EvaluationResult::EvaluatedToOk => Stable::Holds,
+            EvaluationResult::EvaluatedToOkModuloRegions => Stable::HoldsModuloRegions,
+            EvaluationResult::EvaluatedToOkModuloOpaqueTypes => Stable::HoldsModuloOpaqueTypes,
+            EvaluationResult::EvaluatedToAmbig
+            | EvaluationResult::EvaluatedToAmbigStackDependent => Stable::Ambiguous,
+            EvaluationResult::EvaluatedToErr => Stable::Fails,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ImplSource<'tcx, ty::Predicate<'tcx>> {
+    type T = crate::traits::ImplSource;
+    fn stable<'cx>(
+        &self,
+        tables: &mut Tables<'cx, BridgeTys>,
+        cx: &CompilerCtxt<'cx, BridgeTys>,
+    ) -> Self::T {
+        let (kind, nested) = match self {
+            ImplSource::UserDefined(data) => (
+                ImplSourceKind::UserDefined {
+                    impl_def: tables.impl_def(data.impl_def_id),
+                    args: data.args.stable(tables, cx),
+                },
+                &data.nested,
+            ),
+            ImplSource::Param(nested) => (ImplSourceKind::Param, nested),
+            ImplSource::Builtin(BuiltinImplSource::Object(vtable_base), nested) => {
+                (ImplSourceKind::Object { vtable_base: *vtable_base }, nested)
+            }
+            ImplSource::Builtin(_, nested) => (ImplSourceKind::Builtin, nested),
+        };
+        crate::traits::ImplSource {
+            kind,
+            nested: nested
+                .iter()
+                .map(|predicate| predicate.kind().skip_binder().stable(tables, cx))
+                .collect(),
+        }
+    }
+}
@@ -928,6 +928,7 @@ fn stable<'cx>(
                     kind: place_holder.bound.kind.stable(tables, cx),
                 },
             }),
+            ty::ReVar(vid) => RegionKind::ReVar(vid.as_u32()),
             ty::ReErased => RegionKind::ReErased,
             _ => unreachable!("{self:?}"),
         }
@@ -0,0 +1,64 @@
+//! Substitution of the MIR bodies that are code generated.
+//!
+//! Tools may transform the body of an instance and ask the compiler to code generate the
+//! transformed body instead. The replacements are registered in the `instance_mir_overrides`
+//! of the type context, which are consulted by the mono item collector and by the codegen
+//! backends through `TyCtxt::codegen_instance_mir`. Like the bodies, they are dropped together
+//! with the type context of the session.
+
+use rustc_index::bit_set::DenseBitSet;
+use rustc_middle::mir::{self, Body};
+use rustc_middle::ty::{Instance, TyCtxt};
+
+/// Register `body` as the body to be code generated for `instance`, unless it fails validation.
+pub(crate) fn register_body_override<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    instance: Instance<'tcx>,
+    mut body: Body<'tcx>,
+) -> Result<(), String> {
+    mark_cleanup_blocks(&mut body);
+    let failures = rustc_mir_transform::validate_external_body(tcx, &body);
+    if !failures.is_empty() {
+        let failures: Vec<_> =
+            failures.iter().map(|(location, msg)| format!("{location:?}: {msg}")).collect();
+        return Err(format!("Invalid body for `{instance}`:\n{}", failures.join("\n")));
+    }
+    let body = tcx.arena.alloc(body);
+    tcx.instance_mir_overrides.lock().insert(instance, body);
+    Ok(())
+}
+
+/// Mark the blocks that are only reachable through unwinding as cleanup blocks.
+///
+/// Blocks in rustc_public's representation do not record whether they are cleanup blocks, so
+/// we compute it from the unwind edges. Cleanup blocks cannot unwind again, so any terminate
+/// action inside them is also updated to reflect that.
+fn mark_cleanup_blocks(body: &mut Body<'_>) {
+    let mut cleanup = DenseBitSet::new_empty(body.basic_blocks.len());
+    let mut worklist: Vec<_> = body
+        .basic_blocks
+        .iter()
+        .filter_map(|data| match data.terminator().unwind() {
+            Some(mir::UnwindAction::Cleanup(target)) => Some(*target),
+            _ => None,
+        })
+        .collect();
+    while let Some(bb) = worklist.pop() {
+        if cleanup.insert(bb) {
+            worklist.extend(body.basic_blocks[bb].terminator().successors());
+        }
+    }
+    for (bb, data) in body.basic_blocks.as_mut_preserves_cfg().iter_enumerated_mut() {
+        data.is_cleanup = cleanup.contains(bb);
+        if !data.is_cleanup {
+            continue;
+        }
+        let terminator = data.terminator_mut();
+        if let mir::TerminatorKind::UnwindTerminate(reason) = &mut terminator.kind {
+            *reason = mir::UnwindTerminateReason::InCleanup;
+        }
+        if let Some(mir::UnwindAction::Terminate(reason)) = terminator.unwind_mut() {
+            *reason = mir::UnwindTerminateReason::InCleanup;
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Collection of borrow checker facts for rustc_public.
+//!
+//! The borrow checker consumes the promoted MIR of a body, so by the time a rustc_public
+//! callback runs, the bodies required to recompute the facts are long gone.
+//! Instead, we override the `mir_borrowck` query and record the facts of every body in
+//! [`GlobalCtxt::borrowck_facts`](rustc_middle::ty::GlobalCtxt::borrowck_facts) while the
+//! borrow checker runs. Collection is disabled by default, since it roughly doubles the memory
+//! used by borrow checking.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rustc_borrowck::consumers::{self, ConsumerOptions};
+use rustc_middle::mir::borrowck_facts::BorrowckFacts;
+use rustc_middle::query::queries::mir_borrowck::ProvidedValue;
+use rustc_middle::ty::TyCtxt;
+use rustc_middle::util::Providers;
+use rustc_span::def_id::LocalDefId;
+
+/// Whether the overridden `mir_borrowck` query should record the borrowck facts.
+static COLLECT_BORROWCK_FACTS: AtomicBool = AtomicBool::new(false);
+
+/// Request the borrow checker facts to be recorded for every borrow checked body.
+///
+/// This must be invoked before the compiler starts running.
+pub fn enable_borrowck_facts() {
+    COLLECT_BORROWCK_FACTS.store(true, Ordering::Relaxed);
+}
+
+/// Whether borrowck facts are being recorded in this session.
+pub fn borrowck_facts_enabled() -> bool {
+    COLLECT_BORROWCK_FACTS.load(Ordering::Relaxed)
+}
+
+pub(crate) fn provide(providers: &mut Providers) {
+    if borrowck_facts_enabled() {
+        providers.mir_borrowck = mir_borrowck;
+    }
+}
+
+fn mir_borrowck<'tcx>(tcx: TyCtxt<'tcx>, def_id: LocalDefId) -> ProvidedValue<'tcx> {
+    let (result, bodies) =
+        consumers::mir_borrowck_with_facts(tcx, def_id, ConsumerOptions::RegionInferenceContext);
+    let facts: Vec<_> = bodies
+        .into_iter()
+        .map(|(def_id, body)| (def_id, &*tcx.arena.alloc(body.into_borrowck_facts(tcx))))
+        .collect();
+    tcx.borrowck_facts.lock().extend(facts);
+    result
+}
+
+/// Retrieve the body and borrowck facts collected for `def_id`, if any.
+pub(crate) fn borrowck_facts<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: LocalDefId,
+) -> Option<&'tcx BorrowckFacts<'tcx>> {
+    // Make sure the body has been borrow checked.
+    let _ = tcx.mir_borrowck(tcx.typeck_root_def_id(def_id.to_def_id()).expect_local());
+    tcx.borrowck_facts.lock().get(&def_id).copied()
+}
@@ -5,10 +5,15 @@
 use std::iter;
 
 use rustc_abi::{Endian, Layout, ReprOptions};
+use rustc_data_structures::fx::FxHashMap;
 use rustc_hir::def::DefKind;
-use rustc_hir::{Attribute, LangItem};
+use rustc_hir::{self as hir, Attribute, LangItem};
+use rustc_index::IndexVec;
+use rustc_middle::mir::borrowck_facts::BorrowckFacts;
 use rustc_middle::mir::interpret::{AllocId, ConstAllocation, ErrorHandled, GlobalAlloc, Scalar};
+use rustc_middle::mir::visit::{PlaceContext, Visitor};
 use rustc_middle::mir::{BinOp, Body, Const as MirConst, ConstValue, UnOp};
+use rustc_middle::traits::{EvaluationResult, ImplSource};
 use rustc_middle::ty::layout::{FnAbiOf, LayoutOf};
 use rustc_middle::ty::print::{
     with_forced_trimmed_paths, with_no_trimmed_paths, with_resolve_crate_name,
@@ -25,8 +30,15 @@
 use rustc_span::def_id::{CrateNum, DefId, LOCAL_CRATE};
 use rustc_span::{Span, Symbol};
 use rustc_target::callconv::FnAbi;
+use rustc_trait_selection::infer::TyCtxtInferExt;
+use rustc_trait_selection::traits::query::evaluate_obligation::InferCtxtExt;
+use rustc_trait_selection::traits::{
+    Obligation, ObligationCause, ObligationCtxt, SelectionContext,
+};
 
 use super::{AllocRangeHelpers, CompilerCtxt, TyHelpers, TypingEnvHelpers};
+use crate::body_override::register_body_override;
+use crate::borrowck::{borrowck_facts, borrowck_facts_enabled};
 use crate::builder::BodyBuilder;
 use crate::{Bridge, Error, Tables, filter_def_ids};
 
@@ -147,6 +159,19 @@ pub fn crate_functions(&self, crate_num: CrateNum) -> Vec<DefId> {
         filter_def_ids(self.tcx, crate_num, |def_id| self.filter_fn_def(def_id))
     }
 
+    /// Retrieve all items defined in this crate that have a MIR available.
+    ///
+    /// For external crates, this only includes the items whose MIR was encoded in the crate
+    /// metadata. That is the case for all items of crates compiled with `-Zalways-encode-mir`.
+    pub fn crate_items(&self, crate_num: CrateNum) -> Vec<DefId> {
+        if crate_num == LOCAL_CRATE {
+            return self.all_local_items();
+        }
+        filter_def_ids(self.tcx, crate_num, |def_id| {
+            (self.tcx.is_mir_available(def_id) && self.item_has_body(def_id)).then_some(def_id)
+        })
+    }
+
     /// Retrieve all static items defined in this crate.
     pub fn crate_statics(&self, crate_num: CrateNum) -> Vec<DefId> {
         filter_def_ids(self.tcx, crate_num, |def_id| self.filter_static_def(def_id))
@@ -580,7 +605,12 @@ pub fn rigid_ty_discriminant_ty(&self, internal_kind: TyKind<'tcx>) -> Ty<'tcx>
     }
 
     /// Get the body of an Instance which is already monomorphized.
+    ///
+    /// If the body of the instance was replaced, this returns the replacement.
     pub fn instance_body(&self, instance: ty::Instance<'tcx>) -> Option<Body<'tcx>> {
+        if let Some(body) = self.tcx.instance_mir_overrides.lock().get(&instance) {
+            return Some((*body).clone());
+        }
         self.instance_has_body(instance).then(|| BodyBuilder::new(self.tcx, instance).build())
     }
 
@@ -757,4 +787,205 @@ pub fn associated_items(&self, def_id: DefId) -> Vec<AssocItem> {
         };
         assoc_items
     }
+
+    /// Retrieve the borrowck facts of the given item.
+    pub fn borrowck_facts(&self, def_id: DefId) -> Result<&'tcx BorrowckFacts<'tcx>, B::Error> {
+        if !borrowck_facts_enabled() {
+            return Err(B::Error::new(
+                "Borrowck facts were not enabled before running the compiler".to_string(),
+            ));
+        }
+        let Some(local_def_id) = def_id.as_local() else {
+            return Err(B::Error::new(format!("Item `{def_id:?}` is not a local item")));
+        };
+        if !self.tcx.is_mir_available(def_id) || self.tcx.is_trivial_const(def_id) {
+            return Err(B::Error::new(format!("Item `{def_id:?}` has no body")));
+        }
+        borrowck_facts(self.tcx, local_def_id)
+            .ok_or_else(|| B::Error::new(format!("No borrowck facts found for `{def_id:?}`")))
+    }
+
+    /// Retrieve the HIR body of the given item and its type checking results.
+    pub fn hir_body(
+        &self,
+        def_id: DefId,
+    ) -> Result<(&'tcx hir::Body<'tcx>, &'tcx ty::TypeckResults<'tcx>), B::Error> {
+        let Some(local_def_id) = def_id.as_local() else {
+            return Err(B::Error::new(format!("Item `{def_id:?}` is not a local item")));
+        };
+        let Some(body) = self.tcx.hir_maybe_body_owned_by(local_def_id) else {
+            return Err(B::Error::new(format!("Item `{def_id:?}` has no body")));
+        };
+        Ok((body, self.tcx.typeck(local_def_id)))
+    }
+
+    /// The typing environment used to solve trait obligations in the context of the given item,
+    /// or a fully monomorphized one if no item is given.
+    fn trait_solving_env(&self, item: Option<DefId>) -> ty::TypingEnv<'tcx> {
+        match item {
+            Some(def_id) => ty::TypingEnv::post_analysis(self.tcx, def_id),
+            None => ty::TypingEnv::fully_monomorphized(),
+        }
+    }
+
+    /// Evaluate whether the given trait reference holds.
+    pub fn evaluate_trait_ref(
+        &self,
+        trait_ref: TraitRef<'tcx>,
+        item: Option<DefId>,
+    ) -> Result<EvaluationResult, B::Error> {
+        let (infcx, param_env) =
+            self.tcx.infer_ctxt().build_with_typing_env(self.trait_solving_env(item));
+        let obligation = Obligation::new(self.tcx, ObligationCause::dummy(), param_env, trait_ref);
+        infcx
+            .evaluate_obligation(&obligation)
+            .map_err(|_| B::Error::new(format!("Overflow while evaluating `{trait_ref}`")))
+    }
+
+    /// Select the implementation that proves the given trait reference, together with the
+    /// predicates that must hold for it to apply.
+    ///
+    /// Returns `None` if the selection is ambiguous.
+    pub fn select_trait_impl(
+        &self,
+        trait_ref: TraitRef<'tcx>,
+        item: Option<DefId>,
+    ) -> Result<Option<ImplSource<'tcx, ty::Predicate<'tcx>>>, B::Error> {
+        let (infcx, param_env) =
+            self.tcx.infer_ctxt().build_with_typing_env(self.trait_solving_env(item));
+        let predicate = ty::TraitPredicate { trait_ref, polarity: ty::PredicatePolarity::Positive };
+        let obligation = Obligation::new(self.tcx, ObligationCause::dummy(), param_env, predicate);
+        let impl_source = match SelectionContext::new(&infcx).select(&obligation) {
+            Ok(Some(impl_source)) => impl_source,
+            Ok(None) => return Ok(None),
+            Err(_) => return Err(B::Error::new(format!("`{trait_ref}` is not implemented"))),
+        };
+        let impl_source = impl_source.map(|obligation| obligation.predicate);
+        let impl_source =
+            self.tcx.erase_and_anonymize_regions(infcx.resolve_vars_if_possible(impl_source));
+        // The nested obligations may still refer to inference variables that can only be
+        // constrained by processing them, which makes the selection ambiguous.
+        Ok((!impl_source.has_non_region_infer()).then_some(impl_source))
+    }
+
+    /// Normalize the aliases in the given type.
+    pub fn normalize_ty(&self, ty: Ty<'tcx>, item: Option<DefId>) -> Result<Ty<'tcx>, B::Error> {
+        let (infcx, param_env) =
+            self.tcx.infer_ctxt().build_with_typing_env(self.trait_solving_env(item));
+        let ocx = ObligationCtxt::new(&infcx);
+        let normalized = ocx.normalize(&ObligationCause::dummy(), param_env, ty);
+        let errors = ocx.evaluate_obligations_error_on_ambiguity();
+        let normalized =
+            self.tcx.erase_and_anonymize_regions(infcx.resolve_vars_if_possible(normalized));
+        if !errors.is_empty() || normalized.has_non_region_infer() {
+            return Err(B::Error::new(format!("Failed to normalize `{ty}`")));
+        }
+        Ok(normalized)
+    }
+
+    /// Replace the body that is code generated for the given instance.
+    ///
+    /// The new body is derived from the current body of the instance, so its source scopes
+    /// and other metadata are preserved, while its blocks, locals and debug info are replaced.
+    /// Statements and terminators get the scope of the statement or terminator of the current
+    /// body with the same span, or else the innermost scope that contains their span.
+    pub fn replace_instance_body(
+        &self,
+        instance: ty::Instance<'tcx>,
+        blocks: Vec<mir::BasicBlockData<'tcx>>,
+        locals: Vec<mir::LocalDecl<'tcx>>,
+        var_debug_info: Vec<mir::VarDebugInfo<'tcx>>,
+        spread_arg: Option<usize>,
+    ) -> Result<(), B::Error> {
+        if matches!(instance.def, InstanceKind::Intrinsic(..) | InstanceKind::Virtual(..)) {
+            return Err(B::Error::new(format!("Cannot replace the body of `{instance:?}`")));
+        }
+        let Some(mut body) = self.instance_body(instance) else {
+            return Err(B::Error::new(format!("Instance `{instance:?}` has no body")));
+        };
+        if locals.len() <= body.arg_count {
+            return Err(B::Error::new(format!(
+                "Expected at least {} locals, but found {}",
+                body.arg_count + 1,
+                locals.len()
+            )));
+        }
+        for (local, decl) in body.local_decls.iter_enumerated().take(body.arg_count + 1) {
+            if decl.ty != locals[local.as_usize()].ty {
+                return Err(B::Error::new(format!(
+                    "Expected local `{local:?}` to have type `{}`, but found `{}`",
+                    decl.ty,
+                    locals[local.as_usize()].ty
+                )));
+            }
+        }
+        let num_blocks = blocks.len();
+        if blocks
+            .iter()
+            .any(|data| data.terminator().successors().any(|bb| bb.as_usize() >= num_blocks))
+        {
+            return Err(B::Error::new("Body has a jump to a non-existing block".to_string()));
+        }
+        let mut max_local = MaxLocal(None);
+        for (bb, data) in blocks.iter().enumerate() {
+            max_local.visit_basic_block_data(mir::BasicBlock::from_usize(bb), data);
+        }
+        for info in &var_debug_info {
+            max_local.visit_var_debug_info(info);
+        }
+        if let Some(local) = max_local.0.max(spread_arg.map(mir::Local::from_usize))
+            && local.as_usize() >= locals.len()
+        {
+            return Err(B::Error::new(format!(
+                "Body uses local `{local:?}`, but only has {} locals",
+                locals.len()
+            )));
+        }
+
+        let mut scopes = FxHashMap::default();
+        for data in body.basic_blocks.iter() {
+            let source_infos = data.statements.iter().map(|stmt| stmt.source_info);
+            for source_info in source_infos.chain(iter::once(data.terminator().source_info)) {
+                scopes.entry(source_info.span).or_insert(source_info.scope);
+            }
+        }
+        let scope_of = |span: Span| {
+            scopes.get(&span).copied().unwrap_or_else(|| {
+                body.source_scopes
+                    .iter_enumerated()
+                    .filter(|(_, data)| data.span.contains(span))
+                    .map(|(scope, _)| scope)
+                    .last()
+                    .unwrap_or(mir::OUTERMOST_SOURCE_SCOPE)
+            })
+        };
+        let mut blocks: IndexVec<mir::BasicBlock, _> = blocks.into_iter().collect();
+        for data in &mut blocks {
+            for stmt in &mut data.statements {
+                stmt.source_info.scope = scope_of(stmt.source_info.span);
+            }
+            let terminator = data.terminator_mut();
+            terminator.source_info.scope = scope_of(terminator.source_info.span);
+        }
+
+        *body.basic_blocks_mut() = blocks;
+        body.local_decls = locals.into_iter().collect();
+        body.spread_arg = spread_arg.map(mir::Local::from_usize);
+        body.var_debug_info = var_debug_info;
+        for info in &mut body.var_debug_info {
+            if info.source_info.scope.as_usize() >= body.source_scopes.len() {
+                info.source_info.scope = mir::OUTERMOST_SOURCE_SCOPE;
+            }
+        }
+        register_body_override(self.tcx, instance, body).map_err(B::Error::new)
+    }
+}
+
+/// Finds the highest local used by the visited MIR.
+struct MaxLocal(Option<mir::Local>);
+
+impl<'tcx> Visitor<'tcx> for MaxLocal {
+    fn visit_local(&mut self, local: mir::Local, _: PlaceContext, _: mir::Location) {
+        self.0 = self.0.max(Some(local));
+    }
 }
@@ -28,10 +28,14 @@
 use rustc_middle::mir;
 use rustc_middle::mir::interpret::AllocId;
 use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_middle::util::Providers;
+use rustc_session::Session;
 use rustc_span::Span;
 use rustc_span::def_id::{CrateNum, DefId, LOCAL_CRATE};
 
 pub mod alloc;
+mod body_override;
+pub mod borrowck;
 pub mod bridge;
 mod builder;
 pub mod context;
@@ -39,6 +43,15 @@
 #[deprecated(note = "please use `rustc_public::rustc_internal` instead")]
 pub mod rustc_internal {}
 
+/// Override the queries required by rustc_public.
+///
+/// This should be installed as `rustc_interface::Config::override_queries`. The `mir_borrowck`
+/// query is only overridden if borrowck facts were requested with
+/// [`borrowck::enable_borrowck_facts`].
+pub fn override_queries(_session: &Session, providers: &mut Providers) {
+    borrowck::provide(providers);
+}
+
 /// Trait alias for types that can be cached in [`Tables`].
 pub trait Cacheable = Copy + Debug + PartialEq + IndexedVal;
 
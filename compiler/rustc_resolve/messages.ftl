@@ -125,6 +125,9 @@ resolve_const_param_in_non_trivial_anon_const =
 resolve_constructor_private_if_any_field_private =
     a constructor is private if any of the fields is private
 
+resolve_couldnt_dump_import_graph =
+    unexpected error occurred while dumping the import graph: {$error}
+
 resolve_elided_anonymous_lifetime_report_error =
     `&` without an explicit lifetime name cannot be used here
     .label = explicit lifetime name needed here
@@ -1195,6 +1195,12 @@ pub(crate) struct OuterIdentIsNotPubliclyReexported {
     pub(crate) outer_ident: Ident,
 }
 
+#[derive(Diagnostic)]
+#[diag(resolve_couldnt_dump_import_graph)]
+pub(crate) struct CouldntDumpImportGraph {
+    pub(crate) error: String,
+}
+
 #[derive(Subdiagnostic)]
 #[label(resolve_constructor_private_if_any_field_private)]
 pub(crate) struct ConstructorPrivateIfAnyFieldPrivate {
@@ -0,0 +1,300 @@
+//! The module dependency graph written by `-Zdump-import-graph`.
+//!
+//! Every `use` item and every multi-segment path that resolves to an item of the local crate adds
+//! an edge from the module containing it to the module the named item is defined in. Edges of
+//! `use` items also record the modules that re-exported the item on the way to its definition,
+//! so that a dependency on a facade module can be told apart from one on the module behind it.
+//! Block modules are attributed to the `mod` item they are nested in.
+
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_hir::def::PartialRes;
+use rustc_hir::def_id::{DefId, LOCAL_CRATE, LocalDefId};
+use rustc_session::config::SwitchWithOptPath;
+use rustc_span::Span;
+use serde::Serialize;
+
+use crate::imports::{Import, ImportKind};
+use crate::{
+    Module, ModuleOrUniformRoot, NameBinding, NameBindingKind, Res, Resolver, Segment, errors,
+    module_to_string, names_to_string,
+};
+
+/// The edges recorded so far, in the order they were resolved.
+#[derive(Default)]
+pub(crate) struct ImportGraph {
+    edges: Vec<ImportGraphEdge>,
+}
+
+struct ImportGraphEdge {
+    from: LocalDefId,
+    to: LocalDefId,
+    kind: EdgeKind,
+    /// The path as written, e.g. `crate::a::Foo` or `super::b::*`.
+    path: String,
+    span: Span,
+    /// The modules that the imported item was re-exported through after `to`, ending with the
+    /// module that defines it.
+    reexport_chain: Vec<LocalDefId>,
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum EdgeKind {
+    /// A single `use` import.
+    Use,
+    /// A glob import.
+    Glob,
+    /// A path outside of `use` items.
+    Path,
+}
+
+impl<'ra, 'tcx> Resolver<'ra, 'tcx> {
+    /// Returns the `mod` item (or crate root) that the local definition `res` refers to belongs
+    /// to, or the module itself if `res` is one.
+    fn import_graph_module_of_res(&self, res: Res) -> Option<LocalDefId> {
+        let Res::Def(_, def_id) = res else { return None };
+        let def_id = def_id.as_local()?;
+        Some(self.import_graph_module(self.get_nearest_non_block_module(def_id.to_def_id())))
+    }
+
+    fn import_graph_module(&self, module: Module<'ra>) -> LocalDefId {
+        module.nearest_parent_mod().expect_local()
+    }
+
+    /// Returns the modules a binding passes through, starting with the module of the first
+    /// re-export and ending with the module that defines the item. Stops at the first module of
+    /// another crate.
+    fn import_graph_hops(&self, mut binding: NameBinding<'ra>) -> Vec<LocalDefId> {
+        let mut hops: Vec<LocalDefId> = Vec::new();
+        let mut push = |hop: Option<LocalDefId>| {
+            let Some(hop) = hop else { return false };
+            if hops.last() != Some(&hop) {
+                hops.push(hop);
+            }
+            true
+        };
+        loop {
+            match binding.kind {
+                NameBindingKind::Import { binding: source, import } => {
+                    let module = import.parent_scope.module.nearest_parent_mod();
+                    if !push(module.as_local()) {
+                        break;
+                    }
+                    binding = source;
+                }
+                NameBindingKind::Res(res) => {
+                    push(self.import_graph_module_of_res(res));
+                    break;
+                }
+            }
+        }
+        hops
+    }
+
+    /// Records the edge created by a successfully resolved import.
+    pub(crate) fn record_import_graph_import(&mut self, import: Import<'ra>) {
+        if self.import_graph.is_none() {
+            return;
+        }
+
+        let from = self.import_graph_module(import.parent_scope.module);
+        let module_path = || import.module_path.iter().map(|seg| seg.ident.name);
+        let (kind, path, hops) = match import.kind {
+            ImportKind::Single { source, ref bindings, .. } => {
+                // The first namespace the import resolved in is enough to find its module, the
+                // item in another namespace is almost always defined next to it.
+                let Some(binding) = bindings.iter().find_map(|binding| binding.get().binding())
+                else {
+                    return;
+                };
+                let NameBindingKind::Import { binding: source_binding, .. } = binding.kind else {
+                    return;
+                };
+                let path = names_to_string(module_path().chain([source.name]));
+                (EdgeKind::Use, path, self.import_graph_hops(source_binding))
+            }
+            ImportKind::Glob { .. } => {
+                let Some(ModuleOrUniformRoot::Module(module)) = import.imported_module.get() else {
+                    return;
+                };
+                let Some(def_id) = module.opt_def_id().and_then(DefId::as_local) else { return };
+                let to = self.import_graph_module(self.expect_module(def_id.to_def_id()));
+                let mut path = names_to_string(module_path());
+                path.push_str("::*");
+                (EdgeKind::Glob, path, vec![to])
+            }
+            ImportKind::ExternCrate { .. }
+            | ImportKind::MacroUse { .. }
+            | ImportKind::MacroExport => {
+                return;
+            }
+        };
+
+        self.push_import_graph_edge(from, kind, path, import.span, hops);
+    }
+
+    /// Records the edge created by a path outside of `use` items. Single-segment paths are
+    /// skipped, as they either name an item of the same module or one brought into scope by an
+    /// import, which has an edge of its own.
+    pub(crate) fn record_import_graph_path(
+        &mut self,
+        module: Module<'ra>,
+        path: &[Segment],
+        partial_res: PartialRes,
+        span: Span,
+    ) {
+        if self.import_graph.is_none() || path.len() < 2 {
+            return;
+        }
+        let Some(to) = self.import_graph_module_of_res(partial_res.base_res()) else { return };
+        let from = self.import_graph_module(module);
+        self.push_import_graph_edge(
+            from,
+            EdgeKind::Path,
+            Segment::names_to_string(path),
+            span,
+            vec![to],
+        );
+    }
+
+    fn push_import_graph_edge(
+        &mut self,
+        from: LocalDefId,
+        kind: EdgeKind,
+        path: String,
+        span: Span,
+        hops: Vec<LocalDefId>,
+    ) {
+        let Some((&to, reexport_chain)) = hops.split_first() else { return };
+        if from == to {
+            return;
+        }
+        let reexport_chain = reexport_chain.to_vec();
+        let graph = self.import_graph.as_mut().unwrap();
+        graph.edges.push(ImportGraphEdge { from, to, kind, path, span, reexport_chain });
+    }
+
+    /// Writes the graph as `<crate>.import_graph.json` and `<crate>.import_graph.dot` into the
+    /// directory given to `-Zdump-import-graph`, or the current directory.
+    pub(crate) fn dump_import_graph(&mut self) {
+        let Some(graph) = self.import_graph.take() else { return };
+        let SwitchWithOptPath::Enabled(ref dir) =
+            self.tcx.sess.opts.unstable_opts.dump_import_graph
+        else {
+            return;
+        };
+        if let Err(err) = self.write_import_graph(graph, dir.as_deref()) {
+            self.dcx().emit_err(errors::CouldntDumpImportGraph { error: err.to_string() });
+        }
+    }
+
+    fn write_import_graph(
+        &self,
+        graph: ImportGraph,
+        dir: Option<&Path>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let dir = if let Some(dir) = dir {
+            fs::create_dir_all(dir)?;
+            dir
+        } else {
+            Path::new(".")
+        };
+
+        let module_name =
+            |def_id: LocalDefId| match module_to_string(self.expect_module(def_id.to_def_id())) {
+                Some(name) => format!("crate::{name}"),
+                None => "crate".to_owned(),
+            };
+        let mut modules: Vec<_> = self
+            .local_modules
+            .iter()
+            .filter(|module| module.is_normal())
+            .map(|&module| module_name(self.import_graph_module(module)))
+            .collect();
+        modules.sort();
+        modules.dedup();
+
+        #[derive(Serialize)]
+        struct Edge<'a> {
+            from: &'a str,
+            to: &'a str,
+            kind: EdgeKind,
+            path: String,
+            span: String,
+            #[serde(skip_serializing_if = "Vec::is_empty")]
+            reexport_chain: Vec<String>,
+        }
+
+        let source_map = self.tcx.sess.source_map();
+        let names: FxIndexMap<LocalDefId, String> = graph
+            .edges
+            .iter()
+            .flat_map(|edge| [edge.from, edge.to].into_iter().chain(edge.reexport_chain.clone()))
+            .map(|def_id| (def_id, module_name(def_id)))
+            .collect();
+        let edges: Vec<_> = graph
+            .edges
+            .into_iter()
+            .map(|edge| Edge {
+                from: &names[&edge.from],
+                to: &names[&edge.to],
+                kind: edge.kind,
+                path: edge.path,
+                span: source_map.span_to_diagnostic_string(edge.span),
+                reexport_chain: edge
+                    .reexport_chain
+                    .iter()
+                    .map(|def_id| names[def_id].clone())
+                    .collect(),
+            })
+            .collect();
+
+        #[derive(Serialize)]
+        struct Report<'a> {
+            #[serde(rename = "crate")]
+            krate: &'a str,
+            modules: &'a [String],
+            edges: &'a [Edge<'a>],
+        }
+
+        let crate_name = self.tcx.crate_name(LOCAL_CRATE);
+        let crate_name = crate_name.as_str();
+
+        let path = dir.join(format!("{crate_name}.import_graph.json"));
+        let mut file = File::create_buffered(path)?;
+        serde_json::to_writer_pretty(
+            &mut file,
+            &Report { krate: crate_name, modules: &modules, edges: &edges },
+        )?;
+        file.flush()?;
+
+        // The Graphviz output has one edge per pair of modules, labelled with how many `use`
+        // items and paths create it.
+        let mut counts: FxIndexMap<(&str, &str), [usize; 3]> = FxIndexMap::default();
+        for edge in &edges {
+            counts.entry((edge.from, edge.to)).or_default()[edge.kind as usize] += 1;
+        }
+        let mut dot = String::new();
+        writeln!(dot, "digraph {crate_name:?} {{")?;
+        for module in &modules {
+            writeln!(dot, "    {module:?};")?;
+        }
+        for ((from, to), [uses, globs, paths]) in counts {
+            let label = [(uses, "use"), (globs, "glob"), (paths, "path")]
+                .into_iter()
+                .filter(|&(count, _)| count > 0)
+                .map(|(count, kind)| format!("{kind}: {count}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(dot, "    {from:?} -> {to:?} [label={label:?}];")?;
+        }
+        writeln!(dot, "}}")?;
+        fs::write(dir.join(format!("{crate_name}.import_graph.dot")), dot)?;
+        Ok(())
+    }
+}
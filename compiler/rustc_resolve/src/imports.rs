@@ -594,7 +594,10 @@ pub(crate) fn finalize_imports(&mut self) {
             // resolution for it so that later resolve stages won't complain.
             self.import_dummy_binding(*import, is_indeterminate);
 
-            let Some(err) = unresolved_import_error else { continue };
+            let Some(err) = unresolved_import_error else {
+                self.record_import_graph_import(*import);
+                continue;
+            };
 
             glob_error |= import.is_glob();
 
@@ -4584,6 +4584,7 @@ fn smart_resolve_path_fragment(
         if record_partial_res == RecordPartialRes::Yes {
             // Avoid recording definition of `A::B` in `<T as A>::B::C`.
             self.r.record_partial_res(node_id, partial_res);
+            self.r.record_import_graph_path(self.parent_scope.module, path, partial_res, path_span);
             self.resolve_elided_lifetimes_in_path(partial_res, path, source, path_span);
             self.lint_unused_qualifications(path, ns, finalize);
         }
@@ -33,6 +33,7 @@
 use diagnostics::{ImportSuggestion, LabelSuggestion, Suggestion};
 use effective_visibilities::EffectiveVisibilitiesVisitor;
 use errors::{ParamKindInEnumDiscriminant, ParamKindInNonTrivialAnonConst};
+use import_graph::ImportGraph;
 use imports::{Import, ImportData, ImportKind, NameResolution, PendingBinding};
 use late::{
     ForwardGenericParamBanReason, HasGenericParams, PathSource, PatternSource,
@@ -89,6 +90,7 @@
 mod effective_visibilities;
 mod errors;
 mod ident;
+mod import_graph;
 mod imports;
 mod late;
 mod macros;
@@ -1286,6 +1288,8 @@ pub struct Resolver<'ra, 'tcx> {
     delegation_infos: LocalDefIdMap<DelegationInfo>,
 
     main_def: Option<MainDefinition> = None,
+    /// Module dependencies collected for `-Zdump-import-graph`.
+    import_graph: Option<ImportGraph>,
     trait_impls: FxIndexMap<DefId, Vec<LocalDefId>>,
     /// A list of proc macro LocalDefIds, written out in the order in which
     /// they are declared in the static array generated by proc_macro_harness.
@@ -1687,6 +1691,13 @@ pub fn new(
             invocation_parents,
             item_generics_num_lifetimes: Default::default(),
             trait_impls: Default::default(),
+            import_graph: tcx
+                .sess
+                .opts
+                .unstable_opts
+                .dump_import_graph
+                .enabled()
+                .then(ImportGraph::default),
             confused_type_with_std_module: Default::default(),
             lifetime_elision_allowed: Default::default(),
             stripped_cfg_items: Default::default(),
@@ -1903,6 +1914,7 @@ pub fn resolve_crate(&mut self, krate: &Crate) {
                 .sess
                 .time("finalize_macro_resolutions", || self.finalize_macro_resolutions(krate));
             self.tcx.sess.time("late_resolve_crate", || self.late_resolve_crate(krate));
+            self.tcx.sess.time("dump_import_graph", || self.dump_import_graph());
             self.tcx.sess.time("resolve_main", || self.resolve_main());
             self.tcx.sess.time("resolve_check_unused", || self.check_unused(krate));
             self.tcx.sess.time("resolve_report_errors", || self.report_errors(krate));
@@ -686,25 +686,45 @@ fn smart_resolve_macro_path(
             feature_err(&self.tcx.sess, sym::custom_inner_attributes, path.span, msg).emit();
         }
 
-        const DIAG_ATTRS: &[Symbol] =
-            &[sym::on_unimplemented, sym::do_not_recommend, sym::on_const];
+        const DIAG_ATTRS: &[Symbol] = &[
+            sym::on_unimplemented,
+            sym::do_not_recommend,
+            sym::on_const,
+            sym::on_move,
+            sym::on_unknown_method,
+            sym::on_type_mismatch,
+        ];
 
         if res == Res::NonMacroAttr(NonMacroAttrKind::Tool)
             && let [namespace, attribute, ..] = &*path.segments
             && namespace.ident.name == sym::diagnostic
-            && !DIAG_ATTRS.contains(&attribute.ident.name)
         {
             let span = attribute.span();
+            let feature = match attribute.ident.name {
+                sym::on_move => Some(sym::diagnostic_on_move),
+                sym::on_unknown_method => Some(sym::diagnostic_on_unknown_method),
+                sym::on_type_mismatch => Some(sym::diagnostic_on_type_mismatch),
+                _ => None,
+            };
 
-            let typo = find_best_match_for_name(DIAG_ATTRS, attribute.ident.name, Some(5))
-                .map(|typo_name| errors::UnknownDiagnosticAttributeTypoSugg { span, typo_name });
+            if !DIAG_ATTRS.contains(&attribute.ident.name) {
+                let typo = find_best_match_for_name(DIAG_ATTRS, attribute.ident.name, Some(5)).map(
+                    |typo_name| errors::UnknownDiagnosticAttributeTypoSugg { span, typo_name },
+                );
 
-            self.tcx.sess.psess.buffer_lint(
-                UNKNOWN_DIAGNOSTIC_ATTRIBUTES,
-                span,
-                node_id,
-                errors::UnknownDiagnosticAttribute { typo },
-            );
+                self.tcx.sess.psess.buffer_lint(
+                    UNKNOWN_DIAGNOSTIC_ATTRIBUTES,
+                    span,
+                    node_id,
+                    errors::UnknownDiagnosticAttribute { typo },
+                );
+            } else if let Some(feature) = feature
+                && !self.tcx.features().enabled(feature)
+                && !span.allows_unstable(feature)
+            {
+                let msg = format!("the `#[diagnostic::{}]` attribute is unstable", attribute.ident);
+                feature_err(&self.tcx.sess, feature, span, msg).emit();
+            }
         }
 
         Ok((ext, res))
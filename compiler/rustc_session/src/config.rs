@@ -791,6 +791,15 @@ pub fn default_filename(&self) -> &'static str {
         is_text: false,
         compatible_with_cgus_and_single_output: false
     },
+    #[doc = "A JSON report of the code size of each function in the produced objects."]
+    SizeReport => {
+        shorthand: "size-report",
+        extension: "size.json",
+        description: "Generates a JSON file with the code size of each function, aggregated by crate and by generic definition",
+        default_filename: "CRATE_NAME.size.json",
+        is_text: true,
+        compatible_with_cgus_and_single_output: true
+    },
     #[doc = "This is the summary or index data part of the ThinLTO bitcode."]
     ThinLinkBitcode => {
         shorthand: "thin-link-bitcode",
@@ -884,6 +893,7 @@ pub fn should_codegen(&self) -> bool {
             | OutputType::LlvmAssembly
             | OutputType::Mir
             | OutputType::Object
+            | OutputType::SizeReport
             | OutputType::Exe => true,
             OutputType::Metadata | OutputType::DepInfo => false,
         })
@@ -899,6 +909,7 @@ pub fn should_link(&self) -> bool {
             | OutputType::Mir
             | OutputType::Metadata
             | OutputType::Object
+            | OutputType::SizeReport
             | OutputType::DepInfo => false,
             OutputType::Exe => true,
         })
@@ -1472,6 +1483,7 @@ pub fn dcx_flags(&self, can_emit_warnings: bool) -> DiagCtxtFlags {
             macro_backtrace: self.macro_backtrace,
             deduplicate_diagnostics: self.deduplicate_diagnostics,
             track_diagnostics: self.track_diagnostics,
+            collect_lint_diagnostics: self.edition_migration_report.is_some(),
         }
     }
 
@@ -2136,10 +2148,12 @@ fn parse_output_types(
                         display = OutputType::shorthands_display(),
                     ))
                 });
-                if output_type == OutputType::ThinLinkBitcode && !unstable_opts.unstable_options {
+                if matches!(output_type, OutputType::ThinLinkBitcode | OutputType::SizeReport)
+                    && !unstable_opts.unstable_options
+                {
                     early_dcx.early_fatal(format!(
                         "{} requested but -Zunstable-options not specified",
-                        OutputType::ThinLinkBitcode.shorthand()
+                        output_type.shorthand()
                     ));
                 }
                 output_types.insert(output_type, path);
@@ -2457,7 +2471,24 @@ pub fn build_session_options(early_dcx: &mut EarlyDiagCtxt, matches: &getopts::M
     let mut target_modifiers = BTreeMap::<OptionsTargetModifiers, String>::new();
 
     let mut unstable_opts = UnstableOptions::build(early_dcx, matches, &mut target_modifiers);
-    let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(early_dcx, matches);
+    let (mut lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(early_dcx, matches);
+
+    if let Some(target_edition) = unstable_opts.edition_migration_report {
+        if target_edition <= edition {
+            early_dcx.early_fatal(format!(
+                "`-Zedition-migration-report` must name an edition later than the crate's \
+                 edition ({edition}), but it was `{target_edition}`"
+            ));
+        }
+        // The report is built from the hits of the lints in the `rust-20xx-compatibility` group
+        // of the target edition, which is what `cargo fix --edition` force-warns as well.
+        lint_opts.push((target_edition.lint_name().to_owned(), lint::ForceWarn));
+    }
+
+    // Only the next trait solver builds proof trees.
+    if unstable_opts.dump_proof_trees.enabled() && !unstable_opts.next_solver.globally {
+        early_dcx.early_fatal("`-Zdump-proof-trees` requires `-Znext-solver`");
+    }
 
     if !unstable_opts.unstable_options && json_timings {
         early_dcx.early_fatal("--json=timings is unstable and requires using `-Zunstable-options`");
@@ -2490,6 +2521,12 @@ pub fn build_session_options(early_dcx: &mut EarlyDiagCtxt, matches: &getopts::M
 
     let incremental = cg.incremental.as_ref().map(PathBuf::from);
 
+    // The proof trees are recorded while reporting trait errors, which does not happen for the
+    // errors of queries loaded from the incremental cache.
+    if unstable_opts.dump_proof_trees.enabled() && incremental.is_some() {
+        early_dcx.early_fatal("`-Zdump-proof-trees` cannot be used with `-C incremental`");
+    }
+
     let assert_incr_state = parse_assert_incr_state(early_dcx, &unstable_opts.assert_incr_state);
 
     if cg.profile_generate.enabled() && cg.profile_use.is_some() {
@@ -3298,6 +3335,10 @@ pub enum ProcMacroExecutionStrategy {
 
     /// Run the proc-macro code on a different thread.
     CrossThread,
+
+    /// Run the proc-macro code in a separate process that is not allowed to access the network,
+    /// or any file that was not declared through `proc_macro::tracked::path`.
+    Sandboxed,
 }
 
 /// How to perform collapse macros debug info
@@ -21,7 +21,9 @@
 pub mod config;
 pub mod cstore;
 pub mod filesearch;
+pub mod macro_stats;
 mod macros;
+pub mod mir_pass_stats;
 mod options;
 pub mod search_paths;
 
@@ -29,6 +31,7 @@
 pub use session::*;
 
 pub mod output;
+pub mod proof_trees;
 
 pub use getopts;
 
@@ -0,0 +1,41 @@
+//! The costs of the macros used by the crate, recorded for `-Zmacro-stats`.
+
+use std::time::Duration;
+
+use rustc_span::hygiene::{ExpnKind, MacroKind};
+use rustc_span::{Span, Symbol};
+
+/// The cost of a macro, summed over all of its uses.
+#[derive(Default)]
+pub struct MacroStat {
+    /// Number of uses of the macro.
+    pub uses: usize,
+
+    /// Number of lines of code (when pretty-printed).
+    pub lines: usize,
+
+    /// Number of bytes of code (when pretty-printed).
+    pub bytes: usize,
+
+    /// Wall time spent expanding the macro, including the round-trips of proc macros to the
+    /// proc macro server.
+    pub time: Duration,
+
+    /// Number of HIR nodes whose span comes from an expansion of the macro, only counted with
+    /// `-Zmacro-stats-costs` or `-Zmacro-stats-json`.
+    pub hir_nodes: usize,
+
+    /// Time spent type checking the bodies whose span comes from an expansion of the macro, not
+    /// including the bodies they cause to be type checked. Only measured with
+    /// `-Zmacro-stats-costs` or `-Zmacro-stats-json`, and without incremental compilation.
+    pub typeck_time: Duration,
+}
+
+/// The name and kind of the macro whose expansion `span` comes from, if any, under which its
+/// costs are recorded.
+pub fn expanded_macro(span: Span) -> Option<(Symbol, MacroKind)> {
+    match span.ctxt().outer_expn_data().kind {
+        ExpnKind::Macro(kind, name) => Some((name, kind)),
+        _ => None,
+    }
+}
@@ -0,0 +1,60 @@
+//! The MIR passes that ran during the compilation, recorded for `-Zmir-pass-stats`.
+
+use std::time::Duration;
+
+use rustc_data_structures::fx::FxIndexMap;
+
+/// The effect of running a MIR pass on a single body.
+#[derive(Debug)]
+pub struct MirPassStats {
+    pub pass: &'static str,
+    /// The path of the function whose body the pass ran on.
+    pub body: String,
+    /// The number of statements, basic blocks and locals removed by the pass. These are negative
+    /// if the pass added some.
+    pub statements_removed: i64,
+    pub blocks_removed: i64,
+    pub locals_removed: i64,
+    pub time: Duration,
+}
+
+impl MirPassStats {
+    fn changed_body(&self) -> bool {
+        self.statements_removed != 0 || self.blocks_removed != 0 || self.locals_removed != 0
+    }
+}
+
+/// Prints the totals of each pass, followed by every run of a pass that changed a body.
+pub fn print_mir_pass_stats(stats: &[MirPassStats]) {
+    // First the totals of each pass, in the order in which the passes first ran.
+    let mut totals: FxIndexMap<&str, (usize, i64, i64, i64, Duration)> = FxIndexMap::default();
+    for s in stats.iter() {
+        let (runs, statements, blocks, locals, time) = totals.entry(s.pass).or_default();
+        *runs += 1;
+        *statements += s.statements_removed;
+        *blocks += s.blocks_removed;
+        *locals += s.locals_removed;
+        *time += s.time;
+    }
+    println!(
+        "mir-pass-stats {:<40} {:>8} {:>12} {:>10} {:>10} {:>12}",
+        "pass", "runs", "-statements", "-blocks", "-locals", "time"
+    );
+    for (pass, (runs, statements, blocks, locals, time)) in totals {
+        println!(
+            "mir-pass-stats {pass:<40} {runs:>8} {statements:>12} {blocks:>10} {locals:>10} \
+             {:>12}",
+            format!("{time:.2?}")
+        );
+    }
+
+    // Then each application of a pass that changed the size of a body.
+    for s in stats.iter().filter(|s| s.changed_body()) {
+        let MirPassStats { pass, body, statements_removed, blocks_removed, locals_removed, time } =
+            s;
+        println!(
+            "mir-pass-stats `{pass}` on `{body}`: {statements_removed} statements, \
+             {blocks_removed} blocks, {locals_removed} locals removed in {time:.2?}"
+        );
+    }
+}
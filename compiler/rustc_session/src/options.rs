@@ -841,6 +841,8 @@ mod desc {
         "one of: `disabled`, `trampolines`, or `aliases`";
     pub(crate) const parse_symbol_mangling_version: &str =
         "one of: `legacy`, `v0` (RFC 2603), or `hashed`";
+    pub(crate) const parse_opt_edition: &str =
+        "one of: `2015`, `2018`, `2021`, `2024`, or `future`";
     pub(crate) const parse_opt_symbol_visibility: &str =
         "one of: `hidden`, `protected`, or `interposable`";
     pub(crate) const parse_cargo_src_file_hash: &str =
@@ -871,7 +873,7 @@ mod desc {
         "one of (`none` (default), `basic`, `strong`, or `all`)";
     pub(crate) const parse_branch_protection: &str = "a `,` separated combination of `bti`, `gcs`, `pac-ret`, (optionally with `pc`, `b-key`, `leaf` if `pac-ret` is set)";
     pub(crate) const parse_proc_macro_execution_strategy: &str =
-        "one of supported execution strategies (`same-thread`, or `cross-thread`)";
+        "one of supported execution strategies (`same-thread`, `cross-thread`, or `sandboxed`)";
     pub(crate) const parse_remap_path_scope: &str = "comma separated list of scopes: `macro`, `diagnostics`, `debuginfo`, `coverage`, `object`, `all`";
     pub(crate) const parse_inlining_threshold: &str =
         "either a boolean (`yes`, `no`, `on`, `off`, etc), or a non-negative number";
@@ -1402,6 +1404,16 @@ pub(crate) fn parse_opt_symbol_visibility(
         true
     }
 
+    pub(crate) fn parse_opt_edition(slot: &mut Option<Edition>, v: Option<&str>) -> bool {
+        match v.map(Edition::from_str) {
+            Some(Ok(edition)) => {
+                *slot = Some(edition);
+                true
+            }
+            _ => false,
+        }
+    }
+
     pub(crate) fn parse_unpretty(slot: &mut Option<String>, v: Option<&str>) -> bool {
         match v {
             None => false,
@@ -1983,6 +1995,7 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         *slot = match v {
             Some("same-thread") => ProcMacroExecutionStrategy::SameThread,
             Some("cross-thread") => ProcMacroExecutionStrategy::CrossThread,
+            Some("sandboxed") => ProcMacroExecutionStrategy::Sandboxed,
             _ => return false,
         };
         true
@@ -2308,6 +2321,14 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
     dump_dep_graph: bool = (false, parse_bool, [UNTRACKED],
         "dump the dependency graph to $RUST_DEP_GRAPH (default: /tmp/dep_graph.gv) \
         (default: no)"),
+    dump_import_graph: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
+        parse_switch_with_opt_path, [UNTRACKED],
+        "write the module dependency graph found by name resolution, with the spans of the \
+        `use` items and paths that create each edge, as JSON and Graphviz files"),
+    dump_macro_rules_trace: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
+        parse_switch_with_opt_path, [UNTRACKED],
+        "write a JSON tree of every `macro_rules!` expansion, including the arms tried, \
+        why they failed, the bound metavariables and the produced tokens"),
     dump_mir: Option<String> = (None, parse_opt_string, [UNTRACKED],
         "dump MIR state to file.
         `val` is used to select which passes and functions to dump. For example:
@@ -2331,6 +2352,10 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
         "output statistics about monomorphization collection"),
     dump_mono_stats_format: DumpMonoStatsFormat = (DumpMonoStatsFormat::Markdown, parse_dump_mono_stats, [UNTRACKED],
         "the format to use for -Z dump-mono-stats (`markdown` (default) or `json`)"),
+    dump_proof_trees: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
+        parse_switch_with_opt_path, [UNTRACKED],
+        "write the proof tree of every trait error reported by the next trait solver, with the \
+        candidates considered, why they failed and their nested goals, as JSON and HTML files"),
     #[rustc_lint_opt_deny_field_access("use `Session::dwarf_version` instead of this field")]
     dwarf_version: Option<u32> = (None, parse_opt_number, [TRACKED],
         "version of DWARF debug information to emit (default: 2 or 4, depending on platform)"),
@@ -2339,6 +2364,10 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
     eagerly_emit_delayed_bugs: bool = (false, parse_bool, [UNTRACKED],
         "emit delayed bugs eagerly as errors instead of stashing them and emitting \
         them only if an error has not been emitted"),
+    edition_migration_report: Option<Edition> = (None, parse_opt_edition, [TRACKED_NO_CRATE_HASH],
+        "force-warn all migration lints of the given edition and write a report of their hits, \
+        classified by whether they can be fixed automatically and whether the code changes \
+        meaning, to `<crate_name>.edition_migration.json` in the output directory"),
     ehcont_guard: bool = (false, parse_bool, [TRACKED],
         "generate Windows EHCont Guard tables"),
     embed_metadata: bool = (true, parse_bool, [TRACKED],
@@ -2441,6 +2470,9 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
         "seed layout randomization"),
     link_directives: bool = (true, parse_bool, [TRACKED],
         "honor #[link] directives in the compiled crate (default: yes)"),
+    link_map_report: bool = (false, parse_bool, [UNTRACKED],
+        "ask the linker for a link map and write a report of the retained and discarded sections \
+        next to the output (default: no)"),
     link_native_libraries: bool = (true, parse_bool, [UNTRACKED],
         "link native libraries in the linker invocation (default: yes)"),
     link_only: bool = (false, parse_bool, [TRACKED],
@@ -2466,6 +2498,13 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
         "show macro backtraces (default: no)"),
     macro_stats: bool = (false, parse_bool, [UNTRACKED],
         "print some statistics about macro expansions (default: no)"),
+    macro_stats_costs: bool = (false, parse_bool, [UNTRACKED],
+        "with `-Z macro-stats`, also print the time spent expanding each macro, and the HIR nodes \
+        and type checking time of its expansions (default: no)"),
+    macro_stats_json: bool = (false, parse_bool, [UNTRACKED],
+        "with `-Z macro-stats`, also write the statistics, including the costs of \
+        `-Z macro-stats-costs`, as JSON to `<crate_name>.macro_stats.json` in the output \
+        directory (default: no)"),
     maximal_hir_to_mir_coverage: bool = (false, parse_bool, [TRACKED],
         "save as much information as possible about the correspondence between MIR and HIR \
         as source scopes (default: no)"),
@@ -2489,9 +2528,17 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
     mir_include_spans: MirIncludeSpans = (MirIncludeSpans::default(), parse_mir_include_spans, [UNTRACKED],
         "include extra comments in mir pretty printing, like line numbers and statement indices, \
          details about types, etc. (boolean for all passes, 'nll' to enable in NLL MIR only, default: 'nll')"),
+    mir_opt_bisect_filter: Option<String> = (None, parse_opt_string, [TRACKED],
+        "only apply `-Zmir-opt-bisect-limit` to the functions whose path contains this string"),
+    mir_opt_bisect_limit: Option<usize> = (None, parse_opt_number, [TRACKED],
+        "only run the first N MIR optimization passes on each function, and print which passes \
+        are run or skipped on which function (default: no limit)"),
     #[rustc_lint_opt_deny_field_access("use `Session::mir_opt_level` instead of this field")]
     mir_opt_level: Option<usize> = (None, parse_opt_number, [TRACKED],
         "MIR optimization level (0-4; default: 1 in non optimized builds and 2 in optimized builds)"),
+    mir_pass_stats: bool = (false, parse_bool, [UNTRACKED],
+        "print how many statements, blocks and locals each MIR pass removes from each function, \
+        and the time spent in it (default: no)"),
     mir_preserve_ub: bool = (false, parse_bool, [TRACKED],
         "keep place mention statements and reads in trivial SwitchInt terminators, which are interpreted \
         e.g., by miri; implies -Zmir-opt-level=0 (default: no)"),
@@ -2591,6 +2638,9 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
         "enable queries of the dependency graph for regression testing (default: no)"),
     randomize_layout: bool = (false, parse_bool, [TRACKED],
         "randomize the layout of types (default: no)"),
+    record_upstream_uses: bool = (false, parse_bool, [TRACKED],
+        "record the items of upstream crates used by this crate in its metadata, so that \
+        `-Z report-unused-workspace-items` can find unused public items (default: no)"),
     reg_struct_return: bool = (false, parse_bool, [TRACKED TARGET_MODIFIER],
         "On x86-32 targets, it overrides the default ABI to return small structs in registers.
         It is UNSOUND to link together crates that use different values for this flag!"),
@@ -2608,6 +2658,9 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
     remark_dir: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
         "directory into which to write optimization remarks (if not specified, they will be \
 written to standard error output)"),
+    report_unused_workspace_items: bool = (false, parse_bool, [TRACKED],
+        "warn about public items of upstream crates compiled with `-Z record-upstream-uses` \
+        that no crate in the crate graph uses (default: no)"),
     retpoline: bool = (false, parse_bool, [TRACKED TARGET_MODIFIER],
         "enables retpoline-indirect-branches and retpoline-indirect-calls target features (default: no)"),
     retpoline_external_thunk: bool = (false, parse_bool, [TRACKED TARGET_MODIFIER],
@@ -2740,6 +2793,9 @@ pub(crate) fn parse_align(slot: &mut Option<Align>, v: Option<&str>) -> bool {
         "in diagnostics, use heuristics to shorten paths referring to items"),
     tune_cpu: Option<String> = (None, parse_opt_string, [TRACKED],
         "select processor to schedule for (`rustc --print target-cpus` for details)"),
+    typed_holes: bool = (false, parse_bool, [TRACKED],
+        "report the type expected of `todo!()` and `_` in expression position, along with the \
+        bindings in scope and short expressions built from them that have that type (default: no)"),
     #[rustc_lint_opt_deny_field_access("use `TyCtxt::use_typing_mode_borrowck` instead of this field")]
     typing_mode_borrowck: bool = (false, parse_bool, [TRACKED],
         "enable `TypingMode::Borrowck`, changing the way opaque types are handled during MIR borrowck"),
@@ -0,0 +1,53 @@
+//! The proof trees of reported trait errors, recorded for `-Zdump-proof-trees`.
+
+use rustc_span::Span;
+
+/// The proof tree of the root obligation of a reported trait error.
+#[derive(Debug)]
+pub struct FailedObligationProofTree {
+    pub span: Span,
+    /// The item whose body or signature required the obligation.
+    pub item: String,
+    pub root: ProofTreeGoal,
+}
+
+/// A goal of a [`FailedObligationProofTree`] and the candidates that were considered to prove it.
+#[derive(Debug)]
+pub struct ProofTreeGoal {
+    pub goal: String,
+    /// Why the goal was required by its parent, e.g. `impl where-bound`.
+    pub source: &'static str,
+    /// One of `yes`, `ambiguous`, `overflow` or `no`.
+    pub result: &'static str,
+    pub candidates: Vec<ProofTreeCandidate>,
+    /// Set if the candidates of the goal were not expanded.
+    pub cut_off: Option<ProofTreeCutOff>,
+}
+
+#[derive(Debug)]
+pub struct ProofTreeCandidate {
+    /// What the candidate is, e.g. `impl<T> Clone for Vec<T>` or `where-bound`.
+    pub kind: String,
+    pub span: Option<Span>,
+    pub result: &'static str,
+    /// The goals the candidate requires to hold. A failing candidate without a failing nested
+    /// goal does not apply to the goal at all.
+    pub nested: Vec<ProofTreeGoal>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum ProofTreeCutOff {
+    /// The goal is already being proven by one of its ancestors.
+    Cycle,
+    /// The tree is too deep.
+    Depth,
+}
+
+impl ProofTreeCutOff {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProofTreeCutOff::Cycle => "cycle",
+            ProofTreeCutOff::Depth => "depth",
+        }
+    }
+}
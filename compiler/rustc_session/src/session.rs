@@ -25,6 +25,7 @@
 use rustc_macros::HashStable_Generic;
 pub use rustc_span::def_id::StableCrateId;
 use rustc_span::edition::Edition;
+use rustc_span::hygiene::MacroKind;
 use rustc_span::source_map::{FilePathMapping, SourceMap};
 use rustc_span::{RealFileName, Span, Symbol};
 use rustc_target::asm::InlineAsmArch;
@@ -42,7 +43,10 @@
 };
 use crate::filesearch::FileSearch;
 use crate::lint::LintId;
+use crate::macro_stats::MacroStat;
+use crate::mir_pass_stats::MirPassStats;
 use crate::parse::{ParseSess, add_feature_diagnostics};
+use crate::proof_trees::FailedObligationProofTree;
 use crate::search_paths::SearchPath;
 use crate::{errors, filesearch, lint};
 
@@ -106,6 +110,15 @@ pub struct Session {
     /// Data about code being compiled, gathered during compilation.
     pub code_stats: CodeStats,
 
+    /// The macros expanded when `-Zmacro-stats` is enabled, by name and kind.
+    pub macro_stats: Lock<FxHashMap<(Symbol, MacroKind), MacroStat>>,
+
+    /// The MIR passes that ran, in order, when `-Zmir-pass-stats` is enabled.
+    pub mir_pass_stats: Lock<Vec<MirPassStats>>,
+
+    /// The proof trees of the trait errors reported when `-Zdump-proof-trees` is enabled.
+    pub proof_trees: Lock<Vec<FailedObligationProofTree>>,
+
     /// This only ever stores a `LintStore` but we don't want a dependency on that type here.
     pub lint_store: Option<Arc<dyn DynLintStore>>,
 
@@ -559,6 +572,12 @@ pub fn verbose_internals(&self) -> bool {
         self.opts.unstable_opts.verbose_internals
     }
 
+    /// Whether `-Zmacro-stats` should also measure the costs of the expansions after expansion.
+    pub fn macro_stats_costs(&self) -> bool {
+        let opts = &self.opts.unstable_opts;
+        opts.macro_stats && (opts.macro_stats_costs || opts.macro_stats_json)
+    }
+
     pub fn print_llvm_stats(&self) -> bool {
         self.opts.unstable_opts.print_codegen_stats
     }
@@ -1079,6 +1098,9 @@ pub fn build_session(
         prof,
         timings,
         code_stats: Default::default(),
+        macro_stats: Default::default(),
+        mir_pass_stats: Default::default(),
+        proof_trees: Default::default(),
         lint_store: None,
         driver_lint_caps,
         ctfe_backtrace,
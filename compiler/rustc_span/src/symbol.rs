@@ -881,6 +881,9 @@
         diagnostic,
         diagnostic_namespace,
         diagnostic_on_const,
+        diagnostic_on_move,
+        diagnostic_on_type_mismatch,
+        diagnostic_on_unknown_method,
         dialect,
         direct,
         discriminant_kind,
@@ -1604,7 +1607,10 @@
         omit_gdb_pretty_printer_section,
         on,
         on_const,
+        on_move,
+        on_type_mismatch,
         on_unimplemented,
+        on_unknown_method,
         opaque,
         opaque_module_name_placeholder: "<opaque>",
         open_options_new,
@@ -1757,6 +1763,7 @@
         proc_macro_mod,
         proc_macro_non_items,
         proc_macro_path_invoc,
+        proc_macro_pure,
         process_abort,
         process_exit,
         profiler_builtins,
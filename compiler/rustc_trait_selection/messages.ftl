@@ -206,7 +206,7 @@ trait_selection_lifetime_param_suggestion = consider {$is_reuse ->
 }
 trait_selection_lifetime_param_suggestion_elided = each elided lifetime in input position becomes a distinct lifetime
 
-trait_selection_malformed_on_unimplemented_attr = malformed `on_unimplemented` attribute
+trait_selection_malformed_on_unimplemented_attr = malformed `{$attr_name}` attribute
     .help = only `message`, `note` and `label` are allowed as options
     .label = invalid option found here
 
@@ -214,7 +214,7 @@ trait_selection_meant_byte_literal = if you meant to write a byte literal, prefi
 trait_selection_meant_char_literal = if you meant to write a `char` literal, use single quotes
 trait_selection_meant_str_literal = if you meant to write a string literal, use double quotes
 trait_selection_mismatched_static_lifetime = incompatible lifetime on type
-trait_selection_missing_options_for_on_unimplemented_attr = missing options for `on_unimplemented` attribute
+trait_selection_missing_options_for_on_unimplemented_attr = missing options for `{$attr_name}` attribute
     .help = at least one of the `message`, `note` and `label` options are expected
 
 trait_selection_msl_introduces_static = introduces a `'static` lifetime requirement
@@ -456,7 +456,7 @@ trait_selection_types_declared_different = these two types are declared with dif
 
 trait_selection_unable_to_construct_constant_value = unable to construct a constant value for the unevaluated constant {$unevaluated}
 
-trait_selection_unknown_format_parameter_for_on_unimplemented_attr = there is no parameter `{$argument_name}` on trait `{$trait_name}`
+trait_selection_unknown_format_parameter_for_on_unimplemented_attr = there is no parameter `{$argument_name}` on {$item_kind} `{$item_name}`
     .help = expect either a generic argument name or {"`{Self}`"} as format argument
 
 trait_selection_warn_removing_apit_params_for_overcapture = you could use a `use<...>` bound to explicitly specify captures, but argument-position `impl Trait`s are not nameable
@@ -72,6 +72,7 @@
 use tracing::{debug, instrument};
 
 use crate::error_reporting::TypeErrCtxt;
+use crate::error_reporting::traits::on_unimplemented::OnUnimplementedNote;
 use crate::errors::{ObligationCauseFailureCode, TypeErrorAdditionalDiags};
 use crate::infer;
 use crate::infer::relate::{self, RelateResult, TypeRelation};
@@ -1913,9 +1914,37 @@ pub fn report_and_explain_type_error(
             false,
             None,
         );
+        self.note_on_type_mismatch(&mut diag, span, trace.values);
         diag
     }
 
+    /// Applies the `#[diagnostic::on_type_mismatch]` attribute of the expected type or, failing
+    /// that, of the found type to a type mismatch error.
+    fn note_on_type_mismatch(&self, diag: &mut Diag<'_>, span: Span, values: ValuePairs<'tcx>) {
+        let ValuePairs::Terms(ExpectedFound { expected, found }) = values else { return };
+        let (Some(expected), Some(found)) = (expected.as_type(), found.as_type()) else { return };
+        let (expected, found) = self.resolve_vars_if_possible((expected, found));
+        let Some(OnUnimplementedNote { message, label, notes, .. }) =
+            [expected, found].into_iter().find_map(|ty| {
+                let note =
+                    self.on_type_note(sym::on_type_mismatch, ty.peel_refs(), diag.long_ty_path());
+                (note.message.is_some() || note.label.is_some() || !note.notes.is_empty())
+                    .then_some(note)
+            })
+        else {
+            return;
+        };
+        if let Some(message) = message {
+            diag.primary_message(message);
+        }
+        if let Some(label) = label {
+            diag.span_label(span, label);
+        }
+        for note in notes {
+            diag.note(note);
+        }
+    }
+
     fn suggest_wrap_to_build_a_tuple(
         &self,
         span: Span,
@@ -878,7 +878,7 @@ fn report_host_effect_error(
                     {
                         let note = command.evaluate(
                             self.tcx,
-                            predicate.skip_binder().trait_ref,
+                            predicate.skip_binder().trait_ref.def_id,
                             &condition_options,
                             &format_args,
                         );
@@ -5,6 +5,7 @@
 pub mod on_unimplemented_condition;
 pub mod on_unimplemented_format;
 mod overflow;
+mod proof_tree;
 pub mod suggestions;
 
 use std::{fmt, iter};
@@ -257,6 +258,11 @@ enum ErrorSortKey {
                 if !suppressed && error.obligation.cause.span.from_expansion() == from_expansion {
                     if !error.references_error() {
                         let guar = self.report_fulfillment_error(error);
+                        if self.tcx.sess.opts.unstable_opts.dump_proof_trees.enabled()
+                            && self.next_trait_solver()
+                        {
+                            self.record_proof_tree(error);
+                        }
                         self.infcx.set_tainted_by_errors(guar);
                         reported = Some(guar);
                         // We want to ignore desugarings here: spans are equivalent even
@@ -11,11 +11,11 @@
 use rustc_macros::LintDiagnostic;
 use rustc_middle::bug;
 use rustc_middle::ty::print::PrintTraitRefExt;
-use rustc_middle::ty::{self, GenericArgsRef, GenericParamDef, GenericParamDefKind, TyCtxt};
+use rustc_middle::ty::{self, GenericArgsRef, GenericParamDef, GenericParamDefKind, Ty, TyCtxt};
 use rustc_session::lint::builtin::{
     MALFORMED_DIAGNOSTIC_ATTRIBUTES, MALFORMED_DIAGNOSTIC_FORMAT_LITERALS,
 };
-use rustc_span::{Span, Symbol, sym};
+use rustc_span::{Span, Symbol, kw, sym};
 use tracing::{debug, info};
 
 use super::{ObligationCauseCode, PredicateObligation};
@@ -108,17 +108,60 @@ pub fn on_unimplemented_note(
             self.on_unimplemented_components(trait_pred, obligation, long_ty_path);
         if let Ok(Some(command)) = OnUnimplementedDirective::of_item(self.tcx, trait_pred.def_id())
         {
-            command.evaluate(
-                self.tcx,
-                trait_pred.skip_binder().trait_ref,
-                &condition_options,
-                &format_args,
-            )
+            command.evaluate(self.tcx, trait_pred.def_id(), &condition_options, &format_args)
         } else {
             OnUnimplementedNote::default()
         }
     }
 
+    /// Evaluates the `#[diagnostic::$attr]` attribute of the struct, enum or union `ty`, where
+    /// `attr` is one of the attributes customizing errors about values of a type, like `on_move`.
+    pub fn on_type_note(
+        &self,
+        attr: Symbol,
+        ty: Ty<'tcx>,
+        long_ty_path: &mut Option<PathBuf>,
+    ) -> OnUnimplementedNote {
+        let ty::Adt(adt, args) = *ty.kind() else {
+            return OnUnimplementedNote::default();
+        };
+        let Ok(Some(command)) = OnUnimplementedDirective::of_adt(self.tcx, adt.did(), attr) else {
+            return OnUnimplementedNote::default();
+        };
+
+        let self_ty = self.tcx.short_string(ty, long_ty_path);
+        // Filters are not allowed in the diagnostic namespace, so these are never looked at.
+        let condition_options = ConditionOptions {
+            self_types: vec![self_ty.clone()],
+            from_desugaring: None,
+            cause: None,
+            crate_local: adt.did().is_local(),
+            direct: true,
+            generic_args: vec![],
+        };
+        // Unlike for traits, `Self` is not one of the generic parameters of the type.
+        let generic_args = iter::once((kw::SelfUpper, self_ty))
+            .chain(self.tcx.generics_of(adt.did()).own_params.iter().filter_map(|param| {
+                let arg = args[param.index as usize];
+                let value = match param.kind {
+                    GenericParamDefKind::Type { .. } => {
+                        self.tcx.short_string(arg.expect_ty(), long_ty_path)
+                    }
+                    GenericParamDefKind::Const { .. } => arg.to_string(),
+                    GenericParamDefKind::Lifetime => return None,
+                };
+                Some((param.name, value))
+            }))
+            .collect();
+        let format_args = FormatArgs {
+            this: self.tcx.def_path_str(adt.did()),
+            trait_sugared: None,
+            item_context: "",
+            generic_args,
+        };
+        command.evaluate(self.tcx, adt.did(), &condition_options, &format_args)
+    }
+
     pub(crate) fn on_unimplemented_components(
         &self,
         trait_pred: ty::PolyTraitPredicate<'tcx>,
@@ -313,7 +356,8 @@ pub(crate) fn on_unimplemented_components(
             })
             .collect();
 
-        let format_args = FormatArgs { this, trait_sugared, generic_args, item_context };
+        let format_args =
+            FormatArgs { this, trait_sugared: Some(trait_sugared), generic_args, item_context };
         (condition_options, format_args)
     }
 }
@@ -365,18 +409,21 @@ pub enum AppendConstMessage {
 pub struct MalformedOnUnimplementedAttrLint {
     #[label]
     pub span: Span,
+    pub attr_name: Symbol,
 }
 
 impl MalformedOnUnimplementedAttrLint {
-    pub fn new(span: Span) -> Self {
-        Self { span }
+    pub fn new(span: Span, attr_name: Symbol) -> Self {
+        Self { span, attr_name }
     }
 }
 
 #[derive(LintDiagnostic)]
 #[diag(trait_selection_missing_options_for_on_unimplemented_attr)]
 #[help]
-pub struct MissingOptionsForOnUnimplementedAttr;
+pub struct MissingOptionsForOnUnimplementedAttr {
+    pub attr_name: Symbol,
+}
 
 #[derive(LintDiagnostic)]
 #[diag(trait_selection_ignored_diagnostic_option)]
@@ -424,6 +471,7 @@ fn parse(
         span: Span,
         is_root: bool,
         is_diagnostic_namespace_variant: bool,
+        attr_name: Symbol,
     ) -> Result<Option<Self>, ErrorGuaranteed> {
         let mut errored = None;
         let mut item_iter = items.iter();
@@ -525,6 +573,7 @@ fn parse(
                         item.span(),
                         false,
                         is_diagnostic_namespace_variant,
+                        attr_name,
                     ) {
                         Ok(Some(subcommand)) => subcommands.push(subcommand),
                         Ok(None) => bug!(
@@ -553,7 +602,7 @@ fn parse(
                         MALFORMED_DIAGNOSTIC_ATTRIBUTES,
                         tcx.local_def_id_to_hir_id(def_id),
                         vec![item.span()],
-                        MalformedOnUnimplementedAttrLint::new(item.span()),
+                        MalformedOnUnimplementedAttrLint::new(item.span(), attr_name),
                     );
                 }
             } else {
@@ -590,79 +639,103 @@ pub fn of_item(tcx: TyCtxt<'tcx>, item_def_id: DefId) -> Result<Option<Self>, Er
             return Ok(None);
         };
         if let Some(attr) = tcx.get_attr(item_def_id, sym::rustc_on_unimplemented) {
-            return Self::parse_attribute(attr, false, tcx, item_def_id);
+            Self::parse_attribute(attr, false, tcx, item_def_id, sym::on_unimplemented)
         } else {
-            tcx.get_attrs_by_path(item_def_id, &[sym::diagnostic, attr])
-                .filter_map(|attr| Self::parse_attribute(attr, true, tcx, item_def_id).transpose())
-                .try_fold(None, |aggr: Option<Self>, directive| {
-                    let directive = directive?;
-                    if let Some(aggr) = aggr {
-                        let mut subcommands = aggr.subcommands;
-                        subcommands.extend(directive.subcommands);
-                        let mut notes = aggr.notes;
-                        notes.extend(directive.notes);
-                        IgnoredDiagnosticOption::maybe_emit_warning(
-                            tcx,
-                            item_def_id,
-                            directive.message.as_ref().map(|f| f.0),
-                            aggr.message.as_ref().map(|f| f.0),
-                            "message",
-                        );
-                        IgnoredDiagnosticOption::maybe_emit_warning(
-                            tcx,
-                            item_def_id,
-                            directive.label.as_ref().map(|f| f.0),
-                            aggr.label.as_ref().map(|f| f.0),
-                            "label",
-                        );
-                        IgnoredDiagnosticOption::maybe_emit_warning(
-                            tcx,
-                            item_def_id,
-                            directive.condition.as_ref().map(|i| i.span()),
-                            aggr.condition.as_ref().map(|i| i.span()),
-                            "condition",
-                        );
-                        IgnoredDiagnosticOption::maybe_emit_warning(
-                            tcx,
-                            item_def_id,
-                            directive.parent_label.as_ref().map(|f| f.span),
-                            aggr.parent_label.as_ref().map(|f| f.span),
-                            "parent_label",
-                        );
-                        IgnoredDiagnosticOption::maybe_emit_warning(
-                            tcx,
-                            item_def_id,
-                            directive.append_const_msg.as_ref().and_then(|c| {
-                                if let AppendConstMessage::Custom(_, s) = c {
-                                    Some(*s)
-                                } else {
-                                    None
-                                }
-                            }),
-                            aggr.append_const_msg.as_ref().and_then(|c| {
-                                if let AppendConstMessage::Custom(_, s) = c {
-                                    Some(*s)
-                                } else {
-                                    None
-                                }
-                            }),
-                            "append_const_msg",
-                        );
+            Self::of_diagnostic_attrs(tcx, item_def_id, attr)
+        }
+    }
 
-                        Ok(Some(Self {
-                            condition: aggr.condition.or(directive.condition),
-                            subcommands,
-                            message: aggr.message.or(directive.message),
-                            label: aggr.label.or(directive.label),
-                            notes,
-                            parent_label: aggr.parent_label.or(directive.parent_label),
-                            append_const_msg: aggr.append_const_msg.or(directive.append_const_msg),
-                        }))
-                    } else {
-                        Ok(Some(directive))
-                    }
-                })
+    /// Parses one of the attributes customizing errors about the values of a struct, enum or
+    /// union: `#[diagnostic::on_move]`, `#[diagnostic::on_unknown_method]` or
+    /// `#[diagnostic::on_type_mismatch]`. Unlike `on_unimplemented`, these are unstable, so
+    /// they are ignored on local types unless their feature is enabled.
+    pub fn of_adt(
+        tcx: TyCtxt<'tcx>,
+        adt_def_id: DefId,
+        attr: Symbol,
+    ) -> Result<Option<Self>, ErrorGuaranteed> {
+        let features = tcx.features();
+        let enabled = match attr {
+            sym::on_move => features.diagnostic_on_move(),
+            sym::on_unknown_method => features.diagnostic_on_unknown_method(),
+            sym::on_type_mismatch => features.diagnostic_on_type_mismatch(),
+            _ => bug!("`{attr}` is not a diagnostic attribute for types"),
+        };
+        if adt_def_id.is_local() && !enabled {
+            return Ok(None);
         }
+        Self::of_diagnostic_attrs(tcx, adt_def_id, attr)
+    }
+
+    /// Merges all the `#[diagnostic::$attr]` attributes of `item_def_id`, warning about options
+    /// that are given more than once.
+    fn of_diagnostic_attrs(
+        tcx: TyCtxt<'tcx>,
+        item_def_id: DefId,
+        attr: Symbol,
+    ) -> Result<Option<Self>, ErrorGuaranteed> {
+        tcx.get_attrs_by_path(item_def_id, &[sym::diagnostic, attr])
+            .filter_map(|a| Self::parse_attribute(a, true, tcx, item_def_id, attr).transpose())
+            .try_fold(None, |aggr: Option<Self>, directive| {
+                let directive = directive?;
+                if let Some(aggr) = aggr {
+                    let mut subcommands = aggr.subcommands;
+                    subcommands.extend(directive.subcommands);
+                    let mut notes = aggr.notes;
+                    notes.extend(directive.notes);
+                    IgnoredDiagnosticOption::maybe_emit_warning(
+                        tcx,
+                        item_def_id,
+                        directive.message.as_ref().map(|f| f.0),
+                        aggr.message.as_ref().map(|f| f.0),
+                        "message",
+                    );
+                    IgnoredDiagnosticOption::maybe_emit_warning(
+                        tcx,
+                        item_def_id,
+                        directive.label.as_ref().map(|f| f.0),
+                        aggr.label.as_ref().map(|f| f.0),
+                        "label",
+                    );
+                    IgnoredDiagnosticOption::maybe_emit_warning(
+                        tcx,
+                        item_def_id,
+                        directive.condition.as_ref().map(|i| i.span()),
+                        aggr.condition.as_ref().map(|i| i.span()),
+                        "condition",
+                    );
+                    IgnoredDiagnosticOption::maybe_emit_warning(
+                        tcx,
+                        item_def_id,
+                        directive.parent_label.as_ref().map(|f| f.span),
+                        aggr.parent_label.as_ref().map(|f| f.span),
+                        "parent_label",
+                    );
+                    IgnoredDiagnosticOption::maybe_emit_warning(
+                        tcx,
+                        item_def_id,
+                        directive.append_const_msg.as_ref().and_then(|c| {
+                            if let AppendConstMessage::Custom(_, s) = c { Some(*s) } else { None }
+                        }),
+                        aggr.append_const_msg.as_ref().and_then(|c| {
+                            if let AppendConstMessage::Custom(_, s) = c { Some(*s) } else { None }
+                        }),
+                        "append_const_msg",
+                    );
+
+                    Ok(Some(Self {
+                        condition: aggr.condition.or(directive.condition),
+                        subcommands,
+                        message: aggr.message.or(directive.message),
+                        label: aggr.label.or(directive.label),
+                        notes,
+                        parent_label: aggr.parent_label.or(directive.parent_label),
+                        append_const_msg: aggr.append_const_msg.or(directive.append_const_msg),
+                    }))
+                } else {
+                    Ok(Some(directive))
+                }
+            })
     }
 
     fn parse_attribute(
@@ -670,6 +743,7 @@ fn parse_attribute(
         is_diagnostic_namespace_variant: bool,
         tcx: TyCtxt<'tcx>,
         item_def_id: DefId,
+        attr_name: Symbol,
     ) -> Result<Option<Self>, ErrorGuaranteed> {
         let result = if let Some(items) = attr.meta_item_list() {
             Self::parse(
@@ -679,6 +753,7 @@ fn parse_attribute(
                 attr.span(),
                 true,
                 is_diagnostic_namespace_variant,
+                attr_name,
             )
         } else if let Some(value) = attr.value_str() {
             if !is_diagnostic_namespace_variant {
@@ -713,7 +788,7 @@ fn parse_attribute(
                         MALFORMED_DIAGNOSTIC_ATTRIBUTES,
                         tcx.local_def_id_to_hir_id(item_def_id),
                         report_span,
-                        MalformedOnUnimplementedAttrLint::new(report_span),
+                        MalformedOnUnimplementedAttrLint::new(report_span, attr_name),
                     );
                 }
                 Ok(None)
@@ -726,7 +801,7 @@ fn parse_attribute(
                             MALFORMED_DIAGNOSTIC_ATTRIBUTES,
                             tcx.local_def_id_to_hir_id(item_def_id),
                             attr.span(),
-                            MalformedOnUnimplementedAttrLint::new(attr.span()),
+                            MalformedOnUnimplementedAttrLint::new(attr.span(), attr_name),
                         );
                     }
                 }
@@ -736,7 +811,7 @@ fn parse_attribute(
                             MALFORMED_DIAGNOSTIC_ATTRIBUTES,
                             tcx.local_def_id_to_hir_id(item_def_id),
                             attr.span(),
-                            MissingOptionsForOnUnimplementedAttr,
+                            MissingOptionsForOnUnimplementedAttr { attr_name },
                         )
                     }
                 }
@@ -754,7 +829,7 @@ fn parse_attribute(
     pub(crate) fn evaluate(
         &self,
         tcx: TyCtxt<'tcx>,
-        trait_ref: ty::TraitRef<'tcx>,
+        item_def_id: DefId,
         condition_options: &ConditionOptions,
         args: &FormatArgs<'tcx>,
     ) -> OnUnimplementedNote {
@@ -764,8 +839,8 @@ pub(crate) fn evaluate(
         let mut parent_label = None;
         let mut append_const_msg = None;
         info!(
-            "evaluate({:?}, trait_ref={:?}, options={:?}, args ={:?})",
-            self, trait_ref, condition_options, args
+            "evaluate({:?}, item_def_id={:?}, options={:?}, args ={:?})",
+            self, item_def_id, condition_options, args
         );
 
         for command in self.subcommands.iter().chain(Some(self)).rev() {
@@ -795,10 +870,10 @@ pub(crate) fn evaluate(
         }
 
         OnUnimplementedNote {
-            label: label.map(|l| l.1.format(tcx, trait_ref, args)),
-            message: message.map(|m| m.1.format(tcx, trait_ref, args)),
-            notes: notes.into_iter().map(|n| n.format(tcx, trait_ref, args)).collect(),
-            parent_label: parent_label.map(|e_s| e_s.format(tcx, trait_ref, args)),
+            label: label.map(|l| l.1.format(tcx, item_def_id, args)),
+            message: message.map(|m| m.1.format(tcx, item_def_id, args)),
+            notes: notes.into_iter().map(|n| n.format(tcx, item_def_id, args)).collect(),
+            parent_label: parent_label.map(|e_s| e_s.format(tcx, item_def_id, args)),
             append_const_msg,
         }
     }
@@ -818,15 +893,20 @@ fn try_parse(
         Ok(result)
     }
 
-    fn verify(&self, tcx: TyCtxt<'tcx>, trait_def_id: DefId) -> Result<(), ErrorGuaranteed> {
-        if !tcx.is_trait(trait_def_id) {
+    fn verify(&self, tcx: TyCtxt<'tcx>, item_def_id: DefId) -> Result<(), ErrorGuaranteed> {
+        if !tcx.is_trait(item_def_id)
+            && !matches!(
+                tcx.def_kind(item_def_id),
+                DefKind::Struct | DefKind::Enum | DefKind::Union
+            )
+        {
             return Ok(());
         };
 
         let ctx = if self.is_diagnostic_namespace_variant {
-            Ctx::DiagnosticOnUnimplemented { tcx, trait_def_id }
+            Ctx::DiagnosticOnUnimplemented { tcx, item_def_id }
         } else {
-            Ctx::RustcOnUnimplemented { tcx, trait_def_id }
+            Ctx::RustcOnUnimplemented { tcx, item_def_id }
         };
 
         let mut result = Ok(());
@@ -838,7 +918,7 @@ fn verify(&self, tcx: TyCtxt<'tcx>, trait_def_id: DefId) -> Result<(), ErrorGuar
             Ok(FormatString { warnings, .. }) => {
                 if self.is_diagnostic_namespace_variant {
                     for w in warnings {
-                        w.emit_warning(tcx, trait_def_id)
+                        w.emit_warning(tcx, item_def_id)
                     }
                 } else {
                     for w in warnings {
@@ -878,10 +958,10 @@ fn verify(&self, tcx: TyCtxt<'tcx>, trait_def_id: DefId) -> Result<(), ErrorGuar
                 // if we encounter any error while processing we nevertheless want to show it as warning
                 // so that users are aware that something is not correct
                 if self.is_diagnostic_namespace_variant {
-                    if let Some(trait_def_id) = trait_def_id.as_local() {
+                    if let Some(item_def_id) = item_def_id.as_local() {
                         tcx.emit_node_span_lint(
                             MALFORMED_DIAGNOSTIC_FORMAT_LITERALS,
-                            tcx.local_def_id_to_hir_id(trait_def_id),
+                            tcx.local_def_id_to_hir_id(item_def_id),
                             self.span,
                             WrappedParserError { description: e.description, label: e.label },
                         );
@@ -898,17 +978,11 @@ fn verify(&self, tcx: TyCtxt<'tcx>, trait_def_id: DefId) -> Result<(), ErrorGuar
         result
     }
 
-    pub fn format(
-        &self,
-        tcx: TyCtxt<'tcx>,
-        trait_ref: ty::TraitRef<'tcx>,
-        args: &FormatArgs<'tcx>,
-    ) -> String {
-        let trait_def_id = trait_ref.def_id;
+    pub fn format(&self, tcx: TyCtxt<'tcx>, item_def_id: DefId, args: &FormatArgs<'tcx>) -> String {
         let ctx = if self.is_diagnostic_namespace_variant {
-            Ctx::DiagnosticOnUnimplemented { tcx, trait_def_id }
+            Ctx::DiagnosticOnUnimplemented { tcx, item_def_id }
         } else {
-            Ctx::RustcOnUnimplemented { tcx, trait_def_id }
+            Ctx::RustcOnUnimplemented { tcx, item_def_id }
         };
 
         // No point passing a snippet here, we already did that in `verify`
@@ -47,11 +47,14 @@ enum FormatArg {
     AsIs(String),
 }
 
+/// Where a format string comes from. `item_def_id` is the item the attribute is on, whose
+/// generic parameters can be used as format arguments: a trait for `on_unimplemented`, or a
+/// struct, enum or union for `on_move`, `on_unknown_method` and `on_type_mismatch`.
 pub enum Ctx<'tcx> {
     // `#[rustc_on_unimplemented]`
-    RustcOnUnimplemented { tcx: TyCtxt<'tcx>, trait_def_id: DefId },
+    RustcOnUnimplemented { tcx: TyCtxt<'tcx>, item_def_id: DefId },
     // `#[diagnostic::...]`
-    DiagnosticOnUnimplemented { tcx: TyCtxt<'tcx>, trait_def_id: DefId },
+    DiagnosticOnUnimplemented { tcx: TyCtxt<'tcx>, item_def_id: DefId },
 }
 
 #[derive(Debug)]
@@ -67,6 +70,7 @@ pub fn emit_warning<'tcx>(&self, tcx: TyCtxt<'tcx>, item_def_id: DefId) {
         match *self {
             FormatWarning::UnknownParam { argument_name, span } => {
                 let this = tcx.item_ident(item_def_id);
+                let item_kind = tcx.def_descr(item_def_id);
                 if let Some(item_def_id) = item_def_id.as_local() {
                     tcx.emit_node_span_lint(
                         MALFORMED_DIAGNOSTIC_FORMAT_LITERALS,
@@ -74,7 +78,8 @@ pub fn emit_warning<'tcx>(&self, tcx: TyCtxt<'tcx>, item_def_id: DefId) {
                         span,
                         UnknownFormatParameterForOnUnimplementedAttr {
                             argument_name,
-                            trait_name: this,
+                            item_kind,
+                            item_name: this,
                         },
                     );
                 }
@@ -139,7 +144,7 @@ pub fn emit_warning<'tcx>(&self, tcx: TyCtxt<'tcx>, item_def_id: DefId) {
 /// ```rust,ignore (just an example)
 /// FormatArgs {
 ///     this: "FromResidual",
-///     trait_sugared: "FromResidual<Option<Infallible>>",
+///     trait_sugared: Some("FromResidual<Option<Infallible>>"),
 ///     item_context: "an async function",
 ///     generic_args: [("Self", "u32"), ("R", "Option<Infallible>")],
 /// }
@@ -147,7 +152,8 @@ pub fn emit_warning<'tcx>(&self, tcx: TyCtxt<'tcx>, item_def_id: DefId) {
 #[derive(Debug)]
 pub struct FormatArgs<'tcx> {
     pub this: String,
-    pub trait_sugared: TraitRefPrintSugared<'tcx>,
+    /// `None` for attributes that are not about a trait, like `#[diagnostic::on_move]`.
+    pub trait_sugared: Option<TraitRefPrintSugared<'tcx>>,
     pub item_context: &'static str,
     pub generic_args: Vec<(Symbol, String)>,
 }
@@ -214,7 +220,9 @@ pub fn format(&self, args: &FormatArgs<'_>) -> String {
                 // It's only `rustc_onunimplemented` from here
                 Piece::Arg(FormatArg::This) => ret.push_str(&args.this),
                 Piece::Arg(FormatArg::Trait) => {
-                    let _ = fmt::write(&mut ret, format_args!("{}", &args.trait_sugared));
+                    if let Some(trait_sugared) = &args.trait_sugared {
+                        let _ = fmt::write(&mut ret, format_args!("{trait_sugared}"));
+                    }
                 }
                 Piece::Arg(FormatArg::ItemContext) => ret.push_str(args.item_context),
             }
@@ -230,8 +238,8 @@ fn parse_arg<'tcx>(
     input_span: Span,
     is_source_literal: bool,
 ) -> FormatArg {
-    let (Ctx::RustcOnUnimplemented { tcx, trait_def_id }
-    | Ctx::DiagnosticOnUnimplemented { tcx, trait_def_id }) = ctx;
+    let (Ctx::RustcOnUnimplemented { tcx, item_def_id }
+    | Ctx::DiagnosticOnUnimplemented { tcx, item_def_id }) = ctx;
 
     let span = slice_span(input_span, arg.position_span.clone(), is_source_literal);
 
@@ -250,7 +258,7 @@ fn parse_arg<'tcx>(
             (
                 Ctx::RustcOnUnimplemented { .. } | Ctx::DiagnosticOnUnimplemented { .. },
                 generic_param,
-            ) if tcx.generics_of(trait_def_id).own_params.iter().any(|param| {
+            ) if tcx.generics_of(item_def_id).own_params.iter().any(|param| {
                 !matches!(param.kind, GenericParamDefKind::Lifetime) && param.name == generic_param
             }) =>
             {
@@ -314,7 +322,8 @@ pub mod errors {
     #[help]
     pub struct UnknownFormatParameterForOnUnimplementedAttr {
         pub argument_name: Symbol,
-        pub trait_name: Ident,
+        pub item_kind: &'static str,
+        pub item_name: Ident,
     }
 
     #[derive(LintDiagnostic)]
@@ -330,5 +339,7 @@ pub struct UnknownFormatParameterForOnUnimplementedAttr {
     #[derive(LintDiagnostic)]
     #[diag(trait_selection_missing_options_for_on_unimplemented_attr)]
     #[help]
-    pub struct MissingOptionsForOnUnimplementedAttr;
+    pub struct MissingOptionsForOnUnimplementedAttr {
+        pub attr_name: Symbol,
+    }
 }
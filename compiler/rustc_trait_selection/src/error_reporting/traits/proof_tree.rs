@@ -0,0 +1,153 @@
+//! Recording the proof trees of reported trait errors for `-Zdump-proof-trees`.
+//!
+//! The root obligation of each reported error is evaluated again by the next trait solver to get
+//! its proof tree, which is converted into the owned [`ProofTreeGoal`]s of `rustc_session` so that
+//! the trees of all errors of the crate can be written out once type checking is done. Only goals
+//! that do not hold are expanded into their candidates: the goals that hold are leaves, as they
+//! never explain an error and would make the trees of generic code unreadably large.
+
+use rustc_infer::traits::solve::{CandidateSource, Certainty, GoalSource, MaybeCause, NoSolution};
+use rustc_middle::ty::TyCtxt;
+use rustc_session::proof_trees::{
+    FailedObligationProofTree, ProofTreeCandidate, ProofTreeCutOff, ProofTreeGoal,
+};
+use rustc_span::Span;
+
+use crate::error_reporting::TypeErrCtxt;
+use crate::error_reporting::traits::to_pretty_impl_header;
+use crate::solve::inspect::{
+    self, InferCtxtProofTreeExt, InspectCandidate, InspectGoal, ProofTreeVisitor,
+};
+use crate::traits::FulfillmentError;
+
+/// The depth from which on goals are not expanded anymore.
+const MAX_DEPTH: usize = 16;
+
+impl<'a, 'tcx> TypeErrCtxt<'a, 'tcx> {
+    /// Records the proof tree of the root obligation of `error` in the session.
+    pub(super) fn record_proof_tree(&self, error: &FulfillmentError<'tcx>) {
+        let obligation = self.resolve_vars_if_possible(error.root_obligation.clone());
+        let mut dumper = ProofTreeDumper { tcx: self.tcx, span: obligation.cause.span, root: None };
+        self.probe(|_| self.visit_proof_tree(obligation.as_goal(), &mut dumper));
+        let Some(root) = dumper.root else { return };
+        self.tcx.sess.proof_trees.lock().push(FailedObligationProofTree {
+            span: obligation.cause.span,
+            item: self.tcx.def_path_str(obligation.cause.body_id),
+            root,
+        });
+    }
+}
+
+struct ProofTreeDumper<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    span: Span,
+    root: Option<ProofTreeGoal>,
+}
+
+impl<'tcx> ProofTreeDumper<'tcx> {
+    /// Converts `goal` and the candidates considered for it, unless it holds or is one of its own
+    /// `ancestors`.
+    fn goal_tree(
+        &self,
+        goal: &InspectGoal<'_, 'tcx>,
+        source: &'static str,
+        ancestors: &mut Vec<String>,
+    ) -> ProofTreeGoal {
+        let printed = goal.goal().predicate.to_string();
+        let result = goal.result();
+        let cut_off = if ancestors.contains(&printed) {
+            Some(ProofTreeCutOff::Cycle)
+        } else if goal.depth() >= MAX_DEPTH {
+            Some(ProofTreeCutOff::Depth)
+        } else {
+            None
+        };
+
+        let mut candidates = Vec::new();
+        if cut_off.is_none() && !matches!(result, Ok(Certainty::Yes)) {
+            ancestors.push(printed.clone());
+            for candidate in goal.candidates() {
+                candidates.push(self.candidate_tree(&candidate, ancestors));
+            }
+            ancestors.pop();
+        }
+
+        ProofTreeGoal { goal: printed, source, result: result_str(result), candidates, cut_off }
+    }
+
+    fn candidate_tree(
+        &self,
+        candidate: &InspectCandidate<'_, 'tcx>,
+        ancestors: &mut Vec<String>,
+    ) -> ProofTreeCandidate {
+        let tcx = self.tcx;
+        let (kind, span) = match candidate.kind() {
+            inspect::ProbeKind::TraitCandidate { source, result: _ } => match source {
+                CandidateSource::Impl(impl_def_id) => (
+                    to_pretty_impl_header(tcx, impl_def_id)
+                        .unwrap_or_else(|| tcx.def_path_str(impl_def_id)),
+                    Some(tcx.def_span(impl_def_id)),
+                ),
+                CandidateSource::BuiltinImpl(_) => ("builtin impl".to_owned(), None),
+                CandidateSource::ParamEnv(_) => ("where-bound".to_owned(), None),
+                CandidateSource::AliasBound(_) => ("alias bound".to_owned(), None),
+                CandidateSource::CoherenceUnknowable => ("unknowable impl".to_owned(), None),
+            },
+            // Goals which can only be proven in one way, like `WellFormed`, have a single
+            // candidate for the whole evaluation.
+            inspect::ProbeKind::Root { result: _ } => ("builtin rules".to_owned(), None),
+            inspect::ProbeKind::OpaqueTypeStorageLookup { result: _ } => {
+                ("opaque type storage".to_owned(), None)
+            }
+            inspect::ProbeKind::RigidAlias { result: _ } => ("rigid alias".to_owned(), None),
+            kind @ (inspect::ProbeKind::NormalizedSelfTyAssembly
+            | inspect::ProbeKind::UnsizeAssembly
+            | inspect::ProbeKind::ProjectionCompatibility
+            | inspect::ProbeKind::ShadowedEnvProbing) => {
+                unreachable!("not a candidate: {kind:?}")
+            }
+        };
+
+        // The nested goals constrain inference variables of the goal, which must not leak into
+        // the other candidates.
+        let nested = candidate.goal().infcx().probe(|_| {
+            candidate
+                .instantiate_nested_goals(self.span)
+                .iter()
+                .map(|nested| self.goal_tree(nested, source_str(nested.source()), ancestors))
+                .collect()
+        });
+
+        ProofTreeCandidate { kind, span, result: result_str(candidate.result()), nested }
+    }
+}
+
+impl<'tcx> ProofTreeVisitor<'tcx> for ProofTreeDumper<'tcx> {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn visit_goal(&mut self, goal: &InspectGoal<'_, 'tcx>) {
+        self.root = Some(self.goal_tree(goal, "root", &mut Vec::new()));
+    }
+}
+
+fn result_str(result: Result<Certainty, NoSolution>) -> &'static str {
+    match result {
+        Ok(Certainty::Yes) => "yes",
+        Ok(Certainty::Maybe { cause: MaybeCause::Ambiguity, .. }) => "ambiguous",
+        Ok(Certainty::Maybe { cause: MaybeCause::Overflow { .. }, .. }) => "overflow",
+        Err(NoSolution) => "no",
+    }
+}
+
+fn source_str(source: GoalSource) -> &'static str {
+    match source {
+        GoalSource::Misc => "misc",
+        GoalSource::TypeRelating => "type relation",
+        GoalSource::ImplWhereBound => "impl where-bound",
+        GoalSource::AliasBoundConstCondition => "alias bound const condition",
+        GoalSource::AliasWellFormed => "alias well-formedness",
+        GoalSource::NormalizeGoal(_) => "normalization",
+    }
+}
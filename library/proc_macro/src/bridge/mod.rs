@@ -141,7 +141,7 @@ macro_rules! with_api_handle_types {
 #[allow(unsafe_code)]
 mod symbol;
 
-use buffer::Buffer;
+pub use buffer::Buffer;
 pub use rpc::PanicMessage;
 use rpc::{Decode, Encode, Reader, Writer};
 
@@ -337,6 +337,15 @@ fn run_bridge_and_client(
     }
 }
 
+/// Encodes the result of a client that panicked with `message`, like `run_client` does when
+/// it catches a panic. Used by execution strategies whose client can die without unwinding,
+/// e.g. because it runs in another process.
+pub fn client_panicked(message: String) -> Buffer {
+    let mut buf = Buffer::new();
+    Err::<(), _>(PanicMessage::String(message)).encode(&mut buf, &mut ());
+    buf
+}
+
 /// A message pipe used for communicating between server and client threads.
 pub trait MessagePipe<T>: Sized {
     /// Creates a new pair of endpoints for the message pipe.
@@ -377,6 +386,21 @@ fn run_server<
     Result::decode(&mut &buf[..], &mut dispatcher.handle_store)
 }
 
+impl<I, O> client::Client<I, O> {
+    /// Runs the client on the current thread, without a server: `input` is the buffer the
+    /// server-side strategy received in `run_bridge_and_client`, and every request the client
+    /// makes is passed to `dispatch`. This allows the client to run in another process than the
+    /// server, which forwards the buffers between the two.
+    pub fn run_detached(
+        &self,
+        input: Buffer,
+        mut dispatch: impl FnMut(Buffer) -> Buffer,
+        force_show_panics: bool,
+    ) -> Buffer {
+        (self.run)(BridgeConfig { input, dispatch: (&mut dispatch).into(), force_show_panics })
+    }
+}
+
 impl client::Client<crate::TokenStream, crate::TokenStream> {
     pub fn run<S>(
         &self,
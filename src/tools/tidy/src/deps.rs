@@ -563,8 +563,11 @@ pub(crate) struct WorkspaceInfo<'a> {
     "anyhow",
     "arbitrary",
     "bitflags",
+    "block-buffer",
     "bumpalo",
     "cfg-if",
+    "cobs",
+    "cpufeatures",
     "cranelift-assembler-x64",
     "cranelift-assembler-x64-meta",
     "cranelift-bforest",
@@ -582,9 +585,12 @@ pub(crate) struct WorkspaceInfo<'a> {
     "cranelift-object",
     "cranelift-srcgen",
     "crc32fast",
+    "crypto-common",
+    "digest",
     "equivalent",
     "fallible-iterator",
     "foldhash",
+    "generic-array",
     "gimli",
     "hashbrown",
     "heck",
@@ -596,6 +602,7 @@ pub(crate) struct WorkspaceInfo<'a> {
     "mach2",
     "memchr",
     "object",
+    "postcard",
     "proc-macro2",
     "quote",
     "regalloc2",
@@ -604,11 +611,16 @@ pub(crate) struct WorkspaceInfo<'a> {
     "serde",
     "serde_core",
     "serde_derive",
+    "sha2",
     "smallvec",
     "stable_deref_trait",
     "syn",
     "target-lexicon",
+    "thiserror",
+    "thiserror-impl",
+    "typenum",
     "unicode-ident",
+    "version_check",
     "wasmtime-internal-jit-icache-coherence",
     "wasmtime-internal-math",
     "windows-link",
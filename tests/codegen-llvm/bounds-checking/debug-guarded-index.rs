@@ -0,0 +1,20 @@
+//! Check that bounds checks that are implied by an earlier comparison are removed by
+//! `RangeCheckElimination` in debug builds, while unguarded ones are kept.
+
+//@ compile-flags: -Copt-level=0
+
+#![crate_type = "lib"]
+
+// CHECK-LABEL: @guarded(
+#[no_mangle]
+pub fn guarded(a: &[u32; 8], i: usize) -> u32 {
+    // CHECK-NOT: panic_bounds_check
+    if i < 8 { a[i] } else { 0 }
+}
+
+// CHECK-LABEL: @unguarded(
+#[no_mangle]
+pub fn unguarded(a: &[u32; 8], i: usize) -> u32 {
+    // CHECK: panic_bounds_check
+    if i <= 8 { a[i] } else { 0 }
+}
@@ -0,0 +1,37 @@
+//! Check that functions inlined by the MIR inliner show up as inlined frames with their own
+//! variables when compiling with the Cranelift backend.
+
+//@ compile-flags:-g -Copt-level=2 -Zinline-mir
+//@ needs-backends: cranelift
+//@ disable-gdb-pretty-printers
+//@ ignore-lldb
+
+// === GDB TESTS ===================================================================================
+
+//@ gdb-command:run
+
+//@ gdb-command:bt 2
+//@ gdb-check:#0  [...]cranelift_inlined_scopes::add [...]
+//@ gdb-check:#1  [...]cranelift_inlined_scopes::main () [...]
+//@ gdb-command:print sum
+//@ gdb-check:$1 = 3
+//@ gdb-command:up
+//@ gdb-command:print x
+//@ gdb-check:$2 = 1
+//@ gdb-command:continue
+
+#[inline]
+fn add(a: u32, b: u32) -> u32 {
+    let sum = a + b;
+    zzz(sum); // #break
+    sum
+}
+
+fn main() {
+    let x = std::hint::black_box(1);
+    let y = add(x, 2);
+    std::hint::black_box(y);
+}
+
+#[inline(never)]
+fn zzz(_: u32) {}
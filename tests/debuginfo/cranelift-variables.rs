@@ -0,0 +1,90 @@
+//! Check that the locations of variables are described when compiling with the Cranelift backend,
+//! both for variables kept in registers and for variables stored on the stack.
+
+//@ compile-flags:-g
+//@ needs-backends: cranelift
+//@ disable-gdb-pretty-printers
+//@ ignore-lldb
+
+// === GDB TESTS ===================================================================================
+
+//@ gdb-command:run
+
+//@ gdb-command:print a
+//@ gdb-check:$1 = 1
+//@ gdb-command:print b
+//@ gdb-check:$2 = 2.5
+//@ gdb-command:continue
+
+//@ gdb-command:print pair
+//@ gdb-check:$3 = (3, 4)
+//@ gdb-command:print s
+//@ gdb-check:$4 = "five"
+//@ gdb-command:continue
+
+//@ gdb-command:print *p
+//@ gdb-check:$5 = cranelift_variables::Point {x: 6, y: 7}
+//@ gdb-command:continue
+
+//@ gdb-command:print point
+//@ gdb-check:$6 = cranelift_variables::Point {x: 8, y: 9}
+//@ gdb-command:print array
+//@ gdb-check:$7 = [10, 20, 30]
+//@ gdb-command:print x
+//@ gdb-check:$8 = 11
+//@ gdb-command:continue
+
+//@ gdb-command:print x
+//@ gdb-check:$9 = 12.5
+//@ gdb-command:continue
+
+//@ gdb-command:print x
+//@ gdb-check:$10 = 11
+//@ gdb-command:continue
+
+#[derive(Clone, Copy)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn scalars(a: u8, b: f64) {
+    zzz(); // #break
+    let _ = (a, b);
+}
+
+fn pairs(pair: (u32, u64), s: &str) {
+    zzz(); // #break
+    let _ = (pair, s);
+}
+
+fn by_ref(p: &Point) {
+    zzz(); // #break
+    let _ = p;
+}
+
+fn main() {
+    scalars(1, 2.5);
+    pairs((3, 4), "five");
+    by_ref(&Point { x: 6, y: 7 });
+
+    let point = Point { x: 8, y: 9 };
+    let mut array = [10u16, 0, 30];
+    array[1] = 20;
+    let x = 11i32;
+
+    zzz(); // #break
+
+    {
+        let x = 12.5f32;
+
+        zzz(); // #break
+        let _ = x;
+    }
+
+    zzz(); // #break
+    let _ = (point, array, x);
+}
+
+#[inline(never)]
+fn zzz() {}
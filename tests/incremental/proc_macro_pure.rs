@@ -0,0 +1,28 @@
+//@ proc-macro: proc_macro_pure_aux.rs
+//@ revisions: rpass1 rpass2 rpass3
+//@ [rpass1] rustc-env:PROC_MACRO_PURE_VALUE=1
+//@ [rpass2] rustc-env:PROC_MACRO_PURE_VALUE=1
+//@ [rpass3] rustc-env:PROC_MACRO_PURE_VALUE=3
+//@ ignore-backends: gcc
+
+// Checks that outputs of `#![proc_macro_pure]` proc macros reused from a previous session end up
+// at the spans of the current invocation, and that they are discarded once a tracked environment
+// variable changes.
+
+#[macro_use]
+extern crate proc_macro_pure_aux;
+
+// Shifts the spans of everything below in later sessions.
+#[cfg(any(rpass2, rpass3))]
+const _: () = ();
+
+#[derive(Named)]
+struct Foo;
+
+fn main() {
+    assert_eq!(Foo::NAME, "Foo");
+    #[cfg(any(rpass1, rpass2))]
+    assert_eq!(env_value!(), 1);
+    #[cfg(rpass3)]
+    assert_eq!(env_value!(), 3);
+}
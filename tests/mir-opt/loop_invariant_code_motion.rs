@@ -0,0 +1,29 @@
+//@ test-mir-pass: LoopInvariantCodeMotion
+//@ compile-flags: -Coverflow-checks=off
+
+// EMIT_MIR loop_invariant_code_motion.invariant_product.LoopInvariantCodeMotion.diff
+pub fn invariant_product(x: u32, n: u32) -> u32 {
+    // CHECK-LABEL: fn invariant_product(
+    // CHECK: bb0: {
+    // CHECK: [[n:_.*]] = copy _2;
+    // CHECK: [[x:_.*]] = copy _1;
+    // CHECK: [[product:_.*]] = Mul(copy [[x]], const 3_u32);
+    // CHECK: goto -> bb1;
+    // CHECK: bb1: {
+    // CHECK: Lt(move {{_.*}}, copy [[n]]);
+    // CHECK: bb2: {
+    // CHECK-NOT: Mul(
+    // CHECK: BitXor(move {{_.*}}, copy [[product]]);
+    // CHECK: Add(move {{_.*}}, const 1_u32);
+    let mut sum = 0;
+    let mut i = 0;
+    while i < n {
+        sum = sum ^ x * 3;
+        i = i + 1;
+    }
+    sum
+}
+
+fn main() {
+    invariant_product(1, 2);
+}
@@ -0,0 +1,131 @@
+// EMIT_MIR_FOR_EACH_PANIC_STRATEGY
+//@ test-mir-pass: RangeCheckElimination
+//@ compile-flags: -Coverflow-checks=on
+
+#![feature(custom_mir, core_intrinsics)]
+
+use std::intrinsics::mir::*;
+
+// EMIT_MIR range_check_elimination.masked_add.RangeCheckElimination.diff
+pub fn masked_add(x: u8) -> u8 {
+    // CHECK-LABEL: fn masked_add(
+    // CHECK: AddWithOverflow(
+    // CHECK-NOT: assert(
+    // CHECK: goto -> bb1;
+    (x & 15) + 1
+}
+
+// EMIT_MIR range_check_elimination.guarded_index.RangeCheckElimination.diff
+pub fn guarded_index(array: [u32; 8], index: usize) -> u32 {
+    // CHECK-LABEL: fn guarded_index(
+    // CHECK: Lt(move {{_.*}}, const 8_usize);
+    // CHECK: switchInt(
+    // CHECK: [[cond:_.*]] = const true;
+    // CHECK-NOT: assert(
+    // CHECK: goto -> bb2;
+    if index < 8 { array[index] } else { 0 }
+}
+
+// EMIT_MIR range_check_elimination.unguarded_index.RangeCheckElimination.diff
+pub fn unguarded_index(array: [u32; 8], index: usize) -> u32 {
+    // CHECK-LABEL: fn unguarded_index(
+    // CHECK: [[cond:_.*]] = Lt(copy {{_.*}}, const 8_usize);
+    // CHECK: assert(move [[cond]],
+    array[index]
+}
+
+// EMIT_MIR range_check_elimination.masked_shift.RangeCheckElimination.diff
+#[custom_mir(dialect = "runtime", phase = "post-cleanup")]
+pub fn masked_shift(x: u8) -> bool {
+    // `Shr` masks the shift amount by the bit width, so `x >> 9` is `x >> 1` and not always 0.
+    // CHECK-LABEL: fn masked_shift(
+    // CHECK: [[shifted:_.*]] = Shr(copy _1, const 9_u32);
+    // CHECK: _0 = Lt(copy [[shifted]], const 1_u8);
+    mir! {
+        let shifted: u8;
+        {
+            shifted = x >> 9_u32;
+            RET = shifted < 1_u8;
+            Return()
+        }
+    }
+}
+
+// EMIT_MIR range_check_elimination.loop_bound.RangeCheckElimination.diff
+#[custom_mir(dialect = "runtime", phase = "post-cleanup")]
+pub fn loop_bound(array: [u32; 8]) -> u32 {
+    // The range of `index` is widened at the loop head, and narrowed again by the loop condition,
+    // so the bounds check in the loop body is known to succeed.
+    // CHECK-LABEL: fn loop_bound(
+    // CHECK: bb1: {
+    // CHECK: [[cond:_.*]] = Lt(copy [[index:_.*]], const 8_usize);
+    // CHECK: switchInt(copy [[cond]]) -> [0: bb3, otherwise: bb2];
+    // CHECK: bb2: {
+    // CHECK: {{_.*}} = const true;
+    // CHECK: Add(copy [[index]], const 1_usize);
+    mir! {
+        let index: usize;
+        let in_loop: bool;
+        let in_bounds: bool;
+        {
+            RET = 0_u32;
+            index = 0_usize;
+            Goto(bb1)
+        }
+        bb1 = {
+            in_loop = index < 8_usize;
+            match in_loop { false => bb3, _ => bb2 }
+        }
+        bb2 = {
+            in_bounds = index < 8_usize;
+            RET = RET ^ array[index];
+            index = index + 1_usize;
+            Goto(bb1)
+        }
+        bb3 = {
+            Return()
+        }
+    }
+}
+
+// EMIT_MIR range_check_elimination.loop_bound_off_by_one.RangeCheckElimination.diff
+#[custom_mir(dialect = "runtime", phase = "post-cleanup")]
+pub fn loop_bound_off_by_one(array: [u32; 8]) -> u32 {
+    // The loop condition lets `index` reach 8 after widening, so the bounds check must stay.
+    // CHECK-LABEL: fn loop_bound_off_by_one(
+    // CHECK: bb2: {
+    // CHECK: {{_.*}} = Lt(copy {{_.*}}, const 8_usize);
+    // CHECK-NOT: const true;
+    mir! {
+        let index: usize;
+        let in_loop: bool;
+        let in_bounds: bool;
+        {
+            RET = 0_u32;
+            index = 0_usize;
+            Goto(bb1)
+        }
+        bb1 = {
+            in_loop = index <= 8_usize;
+            match in_loop { false => bb3, _ => bb2 }
+        }
+        bb2 = {
+            in_bounds = index < 8_usize;
+            RET = RET ^ array[index];
+            index = index + 1_usize;
+            Goto(bb1)
+        }
+        bb3 = {
+            Return()
+        }
+    }
+}
+
+fn main() {
+    masked_add(3);
+    guarded_index([0; 8], 3);
+    unguarded_index([0; 8], 3);
+    masked_shift(3);
+    loop_bound([0; 8]);
+    loop_bound_off_by_one([0; 8]);
+}
@@ -0,0 +1,47 @@
+//@ test-mir-pass: ScalarReplacementOfAggregates
+//@ compile-flags: -Cpanic=abort
+//@ no-prefer-dynamic
+
+// EMIT_MIR enums.result.ScalarReplacementOfAggregates.diff
+/// Check that SROA splits an enum that is assigned different variants, so that its discriminant
+/// can be tracked by later passes.
+pub fn result(c: bool, a: u32, b: u32) -> u32 {
+    // CHECK-LABEL: fn result(
+
+    // CHECK: [[enum:_[0-9]+]]: std::result::Result<u32, u32>;
+    // CHECK: [[discr:_[0-9]+]]: isize;
+    // CHECK: [[ok:_[0-9]+]]: u32;
+    // CHECK: [[err:_[0-9]+]]: u32;
+
+    // CHECK: bb1: {
+    // CHECK: [[discr]] = const 0_isize;
+    // CHECK: [[ok]] = move {{_[0-9]+}};
+    // CHECK: bb2: {
+    // CHECK: [[discr]] = const 1_isize;
+    // CHECK: [[err]] = move {{_[0-9]+}};
+    // CHECK: bb3: {
+    // CHECK: {{_[0-9]+}} = copy [[discr]];
+    // CHECK: bb4: {
+    // CHECK: {{_[0-9]+}} = copy [[ok]];
+    // CHECK-NOT: [[enum]]
+    if let Ok(x) = if c { Ok::<u32, u32>(a) } else { Err(b) } { x } else { 0 }
+}
+
+// EMIT_MIR enums.copied.ScalarReplacementOfAggregates.diff
+/// Check that SROA excludes enums that are copied as a whole.
+pub fn copied(a: u32) -> Option<u32> {
+    // CHECK-LABEL: fn copied(
+
+    // CHECK: [[enum:_[0-9]+]]: std::option::Option<u32>;
+
+    // CHECK: bb0: {
+    // CHECK: [[enum]] = Option::<u32>::Some(
+    // CHECK: _0 = copy [[enum]];
+    let x = Some(a);
+    x
+}
+
+fn main() {
+    result(true, 5, 7);
+    copied(5);
+}
@@ -23,16 +23,21 @@ pub fn dropping() {
     S(Tag(0), Tag(1), Tag(2)).1;
 }
 
-/// Check that SROA excludes enums.
+/// Check that SROA splits enums into their discriminant and the fields of their variants.
 pub fn enums(a: usize) -> usize {
     // CHECK-LABEL: fn enums(
 
     // CHECK: [[enum:_[0-9]+]]: std::option::Option<usize>;
+    // CHECK: [[discr:_[0-9]+]]: isize;
+    // CHECK: [[some:_[0-9]+]]: usize;
 
     // CHECK: bb0: {
-    // CHECK: [[enum]] = Option::<usize>::Some
-    // CHECK: _5 = copy (([[enum]] as Some).0: usize)
-    // CHECK: _0 = copy _5
+    // CHECK-NOT: [[enum]]
+    // CHECK: [[discr]] = const 1_isize;
+    // CHECK: [[some]] = move _3;
+    // CHECK: _4 = copy [[discr]];
+    // CHECK: _5 = copy [[some]];
+    // CHECK-NOT: [[enum]]
     if let Some(a) = Some(a) { a } else { 0 }
 }
 
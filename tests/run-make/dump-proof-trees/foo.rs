@@ -0,0 +1,13 @@
+pub trait Storage {}
+
+pub struct Raw(*const u8);
+pub struct Shared<T>(T);
+
+impl Storage for Raw {}
+impl<T: Storage + Send> Storage for Shared<T> {}
+
+fn store<S: Storage>(_: S) {}
+
+pub fn share(raw: Raw) {
+    store(Shared(raw));
+}
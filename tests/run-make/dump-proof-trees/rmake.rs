@@ -0,0 +1,72 @@
+//@ needs-target-std
+//
+// `-Zdump-proof-trees` writes the proof tree of every trait error reported by the next trait
+// solver as JSON and HTML. This test checks that the tree of an unsatisfied bound shows the impl
+// that was considered and the where-bound of it that does not hold.
+
+use run_make_support::{cwd, rfs, rustc, serde_json};
+
+fn main() {
+    rustc()
+        .crate_type("lib")
+        .input("foo.rs")
+        .arg("-Znext-solver")
+        .arg(format!("-Zdump-proof-trees={}", cwd().display()))
+        .run_fail()
+        .assert_stderr_contains("cannot be sent between threads safely");
+
+    let report: serde_json::Value =
+        serde_json::from_str(&rfs::read_to_string("foo.proof_trees.json")).unwrap();
+    assert_eq!(report["crate"], "foo");
+    let errors = report["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 1, "{report:#}");
+    assert_eq!(errors[0]["item"], "share");
+    assert!(errors[0]["span"].as_str().unwrap().starts_with("foo.rs:12:"));
+
+    let root = &errors[0]["root"];
+    assert_eq!(root["goal"], "Shared<Raw>: Storage");
+    assert_eq!(root["source"], "root");
+    assert_eq!(root["result"], "no");
+
+    // The only impl for `Shared<T>` applies, but one of its where-bounds does not hold.
+    let candidates = root["candidates"].as_array().unwrap();
+    assert_eq!(candidates.len(), 1, "{root:#}");
+    assert!(candidates[0]["kind"].as_str().unwrap().starts_with("impl<T> Storage for Shared<T>"));
+    assert!(candidates[0]["span"].as_str().unwrap().starts_with("foo.rs:7:"));
+    assert_eq!(candidates[0]["result"], "no");
+
+    let nested = candidates[0]["nested"].as_array().unwrap();
+    let holds = nested.iter().find(|goal| goal["goal"] == "Raw: Storage").unwrap();
+    assert_eq!(holds["result"], "yes");
+    assert_eq!(holds["candidates"], serde_json::json!([]));
+    let fails = nested.iter().find(|goal| goal["goal"] == "Raw: Send").unwrap();
+    assert_eq!(fails["source"], "impl where-bound");
+    assert_eq!(fails["result"], "no");
+
+    // The failing goals are expanded in the HTML tree, the others are collapsed.
+    let html = rfs::read_to_string("foo.proof_trees.html");
+    assert!(html.contains(
+        "<details open><summary><code>Shared&lt;Raw&gt;: Storage</code> \
+         <span class=\"no\">no</span>"
+    ));
+    assert!(html.contains("<details><summary><code>Raw: Storage</code>"));
+
+    // Only the next trait solver builds proof trees.
+    rustc()
+        .crate_type("lib")
+        .input("foo.rs")
+        .arg("-Zdump-proof-trees")
+        .run_fail()
+        .assert_stderr_contains("`-Zdump-proof-trees` requires `-Znext-solver`");
+
+    // The errors of queries loaded from the incremental cache are not reported again, so their
+    // proof trees would be missing.
+    rustc()
+        .crate_type("lib")
+        .input("foo.rs")
+        .arg("-Znext-solver")
+        .arg("-Zdump-proof-trees")
+        .incremental("incr")
+        .run_fail()
+        .assert_stderr_contains("`-Zdump-proof-trees` cannot be used with `-C incremental`");
+}
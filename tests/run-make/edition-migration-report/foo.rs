@@ -0,0 +1,23 @@
+// `gen` becomes a keyword in Rust 2024, which `keyword_idents_2024` fixes by renaming it.
+pub fn gen() {}
+
+struct LoudDropper;
+
+impl Drop for LoudDropper {
+    fn drop(&mut self) {
+        println!("loud drop");
+    }
+}
+
+impl LoudDropper {
+    fn get(&self) -> i32 {
+        0
+    }
+}
+
+// The temporary `LoudDropper` is dropped before `x` in Rust 2024, which `tail_expr_drop_order`
+// can only point out.
+pub fn tail() -> i32 {
+    let x = LoudDropper;
+    x.get() + LoudDropper.get()
+}
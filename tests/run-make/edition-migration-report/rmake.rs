@@ -0,0 +1,71 @@
+//@ needs-target-std
+//
+// `-Zedition-migration-report` force-warns the migration lints of the target edition and writes
+// their hits to `<crate>.edition_migration.json`, classified by whether `cargo fix` can apply
+// them and whether the affected code changes meaning or is rejected in the target edition.
+
+use run_make_support::{rfs, rustc, serde_json};
+
+fn main() {
+    // The migration lints are allow-by-default, the report forces them on.
+    rustc()
+        .crate_type("lib")
+        .edition("2021")
+        .input("foo.rs")
+        .arg("-Zedition-migration-report=2024")
+        .run()
+        .assert_stderr_contains("`gen` is a keyword in the 2024 edition")
+        .assert_stderr_contains("relative drop order changing in Rust 2024");
+
+    let report: serde_json::Value =
+        serde_json::from_str(&rfs::read_to_string("foo.edition_migration.json")).unwrap();
+    assert_eq!(report["crate"], "foo");
+    assert_eq!(report["edition"], "2021");
+    assert_eq!(report["target_edition"], "2024");
+
+    let summary = &report["summary"];
+    assert_eq!(summary["hits"], 2, "{report:#}");
+    assert_eq!(summary["machine_applicable"], 1);
+    assert_eq!(summary["needs_review"], 1);
+    assert_eq!(summary["semantic"], 1);
+    assert_eq!(summary["syntactic"], 1);
+
+    let hits = report["hits"].as_array().unwrap();
+    assert_eq!(hits[0]["lint"], "keyword_idents_2024");
+    assert_eq!(hits[0]["fix"], "machine-applicable");
+    assert_eq!(hits[0]["risk"], "syntactic");
+    assert!(hits[0]["span"].as_str().unwrap().starts_with("foo.rs:2:"));
+    assert_eq!(hits[1]["lint"], "tail_expr_drop_order");
+    assert_eq!(hits[1]["fix"], "needs-review");
+    assert_eq!(hits[1]["risk"], "semantic");
+
+    let lints = report["lints"].as_array().unwrap();
+    assert_eq!(lints.len(), 2);
+    assert_eq!(lints[1]["name"], "tail_expr_drop_order");
+    assert_eq!(lints[1]["hits"], 1);
+    assert!(lints[1]["reference"].as_str().unwrap().contains("temporary-tail-expr-scope"));
+
+    // With incremental compilation, the lints of the queries loaded from the cache are replayed
+    // instead of emitted again, and must still be reported.
+    for _ in 0..2 {
+        rustc()
+            .crate_type("lib")
+            .edition("2021")
+            .input("foo.rs")
+            .incremental("incr")
+            .arg("-Zedition-migration-report=2024")
+            .run();
+        let incremental: serde_json::Value =
+            serde_json::from_str(&rfs::read_to_string("foo.edition_migration.json")).unwrap();
+        assert_eq!(incremental, report);
+    }
+
+    // Only editions after the crate's own can be targeted.
+    rustc()
+        .crate_type("lib")
+        .edition("2024")
+        .input("foo.rs")
+        .arg("-Zedition-migration-report=2024")
+        .run_fail()
+        .assert_stderr_contains("must name an edition later than the crate's edition (2024)");
+}
@@ -0,0 +1,10 @@
+#![crate_type = "lib"]
+
+#[inline(never)]
+pub fn generic<T: Default + PartialEq>(x: T) -> bool {
+    x == T::default()
+}
+
+pub fn uses_generic(a: u8, b: u64) -> bool {
+    generic(a) && generic(b)
+}
@@ -0,0 +1,49 @@
+// Check that `--emit=size-report` attributes the code size of each function to its crate and,
+// for generic functions, to the generic definition.
+
+//@ needs-target-std
+
+use run_make_support::{rfs, rustc, serde_json};
+
+fn main() {
+    rustc()
+        .input("foo.rs")
+        .arg("-Zunstable-options")
+        .emit("size-report")
+        .arg("-Cdebuginfo=0")
+        .opt_level("0")
+        .run();
+
+    let report: serde_json::Value =
+        serde_json::from_str(&rfs::read_to_string("foo.size.json")).unwrap();
+
+    let symbols = report["symbols"].as_array().unwrap();
+    let find = |path: &str| symbols.iter().filter(move |s| s["path"] == path).collect::<Vec<_>>();
+
+    let uses_generic = find("foo::uses_generic");
+    assert_eq!(uses_generic.len(), 1);
+    assert_eq!(uses_generic[0]["crate"], "foo");
+    assert_eq!(uses_generic[0]["generic_args"].as_array().unwrap().len(), 0);
+    assert!(uses_generic[0]["size"].as_u64().unwrap() > 0);
+    // `uses_generic` is not `#[inline]`, so it is only instantiated once.
+    assert_eq!(uses_generic[0]["local_copy"], false);
+
+    // Both instantiations of `generic` are reported, and summed up in the generic totals.
+    let instances = find("foo::generic");
+    assert_eq!(instances.len(), 2);
+    let mut args: Vec<_> = instances.iter().map(|s| s["generic_args"][0].clone()).collect();
+    args.sort_by_key(|arg| arg.to_string());
+    assert_eq!(args, ["u64", "u8"]);
+    let generic = report["generics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|g| g["path"] == "foo::generic")
+        .unwrap();
+    assert_eq!(generic["instantiations"], 2);
+    let instances_size: u64 = instances.iter().map(|s| s["size"].as_u64().unwrap()).sum();
+    assert_eq!(generic["size"], instances_size);
+
+    let krate = report["crates"].as_array().unwrap().iter().find(|c| c["crate"] == "foo").unwrap();
+    assert!(krate["symbols"].as_u64().unwrap() >= 3);
+}
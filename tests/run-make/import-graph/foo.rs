@@ -0,0 +1,25 @@
+pub mod storage {
+    pub mod backend {
+        pub struct Disk;
+
+        pub fn open() -> Disk {
+            Disk
+        }
+    }
+
+    pub use self::backend::Disk;
+}
+
+pub mod api {
+    use crate::storage::Disk;
+    use crate::util::*;
+
+    pub fn load() -> Disk {
+        helper();
+        crate::storage::backend::open()
+    }
+}
+
+pub mod util {
+    pub fn helper() {}
+}
@@ -0,0 +1,68 @@
+//@ needs-target-std
+//
+// `-Zdump-import-graph` writes the module dependency graph found by name resolution as JSON and
+// Graphviz. This test checks the edges created by a single import through a re-export, a glob
+// import and a path, and that the re-export chain of the single import is recorded.
+
+use run_make_support::{cwd, rfs, rustc, serde_json};
+
+fn main() {
+    rustc()
+        .crate_type("lib")
+        .input("foo.rs")
+        .arg(format!("-Zdump-import-graph={}", cwd().display()))
+        .run();
+
+    let graph: serde_json::Value =
+        serde_json::from_str(&rfs::read_to_string("foo.import_graph.json")).unwrap();
+    assert_eq!(graph["crate"], "foo");
+    assert_eq!(
+        graph["modules"],
+        serde_json::json!([
+            "crate",
+            "crate::api",
+            "crate::storage",
+            "crate::storage::backend",
+            "crate::util",
+        ])
+    );
+
+    let edges = graph["edges"].as_array().unwrap();
+    let edge = |from: &str, to: &str| {
+        let mut found = edges.iter().filter(|edge| edge["from"] == from && edge["to"] == to);
+        let edge = found.next().unwrap_or_else(|| panic!("no edge {from} -> {to}: {graph:#}"));
+        assert!(found.next().is_none(), "more than one edge {from} -> {to}: {graph:#}");
+        edge
+    };
+    assert_eq!(edges.len(), 4, "{graph:#}");
+
+    let reexport = edge("crate::storage", "crate::storage::backend");
+    assert_eq!(reexport["kind"], "use");
+    assert_eq!(reexport["path"], "self::backend::Disk");
+    assert!(reexport.get("reexport_chain").is_none());
+
+    let single = edge("crate::api", "crate::storage");
+    assert_eq!(single["kind"], "use");
+    assert_eq!(single["path"], "crate::storage::Disk");
+    assert_eq!(single["reexport_chain"], serde_json::json!(["crate::storage::backend"]));
+    assert!(single["span"].as_str().unwrap().starts_with("foo.rs:14:"));
+
+    let glob = edge("crate::api", "crate::util");
+    assert_eq!(glob["kind"], "glob");
+    assert_eq!(glob["path"], "crate::util::*");
+
+    let path = edge("crate::api", "crate::storage::backend");
+    assert_eq!(path["kind"], "path");
+    assert_eq!(path["path"], "crate::storage::backend::open");
+    assert!(path["span"].as_str().unwrap().starts_with("foo.rs:19:"));
+
+    let dot = rfs::read_to_string("foo.import_graph.dot");
+    assert!(dot.starts_with("digraph \"foo\" {"), "{dot}");
+    assert!(dot.contains("    \"crate::util\";"), "{dot}");
+    assert!(dot.contains("    \"crate::api\" -> \"crate::storage\" [label=\"use: 1\"];"), "{dot}");
+    assert!(dot.contains("    \"crate::api\" -> \"crate::util\" [label=\"glob: 1\"];"), "{dot}");
+    assert!(
+        dot.contains("    \"crate::api\" -> \"crate::storage::backend\" [label=\"path: 1\"];"),
+        "{dot}"
+    );
+}
@@ -0,0 +1,14 @@
+#[inline(never)]
+fn used() -> u32 {
+    std::hint::black_box(42)
+}
+
+// Not exported from the executable, so the linker discards it.
+#[unsafe(no_mangle)]
+pub extern "C" fn link_map_report_unused() -> u32 {
+    std::hint::black_box(7)
+}
+
+fn main() {
+    println!("{}", used());
+}
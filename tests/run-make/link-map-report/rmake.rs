@@ -0,0 +1,38 @@
+// Check that `-Z link-map-report` reports the sections that the linker kept, with the crate they
+// come from, and the size of the sections it discarded.
+
+//@ needs-target-std
+//@ only-linux
+// Reason: the link map can only be read for GNU ld and lld.
+
+use run_make_support::{bin_name, rfs, rustc, serde_json};
+
+fn main() {
+    rustc().input("foo.rs").arg("-Zlink-map-report").arg("-Cdebuginfo=0").opt_level("0").run();
+
+    let report: serde_json::Value =
+        serde_json::from_str(&rfs::read_to_string(format!("{}.link-map.json", bin_name("foo"))))
+            .unwrap();
+
+    let sections = report["sections"].as_array().unwrap();
+    let defines = |f: fn(&str) -> bool| {
+        sections
+            .iter()
+            .find(|s| s["symbols"].as_array().unwrap().iter().any(|s| f(s.as_str().unwrap())))
+    };
+
+    let main = defines(|s| s == "main").unwrap();
+    assert_eq!(main["guessed_reason"], "entry");
+    let used = defines(|s| s.starts_with("_ZN3foo4used")).unwrap();
+    assert_eq!(used["crate"], "foo");
+    assert_eq!(used["guessed_reason"], "referenced");
+    assert!(used["size"].as_u64().unwrap() > 0);
+    assert!(defines(|s| s == "link_map_report_unused").is_none());
+
+    let krate = report["crates"].as_array().unwrap().iter().find(|c| c["crate"] == "foo").unwrap();
+    assert!(krate["retained_size"].as_u64().unwrap() > 0);
+    assert!(krate["discarded_size"].as_u64().unwrap() > 0);
+    assert!(
+        report["discarded_size"].as_u64().unwrap() >= krate["discarded_size"].as_u64().unwrap()
+    );
+}
@@ -0,0 +1,10 @@
+// A small tt-muncher: every step tries the `()` arm first, which fails on the first token,
+// then recurses into itself with the rest of the input.
+macro_rules! count {
+    () => { 0usize };
+    ($head:tt $($tail:tt)*) => { 1usize + count!($($tail)*) };
+}
+
+pub fn three() -> usize {
+    count!(a b c)
+}
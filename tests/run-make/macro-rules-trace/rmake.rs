@@ -0,0 +1,56 @@
+//@ needs-target-std
+//
+// `-Zdump-macro-rules-trace` writes a JSON tree of every `macro_rules!` expansion. This test
+// expands a recursive macro and checks that each recursive step is nested under the step that
+// produced it, and that failed arms, bound metavariables and produced tokens are recorded.
+
+use run_make_support::{cwd, rfs, rustc, serde_json};
+
+fn main() {
+    rustc()
+        .crate_type("lib")
+        .input("foo.rs")
+        .arg(format!("-Zdump-macro-rules-trace={}", cwd().display()))
+        .run();
+
+    let trace: serde_json::Value =
+        serde_json::from_str(&rfs::read_to_string("foo.macro_rules_trace.json")).unwrap();
+    assert_eq!(trace["crate"], "foo");
+
+    let expansions = trace["expansions"].as_array().unwrap();
+    assert_eq!(expansions.len(), 1, "{trace:#}");
+
+    // `count!(a b c)`, `count!(b c)`, `count!(c)` and `count!()`, each nested in the previous one.
+    let mut step = &expansions[0];
+    for remaining in ["a b c", "b c", "c"] {
+        assert_eq!(step["name"], "count");
+        assert_eq!(step["kind"], "macro");
+        assert_eq!(step["input"], remaining);
+        assert_eq!(step["matched_arm"], 1);
+
+        let arms = step["arms"].as_array().unwrap();
+        assert_eq!(arms.len(), 2);
+        assert_eq!(arms[0]["index"], 0);
+        assert_eq!(arms[0]["outcome"], "failed");
+        assert_eq!(arms[0]["matcher"], "()");
+        assert_eq!(arms[0]["failure"]["expected"], "end of macro");
+        assert_eq!(arms[1]["outcome"], "matched");
+
+        let bindings = step["bindings"].as_array().unwrap();
+        assert_eq!(bindings[0]["name"], "$head");
+        assert_eq!(bindings[0]["value"], remaining.split(' ').next().unwrap());
+        assert_eq!(bindings[1]["name"], "$tail");
+        assert!(bindings[1]["value"].is_array());
+
+        assert!(step["output"].as_str().unwrap().starts_with("1usize + count!"));
+
+        let children = step["children"].as_array().unwrap();
+        assert_eq!(children.len(), 1, "{step:#}");
+        step = &children[0];
+    }
+
+    assert_eq!(step["input"], "");
+    assert_eq!(step["matched_arm"], 0);
+    assert_eq!(step["output"], "0usize");
+    assert!(step["children"].as_array().unwrap().is_empty());
+}
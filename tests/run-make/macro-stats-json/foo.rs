@@ -0,0 +1,13 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+macro_rules! make_fns {
+    ($($name:ident),*) => {
+        $(pub fn $name() -> Point { Point { x: 0, y: 0 } })*
+    };
+}
+
+make_fns!(a, b, c);
@@ -0,0 +1,52 @@
+//@ needs-target-std
+//
+// `-Zmacro-stats-json` writes the `-Zmacro-stats` statistics as JSON next to the
+// other outputs. This test checks that the file exists, that it is valid JSON,
+// and that it contains the downstream costs (HIR nodes and typeck time) for both
+// derives and `macro_rules!` macros.
+
+use run_make_support::{rfs, rustc, serde_json};
+
+fn main() {
+    rustc().crate_type("lib").input("foo.rs").arg("-Zmacro-stats").arg("-Zmacro-stats-json").run();
+
+    let report: serde_json::Value =
+        serde_json::from_str(&rfs::read_to_string("foo.macro_stats.json")).unwrap();
+    assert_eq!(report["crate"], "foo");
+    let macros = report["macros"].as_array().unwrap();
+
+    let find = |name: &str| {
+        macros
+            .iter()
+            .find(|m| m["name"] == name)
+            .unwrap_or_else(|| panic!("no entry for `{name}` in {report:#}"))
+    };
+
+    let debug = find("Debug");
+    assert_eq!(debug["kind"], "derive macro");
+    assert_eq!(debug["uses"], 1);
+    assert!(debug["hir_nodes"].as_u64().unwrap() > 0);
+    assert!(debug["time_ns"].is_u64());
+    assert!(debug["typeck_time_ns"].is_u64());
+
+    let make_fns = find("make_fns");
+    assert_eq!(make_fns["kind"], "macro");
+    assert_eq!(make_fns["uses"], 1);
+    assert!(make_fns["hir_nodes"].as_u64().unwrap() > 0);
+    // The functions defined by `make_fns!` are type checked.
+    assert!(make_fns["typeck_time_ns"].as_u64().unwrap() > 0);
+
+    // With incremental compilation, type checking is not timed, as its results may come from the
+    // cache.
+    rustc()
+        .crate_type("lib")
+        .input("foo.rs")
+        .incremental("incr")
+        .arg("-Zmacro-stats")
+        .arg("-Zmacro-stats-json")
+        .run();
+    let report: serde_json::Value =
+        serde_json::from_str(&rfs::read_to_string("foo.macro_stats.json")).unwrap();
+    let macros = report["macros"].as_array().unwrap();
+    assert!(macros.iter().all(|m| m["typeck_time_ns"].is_null()), "{report:#}");
+}
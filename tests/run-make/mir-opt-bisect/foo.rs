@@ -0,0 +1,9 @@
+pub fn foo(x: u32) -> u32 {
+    let y = x.wrapping_add(1);
+    if y > 0 { y } else { 0 }
+}
+
+pub fn bar(x: u32) -> u32 {
+    let y = x;
+    y.wrapping_mul(2)
+}
@@ -0,0 +1,55 @@
+//@ needs-target-std
+//
+// `-Zmir-opt-bisect-limit=N` only runs the first N optimization passes of each function, and
+// prints which passes it runs or skips. The passes are numbered per function, so the decisions
+// for a function do not depend on the other functions or on the number of compiler threads.
+// `-Zmir-opt-bisect-filter` restricts the limit to the functions whose path contains a string.
+
+use run_make_support::rustc;
+
+fn bisect(limit: &str, extra: &[&str]) -> String {
+    rustc()
+        .crate_type("lib")
+        .input("foo.rs")
+        .opt_level("2")
+        .emit("obj")
+        .arg(format!("-Zmir-opt-bisect-limit={limit}"))
+        .args(extra)
+        .run()
+        .stderr_utf8()
+}
+
+/// The decisions printed for the body of `path`, in order.
+fn decisions<'a>(stderr: &'a str, path: &str) -> Vec<&'a str> {
+    let suffix = format!(" on {path}");
+    stderr.lines().filter(|line| line.starts_with("BISECT: ") && line.ends_with(&suffix)).collect()
+}
+
+fn main() {
+    let none = bisect("0", &[]);
+    assert!(!none.contains("BISECT: running"), "{none}");
+    for path in ["foo::foo", "foo::bar"] {
+        let decisions = decisions(&none, path);
+        assert!(decisions[0].starts_with("BISECT: NOT running pass (1) "), "{none}");
+    }
+
+    // Every function runs its first pass, and only that one.
+    let one = bisect("1", &[]);
+    for path in ["foo::foo", "foo::bar"] {
+        let decisions = decisions(&one, path);
+        assert!(decisions[0].starts_with("BISECT: running pass (1) "), "{one}");
+        assert!(decisions[1].starts_with("BISECT: NOT running pass (2) "), "{one}");
+    }
+
+    // The decisions for a function are the same with several threads.
+    let threads = bisect("3", &["-Zthreads=4"]);
+    let single = bisect("3", &["-Zthreads=1"]);
+    for path in ["foo::foo", "foo::bar"] {
+        assert_eq!(decisions(&threads, path), decisions(&single, path));
+    }
+
+    // Functions that do not match the filter run all their passes without being reported.
+    let filtered = bisect("0", &["-Zmir-opt-bisect-filter=bar"]);
+    assert!(!decisions(&filtered, "foo::bar").is_empty(), "{filtered}");
+    assert!(decisions(&filtered, "foo::foo").is_empty(), "{filtered}");
+}
@@ -0,0 +1,4 @@
+pub fn dangling() -> &'static String {
+    let s = String::new();
+    &s
+}
@@ -0,0 +1,4 @@
+pub fn foo(x: u32) -> u32 {
+    let y = x.wrapping_add(1);
+    if y > 0 { y } else { 0 }
+}
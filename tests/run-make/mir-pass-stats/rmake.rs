@@ -0,0 +1,40 @@
+//@ needs-target-std
+//
+// `-Zmir-pass-stats` prints the MIR passes that ran once the compilation is over, so they are
+// also printed for builds that do not generate code and for builds that fail.
+
+use run_make_support::rustc;
+
+const HEADER: &str = "mir-pass-stats pass";
+
+fn main() {
+    // A full build runs the optimization passes during code generation.
+    rustc()
+        .crate_type("lib")
+        .input("lib.rs")
+        .opt_level("2")
+        .arg("-Zmir-pass-stats")
+        .run()
+        .assert_stdout_contains(HEADER)
+        .assert_stdout_contains("GVN")
+        .assert_stdout_contains_regex(r"mir-pass-stats `\S+` on `lib::foo`: ");
+
+    // A check build only runs the passes that borrow checking needs.
+    rustc()
+        .crate_type("lib")
+        .input("lib.rs")
+        .emit("metadata")
+        .arg("-Zmir-pass-stats")
+        .run()
+        .assert_stdout_contains(HEADER)
+        .assert_stdout_contains("SimplifyCfg-initial");
+
+    rustc()
+        .crate_type("lib")
+        .input("borrowck_error.rs")
+        .emit("metadata")
+        .arg("-Zmir-pass-stats")
+        .run_fail()
+        .assert_stderr_contains("cannot return reference to local variable `s`")
+        .assert_stdout_contains(HEADER);
+}
@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate pure_macros;
+
+#[derive(Named)]
+struct Foo;
+
+fn main() {
+    assert_eq!(Foo::NAME, "Foo");
+    println!("{}", env_value!());
+}
@@ -0,0 +1,24 @@
+#![crate_type = "proc-macro"]
+#![feature(proc_macro_pure, proc_macro_tracked_env)]
+#![proc_macro_pure]
+
+extern crate proc_macro;
+
+use proc_macro::{TokenStream, TokenTree, tracked};
+
+#[proc_macro_derive(Named)]
+pub fn derive_named(input: TokenStream) -> TokenStream {
+    let name = input
+        .into_iter()
+        .skip_while(
+            |tree| !matches!(tree, TokenTree::Ident(ident) if ident.to_string() == "struct"),
+        )
+        .nth(1)
+        .unwrap();
+    format!("impl {name} {{ pub const NAME: &'static str = \"{name}\"; }}").parse().unwrap()
+}
+
+#[proc_macro]
+pub fn env_value(_input: TokenStream) -> TokenStream {
+    tracked::env_var("PROC_MACRO_PURE_VALUE").unwrap().parse().unwrap()
+}
@@ -0,0 +1,33 @@
+//@ ignore-cross-compile
+//@ needs-crate-type: proc-macro
+//@ ignore-musl (FIXME: can't find `-lunwind`)
+//
+// The outputs of `#![proc_macro_pure]` proc macros are reused by later incremental sessions, as
+// long as the environment variables they read through `proc_macro::tracked` are unchanged. This
+// checks the hits and misses of the cache reported by `-Zincremental-info`, which
+// `tests/incremental/proc_macro_pure.rs` cannot observe.
+
+use run_make_support::{run, rustc};
+
+fn compile(value: &str, expected_cache_info: &str) {
+    rustc()
+        .input("main.rs")
+        .incremental("incr")
+        .arg("-Zincremental-info")
+        .env("PROC_MACRO_PURE_VALUE", value)
+        .run()
+        .assert_stderr_contains(expected_cache_info);
+    // The replayed outputs must be the ones of the current session.
+    assert_eq!(run("main").stdout_utf8().trim(), value);
+}
+
+fn main() {
+    rustc().input("pure_macros.rs").run();
+
+    // Nothing is cached yet.
+    compile("1", "[incremental] proc macro cache: 0 hits, 2 misses");
+    // Both expansions are replayed.
+    compile("1", "[incremental] proc macro cache: 2 hits, 0 misses");
+    // `env_value!` read the changed variable, so it runs again.
+    compile("3", "[incremental] proc macro cache: 1 hits, 1 misses");
+}
@@ -0,0 +1,5 @@
+extern crate macro_def;
+
+macro_def::chmod_file!();
+
+fn main() {}
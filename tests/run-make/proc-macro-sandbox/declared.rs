@@ -0,0 +1,7 @@
+extern crate macro_def;
+
+macro_def::read_declared!();
+
+fn main() {
+    assert_eq!(CONTENTS, "data");
+}
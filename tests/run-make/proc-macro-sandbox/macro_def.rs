@@ -0,0 +1,47 @@
+#![feature(proc_macro_tracked_path)]
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+use proc_macro::*;
+
+fn read(path: &str) -> TokenStream {
+    let contents = std::fs::read_to_string(path).unwrap_or_default();
+    format!("pub const CONTENTS: &str = {contents:?};").parse().unwrap()
+}
+
+#[proc_macro]
+pub fn read_declared(_: TokenStream) -> TokenStream {
+    tracked::path("data.txt");
+    read("data.txt")
+}
+
+#[proc_macro]
+pub fn read_undeclared(_: TokenStream) -> TokenStream {
+    read("secret.txt")
+}
+
+#[proc_macro]
+pub fn write_file(_: TokenStream) -> TokenStream {
+    let _ = std::fs::write("written.txt", "pwned");
+    TokenStream::new()
+}
+
+#[proc_macro]
+pub fn stat_undeclared(_: TokenStream) -> TokenStream {
+    let _ = std::fs::metadata("secret.txt");
+    TokenStream::new()
+}
+
+#[proc_macro]
+pub fn chmod_file(_: TokenStream) -> TokenStream {
+    tracked::path("data.txt");
+    let permissions = std::os::unix::fs::PermissionsExt::from_mode(0o777);
+    let _ = std::fs::set_permissions("data.txt", permissions);
+    TokenStream::new()
+}
+
+#[proc_macro]
+pub fn spawn_process(_: TokenStream) -> TokenStream {
+    let _ = std::process::Command::new("true").status();
+    TokenStream::new()
+}
@@ -0,0 +1,61 @@
+//@ ignore-cross-compile
+//@ needs-crate-type: proc-macro
+//@ only-linux
+//@ only-x86_64
+//@ ignore-musl (FIXME: can't find `-lunwind`)
+
+// With `-Zproc-macro-execution-strategy=sandboxed`, proc macros run in a separate process that
+// can only read the files they declared with `proc_macro::tracked::path`, and can't write any
+// file or spawn processes. This test checks that declared files can be read, and that undeclared
+// reads, writes and other system calls are denied and reported as errors at the invocation.
+
+use std::path::Path;
+
+use run_make_support::{rfs, run, rustc};
+
+fn main() {
+    rustc().input("macro_def.rs").run();
+    rfs::write("data.txt", "data");
+    rfs::write("secret.txt", "secret");
+
+    rustc().input("declared.rs").arg("-Zproc-macro-execution-strategy=sandboxed").run();
+    run("declared");
+
+    // Without the sandbox, the proc macro is free to read whatever it wants.
+    rustc().input("undeclared.rs").run();
+    rustc()
+        .input("undeclared.rs")
+        .arg("-Zproc-macro-execution-strategy=sandboxed")
+        .run_fail()
+        .assert_stderr_contains("sandboxed proc macro tried to read `")
+        .assert_stderr_contains("secret.txt`, which was not declared")
+        .assert_stderr_contains("proc_macro::tracked::path");
+
+    rustc()
+        .input("write.rs")
+        .arg("-Zproc-macro-execution-strategy=sandboxed")
+        .run_fail()
+        .assert_stderr_contains("sandboxed proc macro tried to write to `")
+        .assert_stderr_contains("written.txt`");
+    assert!(!Path::new("written.txt").exists());
+
+    // Reading the metadata of a file is a read as well.
+    rustc()
+        .input("stat.rs")
+        .arg("-Zproc-macro-execution-strategy=sandboxed")
+        .run_fail()
+        .assert_stderr_contains("secret.txt`, which was not declared");
+
+    // Declaring a file only allows reading it.
+    rustc()
+        .input("chmod.rs")
+        .arg("-Zproc-macro-execution-strategy=sandboxed")
+        .run_fail()
+        .assert_stderr_contains("sandboxed proc macro tried to change the permissions of a file");
+
+    rustc()
+        .input("spawn.rs")
+        .arg("-Zproc-macro-execution-strategy=sandboxed")
+        .run_fail()
+        .assert_stderr_contains("sandboxed proc macro tried to spawn a process");
+}
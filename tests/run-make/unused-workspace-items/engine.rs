@@ -0,0 +1,10 @@
+extern crate util;
+
+pub fn run() {
+    util::used_by_engine();
+    util::nested::used_nested();
+    let config = util::Config::new();
+    config.used_method();
+}
+
+pub fn unused_engine_fn() {}
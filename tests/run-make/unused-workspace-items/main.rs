@@ -0,0 +1,7 @@
+extern crate engine;
+extern crate util;
+
+fn main() {
+    engine::run();
+    util::used_by_app();
+}
@@ -0,0 +1,45 @@
+// Checks that `-Zreport-unused-workspace-items` warns about the public items of the crates
+// compiled with `-Zrecord-upstream-uses` that no crate in the crate graph uses, and only
+// about those.
+
+use run_make_support::rustc;
+
+fn main() {
+    rustc().input("util.rs").crate_type("rlib").arg("-Zrecord-upstream-uses").run();
+    rustc().input("engine.rs").crate_type("rlib").arg("-Zrecord-upstream-uses").run();
+
+    let output = rustc().input("main.rs").arg("-Zreport-unused-workspace-items").run();
+    for unused in [
+        "function `util::unused_fn`",
+        "method `util::Config::unused_method`",
+        "struct `util::UnusedStruct`",
+        "function `util::nested::unused_nested`",
+        "function `engine::unused_engine_fn`",
+    ] {
+        output.assert_stderr_contains(format!(
+            "warning: {unused} is public but not used by any downstream crate"
+        ));
+    }
+    for used in [
+        "util::used_by_app",
+        "util::used_by_engine",
+        "util::_unused_but_ignored",
+        "util::private_fn",
+        "util::Config",
+        "util::Config::new",
+        "util::Config::used_method",
+        "util::nested::used_nested",
+        "engine::run",
+    ] {
+        output.assert_stderr_not_contains(format!("`{used}` is public"));
+    }
+
+    // Without the recorded uses, nothing is known about which items are workspace items.
+    rustc().input("util.rs").crate_type("rlib").run();
+    rustc().input("engine.rs").crate_type("rlib").run();
+    rustc()
+        .input("main.rs")
+        .arg("-Zreport-unused-workspace-items")
+        .run()
+        .assert_stderr_not_contains("is public but not used");
+}
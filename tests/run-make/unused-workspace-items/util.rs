@@ -0,0 +1,30 @@
+pub fn used_by_app() {}
+
+pub fn used_by_engine() {}
+
+pub fn unused_fn() {}
+
+pub fn _unused_but_ignored() {}
+
+fn private_fn() {}
+
+pub struct Config;
+
+impl Config {
+    pub fn new() -> Config {
+        private_fn();
+        Config
+    }
+
+    pub fn used_method(&self) {}
+
+    pub fn unused_method(&self) {}
+}
+
+pub struct UnusedStruct;
+
+pub mod nested {
+    pub fn used_nested() {}
+
+    pub fn unused_nested() {}
+}
@@ -0,0 +1,112 @@
+//@ run-pass
+//! Test that borrowck facts can be retrieved for local items.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_middle;
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_public;
+
+use rustc_public::mir::BorrowKind;
+use rustc_public::mir::borrowck::{TwoPhaseActivation, borrowck_facts, enable_borrowck_facts};
+use rustc_public::*;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+/// This function uses the Stable MIR APIs to get information about the test crate.
+fn test_borrowck_facts() -> ControlFlow<()> {
+    let items = rustc_public::all_local_items();
+    let item = |name: &str| *items.iter().find(|item| item.trimmed_name() == name).unwrap();
+
+    let facts = borrowck_facts(item("shared_borrow")).unwrap();
+    assert_eq!(facts.borrows.len(), 1);
+    let borrow = &facts.borrows[0];
+    assert_eq!(borrow.kind, BorrowKind::Shared);
+    assert_eq!(borrow.activation, TwoPhaseActivation::NotTwoPhase);
+    assert!(!facts.universal_regions.is_empty());
+    assert!(!facts.outlives_constraints.is_empty());
+
+    // The borrow must be valid up to the dereference of `r`.
+    assert!(!facts.region_value(borrow.region).locations.is_empty());
+    for region in &facts.universal_regions {
+        assert!(facts.region_value(*region).universal_regions.contains(region));
+    }
+    for scc in &facts.sccs {
+        assert!(scc.successors.iter().all(|successor| *successor < facts.sccs.len()));
+    }
+
+    let facts = borrowck_facts(item("two_phase")).unwrap();
+    assert!(
+        facts
+            .borrows
+            .iter()
+            .any(|borrow| matches!(borrow.activation, TwoPhaseActivation::ActivatedAt(_)))
+    );
+
+    let facts = borrowck_facts(item("moves")).unwrap();
+    assert_eq!(facts.moves.len(), 1);
+    let path = &facts.move_paths[facts.moves[0].path];
+    assert_eq!(path.local(), 1);
+
+    // Items without a body have no borrowck facts.
+    assert!(borrowck_facts(item("CONST")).is_err());
+    ControlFlow::Continue(())
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `RustcPublic` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "borrowck_facts_input.rs";
+    generate_input(&path).unwrap();
+    let args = &[
+        "rustc".to_string(),
+        "-Cpanic=abort".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    enable_borrowck_facts();
+    run!(args, test_borrowck_facts).unwrap();
+
+    // The facts are collected again by the next session, including when the bodies are borrow
+    // checked by the threads of the parallel frontend.
+    let mut args = args.to_vec();
+    args.push("-Zthreads=4".to_string());
+    run!(&args, test_borrowck_facts).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+        pub const CONST: u32 = 1;
+
+        pub fn shared_borrow(x: u32) -> u32 {{
+            let r = &x;
+            *r
+        }}
+
+        pub fn two_phase(v: &mut Vec<usize>) {{
+            v.push(v.len());
+        }}
+
+        pub fn moves(s: String) -> String {{
+            s
+        }}
+        "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,126 @@
+//@ run-pass
+//! Test that the bodies of a dependency can be analyzed together with the local crate.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_middle;
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+#[macro_use]
+extern crate rustc_public;
+
+use std::io::Write;
+use std::ops::ControlFlow;
+
+use rustc_public::mir::mono::Instance;
+use rustc_public::ty::GenericArgs;
+use rustc_public::*;
+
+const DEP_NAME: &str = "dep";
+const PLAIN_DEP_NAME: &str = "plain_dep";
+const CRATE_NAME: &str = "input";
+
+/// Check that the items of the dependency are available.
+fn test_crate_graph() -> ControlFlow<()> {
+    let dep = rustc_public::find_crates(DEP_NAME).pop().unwrap();
+    let dep_items = dep.items();
+    // Non-generic functions only have their MIR encoded with `-Zalways-encode-mir`.
+    let helper = dep_items.iter().find(|item| item.trimmed_name() == "helper").unwrap();
+    assert!(helper.has_body());
+
+    let all_items = rustc_public::all_items();
+    assert!(all_items.iter().any(|item| item.trimmed_name() == "main"));
+    assert!(all_items.contains(helper));
+
+    // Instances of the dependency can be resolved and their bodies retrieved.
+    let compute = dep.fn_defs().into_iter().find(|def| def.trimmed_name() == "compute").unwrap();
+    let instance = Instance::resolve(compute, &GenericArgs(vec![])).unwrap();
+    let body = instance.body().unwrap();
+    assert!(!body.blocks.is_empty());
+
+    // The flag of the local crate doesn't apply to a dependency compiled without it.
+    let plain_dep = rustc_public::find_crates(PLAIN_DEP_NAME).pop().unwrap();
+    let plain_items = plain_dep.items();
+    assert!(!plain_items.iter().any(|item| item.trimmed_name() == "helper"));
+    let compute =
+        plain_dep.fn_defs().into_iter().find(|def| def.trimmed_name() == "compute").unwrap();
+    let instance = Instance::resolve(compute, &GenericArgs(vec![])).unwrap();
+    assert!(instance.body().is_none());
+    ControlFlow::Continue(())
+}
+
+/// This test will generate three dummy crates, and compile them using the stable mir.
+/// The dependencies are compiled first, only one of them with `run_crate_graph!`,
+/// and then the local crate is analyzed.
+fn main() {
+    let dep_path = "crate_graph_dep.rs";
+    generate_dep(&dep_path).unwrap();
+    let args = &[
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        PLAIN_DEP_NAME.to_string(),
+        dep_path.to_string(),
+    ];
+    run!(args, || ControlFlow::<()>::Continue(())).unwrap();
+
+    let args = &[
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        DEP_NAME.to_string(),
+        dep_path.to_string(),
+    ];
+    run_crate_graph!(args, || ControlFlow::<()>::Continue(())).unwrap();
+
+    let path = "crate_graph_input.rs";
+    generate_input(&path).unwrap();
+    let args = &[
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        "--extern".to_string(),
+        format!("{DEP_NAME}=lib{DEP_NAME}.rlib"),
+        "--extern".to_string(),
+        format!("{PLAIN_DEP_NAME}=lib{PLAIN_DEP_NAME}.rlib"),
+        path.to_string(),
+    ];
+    run_crate_graph!(args, test_crate_graph).unwrap();
+}
+
+fn generate_dep(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+        fn helper(x: u32) -> u32 {{
+            x * 2
+        }}
+
+        pub fn compute(x: u32) -> u32 {{
+            helper(x) + 1
+        }}
+        "#
+    )?;
+    Ok(())
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+        pub fn main() -> u32 {{
+            dep::compute(1) + plain_dep::compute(2)
+        }}
+        "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,110 @@
+//@ run-pass
+//! Test that HIR bodies and their type checking results can be retrieved for local items.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+
+extern crate rustc_middle;
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_public;
+
+use std::assert_matches::assert_matches;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+use rustc_public::hir::{Adjust, BinOp, ExprKind, LitKind, PatKind, PathRes, StmtKind};
+use rustc_public::mir::Mutability;
+use rustc_public::ty::{RigidTy, TyKind, UintTy};
+use rustc_public::*;
+
+const CRATE_NAME: &str = "input";
+
+/// This function uses the Stable MIR APIs to get information about the test crate.
+fn test_hir_body() -> ControlFlow<()> {
+    let items = rustc_public::all_local_items();
+    let item = |name: &str| *items.iter().find(|item| item.trimmed_name() == name).unwrap();
+
+    // `a + 1`, where `a` is the first parameter.
+    let body = hir::body(item("add_one")).unwrap();
+    assert_eq!(body.params.len(), 1);
+    assert_matches!(&body.pat(body.params[0]).kind, PatKind::Binding { name, .. } if name == "a");
+    let ExprKind::Block(block) = body.expr(body.value).kind else { unreachable!() };
+    let tail = body.block(block).expr.unwrap();
+    let ExprKind::Binary(BinOp::Add, lhs, rhs) = body.expr(tail).kind else { unreachable!() };
+    assert_eq!(body.expr(lhs).kind, ExprKind::Path(PathRes::Local(body.params[0])));
+    assert_eq!(body.expr(rhs).kind, ExprKind::Lit(LitKind::Int(1)));
+    let ty = body.typeck_results.expr_ty(tail).unwrap();
+    assert_eq!(ty.kind(), TyKind::RigidTy(RigidTy::Uint(UintTy::U32)));
+
+    // `v.len()` auto-borrows `v` and resolves to `Vec::len`.
+    let body = hir::body(item("length")).unwrap();
+    let ExprKind::Block(block) = body.expr(body.value).kind else { unreachable!() };
+    let stmts = &body.block(block).stmts;
+    assert_eq!(stmts.len(), 1);
+    let StmtKind::Let { pat, init: Some(init), .. } = body.stmt(stmts[0]).kind else {
+        unreachable!()
+    };
+    assert_matches!(&body.pat(pat).kind, PatKind::Binding { name, .. } if name == "n");
+    let ExprKind::MethodCall { ref method, receiver, .. } = body.expr(init).kind else {
+        unreachable!()
+    };
+    assert_eq!(method, "len");
+    assert_matches!(
+        body.typeck_results.expr_adjustments(receiver),
+        [adjustment] if adjustment.kind == Adjust::Borrow(Mutability::Not)
+    );
+    let resolution = body.typeck_results.method_resolution(init).unwrap();
+    assert_eq!(resolution.def.trimmed_name(), "Vec::<T, A>::len");
+    let tail = body.block(block).expr.unwrap();
+    let ExprKind::Path(PathRes::Local(local)) = body.expr(tail).kind else { unreachable!() };
+    assert_eq!(local, pat);
+
+    // Constructors have MIR, but no HIR body.
+    assert!(hir::body(item("Struct")).is_err());
+    ControlFlow::Continue(())
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `RustcPublic` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "hir_body_input.rs";
+    generate_input(&path).unwrap();
+    let args = &[
+        "rustc".to_string(),
+        "-Cpanic=abort".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run!(args, test_hir_body).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+        pub struct Struct;
+
+        pub fn add_one(a: u32) -> u32 {{
+            a + 1
+        }}
+
+        pub fn length(v: Vec<u8>) -> usize {{
+            let n = v.len();
+            n
+        }}
+        "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,176 @@
+//@ run-pass
+//! Test that a transformed body can be code generated in place of the original one.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+
+extern crate rustc_middle;
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+#[macro_use]
+extern crate rustc_public;
+
+use std::assert_matches::assert_matches;
+use std::io::Write;
+use std::ops::ControlFlow;
+use std::process::Command;
+
+use rustc_public::mir::mono::Instance;
+use rustc_public::mir::visit::{Location, PlaceContext};
+use rustc_public::mir::{
+    BinOp, Body, BodyLocation, Local, LocalDecl, MutMirVisitor, Mutability, Rvalue, Statement,
+    StatementKind, TerminatorKind,
+};
+use rustc_public::ty::{Ty, UintTy};
+use rustc_public::*;
+
+const CRATE_NAME: &str = "input";
+const OUTPUT: &str = "replace_body_output";
+
+/// This function uses the Stable MIR APIs to replace the body of `compute`.
+fn test_replace_body() -> ControlFlow<()> {
+    let items = rustc_public::all_local_items();
+    let item = *items.iter().find(|item| item.trimmed_name() == "compute").unwrap();
+    let instance = Instance::try_from(item).unwrap();
+
+    let mut body = instance.body().unwrap();
+    check_builder(body.clone());
+
+    // Changing the type of an argument is not allowed.
+    let mut bad_body = body.clone();
+    ChangeArgTy.visit_body(&mut bad_body);
+    assert!(instance.replace_body(&bad_body).is_err());
+
+    // Using a local that is not declared is not allowed.
+    let mut bad_body = body.clone();
+    UseMissingLocal.visit_body(&mut bad_body);
+    assert!(instance.replace_body(&bad_body).is_err());
+
+    // Bodies that fail MIR validation are not allowed.
+    let mut bad_body = body.clone();
+    AssignToArg.visit_body(&mut bad_body);
+    assert!(instance.replace_body(&bad_body).is_err());
+
+    AddToMul.visit_body(&mut body);
+    instance.replace_body(&body).unwrap();
+    let new_body = instance.body().unwrap();
+    assert_eq!(new_body.blocks, body.blocks);
+    assert_eq!(new_body.locals(), body.locals());
+
+    // Continue compilation, so the new body gets code generated.
+    ControlFlow::Continue(())
+}
+
+/// Check the methods used to add new locals and blocks.
+fn check_builder(mut body: Body) {
+    let num_locals = body.locals().len();
+    let span = body.span;
+    let local = body.new_local(Ty::unsigned_ty(UintTy::U32), span, Mutability::Mut);
+    assert_eq!(local, num_locals);
+
+    let num_blocks = body.blocks.len();
+    let terminator = body.blocks[0].terminator.clone();
+    let new_block = body.split_block(BodyLocation { block: 0, statement_index: 0 });
+    assert_eq!(new_block, num_blocks);
+    assert!(body.blocks[0].statements.is_empty());
+    assert_matches!(
+        body.blocks[0].terminator.kind,
+        TerminatorKind::Goto { target } if target == new_block
+    );
+    assert_eq!(body.blocks[new_block].terminator, terminator);
+}
+
+/// Replace additions by multiplications.
+struct AddToMul;
+
+impl MutMirVisitor for AddToMul {
+    fn visit_rvalue(&mut self, rvalue: &mut Rvalue, location: Location) {
+        if let Rvalue::BinaryOp(op, ..) | Rvalue::CheckedBinaryOp(op, ..) = rvalue {
+            if *op == BinOp::Add {
+                *op = BinOp::Mul;
+            }
+        }
+        self.super_rvalue(rvalue, location);
+    }
+}
+
+/// Change the type of the first argument.
+struct ChangeArgTy;
+
+impl MutMirVisitor for ChangeArgTy {
+    fn visit_local_decl(&mut self, local: usize, decl: &mut LocalDecl) {
+        if local == 1 {
+            decl.ty = Ty::unsigned_ty(UintTy::U64);
+        }
+    }
+}
+
+/// Replace the uses of the return place by a local that is not declared.
+struct UseMissingLocal;
+
+impl MutMirVisitor for UseMissingLocal {
+    fn visit_local(&mut self, local: &mut Local, _: PlaceContext, _: Location) {
+        if *local == 0 {
+            *local = 100;
+        }
+    }
+}
+
+/// Assign the result of the overflow checked addition to the first argument, which has a
+/// different type.
+struct AssignToArg;
+
+impl MutMirVisitor for AssignToArg {
+    fn visit_statement(&mut self, stmt: &mut Statement, location: Location) {
+        if let StatementKind::Assign(place, Rvalue::CheckedBinaryOp(..)) = &mut stmt.kind {
+            place.local = 1;
+        }
+        self.super_statement(stmt, location);
+    }
+}
+
+/// This test will generate and compile a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `RustcPublic` using custom arguments and then
+/// it will run the compiler. Finally, it runs the generated binary, which
+/// only succeeds if it was built with the new body.
+fn main() {
+    let path = "replace_body_input.rs";
+    generate_input(&path).unwrap();
+    let args = &[
+        "rustc".to_string(),
+        "--crate-type=bin".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        "-o".to_string(),
+        OUTPUT.to_string(),
+        path.to_string(),
+    ];
+    run!(args, test_replace_body).unwrap();
+    let status = Command::new(format!("./{OUTPUT}")).status().unwrap();
+    assert!(status.success());
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+        #[inline(never)]
+        pub fn compute(a: u32, b: u32) -> u32 {{
+            a + b
+        }}
+
+        fn main() {{
+            assert_eq!(compute(6, 7), 42);
+        }}
+        "#
+    )?;
+    Ok(())
+}
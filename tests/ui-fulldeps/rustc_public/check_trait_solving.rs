@@ -0,0 +1,121 @@
+//@ run-pass
+//! Test that trait solving queries can be used to select impls and normalize types.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+
+extern crate rustc_middle;
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_public;
+
+use std::assert_matches::assert_matches;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+use rustc_public::traits::{
+    EvaluationResult, ImplSourceKind, ParamEnv, evaluate, normalize, select,
+};
+use rustc_public::ty::{GenericArgs, RigidTy, TraitRef, Ty, TyKind, UintTy};
+use rustc_public::*;
+
+const CRATE_NAME: &str = "input";
+
+/// This function uses the Stable MIR APIs to get information about the test crate.
+fn test_trait_solving() -> ControlFlow<()> {
+    let items = rustc_public::all_local_items();
+    let item = |name: &str| *items.iter().find(|item| item.trimmed_name() == name).unwrap();
+    let traits = rustc_public::all_trait_decls();
+    let trait_def = |name: &str| *traits.iter().find(|def| def.trimmed_name() == name).unwrap();
+    let no_args = GenericArgs(vec![]);
+
+    // `Square: Shape` holds through the user-defined impl.
+    let square = item("square_area").ty().kind().fn_sig().unwrap().skip_binder().inputs()[0];
+    let trait_ref = TraitRef::new(trait_def("Shape"), square, &no_args);
+    assert_eq!(evaluate(&trait_ref, ParamEnv::Empty).unwrap(), EvaluationResult::Holds);
+    let impl_source = select(&trait_ref, ParamEnv::Empty).unwrap().unwrap();
+    let ImplSourceKind::UserDefined { impl_def, .. } = impl_source.kind else { unreachable!() };
+    assert_eq!(impl_def.trimmed_name(), "<Square as Shape>");
+
+    // `u32: Shape` does not hold.
+    let trait_ref = TraitRef::new(trait_def("Shape"), Ty::unsigned_ty(UintTy::U32), &no_args);
+    assert_eq!(evaluate(&trait_ref, ParamEnv::Empty).unwrap(), EvaluationResult::Fails);
+    assert!(select(&trait_ref, ParamEnv::Empty).is_err());
+
+    // `T: Shape` holds through the where-clause of `generic`.
+    let generic = item("generic");
+    let param = generic.ty().kind().fn_sig().unwrap().skip_binder().inputs()[0];
+    let trait_ref = TraitRef::new(trait_def("Shape"), param, &no_args);
+    let param_env = ParamEnv::Item(generic.def_id());
+    assert!(evaluate(&trait_ref, param_env).unwrap().holds());
+    assert_matches!(select(&trait_ref, param_env).unwrap().unwrap().kind, ImplSourceKind::Param);
+
+    // Tuples of `Copy` types are `Copy`.
+    let u32_ty = Ty::unsigned_ty(UintTy::U32);
+    let tuple = Ty::new_tuple(&[u32_ty, u32_ty]);
+    let trait_ref = TraitRef::new(trait_def("Copy"), tuple, &no_args);
+    let impl_source = select(&trait_ref, ParamEnv::Empty).unwrap().unwrap();
+    assert_matches!(impl_source.kind, ImplSourceKind::Builtin);
+
+    // `<Square as Shape>::Area` normalizes to `u64`.
+    let output = item("square_area").ty().kind().fn_sig().unwrap().skip_binder().output();
+    assert_matches!(output.kind(), TyKind::Alias(..));
+    let normalized = normalize(output, ParamEnv::Empty).unwrap();
+    assert_eq!(normalized.kind(), TyKind::RigidTy(RigidTy::Uint(UintTy::U64)));
+    ControlFlow::Continue(())
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `RustcPublic` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "trait_solving_input.rs";
+    generate_input(&path).unwrap();
+    let args = &[
+        "rustc".to_string(),
+        "-Cpanic=abort".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run!(args, test_trait_solving).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+        pub trait Shape {{
+            type Area;
+            fn area(&self) -> Self::Area;
+        }}
+
+        pub struct Square(pub u32);
+
+        impl Shape for Square {{
+            type Area = u64;
+            fn area(&self) -> u64 {{
+                self.0 as u64 * self.0 as u64
+            }}
+        }}
+
+        pub fn square_area(s: Square) -> <Square as Shape>::Area {{
+            s.area()
+        }}
+
+        pub fn generic<T: Shape>(t: T) -> T::Area {{
+            t.area()
+        }}
+        "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,15 @@
+#![feature(diagnostic_on_move)]
+
+#[diagnostic::on_move(message = "the `{T}` handle was already given away")]
+struct Handle<T> {
+    value: T,
+}
+
+fn take<T>(_: Handle<T>) {}
+
+fn main() {
+    let handle = Handle { value: 1u8 };
+    take(handle);
+    take(handle);
+    //~^ ERROR: the `u8` handle was already given away
+}
@@ -0,0 +1,18 @@
+#![feature(diagnostic_on_move)]
+#![deny(misplaced_diagnostic_attributes)]
+
+#[diagnostic::on_move(message = "tadaa")]
+//~^ ERROR: `#[diagnostic::on_move]` can only be applied to structs, enums and unions
+trait Foo {}
+
+#[diagnostic::on_move(message = "tadaa")]
+//~^ ERROR: `#[diagnostic::on_move]` can only be applied to structs, enums and unions
+fn foo() {}
+
+#[diagnostic::on_move(message = "tadaa")]
+struct Bar;
+
+#[diagnostic::on_move(message = "tadaa")]
+enum Baz {}
+
+fn main() {}
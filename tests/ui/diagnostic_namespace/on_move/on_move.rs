@@ -0,0 +1,15 @@
+#![feature(diagnostic_on_move)]
+
+#[diagnostic::on_move(
+    message = "`{Self}` cannot be used after it was moved",
+    label = "this token was already spent",
+    note = "create a new token with `Token::new`"
+)]
+struct Token;
+
+fn main() {
+    let token = Token;
+    let _a = token;
+    let _b = token;
+    //~^ ERROR: `Token` cannot be used after it was moved
+}
@@ -0,0 +1,18 @@
+#![feature(diagnostic_on_type_mismatch)]
+#![deny(misplaced_diagnostic_attributes)]
+
+#[diagnostic::on_type_mismatch(message = "tadaa")]
+//~^ ERROR: `#[diagnostic::on_type_mismatch]` can only be applied to structs, enums and unions
+trait Foo {}
+
+#[diagnostic::on_type_mismatch(message = "tadaa")]
+//~^ ERROR: `#[diagnostic::on_type_mismatch]` can only be applied to structs, enums and unions
+fn foo() {}
+
+#[diagnostic::on_type_mismatch(message = "tadaa")]
+struct Bar;
+
+#[diagnostic::on_type_mismatch(message = "tadaa")]
+enum Baz {}
+
+fn main() {}
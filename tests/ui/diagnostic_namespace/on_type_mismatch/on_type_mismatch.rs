@@ -0,0 +1,15 @@
+#![feature(diagnostic_on_type_mismatch)]
+
+#[diagnostic::on_type_mismatch(
+    message = "expected a `{Self}`",
+    label = "meters were expected here",
+    note = "use `Meters::from_feet` to convert from other units"
+)]
+struct Meters(f64);
+
+struct Feet(f64);
+
+fn main() {
+    let _m: Meters = Feet(3.0);
+    //~^ ERROR: expected a `Meters`
+}
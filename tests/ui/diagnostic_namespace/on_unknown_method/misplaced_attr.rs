@@ -0,0 +1,18 @@
+#![feature(diagnostic_on_unknown_method)]
+#![deny(misplaced_diagnostic_attributes)]
+
+#[diagnostic::on_unknown_method(message = "tadaa")]
+//~^ ERROR: `#[diagnostic::on_unknown_method]` can only be applied to structs, enums and unions
+trait Foo {}
+
+#[diagnostic::on_unknown_method(message = "tadaa")]
+//~^ ERROR: `#[diagnostic::on_unknown_method]` can only be applied to structs, enums and unions
+fn foo() {}
+
+#[diagnostic::on_unknown_method(message = "tadaa")]
+struct Bar;
+
+#[diagnostic::on_unknown_method(message = "tadaa")]
+enum Baz {}
+
+fn main() {}
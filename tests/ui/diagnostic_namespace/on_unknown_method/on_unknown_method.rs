@@ -0,0 +1,16 @@
+#![feature(diagnostic_on_unknown_method)]
+
+#[diagnostic::on_unknown_method(
+    message = "`{Self}` has no method with this name",
+    label = "not a request method",
+    note = "requests are sent with `Client::dispatch`"
+)]
+struct Request;
+
+fn main() {
+    let request = Request;
+    request.dispatch_now();
+    //~^ ERROR: `Request` has no method with this name
+    (&request).dispatch_now();
+    //~^ ERROR: `Request` has no method with this name
+}
@@ -0,0 +1,5 @@
+#[diagnostic::on_move(message = "custom message")]
+//~^ ERROR: the `#[diagnostic::on_move]` attribute is unstable
+struct Foo;
+
+fn main() {}
@@ -0,0 +1,5 @@
+#[diagnostic::on_type_mismatch(message = "custom message")]
+//~^ ERROR: the `#[diagnostic::on_type_mismatch]` attribute is unstable
+struct Foo;
+
+fn main() {}
@@ -0,0 +1,5 @@
+#[diagnostic::on_unknown_method(message = "custom message")]
+//~^ ERROR: the `#[diagnostic::on_unknown_method]` attribute is unstable
+struct Foo;
+
+fn main() {}
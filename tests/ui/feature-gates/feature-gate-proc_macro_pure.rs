@@ -0,0 +1,3 @@
+#![proc_macro_pure] //~ ERROR the `#[proc_macro_pure]` attribute is an experimental feature
+
+fn main() {}
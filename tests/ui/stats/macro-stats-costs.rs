@@ -0,0 +1,16 @@
+//@ check-pass
+//@ compile-flags: -Zmacro-stats -Zmacro-stats-costs
+// The times vary between runs.
+//@ normalize-stderr: " +[0-9]+\.[0-9][nµm]?s\b" -> " TIME"
+
+macro_rules! zero {
+    () => { 0 }
+}
+
+fn f() -> u32 {
+    zero!()
+}
+
+fn main() {
+    f();
+}
@@ -0,0 +1,38 @@
+// Checks that `-Ztyped-holes` reports the type expected of `todo!()` and `_` holes, along with
+// the bindings in scope and the expressions built from them that have that type.
+
+//@ compile-flags: -Ztyped-holes
+
+#![allow(unused)]
+
+pub struct Celsius(pub f64);
+
+pub struct Reading {
+    pub celsius: Celsius,
+    pub offset: f64,
+}
+
+impl Reading {
+    pub fn calibrated(&self) -> Celsius {
+        Celsius(self.celsius.0 + self.offset)
+    }
+}
+
+pub fn warmest(reading: Reading, current: Celsius) -> Celsius {
+    todo!()
+    //~^ NOTE typed hole of type `Celsius`
+}
+
+pub fn coldest(current: Celsius) -> Celsius {
+    let offset = 1.5;
+    _
+    //~^ ERROR in expressions, `_` can only be used on the left-hand side of an assignment
+    //~| NOTE typed hole of type `Celsius`
+}
+
+pub fn log(reading: Reading) {
+    // Unit holes are not reported.
+    todo!()
+}
+
+fn main() {}